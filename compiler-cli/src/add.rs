@@ -1,80 +1,286 @@
 use camino::{Utf8Path, Utf8PathBuf};
 
+use ecow::EcoString;
 use gleam_core::{
     error::{FileIoAction, FileKind},
+    requirement::{GitReference, Requirement},
     Error, Result,
 };
+use hexpm::version::{Range, Version};
 
 use crate::{cli, dependencies::UseManifest, fs};
 
-pub fn command(packages: Vec<String>, dev: bool) -> Result<()> {
+/// Where the packages given to `gleam add` are taken from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Hex,
+    Path(Utf8PathBuf),
+    Git {
+        url: EcoString,
+        reference: GitReference,
+    },
+}
+
+pub fn command(packages: Vec<String>, dev: bool, source: Source) -> Result<()> {
     let paths = crate::find_project_paths()?;
+    let packages = parse_packages(&packages, source)?;
 
     // Insert the new packages into the manifest and perform dependency
     // resolution to determine suitable versions
     let manifest = crate::dependencies::download(
         &paths,
         cli::Reporter::new(),
-        Some((packages.to_vec(), dev)),
+        Some((requirements_to_resolve(&packages), dev)),
         UseManifest::Yes,
     )?;
 
     // Read gleam.toml and manifest.toml so we can insert new deps into it
-    let mut gleam_toml = read_toml_edit("gleam.toml")?;
-    let mut manifest_toml = read_toml_edit("manifest.toml")?;
+    let mut gleam_toml = read_toml_edit(&paths.root_config())?;
+    let mut manifest_toml = read_toml_edit(&paths.manifest())?;
 
     // Insert the new deps
-    for package_to_add in packages {
+    for (package_to_add, requirement) in packages {
         // Pull the selected version out of the new manifest so we know what it is
         let version = &manifest
             .packages
             .iter()
-            .find(|package| package.name == *package_to_add)
+            .find(|package| package.name == package_to_add)
             .expect("Added package not found in resolved manifest")
             .version;
 
         tracing::info!(version=%version, "new_package_version_resolved");
 
-        // Produce a version requirement locked to the major version.
-        // i.e. if 1.2.3 is selected we want >= 1.2.3 and < 2.0.0
-        let range = format!(
-            ">= {}.{}.{} and < {}.0.0",
-            version.major,
-            version.minor,
-            version.patch,
-            version.major + 1
-        );
+        let value = match &requirement {
+            // Produce a version requirement locked to the major version.
+            // i.e. if 1.2.3 is selected we want >= 1.2.3 and < 2.0.0
+            None => toml_edit::value(major_version_range(version).to_string()),
+            Some(Requirement::Hex { version, .. }) => toml_edit::value(version.to_string()),
+            Some(requirement) => requirement
+                .to_toml(paths.root())
+                .parse::<toml_edit::Value>()
+                .map(toml_edit::Item::Value)
+                .expect("Requirement is valid toml"),
+        };
 
         // False positive. This package doesn't use the indexing API correctly.
         #[allow(clippy::indexing_slicing)]
         {
             if dev {
-                gleam_toml["dev-dependencies"][&package_to_add] = toml_edit::value(range.clone());
+                gleam_toml["dev-dependencies"][package_to_add.as_str()] = value;
             } else {
-                gleam_toml["dependencies"][&package_to_add] = toml_edit::value(range.clone());
+                gleam_toml["dependencies"][package_to_add.as_str()] = value;
             };
-            manifest_toml["requirements"][&package_to_add]
-                .as_inline_table_mut()
-                .expect("Invalid manifest format")["version"] = range.into();
+            // The manifest records the requirement the package was resolved
+            // with, which is only a placeholder when no requirement was given.
+            if requirement.is_none() {
+                manifest_toml["requirements"][package_to_add.as_str()]
+                    .as_inline_table_mut()
+                    .expect("Invalid manifest format")["version"] =
+                    major_version_range(version).to_string().into();
+            }
         }
 
         cli::print_added(&format!("{package_to_add} v{version}"));
     }
 
     // Write the updated config
-    fs::write(Utf8Path::new("gleam.toml"), &gleam_toml.to_string())?;
-    fs::write(Utf8Path::new("manifest.toml"), &manifest_toml.to_string())?;
+    fs::write(&paths.root_config(), &gleam_toml.to_string())?;
+    fs::write(&paths.manifest(), &manifest_toml.to_string())?;
 
     Ok(())
 }
 
-fn read_toml_edit(name: &str) -> Result<toml_edit::Document, Error> {
-    fs::read(name)?
+/// The packages to add, with the requirement given for each. Packages given
+/// without a requirement are added with a requirement on the major version
+/// that is resolved for them.
+fn parse_packages(
+    packages: &[String],
+    source: Source,
+) -> Result<Vec<(EcoString, Option<Requirement>)>> {
+    let (source_flag, requirement) = match source {
+        Source::Hex => {
+            return packages
+                .iter()
+                .map(|package| parse_hex_package(package))
+                .collect()
+        }
+        Source::Path(path) => (
+            "--path",
+            Requirement::Path {
+                path: fs::canonicalise(&path)?,
+            },
+        ),
+        Source::Git { url, reference } => (
+            "--git",
+            Requirement::Git {
+                git: url,
+                reference,
+            },
+        ),
+    };
+
+    let [package] = packages else {
+        return Err(Error::InvalidAddArguments {
+            problem: format!("`{source_flag}` can only be used when adding a single package."),
+        });
+    };
+    if package.contains('@') {
+        return Err(Error::InvalidAddArguments {
+            problem: format!("A version requirement cannot be given with `{source_flag}`."),
+        });
+    }
+    Ok(vec![(package.into(), Some(requirement))])
+}
+
+fn parse_hex_package(package: &str) -> Result<(EcoString, Option<Requirement>)> {
+    match package.split_once('@') {
+        None => Ok((package.into(), None)),
+        Some((name, constraint)) => {
+            let version = parse_constraint(constraint)?;
+            Ok((name.into(), Some(Requirement::hex(&version.to_string()))))
+        }
+    }
+}
+
+/// A version constraint given after the `@` of a package. A version on its
+/// own, such as `1.4` or `1.4.2`, allows any later version with the same
+/// major version. Anything else is used as a version requirement as written.
+fn parse_constraint(constraint: &str) -> Result<Range> {
+    if let Some(version) = parse_partial_version(constraint) {
+        return Ok(major_version_range(&version));
+    }
+    let range = Range::new(constraint.trim().into());
+    match range.to_pubgrub() {
+        Ok(_) => Ok(range),
+        Err(error) => Err(Error::InvalidVersionFormat {
+            input: constraint.into(),
+            error: error.to_string(),
+        }),
+    }
+}
+
+fn parse_partial_version(version: &str) -> Option<Version> {
+    if let Ok(version) = Version::parse(version) {
+        return Some(version);
+    }
+    let parts: Vec<u32> = version
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [major] => Some(Version::new(*major, 0, 0)),
+        [major, minor] => Some(Version::new(*major, *minor, 0)),
+        _ => None,
+    }
+}
+
+fn major_version_range(version: &Version) -> Range {
+    Range::new(format!(
+        ">= {}.{}.{} and < {}.0.0",
+        version.major,
+        version.minor,
+        version.patch,
+        version.major + 1
+    ))
+}
+
+/// The requirements the packages are resolved with. Packages given without a
+/// requirement may resolve to any version.
+fn requirements_to_resolve(
+    packages: &[(EcoString, Option<Requirement>)],
+) -> Vec<(EcoString, Requirement)> {
+    packages
+        .iter()
+        .map(|(name, requirement)| {
+            let requirement = requirement
+                .clone()
+                .unwrap_or_else(|| Requirement::hex(">= 0.0.0"));
+            (name.clone(), requirement)
+        })
+        .collect()
+}
+
+fn read_toml_edit(path: &Utf8Path) -> Result<toml_edit::Document, Error> {
+    fs::read(path)?
         .parse::<toml_edit::Document>()
         .map_err(|e| Error::FileIo {
             kind: FileKind::File,
             action: FileIoAction::Parse,
-            path: Utf8PathBuf::from("gleam.toml"),
+            path: path.to_path_buf(),
             err: Some(e.to_string()),
         })
 }
+
+#[test]
+fn parse_package_without_constraint() {
+    assert_eq!(
+        parse_packages(&["lustre".into()], Source::Hex),
+        Ok(vec![("lustre".into(), None)])
+    );
+}
+
+#[test]
+fn parse_package_with_partial_version() {
+    assert_eq!(
+        parse_packages(&["lustre@1.4".into(), "wisp@0".into()], Source::Hex),
+        Ok(vec![
+            (
+                "lustre".into(),
+                Some(Requirement::hex(">= 1.4.0 and < 2.0.0"))
+            ),
+            (
+                "wisp".into(),
+                Some(Requirement::hex(">= 0.0.0 and < 1.0.0"))
+            ),
+        ])
+    );
+}
+
+#[test]
+fn parse_package_with_requirement() {
+    assert_eq!(
+        parse_packages(&["lustre@>= 0.3.0 and < 0.5.0".into()], Source::Hex),
+        Ok(vec![(
+            "lustre".into(),
+            Some(Requirement::hex(">= 0.3.0 and < 0.5.0"))
+        )])
+    );
+}
+
+#[test]
+fn parse_package_with_invalid_requirement() {
+    assert!(matches!(
+        parse_packages(&["lustre@>= wibble".into()], Source::Hex),
+        Err(Error::InvalidVersionFormat { .. })
+    ));
+}
+
+#[test]
+fn parse_git_package() {
+    let source = Source::Git {
+        url: "https://example.com/wibble.git".into(),
+        reference: GitReference::Tag("v1.0.0".into()),
+    };
+    assert_eq!(
+        parse_packages(&["wibble".into()], source),
+        Ok(vec![(
+            "wibble".into(),
+            Some(Requirement::Git {
+                git: "https://example.com/wibble.git".into(),
+                reference: GitReference::Tag("v1.0.0".into()),
+            })
+        )])
+    );
+}
+
+#[test]
+fn parse_many_packages_with_source() {
+    let source = Source::Git {
+        url: "https://example.com/wibble.git".into(),
+        reference: GitReference::Head,
+    };
+    assert!(matches!(
+        parse_packages(&["wibble".into(), "wobble".into()], source),
+        Err(Error::InvalidAddArguments { .. })
+    ));
+}
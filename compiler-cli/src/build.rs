@@ -1,7 +1,8 @@
 use std::{sync::Arc, time::Instant};
 
 use gleam_core::{
-    build::{Built, Codegen, Options, ProjectCompiler},
+    build::{Built, Codegen, NullTelemetry, Options, ProjectCompiler},
+    error::Error,
     manifest::Manifest,
     paths::ProjectPaths,
     Result,
@@ -9,29 +10,27 @@ use gleam_core::{
 
 use crate::{
     build_lock::BuildLock,
-    cli,
+    cli::{self, DiagnosticFormat},
     dependencies::UseManifest,
-    fs::{self, get_current_directory, get_project_root, ConsoleWarningEmitter},
+    fs::{self, ConsoleWarningEmitter, DiagnosticWarningEmitter},
+    watch::Watcher,
 };
 
-pub fn download_dependencies() -> Result<Manifest> {
-    let paths = crate::find_project_paths()?;
-    crate::dependencies::download(&paths, cli::Reporter::new(), None, UseManifest::Yes)
+pub fn download_dependencies(paths: &ProjectPaths) -> Result<Manifest> {
+    crate::dependencies::download(paths, cli::Reporter::new(), None, UseManifest::Yes)
 }
 
-pub fn main(options: Options, manifest: Manifest) -> Result<Built> {
-    let paths = crate::find_project_paths()?;
+pub fn main(paths: &ProjectPaths, options: Options, manifest: Manifest) -> Result<Built> {
     let perform_codegen = options.codegen;
-    let root_config = crate::config::root_config()?;
+    let root_config = crate::config::root_config(paths)?;
     let telemetry = Box::new(cli::Reporter::new());
     let io = fs::ProjectIO::new();
     let start = Instant::now();
     let lock = BuildLock::new_target(
-        &paths,
+        paths,
         options.mode,
         options.target.unwrap_or(root_config.target),
     )?;
-    let current_dir = get_project_root(get_current_directory()?)?;
 
     tracing::info!("Compiling packages");
     let result = {
@@ -42,7 +41,7 @@ pub fn main(options: Options, manifest: Manifest) -> Result<Built> {
             manifest.packages,
             telemetry,
             Arc::new(ConsoleWarningEmitter),
-            ProjectPaths::new(current_dir),
+            paths.clone(),
             io,
         );
         compiler.compile()?
@@ -55,3 +54,158 @@ pub fn main(options: Options, manifest: Manifest) -> Result<Built> {
 
     Ok(result)
 }
+
+/// Build the project, printing its errors and warnings to stdout in a machine
+/// readable format instead of reporting progress and problems for people to
+/// read. The error is still returned if the build failed.
+pub fn main_with_diagnostics(
+    paths: &ProjectPaths,
+    options: Options,
+    format: DiagnosticFormat,
+) -> Result<()> {
+    let warnings = Arc::new(DiagnosticWarningEmitter::default());
+    let result = build_quietly(paths, options, warnings.clone());
+
+    let mut diagnostics = warnings.take();
+    if let Err(error) = &result {
+        diagnostics.extend(error.to_diagnostics());
+    }
+    cli::print_diagnostics(format, &diagnostics, paths.root());
+
+    result.map(|_| ())
+}
+
+fn build_quietly(
+    paths: &ProjectPaths,
+    options: Options,
+    warnings: Arc<DiagnosticWarningEmitter>,
+) -> Result<Built> {
+    let manifest = crate::dependencies::download(paths, NullTelemetry, None, UseManifest::Yes)?;
+    let root_config = crate::config::root_config(paths)?;
+    let lock = BuildLock::new_target(
+        paths,
+        options.mode,
+        options.target.unwrap_or(root_config.target),
+    )?;
+    let _guard = lock.lock(&NullTelemetry)?;
+    ProjectCompiler::new(
+        root_config,
+        options,
+        manifest.packages,
+        Box::new(NullTelemetry),
+        warnings,
+        paths.clone(),
+        fs::ProjectIO::new(),
+    )
+    .compile()
+}
+
+/// Build the project, then rebuild it each time its config or source files
+/// change, calling `on_built` after each successful build. Errors are printed
+/// rather than returned so the project continues to be watched until the
+/// user presses Ctrl-C.
+///
+/// The given manifest is used for the first build, and the dependencies are
+/// downloaded again each time the config changes.
+pub fn watch(
+    paths: &ProjectPaths,
+    options: Options,
+    manifest: Manifest,
+    mut on_built: impl FnMut(Built) -> Result<()>,
+) -> Result<()> {
+    let mut watcher = Watcher::new(paths);
+    let mut manifest = Some(manifest);
+    crate::watch::stop_on_ctrl_c();
+
+    loop {
+        // A new compiler is created each time the config changes as the
+        // dependencies of the project may have changed.
+        let result = watch_until_config_changes(
+            paths,
+            &options,
+            manifest.take(),
+            &mut watcher,
+            &mut on_built,
+        );
+        match result {
+            Ok(WatchEnd::ConfigChanged) => (),
+            Ok(WatchEnd::Interrupted) => return Ok(()),
+            Err(error) => {
+                cli::print_error(&error);
+                cli::print_watching();
+                loop {
+                    match watcher.wait_for_changes() {
+                        None => return Ok(()),
+                        Some(changed) if watcher.config_changed(&changed) => break,
+                        Some(_) => (),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Why the project stopped being rebuilt with its current config.
+enum WatchEnd {
+    ConfigChanged,
+    Interrupted,
+}
+
+fn watch_until_config_changes(
+    paths: &ProjectPaths,
+    options: &Options,
+    manifest: Option<Manifest>,
+    watcher: &mut Watcher,
+    on_built: &mut impl FnMut(Built) -> Result<()>,
+) -> Result<WatchEnd, Error> {
+    let manifest = match manifest {
+        Some(manifest) => manifest,
+        None => download_dependencies(paths)?,
+    };
+    let root_config = crate::config::root_config(paths)?;
+    let telemetry = cli::Reporter::new();
+    let lock = BuildLock::new_target(
+        paths,
+        options.mode,
+        options.target.unwrap_or(root_config.target),
+    )?;
+    let mut compiler = ProjectCompiler::new(
+        root_config,
+        options.clone(),
+        manifest.packages,
+        Box::new(telemetry.clone()),
+        Arc::new(ConsoleWarningEmitter),
+        paths.clone(),
+        fs::ProjectIO::new(),
+    );
+
+    loop {
+        let start = Instant::now();
+        let result = {
+            let _guard = lock.lock(&telemetry)?;
+            compiler.recompile()
+        };
+
+        match result {
+            Ok(built) => {
+                match options.codegen {
+                    Codegen::All | Codegen::DepsOnly => cli::print_compiled(start.elapsed()),
+                    Codegen::None => cli::print_checked(start.elapsed()),
+                };
+                if let Err(error) = on_built(built) {
+                    cli::print_error(&error);
+                }
+            }
+            Err(error) => cli::print_error(&error),
+        }
+
+        cli::print_watching();
+        match watcher.wait_for_changes() {
+            None => return Ok(WatchEnd::Interrupted),
+            Some(changed) if watcher.config_changed(&changed) => {
+                return Ok(WatchEnd::ConfigChanged)
+            }
+            Some(_) => (),
+        }
+    }
+}
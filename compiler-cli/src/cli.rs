@@ -1,5 +1,8 @@
+use camino::Utf8Path;
+use clap::ValueEnum;
 use gleam_core::{
     build::Telemetry,
+    diagnostic::{self, Diagnostic},
     error::{Error, StandardIoAction},
 };
 use hexpm::version::Version;
@@ -9,6 +12,18 @@ use std::{
 };
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
+/// How errors and warnings are reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagnosticFormat {
+    /// Coloured text for people to read
+    #[default]
+    Human,
+    /// A JSON object with a list of diagnostics
+    Json,
+    /// A SARIF 2.1.0 log, as used by code scanning tools
+    Sarif,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Reporter;
 
@@ -131,6 +146,17 @@ pub(crate) fn print_running(text: &str) {
     print_colourful_prefix("Running", text)
 }
 
+pub(crate) fn print_watching() {
+    print_colourful_prefix("Watching", "for changes")
+}
+
+pub(crate) fn print_error(error: &Error) {
+    let stderr = stderr_buffer_writer();
+    let mut buffer = stderr.buffer();
+    error.pretty(&mut buffer);
+    stderr.print(&buffer).expect("print_error");
+}
+
 pub(crate) fn print_added(text: &str) {
     print_colourful_prefix("Added", text)
 }
@@ -139,6 +165,10 @@ pub(crate) fn print_removed(text: &str) {
     print_colourful_prefix("Removed", text)
 }
 
+pub(crate) fn print_updated(text: &str) {
+    print_colourful_prefix("Updated", text)
+}
+
 pub(crate) fn print_generating_documentation() {
     print_colourful_prefix("Generating", "documentation")
 }
@@ -152,6 +182,26 @@ fn print_packages_downloaded(start: Instant, count: usize) {
     print_colourful_prefix("Downloaded", &msg)
 }
 
+/// Print diagnostics to stdout in a machine readable format, with file paths
+/// relative to the project root.
+pub fn print_diagnostics(format: DiagnosticFormat, diagnostics: &[Diagnostic], root: &Utf8Path) {
+    let output = match format {
+        DiagnosticFormat::Json => diagnostic::json::render(diagnostics, root),
+        DiagnosticFormat::Sarif => diagnostic::sarif::render(diagnostics, root),
+        DiagnosticFormat::Human => {
+            let buffer_writer = stderr_buffer_writer();
+            let mut buffer = buffer_writer.buffer();
+            for diagnostic in diagnostics {
+                diagnostic.write(&mut buffer);
+                writeln!(buffer).expect("print_diagnostics");
+            }
+            buffer_writer.print(&buffer).expect("print_diagnostics");
+            return;
+        }
+    };
+    println!("{output}");
+}
+
 pub fn seconds(duration: Duration) -> String {
     format!("{:.2}s", duration.as_millis() as f32 / 1000.)
 }
@@ -7,11 +7,7 @@ use gleam_core::{
     paths::ProjectPaths,
 };
 
-use crate::fs::{get_current_directory, get_project_root};
-
-pub fn root_config() -> Result<PackageConfig, Error> {
-    let dir = get_project_root(get_current_directory()?)?;
-    let paths = ProjectPaths::new(dir);
+pub fn root_config(paths: &ProjectPaths) -> Result<PackageConfig, Error> {
     read(paths.root_config())
 }
 
@@ -47,12 +43,12 @@ pub fn find_package_config_for_module(
         return Ok((configuration, PackageKind::Dependency));
     }
 
-    Ok((root_config()?, PackageKind::Root))
+    Ok((root_config(project_paths)?, PackageKind::Root))
 }
 
 fn package_root(package: &ManifestPackage, project_paths: &ProjectPaths) -> Utf8PathBuf {
     match &package.source {
-        ManifestPackageSource::Local { path } => project_paths.root().join(path),
+        ManifestPackageSource::Local { path } => project_paths.shared_root().join(path),
 
         ManifestPackageSource::Hex { .. } | ManifestPackageSource::Git { .. } => {
             project_paths.build_packages_package(&package.name)
@@ -101,6 +97,7 @@ mod tests {
             requirements: vec![],
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
         };
         assert_eq!(
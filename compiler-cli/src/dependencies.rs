@@ -1,6 +1,8 @@
 use std::{
+    cell::RefCell,
     collections::{HashMap, HashSet},
-    time::Instant,
+    rc::Rc,
+    time::{Instant, SystemTime},
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
@@ -9,14 +11,15 @@ use flate2::read::GzDecoder;
 use futures::future;
 use gleam_core::{
     build::{Mode, Target, Telemetry},
-    config::PackageConfig,
+    config::{self, PackageConfig, UserConfig},
     dependency,
     error::{FileIoAction, FileKind, StandardIoAction},
-    hex::{self, HEXPM_PUBLIC_KEY},
+    hex::{self, HexRepositories, HexRepository, HEXPM_REPOSITORY},
     io::{HttpClient as _, TarUnpacker, WrappedReader},
     manifest::{Base16Checksum, Manifest, ManifestPackage, ManifestPackageSource},
-    paths::ProjectPaths,
-    requirement::Requirement,
+    paths::{self, ProjectPaths},
+    requirement::{GitReference, Requirement},
+    sbom::{self, Sbom},
     Error, Result,
 };
 use hexpm::version::Version;
@@ -29,24 +32,371 @@ use crate::{
     cli,
     fs::{self, ProjectIO},
     http::HttpClient,
+    workspace::{self, Workspace},
 };
 
+mod git;
+mod licences;
+mod tree;
+
 pub fn list() -> Result<()> {
     let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
-    let project = fs::get_project_root(fs::get_current_directory()?)?;
-    let paths = ProjectPaths::new(project);
-    let config = crate::config::root_config()?;
+    let (paths, config, members) = resolution_config(&crate::find_project_paths()?)?;
     let (_, manifest) = get_manifest(
         &paths,
         runtime.handle().clone(),
         Mode::Dev,
         &config,
+        &members,
         &cli::Reporter::new(),
         UseManifest::Yes,
     )?;
     list_manifest_packages(std::io::stdout(), manifest)
 }
 
+pub fn tree() -> Result<()> {
+    print!("{}", dependency_graph()?.tree());
+    Ok(())
+}
+
+pub fn why(package: &str) -> Result<()> {
+    print!("{}", dependency_graph()?.why(package)?);
+    Ok(())
+}
+
+/// Copies the tarball of every Hex package in the manifest into the vendor
+/// directory, so the project can be built without network access.
+pub fn vendor() -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let (paths, config, _) = resolution_config(&crate::find_project_paths()?)?;
+    let manifest = crate::build::download_dependencies(&paths)?;
+    let packages = manifest
+        .packages
+        .iter()
+        .filter(|package| package.is_hex())
+        .collect_vec();
+
+    // Remove the tarballs of packages that are no longer in the manifest
+    let vendor_directory = paths.vendor_directory();
+    if vendor_directory.is_dir() {
+        let vendored: HashSet<_> = packages
+            .iter()
+            .map(|package| {
+                let repository = match &package.source {
+                    ManifestPackageSource::Hex {
+                        repository: Some(repository),
+                        ..
+                    } => repository.as_str(),
+                    _ => HEXPM_REPOSITORY,
+                };
+                paths.vendor_package_tarball(
+                    repository,
+                    &package.name,
+                    &package.version.to_string(),
+                )
+            })
+            .collect();
+        for entry in fs::read_dir(&vendor_directory)?.filter_map(Result::ok) {
+            let path = entry.into_path();
+            let tarballs = if path.is_dir() {
+                fs::read_dir(&path)?
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.into_path())
+                    .collect_vec()
+            } else {
+                vec![path]
+            };
+            for tarball in tarballs {
+                if tarball.extension() == Some("tar") && !vendored.contains(&tarball) {
+                    fs::delete_file(&tarball)?;
+                }
+            }
+        }
+    }
+
+    let fs = ProjectIO::boxed();
+    let downloader = hex::Downloader::new(
+        fs.clone(),
+        fs,
+        HttpClient::boxed(),
+        Untar::boxed(),
+        hex_repositories(&config)?,
+        paths.clone(),
+        offline(),
+    );
+    let _ = runtime.block_on(future::try_join_all(
+        packages
+            .iter()
+            .map(|package| downloader.vendor_package(package)),
+    ))?;
+    let count = match packages.len() {
+        1 => "1 package".into(),
+        count => format!("{count} packages"),
+    };
+    cli::print_colourful_prefix("Vendored", &format!("{count} into {vendor_directory}"));
+    Ok(())
+}
+
+/// Prints the licences of each production dependency, failing if any are not
+/// in the `allowed-licences` list of the config.
+pub fn licences() -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config(&paths)?;
+    let sbom = bill_of_materials()?;
+    print!("{}", sbom.licence_report());
+    if config.allowed_licences.is_empty() {
+        Ok(())
+    } else {
+        sbom.check_licences(&config.allowed_licences)
+    }
+}
+
+/// The bill of materials for the root package and every package it depends
+/// on when built for production. Dev dependencies are left out as they are
+/// not part of what is shipped.
+pub fn bill_of_materials() -> Result<Sbom> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config(&paths)?;
+    let manifest = crate::build::download_dependencies(&paths)?;
+    let packages: HashMap<_, _> = manifest
+        .packages
+        .iter()
+        .map(|package| (package.name.clone(), package))
+        .collect();
+
+    let mut names = config.dependencies.keys().cloned().collect_vec();
+    let mut seen = HashSet::new();
+    let mut dependencies = vec![];
+    while let Some(name) = names.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let Some(package) = packages.get(&name) else {
+            continue;
+        };
+        names.extend(package.requirements.iter().cloned());
+        let directory = match &package.source {
+            ManifestPackageSource::Local { path } => paths.shared_root().join(path),
+            ManifestPackageSource::Hex { .. } | ManifestPackageSource::Git { .. } => {
+                paths.build_packages_package(&package.name)
+            }
+        };
+        dependencies.push(sbom::Package {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            licences: licences::read(&directory)?,
+            source: Some(package.source.clone()),
+            dependencies: package.requirements.iter().sorted().cloned().collect(),
+        });
+    }
+    dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let root = sbom::Package {
+        name: config.name.clone(),
+        version: config.version.clone(),
+        licences: config
+            .licences
+            .iter()
+            .map(|licence| licence.as_ref().into())
+            .collect(),
+        source: None,
+        dependencies: config.dependencies.keys().sorted().cloned().collect(),
+    };
+    Ok(Sbom {
+        root,
+        dependencies,
+        created: created_timestamp(),
+    })
+}
+
+/// The `SOURCE_DATE_EPOCH` environment variable is used for the creation time
+/// if it is set, so that the output is reproducible.
+///
+/// <https://reproducible-builds.org/specs/source-date-epoch/>
+fn created_timestamp() -> u64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default()
+        })
+}
+
+fn dependency_graph() -> Result<tree::DependencyGraph> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config(&paths)?;
+    let manifest = crate::build::download_dependencies(&paths)?;
+    Ok(tree::DependencyGraph::new(&config, &manifest, |package| {
+        package_requirements(&paths, package)
+    }))
+}
+
+/// The requirements declared in the config of a downloaded or local package.
+/// These are not recorded in the manifest, so if the config can't be read
+/// there are none.
+fn package_requirements(paths: &ProjectPaths, package: &ManifestPackage) -> config::Dependencies {
+    let config_path = match &package.source {
+        ManifestPackageSource::Local { path } => paths.shared_root().join(path).join("gleam.toml"),
+        ManifestPackageSource::Hex { .. } | ManifestPackageSource::Git { .. } => {
+            paths.build_packages_package_config(&package.name)
+        }
+    };
+    PackageConfig::read(config_path, &ProjectIO::new())
+        .map(|config| config.dependencies)
+        .unwrap_or_default()
+}
+
+pub fn outdated() -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let (paths, config, members) = resolution_config(&crate::find_project_paths()?)?;
+    let (_, manifest) = get_manifest(
+        &paths,
+        runtime.handle().clone(),
+        Mode::Dev,
+        &config,
+        &members,
+        &cli::Reporter::new(),
+        UseManifest::Yes,
+    )?;
+    // The packages of a workspace are the dependencies of its members
+    let requirements = if members.is_empty() {
+        config.dependencies_for(Mode::Dev)?.into_iter().collect()
+    } else {
+        workspace::requirements(&members)?
+    };
+    let fetched = FetchedPackages {
+        repositories: manifest
+            .packages
+            .iter()
+            .filter_map(|package| match &package.source {
+                ManifestPackageSource::Hex {
+                    repository: Some(repository),
+                    ..
+                } => Some((package.name.clone(), repository.clone())),
+                _ => None,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let packages = dependency::outdated_packages(
+        PackageFetcher::boxed(
+            runtime.handle().clone(),
+            hex_repositories(&config)?,
+            paths.clone(),
+            Rc::new(RefCell::new(fetched)),
+        )
+        .as_ref(),
+        &requirements,
+        &manifest,
+    )?;
+    print!("{}", outdated_report(&packages));
+    Ok(())
+}
+
+/// A table of the packages that have newer releases, or whose locked release
+/// has been retired.
+fn outdated_report(packages: &[dependency::OutdatedPackage]) -> String {
+    let version = |version: &Option<Version>| match version {
+        Some(version) => version.to_string(),
+        None => "-".into(),
+    };
+    let rows = packages
+        .iter()
+        .filter(|package| package.is_outdated())
+        .map(|package| {
+            let retirement = match &package.retirement {
+                Some(status) if status.message.is_empty() => {
+                    format!("retired ({})", status.reason.to_str())
+                }
+                Some(status) => format!("retired ({}): {}", status.reason.to_str(), status.message),
+                None => String::new(),
+            };
+            [
+                package.name.to_string(),
+                package.locked.to_string(),
+                version(&package.compatible),
+                version(&package.latest),
+                retirement,
+            ]
+        })
+        .collect_vec();
+    if rows.is_empty() {
+        return "All dependencies are up to date.\n".into();
+    }
+
+    let header = ["Package", "Locked", "Compatible", "Latest", ""].map(String::from);
+    let widths = rows
+        .iter()
+        .chain([&header])
+        .fold([0; 5], |mut widths, row| {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+            widths
+        });
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
+
+#[test]
+fn outdated_report_format() {
+    let package = |name: &str, locked: &str, compatible: &str, latest: Option<&str>| {
+        dependency::OutdatedPackage {
+            name: name.into(),
+            locked: Version::parse(locked).unwrap(),
+            compatible: Some(Version::parse(compatible).unwrap()),
+            latest: latest.map(|version| Version::parse(version).unwrap()),
+            retirement: None,
+        }
+    };
+    let mut retired = package("thoas", "0.4.0", "0.4.1", Some("1.0.0"));
+    retired.retirement = Some(hexpm::RetirementStatus {
+        reason: hexpm::RetirementReason::Security,
+        message: "Use 0.4.1".into(),
+    });
+    let packages = [
+        package("gleam_json", "1.0.0", "1.0.1", Some("2.0.0")),
+        package("gleam_stdlib", "0.38.0", "0.38.0", Some("0.38.0")),
+        package("gleeunit", "1.0.0", "1.0.0", Some("1.2.0")),
+        retired,
+    ];
+    assert_eq!(
+        outdated_report(&packages),
+        "Package     Locked  Compatible  Latest
+gleam_json  1.0.0   1.0.1       2.0.0
+gleeunit    1.0.0   1.0.0       1.2.0
+thoas       0.4.0   0.4.1       1.0.0   retired (security): Use 0.4.1
+"
+    );
+}
+
+#[test]
+fn outdated_report_up_to_date() {
+    let packages = [dependency::OutdatedPackage {
+        name: "gleam_stdlib".into(),
+        locked: Version::new(0, 38, 0),
+        compatible: Some(Version::new(0, 38, 0)),
+        latest: Some(Version::new(0, 38, 0)),
+        retirement: None,
+    }];
+    assert_eq!(
+        outdated_report(&packages),
+        "All dependencies are up to date.\n"
+    );
+}
+
 fn list_manifest_packages<W: std::io::Write>(mut buffer: W, manifest: Manifest) -> Result<()> {
     manifest
         .packages
@@ -72,6 +422,7 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -82,6 +433,7 @@ fn list_manifest_format() {
                 requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -92,6 +444,7 @@ fn list_manifest_format() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: None,
                 },
             },
         ],
@@ -106,22 +459,159 @@ zzz 0.4.0
     )
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum UseManifest {
     Yes,
     No,
+    /// Use the manifest, but resolve the versions of the given packages anew.
+    /// Every other package stays locked to the version in the manifest.
+    Unlock(Vec<EcoString>),
 }
 
-pub fn update() -> Result<()> {
+/// Updates the given packages, or every package if none are given, printing
+/// the versions that changed.
+pub fn update(packages: Vec<String>) -> Result<()> {
     let paths = crate::find_project_paths()?;
-    _ = download(&paths, cli::Reporter::new(), None, UseManifest::No)?;
+    let old_manifest = if paths.manifest().exists() {
+        read_manifest_from_disc(&paths)?
+    } else {
+        Manifest {
+            requirements: HashMap::new(),
+            packages: vec![],
+        }
+    };
+
+    let use_manifest = if packages.is_empty() {
+        UseManifest::No
+    } else {
+        let packages = packages.into_iter().map(EcoString::from).collect_vec();
+        if let Some(name) = packages
+            .iter()
+            .find(|name| !old_manifest.packages.iter().any(|p| &&p.name == name))
+        {
+            return Err(Error::UnknownDependency { name: name.clone() });
+        }
+        UseManifest::Unlock(packages)
+    };
+
+    let manifest = download(&paths, cli::Reporter::new(), None, use_manifest)?;
+    for change in version_changes(&old_manifest, &manifest) {
+        match change {
+            VersionChange::Added { name, version } => {
+                cli::print_added(&format!("{name} v{version}"))
+            }
+            VersionChange::Removed { name, version } => {
+                cli::print_removed(&format!("{name} v{version}"))
+            }
+            VersionChange::Updated { name, from, to } => {
+                cli::print_updated(&format!("{name} v{from} -> v{to}"))
+            }
+        }
+    }
     Ok(())
 }
 
+#[derive(Debug, PartialEq, Eq)]
+enum VersionChange<'a> {
+    Added {
+        name: &'a str,
+        version: &'a Version,
+    },
+    Removed {
+        name: &'a str,
+        version: &'a Version,
+    },
+    Updated {
+        name: &'a str,
+        from: &'a Version,
+        to: &'a Version,
+    },
+}
+
+/// The packages whose versions differ between two manifests, sorted by name.
+fn version_changes<'a>(old: &'a Manifest, new: &'a Manifest) -> Vec<VersionChange<'a>> {
+    let old_versions: HashMap<_, _> = old
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), &package.version))
+        .collect();
+    let new_versions: HashMap<_, _> = new
+        .packages
+        .iter()
+        .map(|package| (package.name.as_str(), &package.version))
+        .collect();
+
+    old_versions
+        .keys()
+        .chain(new_versions.keys())
+        .unique()
+        .sorted()
+        .filter_map(
+            |name| match (old_versions.get(name), new_versions.get(name)) {
+                (None, Some(version)) => Some(VersionChange::Added { name, version }),
+                (Some(version), None) => Some(VersionChange::Removed { name, version }),
+                (Some(from), Some(to)) if from != to => {
+                    Some(VersionChange::Updated { name, from, to })
+                }
+                (Some(_), Some(_)) | (None, None) => None,
+            },
+        )
+        .collect()
+}
+
+#[test]
+fn version_changes_between_manifests() {
+    let manifest = |packages: &[(&str, &str)]| Manifest {
+        requirements: HashMap::new(),
+        packages: packages
+            .iter()
+            .map(|(name, version)| ManifestPackage {
+                name: (*name).into(),
+                version: Version::parse(version).unwrap(),
+                build_tools: ["gleam".into()].into(),
+                otp_app: None,
+                requirements: vec![],
+                source: ManifestPackageSource::Hex {
+                    outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
+                },
+            })
+            .collect(),
+    };
+    let old = manifest(&[
+        ("gleam_json", "1.0.0"),
+        ("gleam_stdlib", "0.34.0"),
+        ("thoas", "0.4.0"),
+    ]);
+    let new = manifest(&[
+        ("birl", "1.0.0"),
+        ("gleam_json", "1.0.0"),
+        ("gleam_stdlib", "0.38.0"),
+    ]);
+    assert_eq!(
+        version_changes(&old, &new),
+        vec![
+            VersionChange::Added {
+                name: "birl",
+                version: &Version::new(1, 0, 0)
+            },
+            VersionChange::Updated {
+                name: "gleam_stdlib",
+                from: &Version::new(0, 34, 0),
+                to: &Version::new(0, 38, 0)
+            },
+            VersionChange::Removed {
+                name: "thoas",
+                version: &Version::new(0, 4, 0)
+            },
+        ]
+    );
+}
+
 pub fn download<Telem: Telemetry>(
     paths: &ProjectPaths,
     telemetry: Telem,
-    new_package: Option<(Vec<String>, bool)>,
+    new_package: Option<(Vec<(EcoString, Requirement)>, bool)>,
     // If true we read the manifest from disc. If not set then we ignore any
     // manifest which will result in the latest versions of the dependency
     // packages being resolved (not the locked ones).
@@ -142,17 +632,17 @@ pub fn download<Telem: Telemetry>(
     let fs = ProjectIO::boxed();
 
     // Read the project config
-    let mut config = crate::config::read(paths.root_config())?;
+    let (paths, mut config, members) = resolution_config(paths)?;
+    let paths = &paths;
     let project_name = config.name.clone();
 
     // Insert the new packages to add, if it exists
     if let Some((packages, dev)) = new_package {
-        for package in packages {
-            let version = Requirement::hex(">= 0.0.0");
+        for (package, requirement) in packages {
             let _ = if dev {
-                config.dev_dependencies.insert(package.into(), version)
+                config.dev_dependencies.insert(package, requirement)
             } else {
-                config.dependencies.insert(package.into(), version)
+                config.dependencies.insert(package, requirement)
             };
         }
     }
@@ -166,6 +656,7 @@ pub fn download<Telem: Telemetry>(
         runtime.handle().clone(),
         mode,
         &config,
+        &members,
         &telemetry,
         use_manifest,
     )?;
@@ -174,10 +665,14 @@ pub fn download<Telem: Telemetry>(
     // Remove any packages that are no longer required due to gleam.toml changes
     remove_extra_packages(paths, &local, &manifest, &telemetry)?;
 
+    // Check out the locked commit of any git packages
+    checkout_git_packages(paths, &manifest)?;
+
     // Download them from Hex to the local cache
     runtime.block_on(add_missing_packages(
         paths,
         fs,
+        hex_repositories(&config)?,
         &manifest,
         &local,
         project_name,
@@ -195,9 +690,30 @@ pub fn download<Telem: Telemetry>(
     Ok(manifest)
 }
 
+/// The paths and config that the dependencies of the project are resolved
+/// with. The dependencies of every member of a workspace are resolved at
+/// once, with the members returned alongside.
+fn resolution_config(
+    paths: &ProjectPaths,
+) -> Result<(ProjectPaths, PackageConfig, Vec<workspace::Member>)> {
+    match Workspace::for_paths(paths)? {
+        Some(workspace) => {
+            let members = workspace.members()?;
+            let config = workspace::package_config(&members)?;
+            Ok((workspace.paths(), config, members))
+        }
+        None => Ok((
+            paths.clone(),
+            crate::config::read(paths.root_config())?,
+            vec![],
+        )),
+    }
+}
+
 async fn add_missing_packages<Telem: Telemetry>(
     paths: &ProjectPaths,
     fs: Box<ProjectIO>,
+    repositories: HexRepositories,
     manifest: &Manifest,
     local: &LocalPackages,
     project_name: EcoString,
@@ -218,7 +734,15 @@ async fn add_missing_packages<Telem: Telemetry>(
     // If we need to download at-least one package
     if missing_hex_packages.peek().is_some() {
         let http = HttpClient::boxed();
-        let downloader = hex::Downloader::new(fs.clone(), fs, http, Untar::boxed(), paths.clone());
+        let downloader = hex::Downloader::new(
+            fs.clone(),
+            fs,
+            http,
+            Untar::boxed(),
+            repositories,
+            paths.clone(),
+            offline(),
+        );
         let start = Instant::now();
         telemetry.downloading_package("packages");
         downloader
@@ -230,6 +754,24 @@ async fn add_missing_packages<Telem: Telemetry>(
     Ok(())
 }
 
+/// Makes sure each git package in the build directory is at the commit it is
+/// locked to in the manifest, fetching the commit if it is not in the cache.
+fn checkout_git_packages(paths: &ProjectPaths, manifest: &Manifest) -> Result<()> {
+    for package in &manifest.packages {
+        let ManifestPackageSource::Git { repo, commit } = &package.source else {
+            continue;
+        };
+        let destination = paths.build_packages_package(&package.name);
+        if git::checked_out_commit(&destination).as_ref() == Some(commit) {
+            continue;
+        }
+        let repository = git::Repository::new(&package.name, repo, paths.root());
+        repository.ensure_commit(commit)?;
+        repository.checkout(commit, &destination)?;
+    }
+    Ok(())
+}
+
 fn remove_extra_packages<Telem: Telemetry>(
     paths: &ProjectPaths,
     local: &LocalPackages,
@@ -372,6 +914,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -382,6 +925,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -392,6 +936,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
         ],
@@ -416,6 +961,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             &ManifestPackage {
@@ -426,6 +972,7 @@ fn missing_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
         ]
@@ -453,6 +1000,7 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![1, 2, 3, 4, 5]),
+                    repository: None,
                 },
             },
             ManifestPackage {
@@ -463,6 +1011,7 @@ fn extra_local_packages() {
                 requirements: vec![],
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![4, 5]),
+                    repository: None,
                 },
             },
         ],
@@ -482,6 +1031,7 @@ fn get_manifest<Telem: Telemetry>(
     runtime: tokio::runtime::Handle,
     mode: Mode,
     config: &PackageConfig,
+    members: &[workspace::Member],
     telemetry: &Telem,
     use_manifest: UseManifest,
 ) -> Result<(bool, Manifest)> {
@@ -496,28 +1046,56 @@ fn get_manifest<Telem: Telemetry>(
             tracing::debug!("ignoring_manifest");
             true
         }
-        UseManifest::Yes => false,
+        UseManifest::Yes | UseManifest::Unlock(_) => false,
     };
 
     if should_resolve {
-        let manifest = resolve_versions(runtime, mode, paths, config, None, telemetry)?;
+        let manifest = resolve_versions(runtime, mode, paths, config, None, &[], telemetry)?;
         return Ok((true, manifest));
     }
 
     let manifest = read_manifest_from_disc(paths)?;
 
+    // Packages that have been unlocked are resolved anew, with every other
+    // package kept at its locked version.
+    if let UseManifest::Unlock(packages) = &use_manifest {
+        tracing::debug!("unlocking_packages");
+        let manifest = resolve_versions(
+            runtime,
+            mode,
+            paths,
+            config,
+            Some(&manifest),
+            packages,
+            telemetry,
+        )?;
+        return Ok((true, manifest));
+    }
+
     // If the config has unchanged since the manifest was written then it is up
-    // to date so we can return it unmodified.
-    if is_same_requirements(
-        &manifest.requirements,
-        &config.all_dependencies()?,
-        paths.root(),
-    )? {
+    // to date so we can return it unmodified. The members of a workspace are
+    // also checked, as their requirements are not in the workspace's config.
+    let outdated_members = workspace::outdated_packages(members, &manifest)?;
+    if outdated_members.is_none()
+        && is_same_requirements(
+            &manifest.requirements,
+            &config.all_dependencies()?,
+            paths.root(),
+        )?
+    {
         tracing::debug!("manifest_up_to_date");
         Ok((false, manifest))
     } else {
         tracing::debug!("manifest_outdated");
-        let manifest = resolve_versions(runtime, mode, paths, config, Some(&manifest), telemetry)?;
+        let manifest = resolve_versions(
+            runtime,
+            mode,
+            paths,
+            config,
+            Some(&manifest),
+            outdated_members.as_deref().unwrap_or_default(),
+            telemetry,
+        )?;
         Ok((true, manifest))
     }
 }
@@ -563,6 +1141,9 @@ struct ProvidedPackage {
     version: Version,
     source: ProvidedPackageSource,
     requirements: HashMap<EcoString, hexpm::version::Range>,
+    /// The Hex repositories of the requirements that are not fetched from
+    /// hex.pm.
+    repositories: HashMap<EcoString, EcoString>,
 }
 
 #[derive(Clone, Eq, Debug)]
@@ -670,44 +1251,103 @@ fn resolve_versions<Telem: Telemetry>(
     project_paths: &ProjectPaths,
     config: &PackageConfig,
     manifest: Option<&Manifest>,
+    unlocked: &[EcoString],
     telemetry: &Telem,
 ) -> Result<Manifest, Error> {
     telemetry.resolving_package_versions();
     let dependencies = config.dependencies_for(mode)?;
-    let locked = config.locked(manifest)?;
+    let mut locked = config.locked(manifest)?;
+    for name in unlocked {
+        let _ = locked.remove(name);
+    }
+
+    // Git packages that are still locked keep the commit they are locked to
+    let locked_commits = manifest
+        .iter()
+        .flat_map(|manifest| &manifest.packages)
+        .filter(|package| locked.contains_key(&package.name))
+        .filter_map(|package| match &package.source {
+            ManifestPackageSource::Git { commit, .. } => {
+                Some((package.name.clone(), commit.clone()))
+            }
+            ManifestPackageSource::Hex { .. } | ManifestPackageSource::Local { .. } => None,
+        })
+        .collect();
 
     // Packages which are provided directly instead of downloaded from hex
     let mut provided_packages = HashMap::new();
     // The version requires of the current project
     let mut root_requirements = HashMap::new();
+    // The Hex repositories that packages not from hex.pm are fetched from
+    let mut package_repositories = HashMap::new();
 
     // Populate the provided_packages and root_requirements maps
     for (name, requirement) in dependencies.into_iter() {
         let version = match requirement {
-            Requirement::Hex { version } => version,
+            Requirement::Hex {
+                version,
+                repository,
+            } => {
+                insert_repository(&mut package_repositories, &name, repository);
+                version
+            }
             Requirement::Path { path } => provide_local_package(
                 name.clone(),
                 &path,
                 project_paths.root(),
                 project_paths,
                 &mut provided_packages,
+                &locked_commits,
+                &mut vec![],
+            )?,
+            Requirement::Git { git, reference } => provide_git_package(
+                name.clone(),
+                &git,
+                &reference,
+                project_paths,
+                &mut provided_packages,
+                &locked_commits,
                 &mut vec![],
             )?,
-            Requirement::Git { git } => {
-                provide_git_package(name.clone(), &git, project_paths, &mut provided_packages)?
-            }
         };
         let _ = root_requirements.insert(name, version);
     }
 
+    // Every member of a workspace is built using the shared manifest, so the
+    // dev dependencies of the members are resolved as well.
+    if project_paths.workspace_root().is_some() {
+        for name in root_requirements.keys() {
+            provide_dev_dependencies(name, project_paths, &mut provided_packages, &locked_commits)?;
+        }
+    }
+
+    for package in provided_packages.values() {
+        for (name, repository) in &package.repositories {
+            insert_repository(&mut package_repositories, name, Some(repository.clone()));
+        }
+    }
+
     // Convert provided packages into hex packages for pub-grub resolve
     let provided_hex_packages = provided_packages
         .iter()
         .map(|(name, package)| (name.clone(), package.to_hex_package(name)))
         .collect();
 
+    let repositories = hex_repositories(config)?;
+    for (name, repository) in &package_repositories {
+        let _ = repositories.get(name, Some(repository))?;
+    }
+    let fetched = Rc::new(RefCell::new(FetchedPackages {
+        repositories: package_repositories,
+        ..Default::default()
+    }));
     let resolved = dependency::resolve_versions(
-        PackageFetcher::boxed(runtime.clone()),
+        PackageFetcher::boxed(
+            runtime.clone(),
+            repositories.clone(),
+            project_paths.clone(),
+            fetched.clone(),
+        ),
         provided_hex_packages,
         config.name.clone(),
         root_requirements.into_iter(),
@@ -715,11 +1355,19 @@ fn resolve_versions<Telem: Telemetry>(
     )?;
 
     // Convert the hex packages and local packages into manifest packages
-    let manifest_packages = runtime.block_on(future::try_join_all(
-        resolved
-            .into_iter()
-            .map(|(name, version)| lookup_package(name, version, &provided_packages)),
-    ))?;
+    let fetched = fetched.borrow();
+    let manifest_packages = runtime.block_on(future::try_join_all(resolved.into_iter().map(
+        |(name, version)| {
+            lookup_package(
+                name,
+                version,
+                &provided_packages,
+                &repositories,
+                &fetched,
+                offline(),
+            )
+        },
+    )))?;
 
     let manifest = Manifest {
         packages: manifest_packages,
@@ -729,6 +1377,48 @@ fn resolve_versions<Telem: Telemetry>(
     Ok(manifest)
 }
 
+/// Records the Hex repository a package is fetched from. Packages from hex.pm
+/// are not recorded, and the first repository given for a package is kept.
+fn insert_repository(
+    package_repositories: &mut HashMap<EcoString, EcoString>,
+    package: &EcoString,
+    repository: Option<EcoString>,
+) {
+    if let Some(repository) = repository.filter(|name| name != HEXPM_REPOSITORY) {
+        let _ = package_repositories
+            .entry(package.clone())
+            .or_insert(repository);
+    }
+}
+
+#[test]
+fn insert_repository_keeps_first_repository() {
+    let mut repositories = HashMap::new();
+    insert_repository(&mut repositories, &"wibble".into(), Some("acme".into()));
+    insert_repository(&mut repositories, &"wibble".into(), Some("other".into()));
+    insert_repository(&mut repositories, &"wobble".into(), Some("hexpm".into()));
+    insert_repository(&mut repositories, &"wubble".into(), None);
+    assert_eq!(
+        repositories,
+        HashMap::from([("wibble".into(), "acme".into())])
+    );
+}
+
+/// Whether the `GLEAM_OFFLINE` environment variable is set, in which case Hex
+/// packages are only taken from the vendor directory and the package cache.
+fn offline() -> bool {
+    std::env::var("GLEAM_OFFLINE").is_ok_and(|value| matches!(value.as_str(), "1" | "true"))
+}
+
+/// The Hex repositories the project fetches packages from, with the keys used
+/// to authenticate with them read from the environment.
+pub(crate) fn hex_repositories(config: &PackageConfig) -> Result<HexRepositories> {
+    let user_config = UserConfig::read(paths::global_user_config(), &ProjectIO::new())?;
+    Ok(HexRepositories::new(&user_config, config, |variable| {
+        std::env::var(variable).ok()
+    }))
+}
+
 /// Provide a package from a local project
 fn provide_local_package(
     package_name: EcoString,
@@ -736,6 +1426,7 @@ fn provide_local_package(
     parent_path: &Utf8Path,
     project_paths: &ProjectPaths,
     provided: &mut HashMap<EcoString, ProvidedPackage>,
+    locked_commits: &HashMap<EcoString, EcoString>,
     parents: &mut Vec<EcoString>,
 ) -> Result<hexpm::version::Range> {
     let package_path = if package_path.is_absolute() {
@@ -752,22 +1443,48 @@ fn provide_local_package(
         package_source,
         project_paths,
         provided,
+        locked_commits,
         parents,
     )
 }
 
-/// Provide a package from a git repository
+/// Provide a package from a git repository, checking out the commit it is
+/// locked to or, if it is not locked, the commit the reference points to.
 fn provide_git_package(
-    _package_name: EcoString,
-    _repo: &str,
-    _project_paths: &ProjectPaths,
-    _provided: &mut HashMap<EcoString, ProvidedPackage>,
+    package_name: EcoString,
+    repo: &str,
+    reference: &GitReference,
+    project_paths: &ProjectPaths,
+    provided: &mut HashMap<EcoString, ProvidedPackage>,
+    locked_commits: &HashMap<EcoString, EcoString>,
+    parents: &mut Vec<EcoString>,
 ) -> Result<hexpm::version::Range> {
-    let _git = ProvidedPackageSource::Git {
-        repo: "repo".into(),
-        commit: "commit".into(),
+    let repository = git::Repository::new(&package_name, repo, project_paths.root());
+    let commit = match locked_commits.get(&package_name) {
+        Some(commit) => {
+            repository.ensure_commit(commit)?;
+            commit.clone()
+        }
+        None => {
+            repository.fetch(reference)?;
+            repository.resolve(reference)?
+        }
     };
-    Err(Error::GitDependencyUnsupported)
+    let package_path = project_paths.build_packages_package(&package_name);
+    repository.checkout(&commit, &package_path)?;
+    let package_source = ProvidedPackageSource::Git {
+        repo: repo.into(),
+        commit,
+    };
+    provide_package(
+        package_name,
+        package_path,
+        package_source,
+        project_paths,
+        provided,
+        locked_commits,
+        parents,
+    )
 }
 
 /// Adds a gleam project located at a specific path to the list of "provided packages"
@@ -777,6 +1494,7 @@ fn provide_package(
     package_source: ProvidedPackageSource,
     project_paths: &ProjectPaths,
     provided: &mut HashMap<EcoString, ProvidedPackage>,
+    locked_commits: &HashMap<EcoString, EcoString>,
     parents: &mut Vec<EcoString>,
 ) -> Result<hexpm::version::Range> {
     // Return early if a package cycle is detected
@@ -820,10 +1538,17 @@ fn provide_package(
     };
     // Walk the requirements of the package
     let mut requirements = HashMap::new();
+    let mut repositories = HashMap::new();
     parents.push(package_name);
     for (name, requirement) in config.dependencies.into_iter() {
         let version = match requirement {
-            Requirement::Hex { version } => version,
+            Requirement::Hex {
+                version,
+                repository,
+            } => {
+                insert_repository(&mut repositories, &name, repository);
+                version
+            }
             Requirement::Path { path } => {
                 // Recursively walk local packages
                 provide_local_package(
@@ -832,12 +1557,19 @@ fn provide_package(
                     &package_path,
                     project_paths,
                     provided,
+                    locked_commits,
                     parents,
                 )?
             }
-            Requirement::Git { git } => {
-                provide_git_package(name.clone(), &git, project_paths, provided)?
-            }
+            Requirement::Git { git, reference } => provide_git_package(
+                name.clone(),
+                &git,
+                &reference,
+                project_paths,
+                provided,
+                locked_commits,
+                parents,
+            )?,
         };
         let _ = requirements.insert(name, version);
     }
@@ -850,12 +1582,68 @@ fn provide_package(
             version: config.version,
             source: package_source,
             requirements,
+            repositories,
         },
     );
     // Return the version
     Ok(version)
 }
 
+/// Adds the dev dependencies of a local package that has been provided to its
+/// requirements, providing any of them that are not fetched from Hex.
+fn provide_dev_dependencies(
+    package_name: &EcoString,
+    project_paths: &ProjectPaths,
+    provided: &mut HashMap<EcoString, ProvidedPackage>,
+    locked_commits: &HashMap<EcoString, EcoString>,
+) -> Result<()> {
+    let package_path = match provided.get(package_name).map(|package| &package.source) {
+        Some(ProvidedPackageSource::Local { path }) => path.clone(),
+        Some(ProvidedPackageSource::Git { .. }) | None => return Ok(()),
+    };
+    let config = crate::config::read(package_path.join("gleam.toml"))?;
+    let mut requirements = HashMap::new();
+    let mut repositories = HashMap::new();
+    let mut parents = vec![package_name.clone()];
+    for (name, requirement) in config.dev_dependencies.into_iter() {
+        let version = match requirement {
+            Requirement::Hex {
+                version,
+                repository,
+            } => {
+                insert_repository(&mut repositories, &name, repository);
+                version
+            }
+            Requirement::Path { path } => provide_local_package(
+                name.clone(),
+                &path,
+                &package_path,
+                project_paths,
+                provided,
+                locked_commits,
+                &mut parents,
+            )?,
+            Requirement::Git { git, reference } => provide_git_package(
+                name.clone(),
+                &git,
+                &reference,
+                project_paths,
+                provided,
+                locked_commits,
+                &mut parents,
+            )?,
+        };
+        let _ = requirements.insert(name, version);
+    }
+    if let Some(package) = provided.get_mut(package_name) {
+        package.requirements.extend(requirements);
+        for (name, repository) in repositories {
+            insert_repository(&mut package.repositories, &name, Some(repository));
+        }
+    }
+    Ok(())
+}
+
 #[test]
 fn provide_wrong_package() {
     let mut provided = HashMap::new();
@@ -866,6 +1654,7 @@ fn provide_wrong_package() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     if let Err(Error::WrongDependencyProvided {
@@ -890,6 +1679,7 @@ fn provide_existing_package() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     assert_eq!(result, Ok(hexpm::version::Range::new("== 0.1.0".into())));
@@ -900,6 +1690,7 @@ fn provide_existing_package() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     assert_eq!(result, Ok(hexpm::version::Range::new("== 0.1.0".into())));
@@ -915,6 +1706,7 @@ fn provide_conflicting_package() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     assert_eq!(result, Ok(hexpm::version::Range::new("== 0.1.0".into())));
@@ -927,6 +1719,7 @@ fn provide_conflicting_package() {
         },
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     if let Err(Error::ProvidedDependencyConflict { package, .. }) = result {
@@ -946,6 +1739,7 @@ fn provided_is_absolute() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "subpackage".into()],
     );
     assert_eq!(result, Ok(hexpm::version::Range::new("== 0.1.0".into())));
@@ -967,6 +1761,7 @@ fn provided_recursive() {
         Utf8Path::new("./"),
         &project_paths,
         &mut provided,
+        &HashMap::new(),
         &mut vec!["root".into(), "hello_world".into(), "subpackage".into()],
     );
     assert_eq!(
@@ -982,13 +1777,43 @@ async fn lookup_package(
     name: String,
     version: Version,
     provided: &HashMap<EcoString, ProvidedPackage>,
+    repositories: &HexRepositories,
+    fetched: &FetchedPackages,
+    offline: bool,
 ) -> Result<ManifestPackage> {
     match provided.get(name.as_str()) {
         Some(provided_package) => Ok(provided_package.to_manifest_package(name.as_str())),
         None => {
-            let config = hexpm::Config::new();
-            let release =
-                hex::get_package_release(&name, &version, &config, &HttpClient::new()).await?;
+            let repository_name = fetched.repositories.get(name.as_str());
+            let repository = repositories.get(&name, repository_name)?;
+            let key = (EcoString::from(name.as_str()), version.clone());
+            let release = match fetched.offline_releases.get(&key) {
+                Some(release) => release.clone(),
+                None if offline => {
+                    return Err(Error::OfflinePackageUnavailable {
+                        package: name.into(),
+                        version,
+                    })
+                }
+                None => {
+                    hex::get_package_release(
+                        &name,
+                        &version,
+                        repository.auth_key(),
+                        &repository.config,
+                        &HttpClient::new(),
+                    )
+                    .await?
+                }
+            };
+            // The checksum is taken from the signed registry of the
+            // repository where possible, so the tarball is verified against
+            // it when downloaded.
+            let outer_checksum = fetched
+                .checksums
+                .get(&key)
+                .cloned()
+                .unwrap_or(release.outer_checksum);
             let build_tools = release
                 .meta
                 .build_tools
@@ -1007,7 +1832,8 @@ async fn lookup_package(
                 build_tools,
                 requirements,
                 source: ManifestPackageSource::Hex {
-                    outer_checksum: Base16Checksum(release.outer_checksum),
+                    outer_checksum: Base16Checksum(outer_checksum),
+                    repository: repository_name.cloned(),
                 },
             })
         }
@@ -1017,15 +1843,85 @@ async fn lookup_package(
 struct PackageFetcher {
     runtime: tokio::runtime::Handle,
     http: HttpClient,
+    repositories: HexRepositories,
+    paths: ProjectPaths,
+    fetched: Rc<RefCell<FetchedPackages>>,
+    /// When set packages are looked up in the vendor directory and the
+    /// package cache instead of on Hex.
+    offline: bool,
 }
 
 impl PackageFetcher {
-    pub fn boxed(runtime: tokio::runtime::Handle) -> Box<Self> {
+    pub fn boxed(
+        runtime: tokio::runtime::Handle,
+        repositories: HexRepositories,
+        paths: ProjectPaths,
+        fetched: Rc<RefCell<FetchedPackages>>,
+    ) -> Box<Self> {
         Box::new(Self {
             runtime,
             http: HttpClient::new(),
+            repositories,
+            paths,
+            fetched,
+            offline: offline(),
         })
     }
+
+    /// The releases of a package that are in the vendor directory or the
+    /// package cache, recording what the manifest needs to know about them.
+    fn offline_package(
+        &self,
+        package: &str,
+        repository: &HexRepository,
+    ) -> Result<hexpm::Package, Box<dyn std::error::Error>> {
+        let releases =
+            hex::offline_releases(&ProjectIO::new(), &self.paths, &repository.name, package)
+                .map_err(Box::new)?;
+        if releases.is_empty() {
+            return Err(format!(
+                "the package `{package}` is neither in the vendor directory nor in the \
+package cache, and it cannot be looked up on Hex because GLEAM_OFFLINE is set. \
+Run `gleam deps vendor` with network access after changing the dependencies in gleam.toml"
+            )
+            .into());
+        }
+        let mut fetched = self.fetched.borrow_mut();
+        let releases = releases
+            .into_iter()
+            .map(|release| {
+                let key = (package.into(), release.version.clone());
+                let _ = fetched.offline_releases.insert(key, release.clone());
+                hexpm::Release {
+                    version: release.version,
+                    requirements: release.requirements,
+                    retirement_status: None,
+                    outer_checksum: release.outer_checksum,
+                    meta: (),
+                }
+            })
+            .collect();
+        Ok(hexpm::Package {
+            name: package.into(),
+            repository: repository.name.to_string(),
+            releases,
+        })
+    }
+}
+
+/// What the package fetcher learns about the Hex packages it looks up, kept
+/// so the manifest can record where each package was fetched from.
+#[derive(Debug, Default)]
+struct FetchedPackages {
+    /// The Hex repository each package is fetched from. Packages that are
+    /// not in here are fetched from hex.pm.
+    repositories: HashMap<EcoString, EcoString>,
+    /// The checksum of each release in the signed registry of the repository
+    /// it is fetched from.
+    checksums: HashMap<(EcoString, Version), Vec<u8>>,
+    /// The releases found in the vendor directory and the package cache when
+    /// offline, which describe the packages in place of the Hex API.
+    offline_releases: HashMap<(EcoString, Version), hexpm::Release<hexpm::ReleaseMeta>>,
 }
 
 #[derive(Debug)]
@@ -1060,16 +1956,152 @@ impl dependency::PackageFetcher for PackageFetcher {
         package: &str,
     ) -> Result<hexpm::Package, Box<dyn std::error::Error>> {
         tracing::debug!(package = package, "looking_up_hex_package");
-        let config = hexpm::Config::new();
-        let request = hexpm::get_package_request(package, None, &config);
-        let response = self
-            .runtime
-            .block_on(self.http.send(request))
+        let repository_name = self.fetched.borrow().repositories.get(package).cloned();
+        let repository = self
+            .repositories
+            .get(package, repository_name.as_ref())
             .map_err(Box::new)?;
-        hexpm::get_package_response(response, HEXPM_PUBLIC_KEY).map_err(|e| e.into())
+        let hex_package = if self.offline {
+            self.offline_package(package, repository)?
+        } else {
+            let request =
+                hexpm::get_package_request(package, repository.auth_key(), &repository.config);
+            let response = self
+                .runtime
+                .block_on(self.http.send(request))
+                .map_err(Box::new)?;
+            hexpm::get_package_response(response, &repository.public_key)?
+        };
+
+        // Dependencies without a repository of their own are in the same
+        // repository as the package that depends on them.
+        let mut fetched = self.fetched.borrow_mut();
+        for release in &hex_package.releases {
+            let _ = fetched.checksums.insert(
+                (package.into(), release.version.clone()),
+                release.outer_checksum.clone(),
+            );
+            for (name, dependency) in &release.requirements {
+                let repository = match &dependency.repository {
+                    Some(repository) => Some(repository.into()),
+                    None => repository_name.clone(),
+                };
+                insert_repository(&mut fetched.repositories, &name.into(), repository);
+            }
+        }
+        Ok(hex_package)
     }
 }
 
+/// A Hex package tarball with just the `metadata.config` file, which is all
+/// that is read of it when resolving versions offline.
+#[cfg(test)]
+fn metadata_tarball(name: &str, version: &str) -> Vec<u8> {
+    let metadata = format!(
+        r#"{{<<"name">>, <<"{name}">>}}.
+{{<<"app">>, <<"{name}">>}}.
+{{<<"version">>, <<"{version}">>}}.
+{{<<"build_tools">>, [<<"gleam">>]}}.
+{{<<"requirements">>, []}}.
+"#
+    );
+    let mut builder = tar::Builder::new(vec![]);
+    let mut header = tar::Header::new_gnu();
+    header.set_size(metadata.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "metadata.config", metadata.as_bytes())
+        .expect("append metadata");
+    builder.into_inner().expect("tarball")
+}
+
+#[test]
+fn offline_resolution_uses_vendored_packages() {
+    use sha2::Digest;
+
+    let runtime = tokio::runtime::Runtime::new().expect("runtime");
+    let directory = tempfile::tempdir().expect("temporary directory");
+    let root = Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).expect("utf8 path");
+    let paths = ProjectPaths::new(root);
+    // A name that is not going to be in the package cache of whoever runs
+    // the tests.
+    let name = "gleam_offline_test_wibble";
+    let tarball = metadata_tarball(name, "1.0.0");
+    fs::write_bytes(
+        &paths.vendor_package_tarball(HEXPM_REPOSITORY, name, "1.0.0"),
+        &tarball,
+    )
+    .expect("write tarball");
+
+    let fetched = Rc::new(RefCell::new(FetchedPackages::default()));
+    let fetcher = PackageFetcher {
+        runtime: runtime.handle().clone(),
+        http: HttpClient::new(),
+        repositories: HexRepositories::default(),
+        paths,
+        fetched: fetched.clone(),
+        offline: true,
+    };
+    let package = dependency::PackageFetcher::get_dependencies(&fetcher, name).expect("package");
+    let versions = package
+        .releases
+        .iter()
+        .map(|release| release.version.clone())
+        .collect_vec();
+    assert_eq!(versions, vec![Version::new(1, 0, 0)]);
+
+    let manifest_package = runtime
+        .block_on(lookup_package(
+            name.into(),
+            Version::new(1, 0, 0),
+            &HashMap::new(),
+            &HexRepositories::default(),
+            &fetched.borrow(),
+            true,
+        ))
+        .expect("manifest package");
+    assert_eq!(
+        manifest_package,
+        ManifestPackage {
+            name: name.into(),
+            version: Version::new(1, 0, 0),
+            otp_app: Some(name.into()),
+            build_tools: vec!["gleam".into()],
+            requirements: vec![],
+            source: ManifestPackageSource::Hex {
+                outer_checksum: Base16Checksum(sha2::Sha256::digest(&tarball).to_vec()),
+                repository: None,
+            },
+        }
+    );
+
+    let missing =
+        dependency::PackageFetcher::get_dependencies(&fetcher, "gleam_offline_test_wobble")
+            .expect_err("missing package");
+    assert!(missing.to_string().contains("GLEAM_OFFLINE is set"));
+}
+
+#[test]
+fn offline_lookup_of_unavailable_release() {
+    let runtime = tokio::runtime::Runtime::new().expect("runtime");
+    let result = runtime.block_on(lookup_package(
+        "wibble".into(),
+        Version::new(1, 0, 0),
+        &HashMap::new(),
+        &HexRepositories::default(),
+        &FetchedPackages::default(),
+        true,
+    ));
+    assert_eq!(
+        result,
+        Err(Error::OfflinePackageUnavailable {
+            package: "wibble".into(),
+            version: Version::new(1, 0, 0),
+        })
+    );
+}
+
 #[test]
 fn provided_local_to_hex() {
     let provided_package = ProvidedPackage {
@@ -1088,6 +2120,7 @@ fn provided_local_to_hex() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let hex_package = hexpm::Package {
@@ -1147,6 +2180,7 @@ fn provided_git_to_hex() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let hex_package = hexpm::Package {
@@ -1205,6 +2239,7 @@ fn provided_local_to_manifest() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let manifest_package = ManifestPackage {
@@ -1243,6 +2278,7 @@ fn provided_git_to_manifest() {
             ),
         ]
         .into(),
+        repositories: HashMap::new(),
     };
 
     let manifest_package = ManifestPackage {
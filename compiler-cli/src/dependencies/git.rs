@@ -0,0 +1,448 @@
+use std::process::{Command, Output};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use gleam_core::{paths, requirement::GitReference, Error, Result};
+use sha2::Digest;
+
+use crate::fs;
+
+/// The git repository a dependency is taken from. Its commits are fetched into
+/// a bare repository in the global cache, from which the locked commit is
+/// checked out into the build directory.
+#[derive(Debug)]
+pub struct Repository<'a> {
+    package: &'a str,
+    url: &'a str,
+    /// Where git fetches the repository from. This is the URL, unless the URL
+    /// is a path relative to the project root.
+    location: String,
+    cache: Utf8PathBuf,
+}
+
+impl<'a> Repository<'a> {
+    pub fn new(package: &'a str, url: &'a str, project_root: &Utf8Path) -> Self {
+        let location = if is_relative_path(url) {
+            project_root.join(url).to_string()
+        } else {
+            url.to_string()
+        };
+        let cache = paths::global_git_repository_cache(&cache_directory_name(&location));
+        Self {
+            package,
+            url,
+            location,
+            cache,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_cache(package: &'a str, url: &'a str, cache: Utf8PathBuf) -> Self {
+        Self {
+            package,
+            url,
+            location: url.to_string(),
+            cache,
+        }
+    }
+
+    /// Fetches the branches and tags of the repository into the cache, along
+    /// with the given reference if it is not one of those.
+    pub fn fetch(&self, reference: &GitReference) -> Result<()> {
+        let cache = self.cache.as_str();
+        if !self.cache.join("HEAD").exists() {
+            if let Some(parent) = self.cache.parent() {
+                fs::mkdir(parent)?;
+            }
+            tracing::debug!(repo = self.url, "cloning_git_repository");
+            let _ = self.git(&["clone", "--bare", "--quiet", "--", &self.location, cache])?;
+        } else {
+            tracing::debug!(repo = self.url, "fetching_git_repository");
+            let _ = self.git(&[
+                "--git-dir",
+                cache,
+                "fetch",
+                "--quiet",
+                "--force",
+                "--",
+                &self.location,
+                "+refs/heads/*:refs/heads/*",
+                "+refs/tags/*:refs/tags/*",
+            ])?;
+        }
+
+        if let GitReference::Ref(ref_) = reference {
+            let refspec = format!("+{ref_}:{ref_}");
+            let _ = self.git(&[
+                "--git-dir",
+                cache,
+                "fetch",
+                "--quiet",
+                "--force",
+                "--",
+                &self.location,
+                &refspec,
+            ])?;
+        }
+        Ok(())
+    }
+
+    /// The commit a reference points to in the cache.
+    pub fn resolve(&self, reference: &GitReference) -> Result<EcoString> {
+        let revision = match reference {
+            GitReference::Head => "HEAD".into(),
+            GitReference::Branch(branch) => format!("refs/heads/{branch}"),
+            GitReference::Tag(tag) => format!("refs/tags/{tag}"),
+            GitReference::Rev(revision) | GitReference::Ref(revision) => revision.to_string(),
+        };
+        self.commit(&revision)?
+            .ok_or_else(|| Error::GitReferenceNotFound {
+                package: self.package.into(),
+                repo: self.url.into(),
+                reference: reference.clone(),
+            })
+    }
+
+    /// Makes sure the cache has a locked commit, fetching the repository if
+    /// it does not.
+    pub fn ensure_commit(&self, commit: &str) -> Result<()> {
+        if self.cache.join("HEAD").exists() && self.commit(commit)?.is_some() {
+            return Ok(());
+        }
+        self.fetch(&GitReference::Head)?;
+        match self.commit(commit)? {
+            Some(_) => Ok(()),
+            None => Err(Error::GitLockedCommitNotFound {
+                package: self.package.into(),
+                repo: self.url.into(),
+                commit: commit.into(),
+            }),
+        }
+    }
+
+    /// Replaces the destination directory with a checkout of the commit.
+    pub fn checkout(&self, commit: &str, destination: &Utf8Path) -> Result<()> {
+        if checked_out_commit(destination).as_deref() == Some(commit) {
+            return Ok(());
+        }
+        tracing::debug!(repo = self.url, commit = commit, "checking_out_git_commit");
+        // The commit comes from the manifest, so it is resolved in the cache
+        // first to make sure it is a commit and not something git would
+        // take as an option.
+        let resolved = self
+            .commit(commit)?
+            .ok_or_else(|| Error::GitLockedCommitNotFound {
+                package: self.package.into(),
+                repo: self.url.into(),
+                commit: commit.into(),
+            })?;
+        fs::delete_directory(destination)?;
+        let _ = self.git(&[
+            "clone",
+            "--quiet",
+            "--no-checkout",
+            "--",
+            self.cache.as_str(),
+            destination.as_str(),
+        ])?;
+        let _ = self.git(&[
+            "-C",
+            destination.as_str(),
+            "checkout",
+            "--quiet",
+            "--detach",
+            &resolved,
+            "--",
+        ])?;
+        Ok(())
+    }
+
+    fn commit(&self, revision: &str) -> Result<Option<EcoString>> {
+        let revision = format!("{revision}^{{commit}}");
+        let output = run(&[
+            "--git-dir",
+            self.cache.as_str(),
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            // `--` would make rev-parse take the revision as a path.
+            "--end-of-options",
+            &revision,
+        ])?;
+        Ok(output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().into()))
+    }
+
+    fn git(&self, arguments: &[&str]) -> Result<Output> {
+        let output = run(arguments)?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            Err(Error::GitCommandFailed {
+                package: self.package.into(),
+                repo: self.url.into(),
+                output: String::from_utf8_lossy(&output.stderr).into(),
+            })
+        }
+    }
+}
+
+/// The commit checked out in a directory, if it is a git repository.
+pub fn checked_out_commit(directory: &Utf8Path) -> Option<EcoString> {
+    // Without this check git would look for a repository in the directories
+    // above, which could be the project's own repository.
+    if !directory.join(".git").exists() {
+        return None;
+    }
+    let output = run(&["-C", directory.as_str(), "rev-parse", "HEAD"]).ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().into())
+}
+
+fn run(arguments: &[&str]) -> Result<Output> {
+    tracing::trace!(arguments = ?arguments, "running_git");
+    Command::new("git")
+        .args(arguments)
+        .output()
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+                program: "git".into(),
+            },
+            kind => Error::ShellCommand {
+                program: "git".into(),
+                err: Some(kind),
+            },
+        })
+}
+
+/// Whether a repository URL is a path on this machine relative to the project
+/// root, rather than a URL or a `host:path` location.
+fn is_relative_path(url: &str) -> bool {
+    !url.contains("://") && !url.contains(':') && Utf8Path::new(url).is_relative()
+}
+
+/// Repositories are cached in a directory named after the repository, with a
+/// hash of its location so that repositories of the same name do not clash.
+fn cache_directory_name(location: &str) -> String {
+    let name: String = location
+        .trim_end_matches('/')
+        .rsplit(['/', '\\', ':'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(".git")
+        .chars()
+        .map(|char| match char {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => char,
+            _ => '_',
+        })
+        .collect();
+    let hash = sha2::Sha256::digest(location.as_bytes());
+    let hash = base16::encode_lower(&hash);
+    format!("{name}-{}", hash.get(..16).unwrap_or(&hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a repository with two commits on `main`, the first of which is
+    /// tagged `v1.0.0`, and a branch `next` with a third commit. Returns the
+    /// path of the repository and its commits, oldest first.
+    fn make_repository(directory: &Utf8Path) -> (Utf8PathBuf, Vec<String>) {
+        let repository = directory.join("wibble");
+        let git = |arguments: &[&str]| {
+            let output = Command::new("git")
+                .args(["-C", repository.as_str()])
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(arguments)
+                .output()
+                .expect("git");
+            assert!(output.status.success(), "{output:?}");
+            String::from_utf8(output.stdout)
+                .expect("utf8")
+                .trim()
+                .to_string()
+        };
+        let commit = |version: &str| {
+            std::fs::write(
+                repository.join("gleam.toml"),
+                format!("name = \"wibble\"\nversion = \"{version}\"\n"),
+            )
+            .expect("write gleam.toml");
+            let _ = git(&["add", "gleam.toml"]);
+            let _ = git(&["commit", "--quiet", "-m", version]);
+            git(&["rev-parse", "HEAD"])
+        };
+
+        std::fs::create_dir_all(&repository).expect("create repository");
+        let _ = git(&["init", "--quiet", "--initial-branch", "main"]);
+        let first = commit("1.0.0");
+        let _ = git(&["tag", "v1.0.0"]);
+        let second = commit("1.1.0");
+        let _ = git(&["checkout", "--quiet", "-b", "next"]);
+        let third = commit("2.0.0");
+        let _ = git(&["checkout", "--quiet", "main"]);
+        (repository, vec![first, second, third])
+    }
+
+    fn temporary_directory() -> (tempfile::TempDir, Utf8PathBuf) {
+        let directory = tempfile::tempdir().expect("temporary directory");
+        let path = Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).expect("utf8 path");
+        (directory, path)
+    }
+
+    #[test]
+    fn resolve_references() {
+        let (_directory, path) = temporary_directory();
+        let (url, commits) = make_repository(&path);
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+
+        let resolve = |reference: GitReference| {
+            repository.fetch(&reference).expect("fetch");
+            repository.resolve(&reference).expect("resolve").to_string()
+        };
+        assert_eq!(Some(&resolve(GitReference::Head)), commits.get(1));
+        assert_eq!(
+            Some(&resolve(GitReference::Tag("v1.0.0".into()))),
+            commits.first()
+        );
+        assert_eq!(
+            Some(&resolve(GitReference::Branch("next".into()))),
+            commits.get(2)
+        );
+        let first = commits.first().expect("first commit");
+        assert_eq!(&resolve(GitReference::Rev(first.into())), first);
+        assert_eq!(
+            Some(&resolve(GitReference::Ref("refs/heads/next".into()))),
+            commits.get(2)
+        );
+    }
+
+    #[test]
+    fn resolve_missing_reference() {
+        let (_directory, path) = temporary_directory();
+        let (url, _) = make_repository(&path);
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+        let reference = GitReference::Tag("v9.9.9".into());
+        repository.fetch(&reference).expect("fetch");
+        assert_eq!(
+            repository.resolve(&reference),
+            Err(Error::GitReferenceNotFound {
+                package: "wibble".into(),
+                repo: url.as_str().into(),
+                reference,
+            })
+        );
+    }
+
+    #[test]
+    fn fetch_missing_repository() {
+        let (_directory, path) = temporary_directory();
+        let url = path.join("missing");
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+        assert!(matches!(
+            repository.fetch(&GitReference::Head),
+            Err(Error::GitCommandFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn fetch_option_like_location() {
+        let (_directory, path) = temporary_directory();
+        let marker = path.join("wobble");
+        let url = format!("--upload-pack=touch {marker}");
+        let repository = Repository::with_cache("wibble", &url, path.join("cache"));
+        assert!(matches!(
+            repository.fetch(&GitReference::Head),
+            Err(Error::GitCommandFailed { .. })
+        ));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn checkout_option_like_commit() {
+        let (_directory, path) = temporary_directory();
+        let (url, commits) = make_repository(&path);
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+        let destination = path.join("build/packages/wibble");
+        let first = commits.first().expect("first commit");
+        repository.ensure_commit(first).expect("ensure commit");
+
+        let commit = "--orphan=wobble";
+        assert_eq!(
+            repository.checkout(commit, &destination),
+            Err(Error::GitLockedCommitNotFound {
+                package: "wibble".into(),
+                repo: url.as_str().into(),
+                commit: commit.into(),
+            })
+        );
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn checkout_commits() {
+        let (_directory, path) = temporary_directory();
+        let (url, commits) = make_repository(&path);
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+        let destination = path.join("build/packages/wibble");
+
+        for commit in &commits {
+            repository.ensure_commit(commit).expect("ensure commit");
+            repository.checkout(commit, &destination).expect("checkout");
+            assert_eq!(
+                checked_out_commit(&destination).as_deref(),
+                Some(commit.as_str())
+            );
+        }
+        let config = std::fs::read_to_string(destination.join("gleam.toml")).expect("read");
+        assert!(config.contains("version = \"2.0.0\""));
+    }
+
+    #[test]
+    fn ensure_missing_commit() {
+        let (_directory, path) = temporary_directory();
+        let (url, _) = make_repository(&path);
+        let repository = Repository::with_cache("wibble", url.as_str(), path.join("cache"));
+        let commit = "0123456789012345678901234567890123456789";
+        assert_eq!(
+            repository.ensure_commit(commit),
+            Err(Error::GitLockedCommitNotFound {
+                package: "wibble".into(),
+                repo: url.as_str().into(),
+                commit: commit.into(),
+            })
+        );
+    }
+
+    #[test]
+    fn checked_out_commit_outside_repository() {
+        let (_directory, path) = temporary_directory();
+        assert_eq!(checked_out_commit(&path), None);
+    }
+
+    #[test]
+    fn cache_directory_names() {
+        let name = cache_directory_name("https://example.com/gleam/wibble.git");
+        assert!(name.starts_with("wibble-"), "{name}");
+        assert_eq!(name.len(), "wibble-".len() + 16);
+        assert_ne!(
+            name,
+            cache_directory_name("https://example.com/other/wibble.git")
+        );
+        assert!(cache_directory_name("git@example.com:wibble.git").starts_with("wibble-"));
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert!(is_relative_path("../wibble"));
+        assert!(is_relative_path("wibble.git"));
+        assert!(!is_relative_path("/srv/git/wibble.git"));
+        assert!(!is_relative_path("https://example.com/wibble.git"));
+        assert!(!is_relative_path("git@example.com:wibble.git"));
+    }
+}
@@ -0,0 +1,165 @@
+use camino::Utf8Path;
+use ecow::EcoString;
+use gleam_core::{erlang::term, Result};
+
+use crate::fs;
+
+/// The licences given in the metadata of a downloaded package. Gleam packages
+/// give them in `gleam.toml`, Erlang packages in the `.app.src` file of the
+/// application, and Elixir packages in `mix.exs`. If none of these can be
+/// read the licences are unknown and none are returned.
+pub fn read(package_directory: &Utf8Path) -> Result<Vec<EcoString>> {
+    let gleam_toml = package_directory.join("gleam.toml");
+    if gleam_toml.is_file() {
+        return Ok(from_gleam_toml(&fs::read(gleam_toml)?));
+    }
+
+    let src = package_directory.join("src");
+    if src.is_dir() {
+        for entry in fs::read_dir(&src)?.filter_map(Result::ok) {
+            let path = entry.into_path();
+            if path.as_str().ends_with(".app.src") {
+                return Ok(from_app_src(&fs::read(path)?));
+            }
+        }
+    }
+
+    let mix_exs = package_directory.join("mix.exs");
+    if mix_exs.is_file() {
+        return Ok(from_list_after(&fs::read(mix_exs)?, "licenses:"));
+    }
+
+    Ok(vec![])
+}
+
+/// The licences are read without validating them as the config is, as the
+/// SPDX licence list changes over time and an old package may use an
+/// identifier that is no longer valid.
+fn from_gleam_toml(toml: &str) -> Vec<EcoString> {
+    let Ok(config) = toml.parse::<toml::Value>() else {
+        return vec![];
+    };
+    config
+        .get("licences")
+        .or_else(|| config.get("licenses"))
+        .and_then(|licences| licences.as_array())
+        .map(|licences| {
+            licences
+                .iter()
+                .filter_map(|licence| licence.as_str())
+                .map(EcoString::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The licences in the `licenses` property of the application in an
+/// `.app.src` file, such as `{licenses, ["Apache 2.0"]}`.
+fn from_app_src(source: &str) -> Vec<EcoString> {
+    let Ok(terms) = term::consult(source) else {
+        return vec![];
+    };
+    let licences = terms.iter().find_map(|term| match term {
+        term::Term::Tuple(application) => match application.as_slice() {
+            [term::Term::Atom(keyword), _, properties] if keyword == "application" => {
+                properties.get("licenses")?.as_list()
+            }
+            _ => None,
+        },
+        _ => None,
+    });
+    licences
+        .unwrap_or_default()
+        .iter()
+        .filter_map(term::Term::as_str)
+        .map(EcoString::from)
+        .collect()
+}
+
+/// The strings in the list that follows `key`, such as the list of licences
+/// in `licenses: ["MIT"]`.
+fn from_list_after(source: &str, key: &str) -> Vec<EcoString> {
+    let Some((_, rest)) = source.split_once(key) else {
+        return vec![];
+    };
+    let Some(list) = rest
+        .trim_start()
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(list, _)| list)
+    else {
+        return vec![];
+    };
+    list.split('"')
+        .skip(1)
+        .step_by(2)
+        .map(EcoString::from)
+        .collect()
+}
+
+#[test]
+fn licences_from_gleam_toml() {
+    let toml = r#"
+name = "wibble"
+licences = ["Apache-2.0", "MIT"]
+"#;
+    assert_eq!(from_gleam_toml(toml), vec!["Apache-2.0", "MIT"]);
+    assert_eq!(
+        from_gleam_toml(r#"licenses = ["Not a real licence"]"#),
+        vec!["Not a real licence"]
+    );
+    assert!(from_gleam_toml(r#"name = "wibble""#).is_empty());
+}
+
+#[test]
+fn licences_from_app_src() {
+    let app_src = r#"
+{application, thoas,
+ [{description, "A blazing fast JSON parser and generator"},
+  {vsn, "1.0.0"},
+  {applications, [kernel, stdlib]},
+  {licenses, ["Apache 2.0", "MIT"]},
+  {links, [{"GitHub", "https://github.com/lpil/thoas"}]}
+ ]}.
+"#;
+    assert_eq!(from_app_src(app_src), vec!["Apache 2.0", "MIT"]);
+}
+
+#[test]
+fn licences_from_app_src_with_whitespace_and_comments() {
+    let app_src = r#"
+%% {licenses, ["Commented out"]},
+{application, 'my_app', [
+    {vsn, "1.0.0"},
+    { licenses ,
+        [ <<"MIT">> % The licence
+        ]
+    }
+]}.
+"#;
+    assert_eq!(from_app_src(app_src), vec!["MIT"]);
+}
+
+#[test]
+fn licences_from_app_src_without_licences() {
+    assert!(from_app_src(r#"{application, my_app, [{vsn, "1.0.0"}]}."#).is_empty());
+    assert!(from_app_src("{application, my_app, [").is_empty());
+}
+
+#[test]
+fn licences_from_mix_exs() {
+    let mix_exs = r#"
+  defp package do
+    [
+      licenses:
+        ["Apache-2.0", "MIT"],
+      links: %{"GitHub" => @source_url}
+    ]
+  end
+"#;
+    assert_eq!(
+        from_list_after(mix_exs, "licenses:"),
+        vec!["Apache-2.0", "MIT"]
+    );
+    assert!(from_list_after("licenses: @licenses", "licenses:").is_empty());
+}
@@ -0,0 +1,390 @@
+use std::collections::{HashMap, HashSet};
+
+use ecow::EcoString;
+use gleam_core::{
+    config::{Dependencies, PackageConfig},
+    manifest::{Manifest, ManifestPackage},
+    requirement::Requirement,
+    Error, Result,
+};
+use hexpm::version::Version;
+use itertools::Itertools;
+
+/// The dependency graph of a project as resolved in its manifest, used by the
+/// `gleam deps tree` and `gleam deps why` commands.
+#[derive(Debug)]
+pub struct DependencyGraph {
+    root: EcoString,
+    packages: HashMap<EcoString, Package>,
+    /// The packages that are only reachable through the dev dependencies of
+    /// the root package.
+    dev_only: HashSet<EcoString>,
+}
+
+#[derive(Debug)]
+struct Package {
+    version: Version,
+    dependencies: Vec<Edge>,
+}
+
+#[derive(Debug)]
+struct Edge {
+    package: EcoString,
+    /// The version constraint the dependent package puts on the dependency.
+    /// The manifest only records the requirements of the root package, so
+    /// this is `None` when the config of the dependent package could not be
+    /// read.
+    requirement: Option<Requirement>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph of the packages in the manifest. `requirements` gives
+    /// the requirements declared in the config of a package, which are used
+    /// to label the edges of the graph.
+    pub fn new(
+        config: &PackageConfig,
+        manifest: &Manifest,
+        requirements: impl Fn(&ManifestPackage) -> Dependencies,
+    ) -> Self {
+        let edges = |dependencies: &Dependencies| {
+            dependencies
+                .iter()
+                .map(|(name, requirement)| Edge {
+                    package: name.clone(),
+                    requirement: Some(requirement.clone()),
+                })
+                .sorted_by(|a, b| a.package.cmp(&b.package))
+                .collect_vec()
+        };
+
+        let mut packages = HashMap::new();
+        for package in &manifest.packages {
+            let mut declared = requirements(package);
+            let dependencies = package
+                .requirements
+                .iter()
+                .sorted()
+                .map(|name| Edge {
+                    package: name.clone(),
+                    requirement: declared.remove(name),
+                })
+                .collect();
+            let _ = packages.insert(
+                package.name.clone(),
+                Package {
+                    version: package.version.clone(),
+                    dependencies,
+                },
+            );
+        }
+
+        // Everything that cannot be reached from the regular dependencies of
+        // the root package is only needed for development.
+        let mut reachable = HashSet::new();
+        let mut stack = config.dependencies.keys().cloned().collect_vec();
+        while let Some(name) = stack.pop() {
+            if !reachable.insert(name.clone()) {
+                continue;
+            }
+            if let Some(package) = packages.get(&name) {
+                stack.extend(package.dependencies.iter().map(|edge| edge.package.clone()));
+            }
+        }
+        let dev_only = packages
+            .keys()
+            .filter(|name| !reachable.contains(*name))
+            .cloned()
+            .collect();
+
+        let mut dependencies = edges(&config.dependencies);
+        dependencies.extend(edges(&config.dev_dependencies));
+        let _ = packages.insert(
+            config.name.clone(),
+            Package {
+                version: config.version.clone(),
+                dependencies,
+            },
+        );
+
+        Self {
+            root: config.name.clone(),
+            packages,
+            dev_only,
+        }
+    }
+
+    /// Renders the graph as a tree rooted at the root package. A package that
+    /// has already been shown with its dependencies is marked with `(*)`
+    /// rather than being expanded again.
+    pub fn tree(&self) -> String {
+        let mut output = self.label(&self.root, None, true);
+        output.push('\n');
+        let mut expanded = HashSet::from([self.root.clone()]);
+        self.write_dependencies(&self.root, "", &mut expanded, &mut output);
+        output
+    }
+
+    fn write_dependencies(
+        &self,
+        name: &EcoString,
+        prefix: &str,
+        expanded: &mut HashSet<EcoString>,
+        output: &mut String,
+    ) {
+        let Some(package) = self.packages.get(name) else {
+            return;
+        };
+        let count = package.dependencies.len();
+        for (index, edge) in package.dependencies.iter().enumerate() {
+            let last = index + 1 == count;
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            output.push_str(prefix);
+            output.push_str(branch);
+            output.push_str(&self.label(&edge.package, edge.requirement.as_ref(), true));
+
+            let has_dependencies = self
+                .packages
+                .get(&edge.package)
+                .is_some_and(|package| !package.dependencies.is_empty());
+            if has_dependencies && !expanded.insert(edge.package.clone()) {
+                output.push_str(" (*)\n");
+                continue;
+            }
+            output.push('\n');
+            let prefix = format!("{prefix}{indent}");
+            self.write_dependencies(&edge.package, &prefix, expanded, output);
+        }
+    }
+
+    /// Renders every path from the root package to the given package, one per
+    /// line.
+    pub fn why(&self, name: &str) -> Result<String> {
+        let Some((name, _)) = self.packages.get_key_value(name) else {
+            return Err(Error::UnknownDependency { name: name.into() });
+        };
+
+        let mut paths = vec![];
+        let mut path = vec![(self.root.clone(), self.label(&self.root, None, false))];
+        self.find_paths(&self.root, name, &mut path, &mut paths);
+
+        let mut output = self.label(name, None, true);
+        output.push('\n');
+        for path in paths {
+            output.push_str("  ");
+            output.push_str(&path);
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    fn find_paths(
+        &self,
+        from: &EcoString,
+        to: &EcoString,
+        path: &mut Vec<(EcoString, String)>,
+        paths: &mut Vec<String>,
+    ) {
+        if from == to {
+            paths.push(path.iter().map(|(_, label)| label).join(" -> "));
+            return;
+        }
+        let Some(package) = self.packages.get(from) else {
+            return;
+        };
+        for edge in &package.dependencies {
+            // The manifest should never contain a cycle, but if it does the
+            // path around it is not followed a second time.
+            if path.iter().any(|(name, _)| name == &edge.package) {
+                continue;
+            }
+            let label = self.label(&edge.package, edge.requirement.as_ref(), false);
+            path.push((edge.package.clone(), label));
+            self.find_paths(&edge.package, to, path, paths);
+            let _ = path.pop();
+        }
+    }
+
+    fn label(&self, name: &EcoString, requirement: Option<&Requirement>, mark_dev: bool) -> String {
+        let mut label = match self.packages.get(name) {
+            Some(package) => format!("{name} {}", package.version),
+            None => name.to_string(),
+        };
+        match requirement {
+            Some(Requirement::Hex {
+                version,
+                repository: None,
+            }) => label.push_str(&format!(" ({version})")),
+            Some(Requirement::Hex {
+                version,
+                repository: Some(repository),
+            }) => label.push_str(&format!(" ({version}, repo: {repository})")),
+            Some(Requirement::Path { path }) => label.push_str(&format!(" (path: {path})")),
+            Some(Requirement::Git { git, reference }) => match reference.key_and_value() {
+                None => label.push_str(&format!(" (git: {git})")),
+                Some((key, value)) => label.push_str(&format!(" (git: {git}, {key}: {value})")),
+            },
+            None => (),
+        }
+        if mark_dev && self.dev_only.contains(name) {
+            label.push_str(" [dev]");
+        }
+        label
+    }
+}
+
+#[cfg(test)]
+fn package(name: &str, requirements: &[&str]) -> ManifestPackage {
+    use gleam_core::manifest::{Base16Checksum, ManifestPackageSource};
+
+    ManifestPackage {
+        name: name.into(),
+        version: Version::new(1, 0, 0),
+        build_tools: ["gleam".into()].into(),
+        otp_app: None,
+        requirements: requirements.iter().map(|name| (*name).into()).collect(),
+        source: ManifestPackageSource::Hex {
+            outer_checksum: Base16Checksum(vec![1, 2, 3, 4]),
+            repository: None,
+        },
+    }
+}
+
+#[cfg(test)]
+fn test_graph() -> DependencyGraph {
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            package("gleam_stdlib", &[]),
+            package("gleam_json", &["gleam_stdlib", "thoas"]),
+            package("thoas", &[]),
+            package("gleeunit", &["gleam_stdlib"]),
+            package("birdie", &["gleam_stdlib", "simplifile"]),
+            package("simplifile", &[]),
+        ],
+    };
+    let config = PackageConfig {
+        name: "app".into(),
+        version: Version::new(0, 1, 0),
+        dependencies: [
+            (
+                "gleam_stdlib".into(),
+                Requirement::hex(">= 0.34.0 and < 2.0.0"),
+            ),
+            (
+                "gleam_json".into(),
+                Requirement::hex(">= 1.0.0 and < 2.0.0"),
+            ),
+        ]
+        .into(),
+        dev_dependencies: [
+            ("gleeunit".into(), Requirement::hex(">= 1.0.0 and < 2.0.0")),
+            ("birdie".into(), Requirement::path("../birdie")),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    DependencyGraph::new(&config, &manifest, |package| match package.name.as_str() {
+        "gleam_json" => [
+            ("gleam_stdlib".into(), Requirement::hex(">= 0.32.0")),
+            ("thoas".into(), Requirement::hex(">= 1.0.0")),
+        ]
+        .into(),
+        "gleeunit" => [("gleam_stdlib".into(), Requirement::hex(">= 0.33.0"))].into(),
+        // The config of birdie could not be read, so its requirements are not
+        // known.
+        _ => HashMap::new(),
+    })
+}
+
+#[test]
+fn tree_format() {
+    assert_eq!(
+        test_graph().tree(),
+        "app 0.1.0
+├── gleam_json 1.0.0 (>= 1.0.0 and < 2.0.0)
+│   ├── gleam_stdlib 1.0.0 (>= 0.32.0)
+│   └── thoas 1.0.0 (>= 1.0.0)
+├── gleam_stdlib 1.0.0 (>= 0.34.0 and < 2.0.0)
+├── birdie 1.0.0 (path: ../birdie) [dev]
+│   ├── gleam_stdlib 1.0.0
+│   └── simplifile 1.0.0 [dev]
+└── gleeunit 1.0.0 (>= 1.0.0 and < 2.0.0) [dev]
+    └── gleam_stdlib 1.0.0 (>= 0.33.0)
+"
+    );
+}
+
+#[test]
+fn tree_marks_repeated_dependencies() {
+    let manifest = Manifest {
+        requirements: HashMap::new(),
+        packages: vec![
+            package("a", &["c"]),
+            package("b", &["c"]),
+            package("c", &["d"]),
+            package("d", &[]),
+        ],
+    };
+    let config = PackageConfig {
+        name: "app".into(),
+        version: Version::new(0, 1, 0),
+        dependencies: [
+            ("a".into(), Requirement::hex("~> 1.0")),
+            ("b".into(), Requirement::hex("~> 1.0")),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let graph = DependencyGraph::new(&config, &manifest, |_| HashMap::new());
+    assert_eq!(
+        graph.tree(),
+        "app 0.1.0
+├── a 1.0.0 (~> 1.0)
+│   └── c 1.0.0
+│       └── d 1.0.0
+└── b 1.0.0 (~> 1.0)
+    └── c 1.0.0 (*)
+"
+    );
+}
+
+#[test]
+fn why_format() {
+    assert_eq!(
+        test_graph()
+            .why("gleam_stdlib")
+            .expect("gleam_stdlib is a dependency"),
+        "gleam_stdlib 1.0.0
+  app 0.1.0 -> gleam_json 1.0.0 (>= 1.0.0 and < 2.0.0) -> gleam_stdlib 1.0.0 (>= 0.32.0)
+  app 0.1.0 -> gleam_stdlib 1.0.0 (>= 0.34.0 and < 2.0.0)
+  app 0.1.0 -> birdie 1.0.0 (path: ../birdie) -> gleam_stdlib 1.0.0
+  app 0.1.0 -> gleeunit 1.0.0 (>= 1.0.0 and < 2.0.0) -> gleam_stdlib 1.0.0 (>= 0.33.0)
+"
+    );
+}
+
+#[test]
+fn why_dev_only_dependency() {
+    assert_eq!(
+        test_graph()
+            .why("simplifile")
+            .expect("simplifile is a dependency"),
+        "simplifile 1.0.0 [dev]
+  app 0.1.0 -> birdie 1.0.0 (path: ../birdie) -> simplifile 1.0.0
+"
+    );
+}
+
+#[test]
+fn why_unknown_dependency() {
+    assert_eq!(
+        test_graph().why("gleam_jsn").unwrap_err(),
+        Error::UnknownDependency {
+            name: "gleam_jsn".into()
+        }
+    );
+}
@@ -61,13 +61,14 @@ pub struct BuildOptions {
 
 pub fn build(options: BuildOptions) -> Result<()> {
     let paths = crate::find_project_paths()?;
-    let config = crate::config::root_config()?;
+    let config = crate::config::root_config(&paths)?;
 
     // Reset the build directory so we know the state of the project
     crate::fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
 
     let out = paths.build_documentation_directory(&config.name);
     let mut built = crate::build::main(
+        &paths,
         Options {
             mode: Mode::Prod,
             target: None,
@@ -75,7 +76,7 @@ pub fn build(options: BuildOptions) -> Result<()> {
             warnings_as_errors: false,
             root_target_support: TargetSupport::Enforced,
         },
-        crate::build::download_dependencies()?,
+        crate::build::download_dependencies(&paths)?,
     )?;
     let outputs = build_documentation(&config, &mut built.root_package, DocContext::Build)?;
 
@@ -155,12 +156,13 @@ pub fn publish() -> Result<()> {
 impl PublishCommand {
     pub fn new() -> Result<Self> {
         let paths = crate::find_project_paths()?;
-        let config = crate::config::root_config()?;
+        let config = crate::config::root_config(&paths)?;
 
         // Reset the build directory so we know the state of the project
         crate::fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
 
         let mut built = crate::build::main(
+            &paths,
             Options {
                 root_target_support: TargetSupport::Enforced,
                 warnings_as_errors: false,
@@ -168,7 +170,7 @@ impl PublishCommand {
                 mode: Mode::Prod,
                 target: None,
             },
-            crate::build::download_dependencies()?,
+            crate::build::download_dependencies(&paths)?,
         )?;
         let outputs =
             build_documentation(&config, &mut built.root_package, DocContext::HexPublish)?;
@@ -1,9 +1,18 @@
+use std::collections::HashMap;
+
 use camino::Utf8PathBuf;
+use clap::ValueEnum;
+use ecow::EcoString;
 use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Target},
-    Result,
+    codegen::TypeScriptDeclarations,
+    io::{CommandExecutor, Stdio},
+    sbom, Error, Result,
 };
+use hexpm::version::Version;
+
+use crate::fs::ProjectIO;
 
 #[cfg(target_os = "windows")]
 static ENTRYPOINT_FILENAME: &str = "entrypoint.ps1";
@@ -15,6 +24,25 @@ static ENTRYPOINT_TEMPLATE: &str = include_str!("../templates/erlang-shipment-en
 #[cfg(not(target_os = "windows"))]
 static ENTRYPOINT_TEMPLATE: &str = include_str!("../templates/erlang-shipment-entrypoint.sh");
 
+static RELEASE_SCRIPT: &str = include_str!("../templates/gleam@@release.erl");
+#[cfg(target_os = "windows")]
+static RELEASE_START_EXTENSION: Option<&str> = Some("ps1");
+#[cfg(not(target_os = "windows"))]
+static RELEASE_START_EXTENSION: Option<&str> = None;
+
+#[cfg(target_os = "windows")]
+static RELEASE_START_TEMPLATE: &str = include_str!("../templates/erlang-release-start.ps1");
+#[cfg(not(target_os = "windows"))]
+static RELEASE_START_TEMPLATE: &str = include_str!("../templates/erlang-release-start.sh");
+static ESCRIPT_SCRIPT: &str = include_str!("../templates/gleam@@escript.erl");
+static ESCRIPT_ENTRYPOINT_TEMPLATE: &str = include_str!("../templates/escript-entrypoint.erl");
+
+static DEFAULT_VM_ARGS: &str = "\
+## Flags given to the Erlang VM when the release is started.
+## See https://www.erlang.org/doc/man/erl.html
+";
+static DEFAULT_SYS_CONFIG: &str = "[].\n";
+
 // TODO: start in embedded mode
 // TODO: test
 
@@ -41,6 +69,7 @@ pub(crate) fn erlang_shipment() -> Result<()> {
 
     // Build project in production mode
     let built = crate::build::main(
+        &paths,
         Options {
             root_target_support: TargetSupport::Enforced,
             warnings_as_errors: false,
@@ -48,7 +77,7 @@ pub(crate) fn erlang_shipment() -> Result<()> {
             mode,
             target: Some(target),
         },
-        crate::build::download_dependencies()?,
+        crate::build::download_dependencies(&paths)?,
     )?;
 
     for entry in crate::fs::read_dir(&build)?.filter_map(Result::ok) {
@@ -101,9 +130,225 @@ the {file} script.
     Ok(())
 }
 
+/// Generate an OTP release of the project, laid out as the release handling
+/// of OTP expects so that later versions can be installed alongside it:
+///
+/// - lib/APPLICATION-VERSION: the ebin, include, and priv directories of
+///   each application.
+/// - releases/VERSION: the `.rel` file, boot script, `sys.config`, and
+///   `vm.args` of the release.
+/// - releases/start_erl.data: the versions of ERTS and the release to start.
+/// - bin/NAME: a script to start the release, or bin/NAME.ps1 on Windows.
+///
+/// The boot script is made by `systools`, so Erlang must be installed. OTP
+/// applications are not copied into the release, they are loaded from the
+/// Erlang installation of the machine the release is run on.
+pub(crate) fn erlang_release() -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let target = Target::Erlang;
+    let mode = Mode::Prod;
+    let build = paths.build_directory_for_target(mode, target);
+    let out = paths.erlang_release_directory();
+
+    // Reset the build directory to ensure we have a clean slate and no old code
+    crate::fs::delete_directory(&build)?;
+
+    let manifest = crate::build::download_dependencies(&paths)?;
+    let mut versions: HashMap<EcoString, Version> = manifest
+        .packages
+        .iter()
+        .map(|package| (package.application_name().clone(), package.version.clone()))
+        .collect();
+
+    // Build project in production mode
+    let built = crate::build::main(
+        &paths,
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode,
+            target: Some(target),
+        },
+        manifest,
+    )?;
+    let config = &built.root_package.config;
+    let name = &config.name;
+    let version = config.version.to_string();
+    let _ = versions.insert(name.clone(), config.version.clone());
+
+    // Copy each application into a directory named after its version, leaving
+    // the other versions of it that are already in the release
+    let mut applications = Vec::new();
+    for (application, application_version) in &versions {
+        let build = build.join(application.as_str());
+        if !build.is_dir() {
+            continue;
+        }
+        let out = out
+            .join("lib")
+            .join(format!("{application}-{application_version}"));
+        crate::fs::delete_directory(&out)?;
+        crate::fs::mkdir(&out)?;
+        for subdirectory in ["ebin", "priv", "include"] {
+            let source = build.join(subdirectory);
+            if source.is_dir() {
+                let source = crate::fs::canonicalise(&source)?;
+                crate::fs::copy_dir(source, out.join(subdirectory))?;
+            }
+        }
+        applications.push(application.to_string());
+    }
+    applications.sort();
+
+    // Write the config files of the release
+    let release_directory = out.join("releases").join(&version);
+    crate::fs::delete_directory(&release_directory)?;
+    crate::fs::mkdir(&release_directory)?;
+    let release = &config.erlang.release;
+    let render = |template: Option<&Utf8PathBuf>, default: &str| -> Result<String> {
+        let template = match template {
+            Some(path) => crate::fs::read(paths.root().join(path))?,
+            None => default.into(),
+        };
+        Ok(template
+            .replace("$RELEASE_NAME", name)
+            .replace("$RELEASE_VERSION", &version))
+    };
+    let vm_args = render(release.vm_args.as_ref(), DEFAULT_VM_ARGS)?;
+    crate::fs::write(&release_directory.join("vm.args"), &vm_args)?;
+    let sys_config = render(release.sys_config.as_ref(), DEFAULT_SYS_CONFIG)?;
+    crate::fs::write(&release_directory.join("sys.config"), &sys_config)?;
+
+    // Write the `.rel` file and boot script
+    let script = build.join("gleam@@release.erl");
+    crate::fs::write(&script, RELEASE_SCRIPT)?;
+    let mut args = vec![
+        script.to_string(),
+        crate::fs::canonicalise(&out)?.to_string(),
+        name.to_string(),
+        version.clone(),
+    ];
+    args.extend(applications);
+    run_escript(&args)?;
+
+    // Write the start script
+    let mut start = out.join("bin").join(name.as_str());
+    if let Some(extension) = RELEASE_START_EXTENSION {
+        let _ = start.set_extension(extension);
+    }
+    let text = RELEASE_START_TEMPLATE.replace("$RELEASE_NAME_FROM_GLEAM", name);
+    crate::fs::write(&start, &text)?;
+    crate::fs::make_executable(&start)?;
+
+    crate::cli::print_exported(name);
+
+    println!(
+        "
+Your Erlang release has been generated to {out}.
+
+It can be copied to a compatible server with Erlang installed and started
+with the {name} script.
+
+    {start} run
+"
+    );
+
+    Ok(())
+}
+
+/// Generate an escript of the project: a single executable file containing
+/// the compiled code and priv directories of the project and its
+/// dependencies, which runs the project's main function. It can be run on
+/// any machine with Erlang installed.
+///
+/// The escript calls an entrypoint module made for it, which runs the
+/// project's main function. As with any escript the command line arguments
+/// are the plain arguments of the VM, after the path of the escript.
+pub(crate) fn escript() -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let target = Target::Erlang;
+    let mode = Mode::Prod;
+    let build = paths.build_directory_for_target(mode, target);
+
+    // Reset the build directory to ensure we have a clean slate and no old code
+    crate::fs::delete_directory(&build)?;
+
+    // Build project in production mode
+    let built = crate::build::main(
+        &paths,
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode,
+            target: Some(target),
+        },
+        crate::build::download_dependencies(&paths)?,
+    )?;
+    let name = &built.root_package.config.name;
+
+    // Every package directory is an application to include
+    let mut applications = Vec::new();
+    for entry in crate::fs::read_dir(&build)?.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            applications.push(path.file_name().expect("Directory name").to_string());
+        }
+    }
+    applications.sort();
+
+    let out = paths.build_export_escript(name);
+    crate::fs::delete_file(&out)?;
+    crate::fs::mkdir(out.parent().expect("Escript directory"))?;
+    let script = build.join("gleam@@escript.erl");
+    crate::fs::write(&script, ESCRIPT_SCRIPT)?;
+    let entrypoint = build.join(format!("{name}@@escript.erl"));
+    let text = ESCRIPT_ENTRYPOINT_TEMPLATE.replace("$PACKAGE_NAME_FROM_GLEAM", name);
+    crate::fs::write(&entrypoint, &text)?;
+    let mut args = vec![
+        script.to_string(),
+        out.to_string(),
+        name.to_string(),
+        build.to_string(),
+        entrypoint.to_string(),
+    ];
+    args.extend(applications);
+    run_escript(&args)?;
+    crate::fs::make_executable(&out)?;
+
+    crate::cli::print_exported(name);
+
+    println!(
+        "
+Your escript has been generated to {out}.
+
+It can be run on any machine with Erlang installed. The arguments it is
+run with are the plain arguments of the Erlang VM, after the path of the
+escript.
+
+    {out}
+"
+    );
+
+    Ok(())
+}
+
+fn run_escript(args: &[String]) -> Result<()> {
+    let status = ProjectIO::new().exec("escript", args, &[], None, Stdio::Inherit)?;
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(Error::ShellCommand {
+            program: "escript".into(),
+            err: None,
+        })
+    }
+}
+
 pub fn hex_tarball() -> Result<()> {
     let paths = crate::find_project_paths()?;
-    let config = crate::config::root_config()?;
+    let config = crate::config::root_config(&paths)?;
     let data: Vec<u8> = crate::publish::build_hex_tarball(&paths, &config)?;
 
     let path = paths.build_export_hex_tarball(&config.name, &config.version.to_string());
@@ -117,6 +362,98 @@ Your hex tarball has been generated in {}.
     Ok(())
 }
 
+/// Generate a single ES module containing the given module of the project and
+/// every module it imports, including FFI modules and the prelude. Functions
+/// that cannot be reached from the public functions of the entry module are
+/// left out.
+pub(crate) fn javascript_bundle(entry: Option<String>, typescript: bool) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let target = Target::JavaScript;
+    let mode = Mode::Prod;
+    let build = paths.build_directory_for_target(mode, target);
+
+    // Reset the build directory so that every module is compiled rather than
+    // loaded from the cache, as the bundle is generated from their code
+    crate::fs::delete_directory(&build)?;
+
+    // Build project in production mode
+    let built = crate::build::main(
+        &paths,
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode,
+            target: Some(target),
+        },
+        crate::build::download_dependencies(&paths)?,
+    )?;
+    let config = &built.root_package.config;
+    let entry = entry.map_or_else(|| config.name.clone(), EcoString::from);
+    let typescript = if typescript || config.javascript.typescript_declarations {
+        TypeScriptDeclarations::Emit
+    } else {
+        TypeScriptDeclarations::None
+    };
+
+    let modules = built.modules().collect::<Vec<_>>();
+    let bundle = gleam_core::javascript::bundle(
+        &ProjectIO::new(),
+        &build,
+        &modules,
+        &entry,
+        TargetSupport::Enforced,
+        typescript,
+    )?;
+
+    let out = paths.build_export_javascript_bundle(&entry);
+    crate::fs::write(&out, &bundle.javascript)?;
+    if let Some(declarations) = &bundle.typescript_declarations {
+        crate::fs::write(&out.with_extension("d.mts"), declarations)?;
+    }
+
+    crate::cli::print_exported(&config.name);
+
+    println!(
+        "
+Your JavaScript bundle has been generated to {out}.
+
+It is an ES module exporting the public functions and constants of
+the {entry} module.
+"
+    );
+
+    Ok(())
+}
+
+/// The formats a software bill of materials can be exported in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[default]
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
+/// Generate a software bill of materials for the project and every package
+/// it depends on in production, printing it or writing it to a file.
+pub fn sbom(format: SbomFormat, output: Option<Utf8PathBuf>) -> Result<()> {
+    let bill = crate::dependencies::bill_of_materials()?;
+    let rendered = match format {
+        SbomFormat::CycloneDx => sbom::cyclonedx::render(&bill),
+        SbomFormat::Spdx => sbom::spdx::render(&bill),
+    };
+    match output {
+        Some(path) => crate::fs::write(&path, &format!("{rendered}\n")),
+        None => {
+            println!("{rendered}");
+            Ok(())
+        }
+    }
+}
+
 pub fn javascript_prelude() -> Result<()> {
     print!("{}", gleam_core::javascript::PRELUDE);
     Ok(())
@@ -128,8 +465,11 @@ pub fn typescript_prelude() -> Result<()> {
 }
 
 pub fn package_interface(path: Utf8PathBuf) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+
     // Build the project
     let mut built = crate::build::main(
+        &paths,
         Options {
             mode: Mode::Prod,
             target: None,
@@ -137,11 +477,11 @@ pub fn package_interface(path: Utf8PathBuf) -> Result<()> {
             warnings_as_errors: false,
             root_target_support: TargetSupport::Enforced,
         },
-        crate::build::download_dependencies()?,
+        crate::build::download_dependencies(&paths)?,
     )?;
     built.root_package.attach_doc_and_module_comments();
 
     let out = gleam_core::docs::generate_json_package_interface(path, &built.root_package);
-    crate::fs::write_outputs_under(&[out], crate::find_project_paths()?.root())?;
+    crate::fs::write_outputs_under(&[out], paths.root())?;
     Ok(())
 }
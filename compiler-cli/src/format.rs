@@ -7,9 +7,18 @@ use std::{io::Read, str::FromStr};
 
 use camino::{Utf8Path, Utf8PathBuf};
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<()> {
+use crate::cli::{self, DiagnosticFormat};
+
+pub fn run(
+    stdin: bool,
+    check: bool,
+    files: Vec<String>,
+    diagnostic_format: DiagnosticFormat,
+) -> Result<()> {
     if stdin {
         process_stdin(check)
+    } else if check && diagnostic_format != DiagnosticFormat::Human {
+        check_files_with_diagnostics(files, diagnostic_format)
     } else {
         process_files(check, files)
     }
@@ -57,6 +66,25 @@ fn check_files(files: Vec<String>) -> Result<()> {
     }
 }
 
+/// Check the files are formatted, printing a diagnostic for each unformatted
+/// file to stdout in a machine readable format. An error is returned if any
+/// file is not formatted.
+fn check_files_with_diagnostics(files: Vec<String>, format: DiagnosticFormat) -> Result<()> {
+    let problem_files = unformatted_files(files)?;
+    let diagnostics: Vec<_> = problem_files
+        .iter()
+        .map(Unformatted::to_diagnostic)
+        .collect();
+    let root = crate::fs::get_current_directory()?;
+    cli::print_diagnostics(format, &diagnostics, &root);
+
+    if problem_files.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Format { problem_files })
+    }
+}
+
 fn format_files(files: Vec<String>) -> Result<()> {
     for file in unformatted_files(files)? {
         crate::fs::write_output(&OutputFile {
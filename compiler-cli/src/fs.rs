@@ -1,5 +1,6 @@
 use gleam_core::{
     build::{NullTelemetry, Target},
+    diagnostic::Diagnostic,
     error::{Error, FileIoAction, FileKind},
     io::{
         CommandExecutor, Content, DirEntry, FileSystemReader, FileSystemWriter, OutputFile,
@@ -16,7 +17,7 @@ use std::{
     fmt::Debug,
     fs::File,
     io::{self, BufRead, BufReader, Write},
-    sync::OnceLock,
+    sync::{Mutex, OnceLock},
     time::SystemTime,
 };
 
@@ -695,3 +696,25 @@ impl WarningEmitterIO for ConsoleWarningEmitter {
             .expect("Writing warning to stderr");
     }
 }
+
+/// Collects warnings as diagnostics, to be printed in a machine readable
+/// format once the build has finished.
+#[derive(Debug, Default)]
+pub struct DiagnosticWarningEmitter {
+    diagnostics: Mutex<Vec<Diagnostic>>,
+}
+
+impl DiagnosticWarningEmitter {
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.diagnostics.lock().expect("Diagnostics lock poisoned"))
+    }
+}
+
+impl WarningEmitterIO for DiagnosticWarningEmitter {
+    fn emit_warning(&self, warning: Warning) {
+        self.diagnostics
+            .lock()
+            .expect("Diagnostics lock poisoned")
+            .push(warning.to_diagnostic());
+    }
+}
@@ -149,9 +149,10 @@ pub fn revertcommand(package: Option<String>, version: Option<String>) -> Result
 
 impl RevertCommand {
     fn setup(package: Option<String>, version: Option<String>) -> Result<Self> {
+        let paths = crate::find_project_paths()?;
         let (package, version): (String, String) = match (package, version) {
             (Some(pkg), Some(ver)) => (pkg, ver),
-            (None, Some(ver)) => (crate::config::root_config()?.name.to_string(), ver),
+            (None, Some(ver)) => (crate::config::root_config(&paths)?.name.to_string(), ver),
             (Some(pkg), None) => {
                 let query =
                     "Which version of package ".to_string() + &pkg + " do you want to revert?";
@@ -160,7 +161,7 @@ impl RevertCommand {
             }
             (None, None) => {
                 // Only want to access root_config once rather than twice
-                let config = crate::config::root_config()?;
+                let config = crate::config::root_config(&paths)?;
 
                 (config.name.to_string(), config.version.to_string())
             }
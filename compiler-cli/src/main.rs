@@ -69,7 +69,11 @@ mod publish;
 mod remove;
 mod run;
 mod shell;
+mod test_runner;
+mod watch;
+mod workspace;
 
+use cli::DiagnosticFormat;
 use config::root_config;
 use dependencies::UseManifest;
 use fs::{get_current_directory, get_project_root};
@@ -78,8 +82,10 @@ pub use gleam_core::error::{Error, Result};
 use gleam_core::{
     analyse::TargetSupport,
     build::{Codegen, Mode, Options, Runtime, Target},
+    config::WorkspaceConfig,
     hex::RetirementReason,
     paths::ProjectPaths,
+    requirement::GitReference,
     version::COMPILER_VERSION,
 };
 use hex::ApiKeyCommand as _;
@@ -118,12 +124,34 @@ enum Command {
 
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        /// Rebuild the project whenever its source files change
+        #[arg(long)]
+        watch: bool,
+
+        /// The format to report errors and warnings in
+        #[arg(long, value_enum, default_value_t, conflicts_with = "watch")]
+        diagnostic_format: DiagnosticFormat,
+
+        /// Build this member of the workspace. May be given more than once.
+        /// Every member is built when run at the root of a workspace
+        #[arg(
+            short,
+            long = "package",
+            value_name = "PACKAGE",
+            conflicts_with = "watch"
+        )]
+        packages: Vec<String>,
     },
 
     /// Type check the project
     Check {
         #[arg(short, long, ignore_case = true, help = target_doc())]
         target: Option<Target>,
+
+        /// The format to report errors and warnings in
+        #[arg(long, value_enum, default_value_t)]
+        diagnostic_format: DiagnosticFormat,
     },
 
     /// Publish the project to the Hex package manager
@@ -139,6 +167,30 @@ enum Command {
         replace: bool,
         #[arg(short, long)]
         yes: bool,
+        /// Run the checks and build the package without publishing it,
+        /// showing its files, metadata, and API changes since the previous
+        /// release
+        #[arg(long, conflicts_with_all = ["replace", "yes"])]
+        dry_run: bool,
+    },
+
+    /// Check the version in gleam.toml is increased enough for the changes
+    /// made to the API since the previous release
+    ///
+    /// The API of the previous release is taken from its documentation on
+    /// Hex. For a release without a package interface in its documentation,
+    /// or to compare with an unpublished version such as the last git tag,
+    /// create one with `gleam export package-interface` and give it with
+    /// `--baseline`.
+    CheckVersion {
+        /// A package interface JSON file to compare the API with
+        #[arg(long)]
+        baseline: Option<Utf8PathBuf>,
+
+        /// The Hex repository the package is published to, as named in the
+        /// `hex-repositories` config. Defaults to hex.pm
+        #[arg(long, value_name = "NAME", conflicts_with = "baseline")]
+        repo: Option<String>,
     },
 
     /// Render HTML documentation
@@ -150,7 +202,13 @@ enum Command {
     Deps(Dependencies),
 
     /// Update dependency packages to their latest versions
-    Update,
+    ///
+    /// If packages are named then only they are updated, with every other
+    /// package kept at its locked version.
+    Update {
+        /// The packages to update
+        packages: Vec<String>,
+    },
 
     /// Work with the Hex package manager
     #[command(subcommand)]
@@ -172,6 +230,20 @@ enum Command {
         /// Check if inputs are formatted without changing them
         #[arg(long)]
         check: bool,
+
+        /// The format to report unformatted files in when checking
+        #[arg(long, value_enum, default_value_t, requires = "check")]
+        diagnostic_format: DiagnosticFormat,
+
+        /// Format this member of the workspace, with the files given relative
+        /// to it. May be given more than once
+        #[arg(
+            short,
+            long = "package",
+            value_name = "PACKAGE",
+            conflicts_with = "stdin"
+        )]
+        packages: Vec<String>,
     },
     /// Rewrite deprecated Gleam code
     Fix,
@@ -179,6 +251,12 @@ enum Command {
     /// Start an Erlang shell
     Shell,
 
+    /// Explain an error or warning in detail
+    Explain {
+        /// The code of the error or warning, such as E2004
+        code: String,
+    },
+
     /// Run the project
     #[command(trailing_var_arg = true)]
     Run {
@@ -192,10 +270,19 @@ enum Command {
         #[arg(short, long)]
         module: Option<String>,
 
+        /// Rebuild and restart the program whenever its source files change
+        #[arg(long)]
+        watch: bool,
+
         arguments: Vec<String>,
     },
 
     /// Run the project tests
+    ///
+    /// Public functions with names ending in `_test` in the modules of the
+    /// `test` directory are run as tests. A test passes if it returns without
+    /// crashing. If the `<package>_test` module has a `main` function it is
+    /// run instead, unless an option of the test runner is given.
     #[command(trailing_var_arg = true)]
     Test {
         #[arg(short, long, ignore_case = true, help = target_doc())]
@@ -204,7 +291,48 @@ enum Command {
         #[arg(long, ignore_case = true, help = runtime_doc())]
         runtime: Option<Runtime>,
 
+        /// Only run tests with a `module.function` name containing this text.
+        /// May be given more than once
+        #[arg(short, long = "filter", value_name = "TEXT")]
+        filters: Vec<String>,
+
+        /// Only run the tests in this module. May be given more than once
+        #[arg(short, long = "module", value_name = "MODULE")]
+        modules: Vec<String>,
+
+        /// The number of tests to run at once on Erlang, defaulting to the
+        /// number of schedulers. Tests are always run one at a time on
+        /// JavaScript
+        #[arg(short, long, value_name = "COUNT")]
+        jobs: Option<usize>,
+
+        /// The number of seconds a test may run for before it fails. On
+        /// JavaScript only tests that return a promise can time out
+        #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+        timeout: u64,
+
+        /// Write a JUnit XML report of the results to this file
+        #[arg(long, value_name = "PATH")]
+        junit: Option<Utf8PathBuf>,
+
+        /// Run the `main` function of the `<package>_test` module instead of
+        /// the built-in test runner. This is the default when that module has
+        /// a `main` function and no options of the test runner are given
+        #[arg(
+            long,
+            conflicts_with_all = ["filters", "modules", "jobs", "junit"]
+        )]
+        main: bool,
+
+        /// Arguments passed to the `main` function of the `<package>_test`
+        /// module, which is run when any are given
+        #[arg(conflicts_with_all = ["filters", "modules", "jobs", "junit"])]
         arguments: Vec<String>,
+
+        /// Test this member of the workspace. May be given more than once.
+        /// Every member is tested when run at the root of a workspace
+        #[arg(short, long = "package", value_name = "PACKAGE")]
+        packages: Vec<String>,
     },
 
     /// Compile a single Gleam package
@@ -217,13 +345,18 @@ enum Command {
 
     /// Add new project dependencies
     Add {
-        /// The names of Hex packages to add
+        /// The names of the packages to add. A version requirement can be
+        /// given for a Hex package, such as `lustre@4.2` or
+        /// `lustre@">= 4.0.0 and < 4.3.0"`
         #[arg(required = true)]
         packages: Vec<String>,
 
         /// Add the packages as dev-only dependencies
         #[arg(long)]
         dev: bool,
+
+        #[command(flatten)]
+        source: AddSource,
     },
 
     /// Remove project dependencies
@@ -257,10 +390,38 @@ fn runtime_doc() -> String {
 pub enum ExportTarget {
     /// Precompiled Erlang, suitable for deployment
     ErlangShipment,
+    /// An OTP release with a boot script, suitable for deployment
+    ErlangRelease,
+    /// A single executable file that runs the project with Erlang
+    Escript,
     /// The package bundled into a tarball, suitable for publishing to Hex
     HexTarball,
+    /// A single JavaScript module containing a module of the project and
+    /// everything it uses
+    JavascriptBundle {
+        /// The module to bundle, which defaults to the one named after the
+        /// project
+        #[arg(long)]
+        entry: Option<String>,
+
+        /// Also generate a TypeScript declaration file for the bundle
+        #[arg(long)]
+        typescript: bool,
+    },
     /// The JavaScript prelude module
     JavascriptPrelude,
+    /// A software bill of materials listing the project and the packages it
+    /// depends on in production, with their licences and checksums
+    Sbom {
+        /// The format of the bill of materials
+        #[arg(long, value_enum, default_value_t)]
+        format: export::SbomFormat,
+
+        /// The path to write the bill of materials to, which defaults to
+        /// printing it
+        #[arg(long = "out")]
+        output: Option<Utf8PathBuf>,
+    },
     /// The TypeScript prelude module
     TypescriptPrelude,
     /// Information on the modules, functions, and types in the project in JSON format
@@ -271,6 +432,59 @@ pub enum ExportTarget {
     },
 }
 
+#[derive(Args, Debug)]
+pub struct AddSource {
+    /// Add the package from a local directory
+    #[arg(long, conflicts_with = "git")]
+    path: Option<Utf8PathBuf>,
+
+    /// Add the package from a git repository
+    #[arg(long)]
+    git: Option<String>,
+
+    /// The branch of the git repository to use
+    #[arg(long, requires = "git", conflicts_with_all = ["tag", "rev", "ref_"])]
+    branch: Option<String>,
+
+    /// The tag of the git repository to use
+    #[arg(long, requires = "git", conflicts_with_all = ["rev", "ref_"])]
+    tag: Option<String>,
+
+    /// The commit of the git repository to use
+    #[arg(long, requires = "git", conflicts_with = "ref_")]
+    rev: Option<String>,
+
+    /// The ref of the git repository to use
+    #[arg(long = "ref", requires = "git")]
+    ref_: Option<String>,
+}
+
+impl AddSource {
+    fn into_source(self) -> add::Source {
+        let Some(url) = self.git else {
+            return match self.path {
+                Some(path) => add::Source::Path(path),
+                None => add::Source::Hex,
+            };
+        };
+        let reference = if let Some(branch) = self.branch {
+            GitReference::Branch(branch.into())
+        } else if let Some(tag) = self.tag {
+            GitReference::Tag(tag.into())
+        } else if let Some(rev) = self.rev {
+            GitReference::Rev(rev.into())
+        } else if let Some(ref_) = self.ref_ {
+            GitReference::Ref(ref_.into())
+        } else {
+            GitReference::Head
+        };
+        add::Source::Git {
+            url: url.into(),
+            reference,
+        }
+    }
+}
+
 #[derive(Args, Debug, Clone)]
 pub struct NewOptions {
     /// Location of the project root
@@ -283,6 +497,15 @@ pub struct NewOptions {
     #[arg(long, ignore_case = true, default_value = "lib")]
     pub template: new::Template,
 
+    /// Create the project from the files in this directory, replacing
+    /// `{{name}}`, `{{gleam_version}}`, and `{{target}}` in their names and
+    /// contents
+    #[arg(long, value_name = "DIRECTORY", conflicts_with = "template")]
+    pub template_directory: Option<Utf8PathBuf>,
+
+    #[arg(long, ignore_case = true, help = target_doc())]
+    pub target: Option<Target>,
+
     /// Skip git initialization and creation of .gitignore, .git/* and .github/* files
     #[arg(long)]
     pub skip_git: bool,
@@ -335,7 +558,38 @@ enum Dependencies {
     Download,
 
     /// Update dependency packages to their latest versions
-    Update,
+    ///
+    /// If packages are named then only they are updated, with every other
+    /// package kept at its locked version.
+    Update {
+        /// The packages to update
+        packages: Vec<String>,
+    },
+
+    /// Print the dependency packages that have newer versions available
+    Outdated,
+
+    /// Print the tree of dependency packages
+    Tree,
+
+    /// Print every path by which the project depends on a package
+    Why {
+        /// The name of the package
+        package: String,
+    },
+
+    /// Copy every Hex dependency package into the vendor directory
+    ///
+    /// Vendored packages are used instead of downloading them from Hex. Set
+    /// the `GLEAM_OFFLINE` environment variable to `true` to never download
+    /// packages.
+    Vendor,
+
+    /// List the licences of the packages the project depends on in production
+    ///
+    /// Fails if `allowed-licences` is set in gleam.toml and a package has a
+    /// licence that is not in it.
+    Licences,
 }
 
 #[derive(Subcommand, Debug)]
@@ -432,9 +686,21 @@ fn main() {
         Command::Build {
             target,
             warnings_as_errors,
-        } => command_build(target, warnings_as_errors),
+            watch,
+            diagnostic_format,
+            packages,
+        } => command_build(
+            target,
+            warnings_as_errors,
+            watch,
+            diagnostic_format,
+            packages,
+        ),
 
-        Command::Check { target } => command_check(target),
+        Command::Check {
+            target,
+            diagnostic_format,
+        } => command_check(target, diagnostic_format),
 
         Command::Docs(Docs::Build { open }) => docs::build(docs::BuildOptions { open }),
 
@@ -442,19 +708,50 @@ fn main() {
 
         Command::Docs(Docs::Remove { package, version }) => docs::remove(package, version),
 
+        // Formatting doesn't need a project, so members are only iterated over
+        // when selected. Formatting the root of a workspace formats every
+        // member anyway.
         Command::Format {
             stdin,
             files,
             check,
-        } => format::run(stdin, check, files),
+            diagnostic_format,
+            packages,
+        } if packages.is_empty() => format::run(stdin, check, files, diagnostic_format),
+
+        Command::Format {
+            stdin,
+            files,
+            check,
+            diagnostic_format,
+            packages,
+        } => workspace::for_each_member(&packages, |paths| {
+            let files = files
+                .iter()
+                .map(|file| paths.root().join(file).to_string())
+                .collect();
+            format::run(stdin, check, files, diagnostic_format)
+        }),
 
         Command::Fix => fix::run(),
 
+        Command::Explain { code } => explain(code),
+
         Command::Deps(Dependencies::List) => dependencies::list(),
 
         Command::Deps(Dependencies::Download) => download_dependencies(),
 
-        Command::Deps(Dependencies::Update) => dependencies::update(),
+        Command::Deps(Dependencies::Update { packages }) => dependencies::update(packages),
+
+        Command::Deps(Dependencies::Outdated) => dependencies::outdated(),
+
+        Command::Deps(Dependencies::Tree) => dependencies::tree(),
+
+        Command::Deps(Dependencies::Why { package }) => dependencies::why(&package),
+
+        Command::Deps(Dependencies::Vendor) => dependencies::vendor(),
+
+        Command::Deps(Dependencies::Licences) => dependencies::licences(),
 
         Command::New(options) => new::create(options, COMPILER_VERSION),
 
@@ -465,17 +762,58 @@ fn main() {
             arguments,
             runtime,
             module,
-        } => run::command(arguments, target, runtime, module, run::Which::Src),
+            watch,
+        } => find_project_paths().and_then(|paths| {
+            run::command(
+                &paths,
+                arguments,
+                target,
+                runtime,
+                module,
+                run::Which::Src,
+                watch,
+            )
+        }),
 
         Command::Test {
             target,
-            arguments,
             runtime,
-        } => run::command(arguments, target, runtime, None, run::Which::Test),
+            filters,
+            modules,
+            jobs,
+            timeout,
+            junit,
+            main,
+            arguments,
+            packages,
+        } => workspace::for_each_member(&packages, |paths| {
+            test_runner::command(
+                paths,
+                test_runner::TestOptions {
+                    target,
+                    runtime,
+                    filters: filters.clone(),
+                    modules: modules.clone(),
+                    jobs,
+                    timeout,
+                    junit: junit.clone(),
+                    main,
+                    arguments: arguments.clone(),
+                },
+            )
+        }),
 
         Command::CompilePackage(opts) => compile_package::command(opts),
 
-        Command::Publish { replace, yes } => publish::command(replace, yes),
+        Command::Publish {
+            replace,
+            yes,
+            dry_run,
+        } => publish::command(replace, yes, dry_run),
+
+        Command::CheckVersion { baseline, repo } => {
+            publish::check_version(baseline, repo.map(Into::into))
+        }
 
         Command::PrintConfig => print_config(),
 
@@ -492,19 +830,29 @@ fn main() {
 
         Command::Hex(Hex::Revert { package, version }) => hex::revertcommand(package, version),
 
-        Command::Add { packages, dev } => add::command(packages, dev),
+        Command::Add {
+            packages,
+            dev,
+            source,
+        } => add::command(packages, dev, source.into_source()),
 
         Command::Remove { packages } => remove::command(packages),
 
-        Command::Update => dependencies::update(),
+        Command::Update { packages } => dependencies::update(packages),
 
         Command::Clean => clean(),
 
         Command::LanguageServer => lsp::main(),
 
         Command::Export(ExportTarget::ErlangShipment) => export::erlang_shipment(),
+        Command::Export(ExportTarget::ErlangRelease) => export::erlang_release(),
+        Command::Export(ExportTarget::Escript) => export::escript(),
         Command::Export(ExportTarget::HexTarball) => export::hex_tarball(),
+        Command::Export(ExportTarget::JavascriptBundle { entry, typescript }) => {
+            export::javascript_bundle(entry, typescript)
+        }
         Command::Export(ExportTarget::JavascriptPrelude) => export::javascript_prelude(),
+        Command::Export(ExportTarget::Sbom { format, output }) => export::sbom(format, output),
         Command::Export(ExportTarget::TypescriptPrelude) => export::typescript_prelude(),
         Command::Export(ExportTarget::PackageInterface { output }) => {
             export::package_interface(output)
@@ -525,36 +873,65 @@ fn main() {
     }
 }
 
-fn command_check(target: Option<Target>) -> Result<()> {
-    let _ = build::main(
-        Options {
-            root_target_support: TargetSupport::Enforced,
-            warnings_as_errors: false,
-            codegen: Codegen::DepsOnly,
-            mode: Mode::Dev,
-            target,
-        },
-        build::download_dependencies()?,
-    )?;
+fn command_check(target: Option<Target>, diagnostic_format: DiagnosticFormat) -> Result<()> {
+    let paths = find_project_paths()?;
+    let options = Options {
+        root_target_support: TargetSupport::Enforced,
+        warnings_as_errors: false,
+        codegen: Codegen::DepsOnly,
+        mode: Mode::Dev,
+        target,
+    };
+    if diagnostic_format != DiagnosticFormat::Human {
+        return build::main_with_diagnostics(&paths, options, diagnostic_format);
+    }
+    let _ = build::main(&paths, options, build::download_dependencies(&paths)?)?;
     Ok(())
 }
 
-fn command_build(target: Option<Target>, warnings_as_errors: bool) -> Result<()> {
-    let _ = build::main(
-        Options {
-            root_target_support: TargetSupport::Enforced,
-            warnings_as_errors,
-            codegen: Codegen::All,
-            mode: Mode::Dev,
-            target,
-        },
-        build::download_dependencies()?,
-    )?;
+fn command_build(
+    target: Option<Target>,
+    warnings_as_errors: bool,
+    watch: bool,
+    diagnostic_format: DiagnosticFormat,
+    packages: Vec<String>,
+) -> Result<()> {
+    let options = Options {
+        root_target_support: TargetSupport::Enforced,
+        warnings_as_errors,
+        codegen: Codegen::All,
+        mode: Mode::Dev,
+        target,
+    };
+    if watch {
+        let paths = find_project_paths()?;
+        return build::watch(
+            &paths,
+            options,
+            build::download_dependencies(&paths)?,
+            |_| Ok(()),
+        );
+    }
+    workspace::for_each_member(&packages, |paths| {
+        if diagnostic_format != DiagnosticFormat::Human {
+            return build::main_with_diagnostics(paths, options.clone(), diagnostic_format);
+        }
+        let _ = build::main(paths, options.clone(), build::download_dependencies(paths)?)?;
+        Ok(())
+    })
+}
+
+fn explain(code: String) -> Result<()> {
+    let explanation =
+        gleam_core::error::explanation(&code).ok_or_else(|| Error::UnknownErrorCode {
+            code: code.as_str().into(),
+        })?;
+    print!("{}\n\n{explanation}", code.trim().to_uppercase());
     Ok(())
 }
 
 fn print_config() -> Result<()> {
-    let config = root_config()?;
+    let config = root_config(&find_project_paths()?)?;
     println!("{config:#?}");
     Ok(())
 }
@@ -577,7 +954,11 @@ fn initialise_logger() {
 
 fn find_project_paths() -> Result<ProjectPaths> {
     let current_dir = get_current_directory()?;
-    get_project_root(current_dir).map(ProjectPaths::new)
+    let root = get_project_root(current_dir)?;
+    match WorkspaceConfig::find_root(&root, &fs::ProjectIO::new())? {
+        Some(workspace_root) => Ok(ProjectPaths::in_workspace(root, workspace_root)),
+        None => Ok(ProjectPaths::new(root)),
+    }
 }
 
 #[cfg(test)]
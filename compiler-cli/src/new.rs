@@ -1,6 +1,7 @@
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::ValueEnum;
 use gleam_core::{
+    build::Target,
     erlang,
     error::{Error, FileIoAction, FileKind, InvalidProjectNameReason},
     parse, Result,
@@ -17,16 +18,48 @@ use crate::{fs::get_current_directory, NewOptions};
 
 const GLEAM_STDLIB_REQUIREMENT: &str = ">= 0.34.0 and < 2.0.0";
 const GLEEUNIT_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
+const GLEAM_ERLANG_REQUIREMENT: &str = ">= 0.25.0 and < 1.0.0";
+const GLEAM_OTP_REQUIREMENT: &str = ">= 0.10.0 and < 1.0.0";
+const ARGV_REQUIREMENT: &str = ">= 1.0.0 and < 2.0.0";
 const ERLANG_OTP_VERSION: &str = "26.0.2";
 const REBAR3_VERSION: &str = "3";
 const ELIXIR_VERSION: &str = "1.15.4";
+const NODE_VERSION: &str = "20";
 
 #[derive(
-    Debug, Serialize, Deserialize, Display, EnumString, VariantNames, ValueEnum, Clone, Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    VariantNames,
+    ValueEnum,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
 )]
 #[strum(serialize_all = "kebab_case")]
 pub enum Template {
+    /// A library
     Lib,
+    /// An Erlang OTP application with a supervision tree
+    OtpApp,
+    /// A command line program that runs on JavaScript
+    JavascriptCli,
+    /// A library tested on both Erlang and JavaScript
+    MultiTargetLib,
+}
+
+impl Template {
+    /// The target the template is made for, if it can only be used with one.
+    fn target(self) -> Option<Target> {
+        match self {
+            Self::OtpApp => Some(Target::Erlang),
+            Self::JavascriptCli => Some(Target::JavaScript),
+            Self::Lib | Self::MultiTargetLib => None,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -39,6 +72,9 @@ pub struct Creator {
     gleam_version: &'static str,
     options: NewOptions,
     project_name: String,
+    /// The target to give in `gleam.toml`, if the project is not to use the
+    /// default one.
+    target: Option<Target>,
 }
 
 #[derive(EnumIter, PartialEq, Eq, Debug, Hash)]
@@ -46,6 +82,7 @@ enum FileToCreate {
     Readme,
     Gitignore,
     SrcModule,
+    ApplicationModule,
     TestModule,
     GleamToml,
     GithubCi,
@@ -61,6 +98,10 @@ impl FileToCreate {
             Self::SrcModule => creator
                 .src
                 .join(Utf8PathBuf::from(format!("{project_name}.gleam"))),
+            Self::ApplicationModule => creator
+                .src
+                .join(project_name)
+                .join(Utf8PathBuf::from("application.gleam")),
             Self::TestModule => creator
                 .test
                 .join(Utf8PathBuf::from(format!("{project_name}_test.gleam"))),
@@ -74,12 +115,19 @@ impl FileToCreate {
         let skip_git = creator.options.skip_git;
         let skip_github = creator.options.skip_github;
         let gleam_version = creator.gleam_version;
+        let template = creator.options.template;
+        let javascript = creator.target == Some(Target::JavaScript);
 
         match self {
-            Self::Readme => Some(format!(
-                r#"# {project_name}
-
-[![Package Version](https://img.shields.io/hexpm/v/{project_name})](https://hex.pm/packages/{project_name})
+            Self::Readme => {
+                let shell = if javascript {
+                    ""
+                } else {
+                    "gleam shell # Run an Erlang shell\n"
+                };
+                let usage = match template {
+                    Template::Lib | Template::MultiTargetLib => format!(
+                        r#"[![Package Version](https://img.shields.io/hexpm/v/{project_name})](https://hex.pm/packages/{project_name})
 [![Hex Docs](https://img.shields.io/badge/hex-docs-ffaff3)](https://hexdocs.pm/{project_name}/)
 
 ```sh
@@ -93,17 +141,33 @@ pub fn main() {{
 }}
 ```
 
-Further documentation can be found at <https://hexdocs.pm/{project_name}>.
+Further documentation can be found at <https://hexdocs.pm/{project_name}>."#
+                    ),
+                    Template::OtpApp => format!(
+                        "An Erlang OTP application. Its supervision tree is started by
+`src/{project_name}/application.gleam` when the application starts."
+                    ),
+                    Template::JavascriptCli => r#"A command line program that runs on JavaScript.
+
+```sh
+gleam run -- Joe
+```"#
+                        .into(),
+                };
+                Some(format!(
+                    r#"# {project_name}
+
+{usage}
 
 ## Development
 
 ```sh
 gleam run   # Run the project
 gleam test  # Run the tests
-gleam shell # Run an Erlang shell
-```
+{shell}```
 "#,
-            )),
+                ))
+            }
 
             Self::Gitignore if !skip_git => Some(
                 "*.beam
@@ -114,14 +178,63 @@ erl_crash.dump
                 .into(),
             ),
 
-            Self::SrcModule => Some(format!(
-                r#"import gleam/io
+            Self::SrcModule => Some(match template {
+                Template::Lib | Template::MultiTargetLib => format!(
+                    r#"import gleam/io
 
 pub fn main() {{
   io.println("Hello from {project_name}!")
 }}
 "#,
-            )),
+                ),
+                Template::OtpApp => format!(
+                    r#"import gleam/erlang/process
+import gleam/io
+
+// The application and its supervision tree have been started by the time
+// `main` is called, so it keeps running until the program is stopped.
+pub fn main() {{
+  io.println("Hello from {project_name}!")
+  process.sleep_forever()
+}}
+"#,
+                ),
+                Template::JavascriptCli => format!(
+                    r#"import argv
+import gleam/io
+
+pub fn main() {{
+  case argv.load().arguments {{
+    [name] -> io.println("Hello, " <> name <> "!")
+    _ -> io.println("usage: {project_name} <name>")
+  }}
+}}
+"#,
+                ),
+            }),
+
+            Self::ApplicationModule if template == Template::OtpApp => Some(
+                r#"import gleam/dynamic.{type Dynamic}
+import gleam/erlang/process.{type Pid}
+import gleam/otp/supervisor
+
+/// Called when the application is started, returning the process at the top
+/// of its supervision tree. Add the processes of the application to the
+/// children of the supervisor.
+pub fn start(_type: Dynamic, _args: Dynamic) -> Result(Pid, Dynamic) {
+  case supervisor.start(fn(children) { children }) {
+    Ok(subject) -> Ok(process.subject_owner(subject))
+    Error(error) -> Error(dynamic.from(error))
+  }
+}
+
+/// Called when the application has stopped.
+pub fn stop(_state: Dynamic) -> Nil {
+  Nil
+}
+"#
+                .into(),
+            ),
 
             Self::TestModule => Some(
                 r#"import gleeunit
@@ -140,10 +253,33 @@ pub fn hello_world_test() {
                 .into(),
             ),
 
-            Self::GleamToml => Some(format!(
-                r#"name = "{project_name}"
+            Self::GleamToml => {
+                let target = match creator.target {
+                    Some(target) => format!("target = \"{target}\"\n"),
+                    None => "".into(),
+                };
+                let dependencies = match template {
+                    Template::Lib | Template::MultiTargetLib => "".into(),
+                    Template::OtpApp => format!(
+                        "gleam_erlang = \"{GLEAM_ERLANG_REQUIREMENT}\"
+gleam_otp = \"{GLEAM_OTP_REQUIREMENT}\"
+"
+                    ),
+                    Template::JavascriptCli => format!("argv = \"{ARGV_REQUIREMENT}\"\n"),
+                };
+                let erlang = match template {
+                    Template::OtpApp => format!(
+                        "
+[erlang]
+application_start_module = \"{project_name}/application\"
+"
+                    ),
+                    Template::Lib | Template::MultiTargetLib | Template::JavascriptCli => "".into(),
+                };
+                Some(format!(
+                    r#"name = "{project_name}"
 version = "1.0.0"
-
+{target}
 # Fill out these fields if you intend to generate HTML documentation or publish
 # your project to the Hex package manager.
 #
@@ -157,14 +293,33 @@ version = "1.0.0"
 
 [dependencies]
 gleam_stdlib = "{GLEAM_STDLIB_REQUIREMENT}"
-
+{dependencies}
 [dev-dependencies]
 gleeunit = "{GLEEUNIT_REQUIREMENT}"
-"#,
-            )),
+{erlang}"#,
+                ))
+            }
 
-            Self::GithubCi if !skip_git && !skip_github => Some(format!(
-                r#"name: test
+            Self::GithubCi if !skip_git && !skip_github => {
+                let node = if javascript || template == Template::MultiTargetLib {
+                    format!(
+                        r#"      - uses: actions/setup-node@v4
+        with:
+          node-version: "{NODE_VERSION}"
+"#
+                    )
+                } else {
+                    "".into()
+                };
+                let test = if template == Template::MultiTargetLib {
+                    "      - run: gleam test --target erlang
+      - run: gleam test --target javascript
+"
+                } else {
+                    "      - run: gleam test\n"
+                };
+                Some(format!(
+                    r#"name: test
 
 on:
   push:
@@ -184,12 +339,12 @@ jobs:
           gleam-version: "{gleam_version}"
           rebar3-version: "{REBAR3_VERSION}"
           # elixir-version: "{ELIXIR_VERSION}"
-      - run: gleam deps download
-      - run: gleam test
-      - run: gleam format --check src test
+{node}      - run: gleam deps download
+{test}      - run: gleam format --check src test
 "#,
-            )),
-            Self::GithubCi | Self::Gitignore => None,
+                ))
+            }
+            Self::GithubCi | Self::Gitignore | Self::ApplicationModule => None,
         }
     }
 }
@@ -206,6 +361,17 @@ impl Creator {
 
         validate_name(&project_name)?;
 
+        let template = options.template;
+        let target = match (template.target(), options.target) {
+            (Some(template_target), Some(target)) if template_target != target => {
+                return Err(Error::TemplateDoesNotSupportTarget {
+                    template: template.to_string().into(),
+                    target,
+                });
+            }
+            (template_target, target) => target.or(template_target),
+        };
+
         let root = get_current_directory()?.join(&options.project_root);
         let src = root.join("src");
         let test = root.join("test");
@@ -220,6 +386,7 @@ impl Creator {
             gleam_version,
             options,
             project_name,
+            target,
         };
 
         validate_root_folder(&me)?;
@@ -229,6 +396,17 @@ impl Creator {
 
     fn run(&self) -> Result<()> {
         crate::fs::mkdir(&self.root)?;
+
+        if let Some(directory) = &self.options.template_directory {
+            if !self.options.skip_git {
+                crate::fs::git_init(&self.root)?;
+            }
+            for (path, contents) in self.template_directory_files(directory)? {
+                crate::fs::write_bytes(&path, &contents)?;
+            }
+            return Ok(());
+        }
+
         crate::fs::mkdir(&self.src)?;
         crate::fs::mkdir(&self.test)?;
 
@@ -241,19 +419,73 @@ impl Creator {
             crate::fs::git_init(&self.root)?;
         }
 
-        match self.options.template {
-            Template::Lib => {
-                for file in FileToCreate::iter() {
-                    let path = file.location(self);
-                    if let Some(contents) = file.contents(self) {
-                        write(path, &contents)?;
-                    }
-                }
+        if self.options.template == Template::OtpApp {
+            crate::fs::mkdir(self.src.join(&self.project_name))?;
+        }
+
+        for file in FileToCreate::iter() {
+            let path = file.location(self);
+            if let Some(contents) = file.contents(self) {
+                write(path, &contents)?;
             }
         }
 
         Ok(())
     }
+
+    /// The files to create from a user's template directory, with the
+    /// variables in their paths and contents replaced. Files that are not
+    /// UTF-8 text are copied as they are.
+    fn template_directory_files(
+        &self,
+        directory: &Utf8Path,
+    ) -> Result<Vec<(Utf8PathBuf, Vec<u8>)>> {
+        let mut files = vec![];
+        let entries = walkdir::WalkDir::new(directory)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git");
+        for entry in entries {
+            let entry = entry.map_err(|err| Error::FileIo {
+                kind: FileKind::Directory,
+                path: directory.to_path_buf(),
+                action: FileIoAction::Read,
+                err: Some(err.to_string()),
+            })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = Utf8Path::from_path(entry.path()).ok_or_else(|| Error::NonUtf8Path {
+                path: entry.path().to_path_buf(),
+            })?;
+            let relative = path
+                .strip_prefix(directory)
+                .expect("template file outside of template directory");
+            let skipped = match relative.components().next().map(|c| c.as_str()) {
+                Some(".gitignore") => self.options.skip_git,
+                Some(".github") => self.options.skip_git || self.options.skip_github,
+                _ => false,
+            };
+            if skipped {
+                continue;
+            }
+            let contents = match String::from_utf8(crate::fs::read_bytes(path)?) {
+                Ok(text) => self.substitute(&text).into_bytes(),
+                Err(error) => error.into_bytes(),
+            };
+            files.push((self.root.join(self.substitute(relative.as_str())), contents));
+        }
+        Ok(files)
+    }
+
+    /// Replaces the variables that can be used in a user's template.
+    fn substitute(&self, text: &str) -> String {
+        let target = self.target.unwrap_or(Target::Erlang);
+        text.replace("{{name}}", &self.project_name)
+            .replace("{{gleam_version}}", self.gleam_version)
+            .replace("{{target}}", &target.to_string())
+    }
 }
 
 pub fn create(options: NewOptions, version: &'static str) -> Result<()> {
@@ -299,10 +531,22 @@ fn write(path: Utf8PathBuf, contents: &str) -> Result<()> {
 fn validate_root_folder(creator: &Creator) -> Result<(), Error> {
     let mut duplicate_files: Vec<Utf8PathBuf> = Vec::new();
 
-    for t in FileToCreate::iter() {
-        let full_path = t.location(creator);
-        if full_path.exists() {
-            duplicate_files.push(full_path);
+    if let Some(directory) = &creator.options.template_directory {
+        for (full_path, _) in creator.template_directory_files(directory)? {
+            if full_path.exists() {
+                duplicate_files.push(full_path);
+            }
+        }
+    } else {
+        for t in FileToCreate::iter() {
+            if t == FileToCreate::ApplicationModule && creator.options.template != Template::OtpApp
+            {
+                continue;
+            }
+            let full_path = t.location(creator);
+            if full_path.exists() {
+                duplicate_files.push(full_path);
+            }
         }
     }
 
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use camino::Utf8PathBuf;
-use gleam_core::Error;
+use gleam_core::{build::Target, Error};
 
 #[test]
 fn new() {
@@ -12,6 +12,8 @@ fn new() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: false,
             skip_github: false,
@@ -42,6 +44,8 @@ fn new_with_skip_git() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: true,
             skip_github: false,
@@ -63,6 +67,8 @@ fn new_with_skip_github() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: false,
             skip_github: true,
@@ -87,6 +93,8 @@ fn new_with_skip_git_and_github() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: true,
             skip_github: true,
@@ -111,6 +119,8 @@ fn invalid_path() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: false,
             skip_github: false,
@@ -129,6 +139,8 @@ fn invalid_name() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: Some("-".into()),
             skip_git: false,
             skip_github: false,
@@ -149,6 +161,8 @@ fn existing_directory_no_files() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: true,
             skip_github: true,
@@ -176,6 +190,8 @@ fn existing_directory_with_one_existing_file() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: true,
             skip_github: true,
@@ -199,6 +215,8 @@ fn existing_directory_with_non_generated_file() {
         super::NewOptions {
             project_root: path.to_string(),
             template: super::Template::Lib,
+            template_directory: None,
+            target: None,
             name: None,
             skip_git: true,
             skip_github: true,
@@ -229,6 +247,8 @@ fn conflict_with_existing_files() {
             super::NewOptions {
                 project_root: path.to_string(),
                 template: super::Template::Lib,
+                template_directory: None,
+                target: None,
                 name: None,
                 skip_git: true,
                 skip_github: true,
@@ -241,3 +261,233 @@ fn conflict_with_existing_files() {
         })
     );
 }
+
+#[test]
+fn new_otp_app() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::OtpApp,
+            template_directory: None,
+            target: None,
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    let toml = crate::fs::read(path.join("gleam.toml")).unwrap();
+    assert!(toml.contains("target = \"erlang\""));
+    assert!(toml.contains("gleam_otp = "));
+    assert!(toml.contains("application_start_module = \"my_project/application\""));
+
+    let application = crate::fs::read(path.join("src/my_project/application.gleam")).unwrap();
+    assert!(application.contains("pub fn start(_type: Dynamic, _args: Dynamic)"));
+    assert!(application.contains("pub fn stop(_state: Dynamic)"));
+}
+
+#[test]
+fn new_javascript_cli() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::JavascriptCli,
+            template_directory: None,
+            target: None,
+            name: None,
+            skip_git: false,
+            skip_github: false,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    let toml = crate::fs::read(path.join("gleam.toml")).unwrap();
+    assert!(toml.contains("target = \"javascript\""));
+    assert!(toml.contains("argv = "));
+    assert!(!toml.contains("[erlang]"));
+    assert!(!path.join("src/my_project").exists());
+
+    let src = crate::fs::read(path.join("src/my_project.gleam")).unwrap();
+    assert!(src.contains("argv.load().arguments"));
+
+    let readme = crate::fs::read(path.join("README.md")).unwrap();
+    assert!(!readme.contains("gleam shell"));
+
+    let ci = crate::fs::read(path.join(".github/workflows/test.yml")).unwrap();
+    assert!(ci.contains("actions/setup-node"));
+}
+
+#[test]
+fn new_multi_target_lib() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::MultiTargetLib,
+            template_directory: None,
+            target: None,
+            name: None,
+            skip_git: false,
+            skip_github: false,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    let toml = crate::fs::read(path.join("gleam.toml")).unwrap();
+    assert!(!toml.contains("target = "));
+
+    let ci = crate::fs::read(path.join(".github/workflows/test.yml")).unwrap();
+    assert!(ci.contains("gleam test --target erlang"));
+    assert!(ci.contains("gleam test --target javascript"));
+}
+
+#[test]
+fn new_lib_with_target() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::Lib,
+            template_directory: None,
+            target: Some(Target::JavaScript),
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    let toml = crate::fs::read(path.join("gleam.toml")).unwrap();
+    assert!(toml.contains("target = \"javascript\""));
+}
+
+#[test]
+fn template_does_not_support_target() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    assert_eq!(
+        super::Creator::new(
+            super::NewOptions {
+                project_root: path.to_string(),
+                template: super::Template::OtpApp,
+                template_directory: None,
+                target: Some(Target::JavaScript),
+                name: None,
+                skip_git: true,
+                skip_github: true,
+            },
+            "1.0.0-gleam",
+        )
+        .err(),
+        Some(Error::TemplateDoesNotSupportTarget {
+            template: "otp-app".into(),
+            target: Target::JavaScript,
+        })
+    );
+}
+
+#[test]
+fn new_from_template_directory() {
+    let tmp = tempfile::tempdir().unwrap();
+    let template = Utf8PathBuf::from_path_buf(tmp.path().join("template")).expect("Non Utf8 Path");
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    crate::fs::write(
+        &template.join("gleam.toml"),
+        "name = \"{{name}}\"\ntarget = \"{{target}}\"\n",
+    )
+    .unwrap();
+    crate::fs::write(
+        &template.join("src/{{name}}.gleam"),
+        "// Created with Gleam {{gleam_version}}\n",
+    )
+    .unwrap();
+    crate::fs::write_bytes(&template.join("priv/logo.png"), &[0xFF, 0xD8, 0x7B]).unwrap();
+    crate::fs::write(&template.join(".github/workflows/test.yml"), "").unwrap();
+    crate::fs::write(&template.join(".git/HEAD"), "").unwrap();
+
+    let creator = super::Creator::new(
+        super::NewOptions {
+            project_root: path.to_string(),
+            template: super::Template::Lib,
+            template_directory: Some(template),
+            target: Some(Target::JavaScript),
+            name: None,
+            skip_git: true,
+            skip_github: true,
+        },
+        "1.0.0-gleam",
+    )
+    .unwrap();
+
+    creator.run().unwrap();
+
+    assert_eq!(
+        crate::fs::read(path.join("gleam.toml")).unwrap(),
+        "name = \"my_project\"\ntarget = \"javascript\"\n"
+    );
+    assert_eq!(
+        crate::fs::read(path.join("src/my_project.gleam")).unwrap(),
+        "// Created with Gleam 1.0.0-gleam\n"
+    );
+    assert_eq!(
+        crate::fs::read_bytes(path.join("priv/logo.png")).unwrap(),
+        vec![0xFF, 0xD8, 0x7B]
+    );
+    assert!(!path.join(".github").exists());
+    assert!(!path.join(".git").exists());
+    assert!(!path.join("README.md").exists());
+}
+
+#[test]
+fn template_directory_conflict_with_existing_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let template = Utf8PathBuf::from_path_buf(tmp.path().join("template")).expect("Non Utf8 Path");
+    let path = Utf8PathBuf::from_path_buf(tmp.path().join("my_project")).expect("Non Utf8 Path");
+
+    crate::fs::write(&template.join("src/{{name}}.gleam"), "").unwrap();
+    crate::fs::write(&path.join("src/my_project.gleam"), "").unwrap();
+
+    assert_eq!(
+        super::Creator::new(
+            super::NewOptions {
+                project_root: path.to_string(),
+                template: super::Template::Lib,
+                template_directory: Some(template),
+                target: None,
+                name: None,
+                skip_git: true,
+                skip_github: true,
+            },
+            "1.0.0-gleam",
+        )
+        .err(),
+        Some(Error::OutputFilesAlreadyExist {
+            file_names: vec![path.join("src/my_project.gleam")]
+        })
+    );
+}
@@ -1,23 +1,31 @@
 use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
 use flate2::{write::GzEncoder, Compression};
 use gleam_core::{
     analyse::TargetSupport,
-    build::{Codegen, Mode, Options, Package, Target},
+    build::{Codegen, Mode, Module, Options, Package, Target},
     config::{PackageConfig, SpdxLicense},
     docs::DocContext,
+    error::{FileIoAction, FileKind},
     hex,
+    package_interface::{diff, PackageInterface},
     paths::{self, ProjectPaths},
     requirement::Requirement,
     Error, Result,
 };
 use hexpm::version::{Range, Version};
 use itertools::Itertools;
+use serde_json::Value;
 use sha2::Digest;
 use std::{io::Write, path::PathBuf, time::Instant};
 
 use crate::{build, cli, docs, fs, hex::ApiKeyCommand, http::HttpClient};
 
-pub fn command(replace: bool, yes: bool) -> Result<()> {
+pub fn command(replace: bool, yes: bool, dry_run: bool) -> Result<()> {
+    if dry_run {
+        return self::dry_run();
+    }
+
     let command = PublishCommand::setup(replace, yes)?;
 
     if let Some(mut command) = command {
@@ -36,7 +44,7 @@ pub struct PublishCommand {
 impl PublishCommand {
     pub fn setup(replace: bool, i_am_sure: bool) -> Result<Option<Self>> {
         let paths = crate::find_project_paths()?;
-        let config = crate::config::root_config()?;
+        let config = crate::config::root_config(&paths)?;
 
         let should_publish = check_for_gleam_prefix(&config, i_am_sure)?
             && check_for_version_zero(&config, i_am_sure)?
@@ -52,6 +60,7 @@ impl PublishCommand {
             data: package_tarball,
             src_files_added,
             generated_files_added,
+            ..
         } = do_build_hex_tarball(&paths, &config)?;
 
         check_for_name_squatting(&compile_result)?;
@@ -64,16 +73,7 @@ impl PublishCommand {
         )?)?;
 
         // Ask user if this is correct
-        if !generated_files_added.is_empty() {
-            println!("\nGenerated files:");
-            for file in generated_files_added.iter().sorted() {
-                println!("  - {}", file.0);
-            }
-        }
-        println!("\nSource files:");
-        for file in src_files_added.iter().sorted() {
-            println!("  - {}", file);
-        }
+        print_files(&src_files_added, &generated_files_added);
         println!("\nName: {}", config.name);
         println!("Version: {}", config.version);
 
@@ -92,6 +92,206 @@ impl PublishCommand {
     }
 }
 
+/// Runs every check made before publishing and builds the package tarball,
+/// showing what would be published and how the API of the package has changed
+/// since the previous release, without publishing anything.
+fn dry_run() -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config(&paths)?;
+
+    // The checks that would ask for confirmation only print their warnings,
+    // as there is nothing to confirm.
+    let _ = check_for_gleam_prefix(&config, true)?;
+    let _ = check_for_version_zero(&config, true)?;
+    let _ = check_repo_url(&config, true)?;
+
+    let Tarball {
+        compile_result,
+        data,
+        src_files_added,
+        generated_files_added,
+        metadata,
+    } = do_build_hex_tarball(&paths, &config)?;
+    check_for_name_squatting(&compile_result)?;
+
+    print_files(&src_files_added, &generated_files_added);
+    println!("\nMetadata:\n{metadata}");
+    println!("Tarball size: {} bytes", data.len());
+
+    // Not being able to reach Hex shouldn't stop the rest of the checks from
+    // being useful, so the comparison failing is reported rather than being
+    // an error.
+    println!();
+    if let Err(error) = api_changes(&config, &compile_result) {
+        println!("The API could not be compared with the previous release.\n");
+        cli::print_error(&error);
+    }
+
+    println!("\nThis was a dry run, nothing has been published.");
+    Ok(())
+}
+
+/// Prints the changes to the API of the package since the latest release
+/// before the version being published, and whether the version has been
+/// increased enough for them.
+fn api_changes(config: &PackageConfig, package: &Package) -> Result<()> {
+    let Some((previous, previous_interface)) = previous_release(config, None)? else {
+        println!("There is no previous release to compare the API with.");
+        return Ok(());
+    };
+    let Some(previous_interface) = previous_interface else {
+        println!(
+            "The documentation of {previous} has no package interface to compare the API with."
+        );
+        return Ok(());
+    };
+
+    let changes = diff::diff(&previous_interface, &package_interface(package));
+    if changes.is_empty() {
+        println!("The API has not changed since {previous}.");
+    } else {
+        println!("API changes since {previous}:");
+        for change in &changes {
+            println!("  - {change}");
+        }
+    }
+    if let Err(error) = diff::check_version(&previous, &config.version, &changes) {
+        println!();
+        cli::print_error(&error);
+    }
+    Ok(())
+}
+
+/// Checks that the version in the config has been increased enough for the
+/// changes made to the API since the previous release. The API of the
+/// previous release is taken from the package interface file given as the
+/// baseline, or from the documentation of the release in the given Hex
+/// repository, which is hex.pm by default.
+pub fn check_version(baseline: Option<Utf8PathBuf>, repository: Option<EcoString>) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let config = crate::config::root_config(&paths)?;
+    let (previous, previous_interface) = match baseline {
+        Some(path) => read_package_interface(&path)?,
+        None => match previous_release(&config, repository.as_ref())? {
+            Some((version, Some(interface))) => (version, interface),
+            Some((version, None)) => {
+                return Err(Error::MissingPackageInterface {
+                    package: config.name.clone(),
+                    version: version.to_string().into(),
+                })
+            }
+            None => {
+                println!(
+                    "There is no release of {} before {} on Hex, so any version can be published.",
+                    config.name, config.version
+                );
+                return Ok(());
+            }
+        },
+    };
+
+    // Reset the build directory so every module is compiled and part of the
+    // interface, rather than some being loaded from the cache
+    fs::delete_directory(&paths.build_directory_for_target(Mode::Prod, config.target))?;
+
+    let built = build::main(
+        &paths,
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            mode: Mode::Prod,
+            target: None,
+            codegen: Codegen::DepsOnly,
+        },
+        build::download_dependencies(&paths)?,
+    )?;
+    let changes = diff::diff(&previous_interface, &package_interface(&built.root_package));
+    diff::check_version(&previous, &config.version, &changes)?;
+
+    let bump = diff::required_bump(&changes);
+    println!(
+        "Version {} is a large enough increase from {previous} for a {bump} release.",
+        config.version
+    );
+    Ok(())
+}
+
+/// The latest release of the package in the Hex repository with the given
+/// name before the version in the config, along with the package interface
+/// from its documentation if it has one. The repository is hex.pm, or a
+/// mirror of it, when no name is given.
+fn previous_release(
+    config: &PackageConfig,
+    repository: Option<&EcoString>,
+) -> Result<Option<(Version, Option<Value>)>> {
+    let runtime = tokio::runtime::Runtime::new().expect("Unable to start Tokio async runtime");
+    let http = HttpClient::new();
+    let repositories = crate::dependencies::hex_repositories(config)?;
+    let repository = repositories.get(&config.name, repository)?;
+    let versions =
+        runtime.block_on(hex::get_published_versions(&config.name, repository, &http))?;
+    let Some(previous) = versions
+        .into_iter()
+        .filter(|version| version < &config.version)
+        .max()
+    else {
+        return Ok(None);
+    };
+
+    let interface = runtime.block_on(hex::get_published_package_interface(
+        &config.name,
+        &previous,
+        &repository.config,
+        &http,
+    ))?;
+    let interface = match interface {
+        Some(interface) => Some(serde_json::from_str(&interface).map_err(|error| {
+            Error::Hex(format!(
+                "the package interface of {previous} could not be read: {error}"
+            ))
+        })?),
+        None => None,
+    };
+    Ok(Some((previous, interface)))
+}
+
+/// Reads a package interface file made by `gleam export package-interface`,
+/// returning the version of the package it describes and the interface.
+fn read_package_interface(path: &Utf8Path) -> Result<(Version, Value)> {
+    let parse_error = |error: String| Error::FileIo {
+        action: FileIoAction::Parse,
+        kind: FileKind::File,
+        path: path.to_path_buf(),
+        err: Some(error),
+    };
+    let interface: Value =
+        serde_json::from_str(&fs::read(path)?).map_err(|error| parse_error(error.to_string()))?;
+    let version = interface
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| parse_error("the package interface has no version".into()))?;
+    let version = Version::parse(version).map_err(|error| parse_error(error.to_string()))?;
+    Ok((version, interface))
+}
+
+fn package_interface(package: &Package) -> Value {
+    serde_json::to_value(PackageInterface::from_package(package))
+        .expect("package interface to json")
+}
+
+fn print_files(src_files: &[Utf8PathBuf], generated_files: &[(Utf8PathBuf, String)]) {
+    if !generated_files.is_empty() {
+        println!("\nGenerated files:");
+        for file in generated_files.iter().sorted() {
+            println!("  - {}", file.0);
+        }
+    }
+    println!("\nSource files:");
+    for file in src_files.iter().sorted() {
+        println!("  - {}", file);
+    }
+}
+
 fn check_for_name_squatting(package: &Package) -> Result<(), Error> {
     if package.modules.len() > 1 {
         return Ok(());
@@ -240,6 +440,8 @@ struct Tarball {
     data: Vec<u8>,
     src_files_added: Vec<Utf8PathBuf>,
     generated_files_added: Vec<(Utf8PathBuf, String)>,
+    /// The `metadata.config` file of the tarball, which is Erlang terms.
+    metadata: String,
 }
 
 pub fn build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<Vec<u8>> {
@@ -256,6 +458,7 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
 
     // Build the project to check that it is valid
     let built = build::main(
+        paths,
         Options {
             root_target_support: TargetSupport::Enforced,
             warnings_as_errors: false,
@@ -263,7 +466,7 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
             target: Some(target),
             codegen: Codegen::All,
         },
-        build::download_dependencies()?,
+        build::download_dependencies(paths)?,
     )?;
 
     // If any of the modules in the package contain a todo then refuse to
@@ -280,10 +483,7 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
         return Err(Error::CannotPublishTodo { unfinished });
     }
 
-    // TODO: If any of the modules in the package contain a leaked internal type then
-    // refuse to publish as the package is not yet finished.
-    // We need to move aliases in to the type system first.
-    // context: https://discord.com/channels/768594524158427167/768594524158427170/1227250677734969386
+    check_for_leaked_internal_types(&built.root_package)?;
 
     // Collect all the files we want to include in the tarball
     let generated_files = match target {
@@ -319,9 +519,41 @@ fn do_build_hex_tarball(paths: &ProjectPaths, config: &PackageConfig) -> Result<
         data: tarball,
         src_files_added: src_files,
         generated_files_added: generated_files,
+        metadata,
     })
 }
 
+/// If any of the public modules in the package have an internal type in their
+/// public API then refuse to publish, as other packages should not be able to
+/// use it.
+// TODO: Type aliases are not checked, as they are not in the type system.
+// context: https://discord.com/channels/768594524158427167/768594524158427170/1227250677734969386
+fn check_for_leaked_internal_types(package: &Package) -> Result<()> {
+    let leaking = package
+        .modules
+        .iter()
+        .filter(|module| !module.ast.type_info.is_internal && leaks_internal_type(module))
+        .map(|module| module.name.clone())
+        .sorted()
+        .collect_vec();
+    if leaking.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::CannotPublishLeakedInternalType {
+            unfinished: leaking,
+        })
+    }
+}
+
+fn leaks_internal_type(module: &Module) -> bool {
+    module
+        .ast
+        .type_info
+        .values
+        .values()
+        .any(|value| value.publicity.is_public() && value.type_.find_internal_type().is_some())
+}
+
 fn check_config_for_publishing(config: &PackageConfig) -> Result<()> {
     // These fields are required to publish a Hex package. Hex will reject
     // packages without them.
@@ -345,7 +577,10 @@ fn metadata_config<'a>(
         .dependencies
         .iter()
         .map(|(name, requirement)| match requirement {
-            Requirement::Hex { version } => Ok(ReleaseRequirement {
+            Requirement::Hex {
+                version,
+                repository: None,
+            } => Ok(ReleaseRequirement {
                 name,
                 requirement: version,
             }),
@@ -691,6 +926,99 @@ fn prevent_publish_git_dependency() {
     );
 }
 
+/// Build a package targeting JavaScript with the given modules, in a temporary
+/// directory.
+#[cfg(test)]
+fn build_package(modules: &[(&str, &str)]) -> Package {
+    let directory = tempfile::tempdir().expect("temporary directory");
+    let root = Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).expect("UTF-8 path");
+    fs::write(
+        &root.join("gleam.toml"),
+        "name = \"wibble\"\nversion = \"1.0.0\"\ntarget = \"javascript\"\n",
+    )
+    .expect("write config");
+    for (name, src) in modules {
+        fs::write(&root.join("src").join(format!("{name}.gleam")), src).expect("write module");
+    }
+    let built = build::main(
+        &ProjectPaths::new(root),
+        Options {
+            root_target_support: TargetSupport::Enforced,
+            warnings_as_errors: false,
+            mode: Mode::Prod,
+            target: Some(Target::JavaScript),
+            codegen: Codegen::None,
+        },
+        gleam_core::manifest::Manifest {
+            requirements: Default::default(),
+            packages: vec![],
+        },
+    )
+    .expect("build");
+    built.root_package
+}
+
+#[test]
+fn prevent_publish_leaked_internal_type() {
+    let package = build_package(&[
+        (
+            "wibble",
+            r#"
+@internal
+pub type Wobble {
+  Wobble
+}
+
+pub fn wobble() -> Wobble {
+  Wobble
+}
+"#,
+        ),
+        ("wibble/internal", "pub type Wubble { Wubble }"),
+        (
+            "wibble/other",
+            "import wibble/internal\npub fn wubble() { internal.Wubble }",
+        ),
+    ]);
+    assert_eq!(
+        check_for_leaked_internal_types(&package),
+        Err(Error::CannotPublishLeakedInternalType {
+            unfinished: vec!["wibble".into(), "wibble/other".into()]
+        })
+    );
+}
+
+#[test]
+fn allow_publish_internal_type_in_internal_api() {
+    let package = build_package(&[
+        (
+            "wibble",
+            r#"
+@internal
+pub type Wobble {
+  Wobble
+}
+
+@internal
+pub fn wobble() -> Wobble {
+  Wobble
+}
+
+pub fn wubble() -> Int {
+  let _ = wobble()
+  1
+}
+"#,
+        ),
+        ("wibble/internal", "pub type Wubble { Wubble }"),
+        (
+            "wibble/internal/helpers",
+            "import wibble/internal\npub fn wubble() { internal.Wubble }",
+        ),
+    ]);
+    assert_eq!(check_for_leaked_internal_types(&package), Ok(()));
+}
+
 pub fn get_hostname() -> String {
     hostname::get()
         .expect("Looking up hostname")
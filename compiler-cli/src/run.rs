@@ -1,6 +1,9 @@
-use std::sync::OnceLock;
+use std::{
+    process::Child,
+    sync::{Once, OnceLock},
+};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use ecow::EcoString;
 use gleam_core::{
     analyse::TargetSupport,
@@ -20,16 +23,24 @@ pub enum Which {
     Test,
 }
 
+/// Stops ctrl+c from exiting gleam so it can be handled by the program being
+/// run. The handler is installed once, as a command may run several programs
+/// when run for each member of a workspace.
+pub fn ignore_ctrl_c() {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler"));
+}
+
 // TODO: test
 pub fn command(
+    paths: &ProjectPaths,
     arguments: Vec<String>,
     target: Option<Target>,
     runtime: Option<Runtime>,
     module: Option<String>,
     which: Which,
+    watch: bool,
 ) -> Result<(), Error> {
-    let paths = crate::find_project_paths()?;
-
     // Validate the module path
     if let Some(mod_path) = &module {
         if !is_gleam_module(mod_path) {
@@ -40,20 +51,20 @@ pub fn command(
     };
 
     // Download dependencies
-    let manifest = crate::build::download_dependencies()?;
+    let manifest = crate::build::download_dependencies(paths)?;
 
     // Get the config for the module that is being run to check the target.
     // Also get the kind of the package the module belongs to: wether the module
     // belongs to a dependency or to the root package.
     let (mod_config, package_kind) = match &module {
         Some(mod_path) => {
-            crate::config::find_package_config_for_module(mod_path, &manifest, &paths)?
+            crate::config::find_package_config_for_module(mod_path, &manifest, paths)?
         }
-        _ => (crate::config::root_config()?, PackageKind::Root),
+        _ => (crate::config::root_config(paths)?, PackageKind::Root),
     };
 
     // The root config is required to run the project.
-    let root_config = crate::config::root_config()?;
+    let root_config = crate::config::root_config(paths)?;
 
     // Determine which module to run
     let module = module.unwrap_or(match which {
@@ -78,49 +89,175 @@ pub fn command(
         },
     };
 
-    let built = crate::build::main(options, manifest)?;
+    let runtime = match target {
+        Target::Erlang => match runtime {
+            Some(r) => {
+                return Err(Error::InvalidRuntime {
+                    target: Target::Erlang,
+                    invalid_runtime: r,
+                })
+            }
+            None => None,
+        },
+        Target::JavaScript => Some(runtime.unwrap_or(mod_config.javascript.runtime)),
+    };
+
+    if watch {
+        // Each time the project is rebuilt the program is stopped and run
+        // again with the new code.
+        let mut running: Option<Child> = None;
+        let result = crate::build::watch(paths, options, manifest, |built| {
+            let main_function = get_or_suggest_main_function(built, &module, target)?;
+            if let Some(mut child) = running.take() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+            let (program, args) = runtime_command(
+                paths,
+                &root_config,
+                runtime,
+                &main_function,
+                &module,
+                &arguments,
+            )?;
+            crate::cli::print_running(&format!("{module}.main"));
+            running = Some(spawn(program, &args)?);
+            Ok(())
+        });
+        if let Some(mut child) = running {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        return result;
+    }
+
+    let built = crate::build::main(paths, options, manifest)?;
+    run_main(
+        paths,
+        &root_config,
+        built,
+        &module,
+        target,
+        runtime,
+        &arguments,
+    )
+}
 
+/// Run the main function of a module of a project that has been built. The
+/// process exits with the status of the program if it fails.
+pub(crate) fn run_main(
+    paths: &ProjectPaths,
+    root_config: &PackageConfig,
+    built: Built,
+    module: &str,
+    target: Target,
+    runtime: Option<Runtime>,
+    arguments: &[String],
+) -> Result<(), Error> {
     // A module can not be run if it does not exist or does not have a public main function.
-    let main_function = get_or_suggest_main_function(built, &module, target)?;
+    let main_function = get_or_suggest_main_function(built, module, target)?;
 
     // Don't exit on ctrl+c as it is used by child erlang shell
-    ctrlc::set_handler(move || {}).expect("Error setting Ctrl-C handler");
+    ignore_ctrl_c();
 
     crate::cli::print_running(&format!("{module}.main"));
 
     // Run the command
-    let status = match target {
-        Target::Erlang => match runtime {
-            Some(r) => Err(Error::InvalidRuntime {
-                target: Target::Erlang,
-                invalid_runtime: r,
-            }),
-            _ => run_erlang(&paths, &root_config.name, &module, arguments),
-        },
-        Target::JavaScript => match runtime.unwrap_or(mod_config.javascript.runtime) {
-            Runtime::Deno => run_javascript_deno(
-                &paths,
-                &root_config,
-                &main_function.package,
-                &module,
+    let (program, args) = runtime_command(
+        paths,
+        root_config,
+        runtime,
+        &main_function,
+        module,
+        arguments,
+    )?;
+    let status = ProjectIO::new().exec(program, &args, &[], None, Stdio::Inherit)?;
+
+    // Other members of a workspace are still run if the program succeeds.
+    if status != 0 {
+        std::process::exit(status);
+    }
+    Ok(())
+}
+
+/// The program and arguments used to run the main function of a module with
+/// the given runtime. No runtime is used for the Erlang target.
+fn runtime_command(
+    paths: &ProjectPaths,
+    root_config: &PackageConfig,
+    runtime: Option<Runtime>,
+    main_function: &ModuleFunction,
+    module: &str,
+    arguments: &[String],
+) -> Result<(&'static str, Vec<String>), Error> {
+    let arguments = arguments.to_vec();
+    match runtime {
+        None => {
+            // gleam modules are separated by `/`. Erlang modules are separated by `@`.
+            let module = module.replace('/', "@");
+            let package = &root_config.name;
+            erlang_command(paths, format!("{package}@@main:run({module})"), arguments)
+        }
+        Some(runtime) => {
+            let entrypoint = write_javascript_entrypoint(paths, &main_function.package, module)?;
+            Ok(javascript_command(
+                runtime,
+                root_config,
+                &entrypoint,
                 arguments,
-            ),
-            Runtime::NodeJs => {
-                run_javascript_node(&paths, &main_function.package, &module, arguments)
-            }
-            Runtime::Bun => run_javascript_bun(&paths, &main_function.package, &module, arguments),
-        },
-    }?;
+            ))
+        }
+    }
+}
+
+/// The program and arguments used to evaluate an Erlang expression with all
+/// of the project's compiled packages available.
+pub(crate) fn erlang_command(
+    paths: &ProjectPaths,
+    eval: String,
+    arguments: Vec<String>,
+) -> Result<(&'static str, Vec<String>), Error> {
+    Ok(("erl", erlang_args(paths, eval, arguments)?))
+}
+
+/// The program and arguments used to run a JavaScript module with the given
+/// runtime.
+pub(crate) fn javascript_command(
+    runtime: Runtime,
+    config: &PackageConfig,
+    entrypoint: &Utf8Path,
+    arguments: Vec<String>,
+) -> (&'static str, Vec<String>) {
+    match runtime {
+        Runtime::Deno => ("deno", javascript_deno_args(config, entrypoint, arguments)),
+        Runtime::NodeJs => ("node", javascript_node_args(entrypoint, arguments)),
+        Runtime::Bun => ("bun", javascript_bun_args(entrypoint, arguments)),
+    }
+}
 
-    std::process::exit(status);
+/// Start a program without waiting for it to finish, so that it can be
+/// stopped when the project is rebuilt in watch mode.
+fn spawn(program: &str, args: &[String]) -> Result<Child, Error> {
+    tracing::trace!(program=program, args=?args.join(" "), "command_spawn");
+    std::process::Command::new(program)
+        .args(args)
+        .spawn()
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+                program: program.to_string(),
+            },
+            other => Error::ShellCommand {
+                program: program.to_string(),
+                err: Some(other),
+            },
+        })
 }
 
-fn run_erlang(
+fn erlang_args(
     paths: &ProjectPaths,
-    package: &str,
-    module: &str,
+    eval: String,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+) -> Result<Vec<String>, Error> {
     let mut args = vec![];
 
     // Specify locations of Erlang applications
@@ -131,11 +268,8 @@ fn run_erlang(
         args.push(entry.path().join("ebin").into());
     }
 
-    // gleam modules are separated by `/`. Erlang modules are separated by `@`.
-    let module = module.replace('/', "@");
-
     args.push("-eval".into());
-    args.push(format!("{package}@@main:run({module})"));
+    args.push(eval);
 
     // Don't run the Erlang shell
     args.push("-noshell".into());
@@ -146,43 +280,31 @@ fn run_erlang(
         args.push(argument);
     }
 
-    ProjectIO::new().exec("erl", &args, &[], None, Stdio::Inherit)
+    Ok(args)
 }
 
-fn run_javascript_bun(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
-    arguments: Vec<String>,
-) -> Result<i32, Error> {
+fn javascript_bun_args(entrypoint: &Utf8Path, arguments: Vec<String>) -> Vec<String> {
     let mut args = vec!["run".to_string()];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for arg in arguments.into_iter() {
         args.push(arg);
     }
 
-    ProjectIO::new().exec("bun", &args, &[], None, Stdio::Inherit)
+    args
 }
 
-fn run_javascript_node(
-    paths: &ProjectPaths,
-    package: &str,
-    module: &str,
-    arguments: Vec<String>,
-) -> Result<i32, Error> {
+fn javascript_node_args(entrypoint: &Utf8Path, arguments: Vec<String>) -> Vec<String> {
     let mut args = vec![];
-    let entry = write_javascript_entrypoint(paths, package, module)?;
 
-    args.push(entry.to_string());
+    args.push(entrypoint.to_string());
 
     for argument in arguments.into_iter() {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("node", &args, &[], None, Stdio::Inherit)
+    args
 }
 
 fn write_javascript_entrypoint(
@@ -203,13 +325,11 @@ main();
     Ok(path)
 }
 
-fn run_javascript_deno(
-    paths: &ProjectPaths,
+fn javascript_deno_args(
     config: &PackageConfig,
-    package: &str,
-    module: &str,
+    entrypoint: &Utf8Path,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
+) -> Vec<String> {
     let mut args = vec![];
 
     // Run the main function.
@@ -269,14 +389,13 @@ fn run_javascript_deno(
         );
     }
 
-    let entrypoint = write_javascript_entrypoint(paths, package, module)?;
     args.push(entrypoint.to_string());
 
     for argument in arguments.into_iter() {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("deno", &args, &[], None, Stdio::Inherit)
+    args
 }
 
 fn add_deno_flag(args: &mut Vec<String>, flag: &str, flags: &DenoFlag) {
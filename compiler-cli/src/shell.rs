@@ -10,6 +10,7 @@ pub fn command() -> Result<(), Error> {
 
     // Build project
     let _ = crate::build::main(
+        &paths,
         Options {
             root_target_support: TargetSupport::Enforced,
             warnings_as_errors: false,
@@ -17,7 +18,7 @@ pub fn command() -> Result<(), Error> {
             mode: Mode::Dev,
             target: Some(Target::Erlang),
         },
-        crate::build::download_dependencies()?,
+        crate::build::download_dependencies(&paths)?,
     )?;
 
     // Don't exit on ctrl+c as it is used by child erlang shell
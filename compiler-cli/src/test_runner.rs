@@ -0,0 +1,628 @@
+//! The test runner built into `gleam test`.
+//!
+//! The public functions with names ending in `_test` in the modules of the
+//! `test` directory are found using the module interfaces from compilation.
+//! Projects that run their tests from the `main` function of the
+//! `<package>_test` module instead, such as those using gleeunit, have that
+//! function run unless an option of the test runner is given.
+//! They are then run by a runner generated for the target: an Erlang module
+//! that runs tests in parallel, or a JavaScript module that runs them one at a
+//! time. The runner writes the outcome of each test to a file which is read
+//! back here to print a summary and optionally a JUnit XML report.
+
+use std::{fmt::Write, io::Write as _, time::Duration};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use gleam_core::{
+    analyse::TargetSupport,
+    build::{Codegen, Mode, Options, Runtime, Target},
+    config::DenoFlag,
+    error::Error,
+    io::{CommandExecutor, Stdio},
+    paths::ProjectPaths,
+    type_::TestFunction,
+};
+use itertools::Itertools;
+use termcolor::{Color, ColorSpec, WriteColor};
+
+use crate::{fs::ProjectIO, run::Which};
+
+static JAVASCRIPT_RUNNER: &str = include_str!("../templates/gleam-test-runner.mjs");
+
+#[derive(Debug)]
+pub struct TestOptions {
+    pub target: Option<Target>,
+    pub runtime: Option<Runtime>,
+    pub filters: Vec<String>,
+    pub modules: Vec<String>,
+    pub jobs: Option<usize>,
+    pub timeout: u64,
+    pub junit: Option<Utf8PathBuf>,
+    /// Run the `main` function of the `<package>_test` module rather than the
+    /// tests found by the runner.
+    pub main: bool,
+    /// Arguments for the `main` function of the `<package>_test` module.
+    pub arguments: Vec<String>,
+}
+
+impl TestOptions {
+    /// Whether any option only used by the built-in test runner was given.
+    fn uses_runner(&self) -> bool {
+        !self.filters.is_empty()
+            || !self.modules.is_empty()
+            || self.jobs.is_some()
+            || self.junit.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Passed,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TestResult {
+    status: Status,
+    module: EcoString,
+    name: EcoString,
+    duration: Duration,
+    message: String,
+}
+
+pub fn command(paths: &ProjectPaths, options: TestOptions) -> Result<(), Error> {
+    if options.main || !options.arguments.is_empty() {
+        return crate::run::command(
+            paths,
+            options.arguments,
+            options.target,
+            options.runtime,
+            None,
+            Which::Test,
+            false,
+        );
+    }
+
+    let manifest = crate::build::download_dependencies(paths)?;
+    let config = crate::config::root_config(paths)?;
+    let target = options.target.unwrap_or(config.target);
+
+    let runtime = match (target, options.runtime) {
+        (Target::Erlang, Some(runtime)) => {
+            return Err(Error::InvalidRuntime {
+                target: Target::Erlang,
+                invalid_runtime: runtime,
+            })
+        }
+        (Target::Erlang, None) => None,
+        (Target::JavaScript, runtime) => Some(runtime.unwrap_or(config.javascript.runtime)),
+    };
+
+    let built = crate::build::main(
+        paths,
+        Options {
+            warnings_as_errors: false,
+            codegen: Codegen::All,
+            mode: Mode::Dev,
+            target: Some(target),
+            root_target_support: TargetSupport::Enforced,
+        },
+        manifest,
+    )?;
+
+    let test_module = format!("{}_test", config.name);
+    if !options.uses_runner()
+        && built
+            .get_main_function(&test_module.as_str().into(), target)
+            .is_ok()
+    {
+        return crate::run::run_main(paths, &config, built, &test_module, target, runtime, &[]);
+    }
+
+    let tests = select(
+        built.test_functions(target),
+        &options.filters,
+        &options.modules,
+    );
+    if tests.is_empty() {
+        return Err(Error::NoTestsFound {
+            filtered: !options.filters.is_empty() || !options.modules.is_empty(),
+        });
+    }
+
+    let out = paths.build_directory_for_package(Mode::Dev, target, &config.name);
+    let results_path = out.join("gleam.test-results");
+    if results_path.exists() {
+        crate::fs::delete_file(&results_path)?;
+    }
+    let timeout = options.timeout * 1000;
+
+    let (program, args) = match runtime {
+        None => {
+            let tests_path = out.join("gleam.tests");
+            crate::fs::write(&tests_path, &erlang_test_list(&tests))?;
+            crate::run::erlang_command(
+                paths,
+                format!("{package}@@test:run()", package = config.name),
+                vec![
+                    tests_path.to_string(),
+                    results_path.to_string(),
+                    options.jobs.unwrap_or(0).to_string(),
+                    timeout.to_string(),
+                ],
+            )?
+        }
+        Some(runtime) => {
+            let entrypoint = out.join("gleam.test.mjs");
+            let runner = javascript_runner(&tests, &results_path, timeout);
+            crate::fs::write(&entrypoint, &runner)?;
+
+            // Deno must be allowed to write the results file.
+            let mut config = config.clone();
+            if let DenoFlag::Allow(paths) = &mut config.javascript.deno.allow_write {
+                paths.push(results_path.to_string());
+            }
+            crate::run::javascript_command(runtime, &config, &entrypoint, vec![])
+        }
+    };
+
+    // Don't exit on ctrl+c as it is used by child erlang shell
+    crate::run::ignore_ctrl_c();
+
+    let count = tests.len();
+    let word = if count == 1 { "test" } else { "tests" };
+    crate::cli::print_running(&format!("{count} {word}"));
+
+    let status = ProjectIO::new().exec(program, &args, &[], None, Stdio::Inherit)?;
+    if status != 0 {
+        std::process::exit(status);
+    }
+
+    let results = parse_results(&crate::fs::read(&results_path)?);
+    print_failures(&results);
+    println!("{}", summary(&results));
+
+    if let Some(path) = options.junit {
+        crate::fs::write(&path, &junit_report(&results))?;
+    }
+
+    if results.iter().any(|result| result.status != Status::Passed) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The tests to run. A test is run if its module is one of the given
+/// modules and its name contains one of the given filters, with no modules
+/// or no filters selecting every test.
+fn select(tests: Vec<TestFunction>, filters: &[String], modules: &[String]) -> Vec<TestFunction> {
+    tests
+        .into_iter()
+        .filter(|test| {
+            modules.is_empty() || modules.iter().any(|module| test.module == module.as_str())
+        })
+        .filter(|test| {
+            let name = format!("{}.{}", test.module, test.name);
+            filters.is_empty() || filters.iter().any(|filter| name.contains(filter.as_str()))
+        })
+        .collect()
+}
+
+/// Each test as a line of its Erlang module and function names, separated
+/// by a tab.
+fn erlang_test_list(tests: &[TestFunction]) -> String {
+    tests
+        .iter()
+        .map(|test| format!("{}\t{}\n", test.module.replace("/", "@"), test.name))
+        .collect()
+}
+
+fn javascript_runner(tests: &[TestFunction], results_path: &Utf8Path, timeout: u64) -> String {
+    let mut runner = String::new();
+    let modules: Vec<&EcoString> = tests.iter().map(|test| &test.module).dedup().collect();
+    for (index, module) in modules.iter().enumerate() {
+        writeln!(runner, "import * as ${index} from \"./{module}.mjs\";").expect("write import");
+    }
+    runner.push_str("\nconst tests = [\n");
+    for test in tests {
+        let index = modules
+            .iter()
+            .position(|module| *module == &test.module)
+            .expect("test module import");
+        let (module, name) = (&test.module, &test.name);
+        writeln!(runner, "  [\"{module}\", \"{name}\", ${index}.{name}],").expect("write test");
+    }
+    runner.push_str("];\n");
+    let results = serde_json::to_string(results_path.as_str()).expect("results path json");
+    writeln!(
+        runner,
+        "const options = {{ timeout: {timeout}, results: {results} }};\n"
+    )
+    .expect("write options");
+    runner.push_str(JAVASCRIPT_RUNNER);
+    runner
+}
+
+/// Read the results written by a runner, one test per line with the status,
+/// module, function, duration in milliseconds, and escaped failure message
+/// separated by tabs.
+fn parse_results(text: &str) -> Vec<TestResult> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(5, '\t');
+            let status = match parts.next()? {
+                "passed" => Status::Passed,
+                "failed" => Status::Failed,
+                "timed_out" => Status::TimedOut,
+                _ => return None,
+            };
+            let module = parts.next()?.replace('@', "/").into();
+            let name = parts.next()?.into();
+            let duration = Duration::from_millis(parts.next()?.parse().ok()?);
+            let message = unescape(parts.next().unwrap_or_default());
+            Some(TestResult {
+                status,
+                module,
+                name,
+                duration,
+                message,
+            })
+        })
+        .collect()
+}
+
+fn unescape(message: &str) -> String {
+    let mut unescaped = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(char) = chars.next() {
+        match (char, chars.clone().next()) {
+            ('\\', Some('n')) => unescaped.push('\n'),
+            ('\\', Some('t')) => unescaped.push('\t'),
+            ('\\', Some('\\')) => unescaped.push('\\'),
+            (char, _) => {
+                unescaped.push(char);
+                continue;
+            }
+        }
+        let _ = chars.next();
+    }
+    unescaped
+}
+
+fn print_failures(results: &[TestResult]) {
+    let buffer_writer = crate::cli::stdout_buffer_writer();
+    let mut buffer = buffer_writer.buffer();
+    for result in results {
+        let status = match result.status {
+            Status::Passed => continue,
+            Status::Failed => "failed",
+            Status::TimedOut => "timed out",
+        };
+        buffer
+            .set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Red)))
+            .expect("print_failures");
+        write!(buffer, "\n{}.{} {status}", result.module, result.name).expect("print_failures");
+        buffer.set_color(&ColorSpec::new()).expect("print_failures");
+        writeln!(buffer).expect("print_failures");
+        for line in result.message.lines() {
+            writeln!(buffer, "  {line}").expect("print_failures");
+        }
+    }
+    buffer_writer.print(&buffer).expect("print_failures");
+}
+
+fn summary(results: &[TestResult]) -> String {
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let word = if results.len() == 1 { "test" } else { "tests" };
+    let mut summary = format!(
+        "\n{total} {word}, {passed} passed, {failed} failed",
+        total = results.len(),
+        passed = count(Status::Passed),
+        failed = count(Status::Failed),
+    );
+    let timed_out = count(Status::TimedOut);
+    if timed_out > 0 {
+        write!(summary, ", {timed_out} timed out").expect("write summary");
+    }
+    summary
+}
+
+/// A JUnit XML report of the results, with a test suite for each module.
+fn junit_report(results: &[TestResult]) -> String {
+    let seconds = |duration: Duration| format!("{:.3}", duration.as_secs_f64());
+    let failures = |results: &[&TestResult]| {
+        results
+            .iter()
+            .filter(|result| result.status != Status::Passed)
+            .count()
+    };
+    let all: Vec<&TestResult> = results.iter().collect();
+    let total_time: Duration = results.iter().map(|result| result.duration).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        r#"<testsuites tests="{}" failures="{}" time="{}">"#,
+        results.len(),
+        failures(&all),
+        seconds(total_time),
+    )
+    .expect("write junit");
+
+    for (module, suite) in &all.iter().copied().group_by(|result| &result.module) {
+        let suite: Vec<&TestResult> = suite.collect();
+        let time: Duration = suite.iter().map(|result| result.duration).sum();
+        writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{}">"#,
+            xml_escape(module),
+            suite.len(),
+            failures(&suite),
+            seconds(time),
+        )
+        .expect("write junit");
+        for result in suite {
+            write!(
+                xml,
+                r#"    <testcase classname="{}" name="{}" time="{}""#,
+                xml_escape(module),
+                xml_escape(&result.name),
+                seconds(result.duration),
+            )
+            .expect("write junit");
+            let kind = match result.status {
+                Status::Passed => {
+                    xml.push_str(" />\n");
+                    continue;
+                }
+                Status::Failed => "failure",
+                Status::TimedOut => "timeout",
+            };
+            let first_line = result.message.lines().next().unwrap_or_default();
+            writeln!(
+                xml,
+                r#">
+      <failure type="{kind}" message="{}">{}</failure>
+    </testcase>"#,
+                xml_escape(first_line),
+                xml_escape(&result.message),
+            )
+            .expect("write junit");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(module: &str, name: &str) -> TestFunction {
+        TestFunction {
+            module: module.into(),
+            name: name.into(),
+        }
+    }
+
+    fn result(status: Status, module: &str, name: &str, message: &str) -> TestResult {
+        TestResult {
+            status,
+            module: module.into(),
+            name: name.into(),
+            duration: Duration::from_millis(5),
+            message: message.into(),
+        }
+    }
+
+    fn options() -> TestOptions {
+        TestOptions {
+            target: None,
+            runtime: None,
+            filters: vec![],
+            modules: vec![],
+            jobs: None,
+            timeout: 5,
+            junit: None,
+            main: false,
+            arguments: vec![],
+        }
+    }
+
+    #[test]
+    fn uses_runner_test() {
+        assert!(!options().uses_runner());
+        assert!(!TestOptions {
+            timeout: 10,
+            ..options()
+        }
+        .uses_runner());
+        assert!(TestOptions {
+            filters: vec!["wibble".into()],
+            ..options()
+        }
+        .uses_runner());
+        assert!(TestOptions {
+            modules: vec!["wibble_test".into()],
+            ..options()
+        }
+        .uses_runner());
+        assert!(TestOptions {
+            jobs: Some(1),
+            ..options()
+        }
+        .uses_runner());
+        assert!(TestOptions {
+            junit: Some("report.xml".into()),
+            ..options()
+        }
+        .uses_runner());
+    }
+
+    #[test]
+    fn select_all() {
+        let tests = vec![test("a_test", "one_test"), test("b_test", "two_test")];
+        assert_eq!(select(tests.clone(), &[], &[]), tests);
+    }
+
+    #[test]
+    fn select_by_filter() {
+        let tests = vec![
+            test("a_test", "parse_test"),
+            test("a_test", "print_test"),
+            test("parser_test", "one_test"),
+        ];
+        assert_eq!(
+            select(tests, &["parse".into()], &[]),
+            vec![
+                test("a_test", "parse_test"),
+                test("parser_test", "one_test")
+            ]
+        );
+    }
+
+    #[test]
+    fn select_by_module() {
+        let tests = vec![
+            test("a_test", "one_test"),
+            test("a/b_test", "two_test"),
+            test("c_test", "three_test"),
+        ];
+        assert_eq!(
+            select(tests, &[], &["a/b_test".into(), "c_test".into()]),
+            vec![test("a/b_test", "two_test"), test("c_test", "three_test")]
+        );
+    }
+
+    #[test]
+    fn select_by_module_and_filter() {
+        let tests = vec![
+            test("a_test", "one_test"),
+            test("a_test", "two_test"),
+            test("b_test", "one_test"),
+        ];
+        assert_eq!(
+            select(tests, &["one".into()], &["a_test".into()]),
+            vec![test("a_test", "one_test")]
+        );
+    }
+
+    #[test]
+    fn erlang_test_list_uses_erlang_module_names() {
+        let tests = vec![test("a_test", "one_test"), test("a/b_test", "two_test")];
+        assert_eq!(
+            erlang_test_list(&tests),
+            "a_test\tone_test\na@b_test\ttwo_test\n"
+        );
+    }
+
+    #[test]
+    fn javascript_runner_imports_each_module_once() {
+        let tests = vec![
+            test("a_test", "one_test"),
+            test("a_test", "two_test"),
+            test("a/b_test", "three_test"),
+        ];
+        let runner = javascript_runner(&tests, Utf8Path::new("/out/results"), 5000);
+        let header = runner
+            .split("// Runs each test")
+            .next()
+            .expect("runner header");
+        assert_eq!(
+            header,
+            r#"import * as $0 from "./a_test.mjs";
+import * as $1 from "./a/b_test.mjs";
+
+const tests = [
+  ["a_test", "one_test", $0.one_test],
+  ["a_test", "two_test", $0.two_test],
+  ["a/b_test", "three_test", $1.three_test],
+];
+const options = { timeout: 5000, results: "/out/results" };
+
+"#
+        );
+    }
+
+    #[test]
+    fn parse_results_test() {
+        let text = "passed\ta@b_test\tone_test\t5\t
+failed\ta_test\ttwo_test\t5\tpanic: oh no\\nvalue: 1\\t\\\\
+timed_out\ta_test\tthree_test\t5\tTimed out after 5000ms
+";
+        assert_eq!(
+            parse_results(text),
+            vec![
+                result(Status::Passed, "a/b_test", "one_test", ""),
+                result(
+                    Status::Failed,
+                    "a_test",
+                    "two_test",
+                    "panic: oh no\nvalue: 1\t\\"
+                ),
+                result(
+                    Status::TimedOut,
+                    "a_test",
+                    "three_test",
+                    "Timed out after 5000ms"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_test() {
+        let results = vec![
+            result(Status::Passed, "a_test", "one_test", ""),
+            result(Status::Failed, "a_test", "two_test", "panic"),
+        ];
+        assert_eq!(summary(&results), "\n2 tests, 1 passed, 1 failed");
+    }
+
+    #[test]
+    fn summary_with_timeouts_test() {
+        let results = vec![result(Status::TimedOut, "a_test", "one_test", "")];
+        assert_eq!(
+            summary(&results),
+            "\n1 test, 0 passed, 0 failed, 1 timed out"
+        );
+    }
+
+    #[test]
+    fn junit_report_test() {
+        let results = vec![
+            result(Status::Passed, "a_test", "one_test", ""),
+            result(Status::Failed, "a_test", "two_test", "panic: 1 < 2\nmore"),
+            result(Status::TimedOut, "b/c_test", "three_test", "Timed out"),
+        ];
+        assert_eq!(
+            junit_report(&results),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="2" time="0.015">
+  <testsuite name="a_test" tests="2" failures="1" time="0.010">
+    <testcase classname="a_test" name="one_test" time="0.005" />
+    <testcase classname="a_test" name="two_test" time="0.005">
+      <failure type="failure" message="panic: 1 &lt; 2">panic: 1 &lt; 2
+more</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="b/c_test" tests="1" failures="1" time="0.005">
+    <testcase classname="b/c_test" name="three_test" time="0.005">
+      <failure type="timeout" message="Timed out">Timed out</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gleam_core::paths::ProjectPaths;
+
+/// How often the project's files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Editors may write a file in several steps, so once a change has been seen
+/// we wait this long for any further changes before reporting it.
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// Set when the user presses Ctrl-C, after which the watcher stops waiting for
+/// changes.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Handle Ctrl-C by stopping the watcher rather than exiting, so that the
+/// caller can clean up, such as stopping a program it is running. A build in
+/// progress is finished first.
+pub fn stop_on_ctrl_c() {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| {
+        ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
+            .expect("Error setting Ctrl-C handler")
+    });
+}
+
+/// Watches the files of a project that affect its build: the `gleam.toml`
+/// config and the Gleam and native source files in `src` and `test`.
+///
+/// Files are polled for changes to their modification time rather than using
+/// filesystem notifications, which behave differently on each platform.
+///
+#[derive(Debug)]
+pub struct Watcher {
+    paths: ProjectPaths,
+    files: HashMap<Utf8PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    pub fn new(paths: &ProjectPaths) -> Self {
+        Self {
+            files: snapshot(paths),
+            paths: paths.clone(),
+        }
+    }
+
+    /// Blocks until one or more files are created, modified, or deleted,
+    /// returning the paths of the files that changed, or `None` if the user
+    /// pressed Ctrl-C.
+    pub fn wait_for_changes(&mut self) -> Option<Vec<Utf8PathBuf>> {
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return None;
+            }
+            if changed_files(&self.files, &snapshot(&self.paths)).is_empty() {
+                continue;
+            }
+
+            thread::sleep(SETTLE_DELAY);
+            let files = snapshot(&self.paths);
+            let changed = changed_files(&self.files, &files);
+            self.files = files;
+            return Some(changed);
+        }
+    }
+
+    /// Whether the project's config is one of the given changed files, in
+    /// which case its dependencies may need to be downloaded again.
+    pub fn config_changed(&self, changed: &[Utf8PathBuf]) -> bool {
+        let config = self.paths.root_config();
+        changed.iter().any(|path| path == &config)
+    }
+}
+
+fn snapshot(paths: &ProjectPaths) -> HashMap<Utf8PathBuf, SystemTime> {
+    let mut files = HashMap::new();
+    insert_modification_time(&mut files, &paths.root_config());
+
+    for directory in [paths.src_directory(), paths.test_directory()] {
+        for entry in walkdir::WalkDir::new(directory)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+        {
+            if let Some(path) = Utf8Path::from_path(entry.path()) {
+                insert_modification_time(&mut files, path);
+            }
+        }
+    }
+
+    files
+}
+
+fn insert_modification_time(files: &mut HashMap<Utf8PathBuf, SystemTime>, path: &Utf8Path) {
+    if let Ok(modified) = path.metadata().and_then(|metadata| metadata.modified()) {
+        let _ = files.insert(path.to_path_buf(), modified);
+    }
+}
+
+fn changed_files(
+    before: &HashMap<Utf8PathBuf, SystemTime>,
+    after: &HashMap<Utf8PathBuf, SystemTime>,
+) -> Vec<Utf8PathBuf> {
+    let modified_or_created = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone());
+    let deleted = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .cloned();
+
+    let mut changed: Vec<_> = modified_or_created.chain(deleted).collect();
+    changed.sort();
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> (tempfile::TempDir, ProjectPaths) {
+        let directory = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(directory.path().to_path_buf()).unwrap();
+        let paths = ProjectPaths::new(root);
+        std::fs::write(paths.root_config(), "name = \"wibble\"").unwrap();
+        std::fs::create_dir_all(paths.src_directory().join("wibble")).unwrap();
+        std::fs::write(paths.src_directory().join("wibble.gleam"), "").unwrap();
+        std::fs::write(paths.src_directory().join("wibble/ffi.mjs"), "").unwrap();
+        (directory, paths)
+    }
+
+    #[test]
+    fn snapshot_includes_config_and_source_files() {
+        let (_directory, paths) = project();
+        let mut files: Vec<_> = snapshot(&paths).into_keys().collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                paths.root_config(),
+                paths.src_directory().join("wibble/ffi.mjs"),
+                paths.src_directory().join("wibble.gleam"),
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_excludes_build_directory() {
+        let (_directory, paths) = project();
+        std::fs::create_dir_all(paths.build_directory()).unwrap();
+        std::fs::write(paths.build_directory().join("wobble.erl"), "").unwrap();
+        assert!(!snapshot(&paths).contains_key(&paths.build_directory().join("wobble.erl")));
+    }
+
+    #[test]
+    fn changed_files_created_modified_and_deleted() {
+        let time = SystemTime::UNIX_EPOCH;
+        let later = time + Duration::from_secs(1);
+        let before = HashMap::from([
+            (Utf8PathBuf::from("/src/unchanged.gleam"), time),
+            (Utf8PathBuf::from("/src/modified.gleam"), time),
+            (Utf8PathBuf::from("/src/deleted.gleam"), time),
+        ]);
+        let after = HashMap::from([
+            (Utf8PathBuf::from("/src/unchanged.gleam"), time),
+            (Utf8PathBuf::from("/src/modified.gleam"), later),
+            (Utf8PathBuf::from("/src/created.gleam"), later),
+        ]);
+        assert_eq!(
+            changed_files(&before, &after),
+            vec![
+                Utf8PathBuf::from("/src/created.gleam"),
+                Utf8PathBuf::from("/src/deleted.gleam"),
+                Utf8PathBuf::from("/src/modified.gleam"),
+            ]
+        );
+    }
+
+    #[test]
+    fn wait_for_changes_stops_when_interrupted() {
+        let (_directory, paths) = project();
+        let mut watcher = Watcher::new(&paths);
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        let changed = watcher.wait_for_changes();
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        assert_eq!(changed, None);
+    }
+
+    #[test]
+    fn config_changed() {
+        let (_directory, paths) = project();
+        let watcher = Watcher::new(&paths);
+        assert!(watcher.config_changed(&[paths.root_config()]));
+        assert!(!watcher.config_changed(&[paths.src_directory().join("wibble.gleam")]));
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+
+use camino::Utf8PathBuf;
+use ecow::EcoString;
+use gleam_core::{
+    config::{HexRepositoryConfig, PackageConfig, WorkspaceConfig},
+    manifest::{Manifest, ManifestPackage, ManifestPackageSource},
+    paths::ProjectPaths,
+    requirement::Requirement,
+    Error, Result,
+};
+use itertools::Itertools;
+
+use crate::fs;
+
+/// The name of the package that depends on every member of a workspace when
+/// the versions of their dependencies are resolved. It is not a valid package
+/// name, so it cannot clash with the name of a real package.
+const WORKSPACE_PACKAGE: &str = "gleam@workspace";
+
+/// A repository of several packages that share one manifest and one build
+/// directory, both at the root of the workspace.
+#[derive(Debug)]
+pub struct Workspace {
+    root: Utf8PathBuf,
+    config: WorkspaceConfig,
+}
+
+#[derive(Debug)]
+pub struct Member {
+    /// The directory of the member, relative to the root of the workspace.
+    pub path: Utf8PathBuf,
+    pub config: PackageConfig,
+}
+
+impl Workspace {
+    /// The workspace the project with the given paths is in, if any.
+    pub fn for_paths(paths: &ProjectPaths) -> Result<Option<Self>> {
+        let Some(root) = paths.workspace_root() else {
+            return Ok(None);
+        };
+        let config = WorkspaceConfig::read(root.join("gleam.toml"), &fs::ProjectIO::new())?;
+        Ok(config.map(|config| Self {
+            root: root.to_path_buf(),
+            config,
+        }))
+    }
+
+    /// The paths of the workspace as a whole, used when resolving and
+    /// downloading the dependencies of its members.
+    pub fn paths(&self) -> ProjectPaths {
+        ProjectPaths::in_workspace(self.root.clone(), self.root.clone())
+    }
+
+    pub fn members(&self) -> Result<Vec<Member>> {
+        self.config
+            .members
+            .iter()
+            .map(|path| {
+                let config = crate::config::read(self.root.join(path).join("gleam.toml"))?;
+                Ok(Member {
+                    path: path.clone(),
+                    config,
+                })
+            })
+            .collect()
+    }
+
+    /// The members with the given names, or every member if no names are
+    /// given.
+    pub fn select_members(&self, names: &[String]) -> Result<Vec<Member>> {
+        let members = self.members()?;
+        let unknown = names.iter().find(|name| {
+            !members
+                .iter()
+                .any(|member| member.config.name == name.as_str())
+        });
+        if let Some(name) = unknown {
+            return Err(Error::UnknownWorkspaceMember {
+                package: name.into(),
+                members: members
+                    .iter()
+                    .map(|member| member.config.name.clone())
+                    .collect(),
+            });
+        }
+        Ok(members
+            .into_iter()
+            .filter(|member| {
+                names.is_empty() || names.iter().any(|name| member.config.name == name.as_str())
+            })
+            .collect())
+    }
+}
+
+/// The config dependency resolution is performed with for a workspace. It
+/// depends on each of the members, whose dev dependencies are resolved along
+/// with their other dependencies as every member is built using the shared
+/// manifest. The Hex repositories of the members are combined, so members
+/// that define a repository of the same name must define it the same way.
+pub fn package_config(members: &[Member]) -> Result<PackageConfig> {
+    let mut config = PackageConfig {
+        name: WORKSPACE_PACKAGE.into(),
+        ..Default::default()
+    };
+    // The member each repository was first defined by.
+    let mut repositories: HashMap<EcoString, (&EcoString, &HexRepositoryConfig)> = HashMap::new();
+    for member in members {
+        let requirement = Requirement::Path {
+            path: member.path.clone(),
+        };
+        let _ = config
+            .dependencies
+            .insert(member.config.name.clone(), requirement);
+        for (name, repository) in &member.config.hex_repositories {
+            match repositories.get(name) {
+                None => {
+                    let _ = repositories.insert(name.clone(), (&member.config.name, repository));
+                }
+                Some((_, existing)) if *existing == repository => (),
+                Some((package, _)) => {
+                    return Err(Error::ConflictingHexRepositories {
+                        repository: name.clone(),
+                        packages: ((*package).clone(), member.config.name.clone()),
+                    })
+                }
+            }
+        }
+    }
+    config.hex_repositories = repositories
+        .into_iter()
+        .map(|(name, (_, repository))| (name, repository.clone()))
+        .collect();
+    Ok(config)
+}
+
+/// The requirements of every member of a workspace, other than those on the
+/// members themselves. A package may be required by several members, with a
+/// requirement from each of them.
+pub fn requirements(members: &[Member]) -> Result<Vec<(EcoString, Requirement)>> {
+    let mut requirements = Vec::new();
+    for member in members {
+        let dependencies = member.config.all_dependencies()?;
+        requirements.extend(
+            dependencies
+                .into_iter()
+                .filter(|(name, _)| !members.iter().any(|member| &member.config.name == name))
+                .sorted_by(|(a, _), (b, _)| a.cmp(b)),
+        );
+    }
+    Ok(requirements)
+}
+
+/// The packages to resolve anew if the manifest no longer meets the
+/// requirements of the members of the workspace, or `None` if it does. These
+/// are the members themselves and any package that is locked to a version a
+/// member no longer accepts.
+pub fn outdated_packages(
+    members: &[Member],
+    manifest: &Manifest,
+) -> Result<Option<Vec<EcoString>>> {
+    let locked = |name: &str| {
+        manifest
+            .packages
+            .iter()
+            .find(|package| package.name == name)
+    };
+    let mut outdated = false;
+    let mut unlocked = Vec::new();
+
+    for member in members {
+        let requirements = member.config.all_dependencies()?;
+        let up_to_date = locked(&member.config.name).is_some_and(|package| {
+            package.version == member.config.version
+                && package.requirements.len() == requirements.len()
+                && package
+                    .requirements
+                    .iter()
+                    .all(|name| requirements.contains_key(name))
+        });
+        outdated |= !up_to_date;
+
+        for (name, requirement) in requirements {
+            if !locked(&name).is_some_and(|package| satisfies(package, &requirement)) {
+                outdated = true;
+                if !unlocked.contains(&name) {
+                    unlocked.push(name);
+                }
+            }
+        }
+    }
+
+    if !outdated {
+        return Ok(None);
+    }
+    unlocked.extend(members.iter().map(|member| member.config.name.clone()));
+    Ok(Some(unlocked))
+}
+
+fn satisfies(package: &ManifestPackage, requirement: &Requirement) -> bool {
+    match (requirement, &package.source) {
+        (Requirement::Hex { version, .. }, ManifestPackageSource::Hex { .. }) => version
+            .to_pubgrub()
+            .is_ok_and(|range| range.contains(&package.version)),
+        (Requirement::Path { .. }, ManifestPackageSource::Local { .. }) => true,
+        (Requirement::Git { git, .. }, ManifestPackageSource::Git { repo, .. }) => git == repo,
+        _ => false,
+    }
+}
+
+/// Runs a command with the paths of each selected member of the workspace the
+/// current directory is in. With no members selected the command is run for
+/// every member at the root of a workspace, and for the current project
+/// anywhere else.
+pub fn for_each_member(
+    selected: &[String],
+    mut command: impl FnMut(&ProjectPaths) -> Result<()>,
+) -> Result<()> {
+    let paths = crate::find_project_paths()?;
+    let workspace = Workspace::for_paths(&paths)?;
+    let members = match workspace {
+        None => match selected.first() {
+            None => return command(&paths),
+            Some(package) => {
+                return Err(Error::UnknownWorkspaceMember {
+                    package: package.into(),
+                    members: vec![],
+                })
+            }
+        },
+        Some(_) if selected.is_empty() && paths.workspace_root() != Some(paths.root()) => {
+            return command(&paths)
+        }
+        Some(workspace) => workspace.select_members(selected)?,
+    };
+
+    let workspace_root = paths.shared_root();
+    for member in members {
+        let member_paths = ProjectPaths::in_workspace(
+            workspace_root.join(&member.path),
+            workspace_root.to_path_buf(),
+        );
+        command(&member_paths)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hexpm::version::Version;
+
+    fn member(name: &str, dependencies: &[(&str, Requirement)]) -> Member {
+        let config = PackageConfig {
+            name: name.into(),
+            version: Version::new(1, 0, 0),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, requirement)| ((*name).into(), requirement.clone()))
+                .collect(),
+            ..Default::default()
+        };
+        Member {
+            path: Utf8PathBuf::from("packages").join(name),
+            config,
+        }
+    }
+
+    fn package(name: &str, version: Version, requirements: &[&str]) -> ManifestPackage {
+        ManifestPackage {
+            name: name.into(),
+            version,
+            build_tools: vec!["gleam".into()],
+            otp_app: None,
+            requirements: requirements.iter().map(|name| (*name).into()).collect(),
+            source: ManifestPackageSource::Hex {
+                outer_checksum: gleam_core::manifest::Base16Checksum(vec![]),
+                repository: None,
+            },
+        }
+    }
+
+    fn local_package(name: &str, requirements: &[&str]) -> ManifestPackage {
+        ManifestPackage {
+            source: ManifestPackageSource::Local {
+                path: Utf8PathBuf::from("packages").join(name),
+            },
+            ..package(name, Version::new(1, 0, 0), requirements)
+        }
+    }
+
+    fn members() -> Vec<Member> {
+        vec![
+            member(
+                "app",
+                &[
+                    ("app_core", Requirement::path("packages/app_core")),
+                    ("gleam_stdlib", Requirement::hex("~> 0.38")),
+                ],
+            ),
+            member("app_core", &[("gleam_stdlib", Requirement::hex("~> 0.38"))]),
+        ]
+    }
+
+    fn manifest(stdlib: Version) -> Manifest {
+        Manifest {
+            requirements: package_config(&members())
+                .unwrap()
+                .all_dependencies()
+                .unwrap(),
+            packages: vec![
+                local_package("app", &["app_core", "gleam_stdlib"]),
+                local_package("app_core", &["gleam_stdlib"]),
+                package("gleam_stdlib", stdlib, &[]),
+            ],
+        }
+    }
+
+    #[test]
+    fn workspace_package_config_depends_on_members() {
+        let config = package_config(&members()).unwrap();
+        assert_eq!(config.name, WORKSPACE_PACKAGE);
+        assert_eq!(
+            config.dependencies,
+            [
+                ("app".into(), Requirement::path("packages/app")),
+                ("app_core".into(), Requirement::path("packages/app_core")),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn manifest_meeting_member_requirements_is_up_to_date() {
+        let manifest = manifest(Version::new(0, 38, 0));
+        assert_eq!(outdated_packages(&members(), &manifest).unwrap(), None);
+    }
+
+    #[test]
+    fn manifest_not_meeting_member_requirements_is_outdated() {
+        let manifest = manifest(Version::new(0, 37, 0));
+        assert_eq!(
+            outdated_packages(&members(), &manifest).unwrap(),
+            Some(vec!["gleam_stdlib".into(), "app".into(), "app_core".into(),])
+        );
+    }
+
+    #[test]
+    fn manifest_missing_member_requirement_is_outdated() {
+        let mut members = members();
+        members.push(member("app_web", &[("mist", Requirement::hex("~> 1.0"))]));
+        let manifest = manifest(Version::new(0, 38, 0));
+        assert_eq!(
+            outdated_packages(&members, &manifest).unwrap(),
+            Some(vec![
+                "mist".into(),
+                "app".into(),
+                "app_core".into(),
+                "app_web".into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn workspace_requirements_include_those_of_every_member() {
+        let members = vec![
+            member("app", &[("gleam_stdlib", Requirement::hex(">= 0.38.0"))]),
+            member(
+                "app_core",
+                &[
+                    ("app", Requirement::path("packages/app")),
+                    ("gleam_stdlib", Requirement::hex("< 0.40.0")),
+                ],
+            ),
+        ];
+        assert_eq!(
+            requirements(&members).unwrap(),
+            vec![
+                ("gleam_stdlib".into(), Requirement::hex(">= 0.38.0")),
+                ("gleam_stdlib".into(), Requirement::hex("< 0.40.0")),
+            ]
+        );
+    }
+
+    fn with_repository(mut member: Member, url: &str) -> Member {
+        let repository = HexRepositoryConfig {
+            url: url.parse().unwrap(),
+            api_url: None,
+            public_key: None,
+            auth_key_env: None,
+        };
+        let _ = member
+            .config
+            .hex_repositories
+            .insert("acme".into(), repository);
+        member
+    }
+
+    #[test]
+    fn workspace_package_config_combines_hex_repositories() {
+        let members = vec![
+            with_repository(member("app", &[]), "https://hex.acme.com/repo"),
+            with_repository(member("app_core", &[]), "https://hex.acme.com/repo"),
+        ];
+        let config = package_config(&members).unwrap();
+        assert_eq!(
+            config.hex_repositories.keys().collect_vec(),
+            vec![&EcoString::from("acme")]
+        );
+    }
+
+    #[test]
+    fn workspace_package_config_rejects_conflicting_hex_repositories() {
+        let members = vec![
+            with_repository(member("app", &[]), "https://hex.acme.com/repo"),
+            with_repository(member("app_core", &[]), "https://hex.wibble.com/repo"),
+        ];
+        assert_eq!(
+            package_config(&members).unwrap_err(),
+            Error::ConflictingHexRepositories {
+                repository: "acme".into(),
+                packages: ("app".into(), "app_core".into()),
+            }
+        );
+    }
+}
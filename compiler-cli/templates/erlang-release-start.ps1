@@ -0,0 +1,39 @@
+$ErrorActionPreference = "Stop"
+
+$Release = "$RELEASE_NAME_FROM_GLEAM"
+$Root = Split-Path -Path $PSScriptRoot -Parent
+$StartErlData = Get-Content -Path (Join-Path -Path $Root -ChildPath "releases\start_erl.data")
+$ErtsVersion, $Version = $StartErlData.Trim() -split " "
+$ReleaseDirectory = Join-Path -Path $Root -ChildPath "releases\$Version"
+$ScriptCommand = $args[0]
+
+function Start-Release {
+  erl `
+    -boot "$ReleaseDirectory\$Release" `
+    -boot_var RELEASE_ROOT "$Root" `
+    -config "$ReleaseDirectory\sys.config" `
+    -args_file "$ReleaseDirectory\vm.args" `
+    @args
+}
+
+switch ($ScriptCommand) {
+  "run" {
+    Start-Release -eval "$Release@@main:run($Release)" -noshell -extra $args[1..($args.Length - 1)]
+  }
+  "start" {
+    Start-Release -noshell
+  }
+  "console" {
+    Start-Release
+  }
+  default {
+    Write-Host "usage:"
+    Write-Host "  $Release.ps1 `$COMMAND"
+    Write-Host ""
+    Write-Host "commands:"
+    Write-Host "  run      Start the release and run the project main function"
+    Write-Host "  start    Start the release's applications in the foreground"
+    Write-Host "  console  Start the release with an Erlang shell"
+    exit 1
+  }
+}
@@ -0,0 +1,42 @@
+#!/bin/sh
+set -eu
+
+RELEASE=$RELEASE_NAME_FROM_GLEAM
+ROOT=$(cd "$(dirname "$0")/.." && pwd)
+read -r _ERTS_VERSION VERSION < "$ROOT/releases/start_erl.data"
+RELEASE_DIRECTORY="$ROOT/releases/$VERSION"
+COMMAND="${1-default}"
+
+erl_release() {
+  erl \
+    -boot "$RELEASE_DIRECTORY/$RELEASE" \
+    -boot_var RELEASE_ROOT "$ROOT" \
+    -config "$RELEASE_DIRECTORY/sys.config" \
+    -args_file "$RELEASE_DIRECTORY/vm.args" \
+    "$@"
+}
+
+case "$COMMAND" in
+  run)
+    shift
+    erl_release -eval "$RELEASE@@main:run($RELEASE)" -noshell -extra "$@"
+  ;;
+
+  start)
+    erl_release -noshell
+  ;;
+
+  console)
+    erl_release
+  ;;
+
+  *)
+    echo "usage:" >&2
+    echo "  $RELEASE \$COMMAND" >&2
+    echo "" >&2
+    echo "commands:" >&2
+    echo "  run      Start the release and run the project main function" >&2
+    echo "  start    Start the release's applications in the foreground" >&2
+    echo "  console  Start the release with an Erlang shell" >&2
+    exit 1
+esac
@@ -0,0 +1,8 @@
+-module('$PACKAGE_NAME_FROM_GLEAM@@escript').
+-export([main/1]).
+
+% Called by escript with the command line arguments given to the escript. The
+% program reads them as it does under `gleam run`, from the plain arguments of
+% the VM, where escript puts them after the path of the escript itself.
+main(_Arguments) ->
+    '$PACKAGE_NAME_FROM_GLEAM@@main':run('$PACKAGE_NAME_FROM_GLEAM').
@@ -0,0 +1,58 @@
+// Runs each test in turn, printing `.` for each one that passes and `F` for
+// each one that fails, then writes the outcome of each test to a file for
+// `gleam test` to report on.
+//
+// `tests` and `options` are defined by `gleam test` above this code.
+
+import { writeFileSync } from "node:fs";
+import process from "node:process";
+
+const timedOut = Symbol("timed out");
+
+// Only a test that returns a promise can be timed out, as there is no way to
+// interrupt synchronous code.
+function withTimeout(value) {
+  if (!(value instanceof Promise)) return value;
+  let timer;
+  const timeout = new Promise((_, reject) => {
+    timer = setTimeout(() => reject(timedOut), options.timeout);
+  });
+  return Promise.race([value, timeout]).finally(() => clearTimeout(timer));
+}
+
+function formatError(error) {
+  if (error?.gleam_error) return `${error.gleam_error}: ${error.message}`;
+  return error?.stack ?? String(error);
+}
+
+function escape(message) {
+  return message
+    .replaceAll("\\", "\\\\")
+    .replaceAll("\n", "\\n")
+    .replaceAll("\t", "\\t");
+}
+
+const results = [];
+for (const [module, name, test] of tests) {
+  const started = Date.now();
+  let status = "passed";
+  let message = "";
+  try {
+    await withTimeout(test());
+  } catch (error) {
+    if (error === timedOut) {
+      status = "timed_out";
+      message = `Timed out after ${options.timeout}ms`;
+    } else {
+      status = "failed";
+      message = formatError(error);
+    }
+  }
+  process.stdout.write(status === "passed" ? "." : "F");
+  const duration = Date.now() - started;
+  results.push([status, module, name, duration, escape(message)].join("\t"));
+}
+
+process.stdout.write("\n");
+writeFileSync(options.results, results.map((result) => result + "\n").join(""));
+process.exit(0);
@@ -0,0 +1,45 @@
+#!/usr/bin/env escript
+
+% Writes an escript that runs the main function of a package, containing the
+% compiled modules and priv directories of the given applications.
+%
+% Usage: gleam@@escript.erl OUT PACKAGE BUILD ENTRYPOINT APPLICATION...
+%
+% BUILD is the directory the applications were compiled into. The archive of
+% the escript has a directory for each application, so their ebin directories
+% are added to the code path when the escript is run. ENTRYPOINT is the source
+% of the module escript calls with the command line arguments, which is
+% compiled into the ebin directory of the package.
+
+main([Out, Package, Build, Entrypoint | Applications]) ->
+    Files = lists:flatmap(fun(Application) -> files(Build, Application) end, Applications),
+    {Module, Beam} = compile(Entrypoint),
+    BeamPath = filename:join([Package, "ebin", atom_to_list(Module) ++ ".beam"]),
+    Options = [
+        shebang,
+        {emu_args, "-escript main " ++ atom_to_list(Module)},
+        {archive, [{BeamPath, Beam} | Files], []}
+    ],
+    ok = escript:create(Out, Options).
+
+compile(Path) ->
+    case compile:file(Path, [binary, report]) of
+        {ok, Module, Beam} ->
+            {Module, Beam};
+        error ->
+            erlang:halt(1)
+    end.
+
+% The ebin and priv files of an application, named by their paths in the
+% archive.
+files(Build, Application) ->
+    Directory = filename:join(Build, Application),
+    Paths = filelib:wildcard("{ebin,priv}/**", Directory),
+    [
+        {filename:join(Application, Path), read(filename:join(Directory, Path))}
+     || Path <- Paths, filelib:is_regular(filename:join(Directory, Path))
+    ].
+
+read(Path) ->
+    {ok, Binary} = file:read_file(Path),
+    Binary.
@@ -0,0 +1,66 @@
+#!/usr/bin/env escript
+
+% Writes the `.rel` file and boot script of an OTP release, along with the
+% `start_erl.data` file recording the versions of ERTS and of the release.
+%
+% Usage: gleam@@release.erl ROOT NAME VERSION APPLICATION...
+%
+% The given applications must be in the `lib` directory of the release root,
+% each in a directory named after the application and its version. Any OTP
+% applications they depend on are taken from the Erlang installation.
+
+main([Root, Name, Version | Applications]) ->
+    Lib = filename:join(Root, "lib"),
+    ok = code:add_pathsa(filelib:wildcard(filename:join([Lib, "*", "ebin"]))),
+    Included = dependencies([kernel, stdlib | [list_to_atom(A) || A <- Applications]], []),
+    Erts = erlang:system_info(version),
+    Release = {release, {Name, Version}, {erts, Erts}, [{A, version(A)} || A <- Included]},
+    ReleaseDirectory = filename:join([Root, "releases", Version]),
+    ok = filelib:ensure_dir(filename:join(ReleaseDirectory, "start_erl.data")),
+    RelFile = filename:join(ReleaseDirectory, Name),
+    ok = file:write_file(RelFile ++ ".rel", io_lib:format("~tp.~n", [Release])),
+    Options = [
+        {path, [filename:join([Lib, "*", "ebin"])]},
+        {outdir, ReleaseDirectory},
+        {variables, [{"RELEASE_ROOT", Root}]},
+        no_warn_sasl,
+        silent
+    ],
+    case systools:make_script(RelFile, Options) of
+        {ok, _Module, _Warnings} ->
+            ok;
+        {error, Module, Error} ->
+            io:format(standard_error, "~ts~n", [Module:format_error(Error)]),
+            erlang:halt(1)
+    end,
+    StartErlData = filename:join([Root, "releases", "start_erl.data"]),
+    ok = file:write_file(StartErlData, [Erts, " ", Version, "\n"]).
+
+% The given applications and every application they depend on.
+dependencies([], Seen) ->
+    lists:reverse(Seen);
+dependencies([Application | Rest], Seen) ->
+    case lists:member(Application, Seen) of
+        true ->
+            dependencies(Rest, Seen);
+        false ->
+            load(Application),
+            {ok, Required} = application:get_key(Application, applications),
+            {ok, Included} = application:get_key(Application, included_applications),
+            dependencies(Required ++ Included ++ Rest, [Application | Seen])
+    end.
+
+load(Application) ->
+    case application:load(Application) of
+        ok ->
+            ok;
+        {error, {already_loaded, Application}} ->
+            ok;
+        {error, Reason} ->
+            io:format(standard_error, "Unable to load application ~p: ~p~n", [Application, Reason]),
+            erlang:halt(1)
+    end.
+
+version(Application) ->
+    {ok, Version} = application:get_key(Application, vsn),
+    Version.
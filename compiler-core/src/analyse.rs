@@ -144,12 +144,27 @@ impl<'a, A> ModuleAnalyzerConstructor<'a, A> {
         line_numbers: LineNumbers,
         src_path: Utf8PathBuf,
     ) -> Outcome<TypedModule, Vec1<Error>> {
-        ModuleAnalyzer {
+        // Warnings silenced with `@allow` are dropped by this emitter, which
+        // keeps track of the attributes that silenced nothing.
+        let warnings = self.warnings.with_suppressions(
+            module
+                .definitions
+                .iter()
+                .filter(|definition| definition.is_for(self.target))
+                .map(|definition| {
+                    (
+                        definition.definition.full_location(),
+                        definition.allowed_warnings.as_slice(),
+                    )
+                }),
+        );
+
+        let outcome = ModuleAnalyzer {
             target: self.target,
             ids: self.ids,
             origin: self.origin,
             importable_modules: self.importable_modules,
-            warnings: self.warnings,
+            warnings: &warnings,
             direct_dependencies: self.direct_dependencies,
             target_support: self.target_support,
             package_config: self.package_config,
@@ -161,7 +176,21 @@ impl<'a, A> ModuleAnalyzerConstructor<'a, A> {
             hydrators: HashMap::with_capacity(module.definitions.len()),
             module_name: module.name.clone(),
         }
-        .infer_module(module)
+        .infer_module(module);
+
+        // If analysis failed some warnings may not have been emitted, so only
+        // a successfully analysed module can have unused suppressions.
+        if let Outcome::Ok(_) = outcome {
+            for allowed in warnings.unused_suppressions() {
+                self.warnings
+                    .emit(type_::Warning::UnusedWarningSuppression {
+                        location: allowed.location,
+                        name: allowed.name,
+                    });
+            }
+        }
+
+        outcome
     }
 }
 
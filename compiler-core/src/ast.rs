@@ -12,7 +12,7 @@ pub use self::untyped::{UntypedExpr, Use};
 pub use self::constant::{Constant, TypedConstant, UntypedConstant};
 
 use crate::analyse::Inferred;
-use crate::build::{Located, Target};
+use crate::build::{BitArraySegmentOption, Located, StringPrefixSide, Target};
 use crate::type_::expression::Implementations;
 use crate::type_::{
     self, Deprecation, ModuleValueConstructor, PatternConstructor, Type, ValueConstructor,
@@ -70,6 +70,21 @@ impl TypedModule {
 pub struct TargetedDefinition {
     pub definition: UntypedDefinition,
     pub target: Option<Target>,
+    pub allowed_warnings: Vec<AllowedWarning>,
+}
+
+/// A kind of warning silenced for a single definition with the `@allow`
+/// attribute.
+///
+/// ```gleam
+/// @allow(todo, unused_variable)
+/// pub fn main(a) { todo }
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllowedWarning {
+    pub location: SrcSpan,
+    pub name: EcoString,
 }
 
 impl TargetedDefinition {
@@ -483,7 +498,7 @@ pub type TypedFunction = Function<Arc<Type>, TypedExpr>;
 pub type UntypedFunction = Function<(), UntypedExpr>;
 
 impl<T, E> Function<T, E> {
-    fn full_location(&self) -> SrcSpan {
+    pub fn full_location(&self) -> SrcSpan {
         SrcSpan::new(self.location.start, self.end_position)
     }
 }
@@ -799,6 +814,21 @@ impl<A, B, C, E> Definition<A, B, C, E> {
         }
     }
 
+    /// The location of the entire definition, including the body of a
+    /// function, the constructors of a custom type and the value of a
+    /// constant.
+    pub fn full_location(&self) -> SrcSpan {
+        match self {
+            Definition::Function(function) => function.full_location(),
+            Definition::CustomType(custom_type) => custom_type.full_location(),
+            Definition::ModuleConstant(ModuleConstant {
+                location, value, ..
+            }) => SrcSpan::new(location.start, value.location().end),
+            Definition::Import(Import { location, .. })
+            | Definition::TypeAlias(TypeAlias { location, .. }) => *location,
+        }
+    }
+
     /// Returns `true` if the definition is [`Import`].
     ///
     /// [`Import`]: Definition::Import
@@ -1507,7 +1537,6 @@ impl TypedPattern {
             | Pattern::Float { .. }
             | Pattern::String { .. }
             | Pattern::Variable { .. }
-            | Pattern::VarUsage { .. }
             | Pattern::Assign { .. }
             | Pattern::Discard { .. }
             | Pattern::List { .. }
@@ -1515,6 +1544,10 @@ impl TypedPattern {
             | Pattern::BitArray { .. }
             | Pattern::StringPrefix { .. } => None,
 
+            Pattern::VarUsage { constructor, .. } => constructor
+                .as_ref()
+                .map(|constructor| constructor.definition_location()),
+
             Pattern::Constructor { constructor, .. } => constructor.definition_location(),
         }
     }
@@ -1577,9 +1610,34 @@ impl TypedPattern {
             | Pattern::Variable { .. }
             | Pattern::VarUsage { .. }
             | Pattern::Assign { .. }
-            | Pattern::Discard { .. }
-            | Pattern::BitArray { .. }
-            | Pattern::StringPrefix { .. } => Some(Located::Pattern(self)),
+            | Pattern::Discard { .. } => Some(Located::Pattern(self)),
+
+            Pattern::BitArray { segments, .. } => segments
+                .iter()
+                .find_map(|segment| segment.find_node(byte_index)),
+
+            Pattern::StringPrefix {
+                left_location,
+                left_side_assignment,
+                right_location,
+                left_side_string,
+                ..
+            } => {
+                if left_location.contains(byte_index) {
+                    Some(Located::StringPrefix(StringPrefixSide::Prefix {
+                        location: *left_location,
+                        prefix: left_side_string,
+                        assignment: left_side_assignment.as_ref().map(|(name, _)| name),
+                    }))
+                } else if right_location.contains(byte_index) {
+                    Some(Located::StringPrefix(StringPrefixSide::Rest {
+                        location: *right_location,
+                        prefix: left_side_string,
+                    }))
+                } else {
+                    None
+                }
+            }
 
             Pattern::Constructor { arguments, .. } => {
                 arguments.iter().find_map(|arg| arg.find_node(byte_index))
@@ -1642,7 +1700,45 @@ pub struct BitArraySegment<Value, Type> {
 
 impl TypedExprBitArraySegment {
     pub fn find_node(&self, byte_index: u32) -> Option<Located<'_>> {
-        self.value.find_node(byte_index)
+        self.value
+            .find_node(byte_index)
+            .or_else(|| {
+                self.options
+                    .iter()
+                    .find_map(|option| option.value()?.find_node(byte_index))
+            })
+            .or_else(|| self.find_option(byte_index))
+    }
+}
+
+impl TypedPatternBitArraySegment {
+    fn find_node(&self, byte_index: u32) -> Option<Located<'_>> {
+        self.value
+            .find_node(byte_index)
+            .or_else(|| {
+                self.options
+                    .iter()
+                    .find_map(|option| option.value()?.find_node(byte_index))
+            })
+            .or_else(|| self.find_option(byte_index))
+    }
+}
+
+impl<Value> BitArraySegment<Value, Arc<Type>>
+where
+    Value: crate::bit_array::GetLiteralValue,
+{
+    fn find_option(&self, byte_index: u32) -> Option<Located<'_>> {
+        let option = self
+            .options
+            .iter()
+            .find(|option| option.location().contains(byte_index))?;
+        Some(Located::BitArraySegmentOption(BitArraySegmentOption {
+            location: option.location(),
+            documentation: option.documentation(),
+            segment_type: self.type_.clone(),
+            segment_size: crate::bit_array::segment_size_in_bits(&self.options),
+        }))
     }
 }
 
@@ -1773,6 +1869,36 @@ impl<A> BitArrayOption<A> {
             BitArrayOption::Unit { .. } => "unit".into(),
         }
     }
+
+    pub fn documentation(&self) -> &'static str {
+        match self {
+            BitArrayOption::Bytes { .. } => {
+                "The segment is a bit array with a whole number of bytes."
+            }
+            BitArrayOption::Int { .. } => "The segment is an integer.",
+            BitArrayOption::Float { .. } => "The segment is a float.",
+            BitArrayOption::Bits { .. } => "The segment is a bit array.",
+            BitArrayOption::Utf8 { .. } => "The segment is a string encoded as UTF-8.",
+            BitArrayOption::Utf16 { .. } => "The segment is a string encoded as UTF-16.",
+            BitArrayOption::Utf32 { .. } => "The segment is a string encoded as UTF-32.",
+            BitArrayOption::Utf8Codepoint { .. } => "The segment is a codepoint encoded as UTF-8.",
+            BitArrayOption::Utf16Codepoint { .. } => {
+                "The segment is a codepoint encoded as UTF-16."
+            }
+            BitArrayOption::Utf32Codepoint { .. } => {
+                "The segment is a codepoint encoded as UTF-32."
+            }
+            BitArrayOption::Signed { .. } => "The integer is signed.",
+            BitArrayOption::Unsigned { .. } => "The integer is unsigned.",
+            BitArrayOption::Big { .. } => "The value is stored in big-endian byte order.",
+            BitArrayOption::Little { .. } => "The value is stored in little-endian byte order.",
+            BitArrayOption::Native { .. } => {
+                "The value is stored in the byte order of the machine running the program."
+            }
+            BitArrayOption::Size { .. } => "The number of units in the segment.",
+            BitArrayOption::Unit { .. } => "The number of bits in each unit of the segment's size.",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +27,41 @@ where
     type_options(input_options, false, must_have_size)
 }
 
+/// The size of a segment in bits, if it is known at compile time and fits in
+/// an `i64`.
+pub fn segment_size_in_bits<TypedValue>(options: &[BitArrayOption<TypedValue>]) -> Option<i64>
+where
+    TypedValue: GetLiteralValue,
+{
+    use BitArrayOption::*;
+
+    let typ = options.iter().find(|option| {
+        !matches!(
+            option,
+            Signed { .. }
+                | Unsigned { .. }
+                | Big { .. }
+                | Little { .. }
+                | Native { .. }
+                | Size { .. }
+                | Unit { .. }
+        )
+    });
+    let size = options.iter().find_map(|option| option.value());
+    let unit = options.iter().find_map(|option| match option {
+        Unit { value, .. } => Some(i64::from(*value)),
+        _ => None,
+    });
+
+    match (typ, size) {
+        (Some(Bytes { .. }), Some(size)) => size.as_int_literal()?.checked_mul(unit.unwrap_or(8)),
+        (_, Some(size)) => size.as_int_literal()?.checked_mul(unit.unwrap_or(1)),
+        (None | Some(Int { .. }), None) => Some(8),
+        (Some(Float { .. }), None) => Some(64),
+        (Some(_), None) => None,
+    }
+}
+
 struct SegmentOptionCategories<'a, T> {
     typ: Option<&'a BitArrayOption<T>>,
     signed: Option<&'a BitArrayOption<T>>,
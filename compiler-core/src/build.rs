@@ -298,6 +298,44 @@ pub struct UnqualifiedImport<'a> {
     pub location: &'a SrcSpan,
 }
 
+/// An option of a bit array segment, such as `size(8)` or `little`, along
+/// with what is known about the segment it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitArraySegmentOption {
+    pub location: SrcSpan,
+    pub documentation: &'static str,
+    /// The type of the value held by the segment.
+    pub segment_type: std::sync::Arc<type_::Type>,
+    /// The size of the segment in bits, if it is known at compile time.
+    pub segment_size: Option<i64>,
+}
+
+/// One side of a string prefix pattern such as `"Hello, " as greeting <> name`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPrefixSide<'a> {
+    /// The prefix string, along with the name it is assigned to if any.
+    Prefix {
+        location: SrcSpan,
+        prefix: &'a EcoString,
+        assignment: Option<&'a EcoString>,
+    },
+    /// The name the rest of the string is assigned to.
+    Rest {
+        location: SrcSpan,
+        prefix: &'a EcoString,
+    },
+}
+
+impl StringPrefixSide<'_> {
+    pub fn location(&self) -> SrcSpan {
+        match self {
+            StringPrefixSide::Prefix { location, .. } | StringPrefixSide::Rest { location, .. } => {
+                *location
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Located<'a> {
     Pattern(&'a TypedPattern),
@@ -308,6 +346,8 @@ pub enum Located<'a> {
     Arg(&'a TypedArg),
     Annotation(SrcSpan, std::sync::Arc<type_::Type>),
     UnqualifiedImport(UnqualifiedImport<'a>),
+    BitArraySegmentOption(BitArraySegmentOption),
+    StringPrefix(StringPrefixSide<'a>),
 }
 
 impl<'a> Located<'a> {
@@ -358,7 +398,7 @@ impl<'a> Located<'a> {
                     })
                 }
             }),
-            Self::Arg(_) => None,
+            Self::Arg(_) | Self::BitArraySegmentOption(_) | Self::StringPrefix(_) => None,
             Self::Annotation(_, type_) => self.type_location(importable_modules, type_.clone()),
         }
     }
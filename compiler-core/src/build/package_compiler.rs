@@ -103,6 +103,9 @@ where
             return e.into();
         }
 
+        // Each package reports warnings at the levels given in its own config.
+        let warnings = &warnings.with_levels(self.config.warnings.clone());
+
         let artefact_directory = self.out.join(paths::ARTEFACT_DIRECTORY_NAME);
         let codegen_required = if self.perform_codegen {
             CodegenRequired::Yes
@@ -166,7 +169,7 @@ where
             return error.into();
         }
 
-        if let Err(error) = self.encode_and_write_metadata(&modules) {
+        if let Err(error) = self.encode_and_write_metadata(&modules, incomplete_modules) {
             return error.into();
         }
 
@@ -252,7 +255,11 @@ where
         Ok(())
     }
 
-    fn encode_and_write_metadata(&mut self, modules: &[Module]) -> Result<()> {
+    fn encode_and_write_metadata(
+        &mut self,
+        modules: &[Module],
+        incomplete_modules: &HashSet<EcoString>,
+    ) -> Result<()> {
         if !self.write_metadata {
             tracing::debug!("package_metadata_writing_disabled");
             return Ok(());
@@ -265,6 +272,10 @@ where
 
         tracing::debug!("writing_module_caches");
         for module in modules {
+            // Incomplete modules are compiled again on the next build.
+            if incomplete_modules.contains(&module.name) {
+                continue;
+            }
             let module_name = module.name.replace("/", "@");
 
             // Write metadata file
@@ -337,6 +348,9 @@ where
 
         if self.compile_beam_bytecode && self.write_entrypoint {
             self.render_erlang_entrypoint_module(&build_dir, &mut written)?;
+            if self.mode.includes_tests() {
+                self.render_erlang_test_runner_module(&build_dir, &mut written)?;
+            }
         } else {
             tracing::debug!("skipping_entrypoint_generation");
         }
@@ -405,6 +419,32 @@ where
         tracing::debug!("erlang_entrypoint_written");
         Ok(())
     }
+
+    /// The module used by `gleam test` to run the tests of the package.
+    fn render_erlang_test_runner_module(
+        &mut self,
+        out: &Utf8Path,
+        modules_to_compile: &mut HashSet<Utf8PathBuf>,
+    ) -> Result<(), Error> {
+        let name = format!("{name}@@test.erl", name = self.config.name);
+        let path = out.join(&name);
+
+        // If the test runner module has already been created then we don't
+        // need to write and compile it again.
+        if self.io.is_file(&path) {
+            tracing::debug!("erlang_test_runner_already_exists");
+            return Ok(());
+        }
+
+        let template = ErlangTestRunnerModule {
+            application: &self.config.name,
+        };
+        let module = template.render().expect("Erlang test runner rendering");
+        self.io.write(&path, &module)?;
+        let _ = modules_to_compile.insert(name.into());
+        tracing::debug!("erlang_test_runner_written");
+        Ok(())
+    }
 }
 
 fn analyse(
@@ -442,6 +482,7 @@ fn analyse(
     {
         tracing::debug!(module = ?name, "Type checking");
 
+        let denied_before = warnings.denied_count();
         let line_numbers = LineNumbers::new(&code);
 
         let analysis = crate::analyse::ModuleAnalyzerConstructor {
@@ -458,8 +499,17 @@ fn analyse(
 
         match analysis {
             Outcome::Ok(ast) => {
-                // Module has compiled successfully. Make sure it isn't marked as incomplete.
-                let _ = incomplete_modules.remove(&name.clone());
+                // A module with warnings that the package denies fails the
+                // build, so it is marked as incomplete and not cached. This way
+                // it is checked again and its warnings are emitted on the next
+                // build, rather than the build passing with the cached module.
+                // Otherwise the module has compiled successfully, so make sure
+                // it isn't marked as incomplete.
+                if warnings.denied_count() > denied_before {
+                    let _ = incomplete_modules.insert(name.clone());
+                } else {
+                    let _ = incomplete_modules.remove(&name.clone());
+                }
                 // Register the types from this module so they can be imported into
                 // other modules.
                 let _ = module_types.insert(name.clone(), ast.type_info.clone());
@@ -630,3 +680,9 @@ pub(crate) struct UncompiledModule {
 struct ErlangEntrypointModule<'a> {
     application: &'a str,
 }
+
+#[derive(Template)]
+#[template(path = "gleam@@test.erl", escape = "none")]
+struct ErlangTestRunnerModule<'a> {
+    application: &'a str,
+}
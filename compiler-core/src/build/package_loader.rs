@@ -161,7 +161,11 @@ where
             .join(info.name.replace("/", "@").as_ref())
             .with_extension("cache");
         let bytes = self.io.read_bytes(&path)?;
-        metadata::ModuleDecoder::new(self.ids.clone()).read(bytes.as_slice())
+        let mut module = metadata::ModuleDecoder::new(self.ids.clone()).read(bytes.as_slice())?;
+        // The origin is not stored in the cache, it depends on which
+        // directory the module was loaded from.
+        module.origin = info.origin;
+        Ok(module)
     }
 
     pub fn is_gleam_path(&self, path: &Utf8Path, dir: &Utf8Path) -> bool {
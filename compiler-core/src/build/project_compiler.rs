@@ -1,21 +1,23 @@
 use crate::{
-    analyse::TargetSupport,
+    analyse::{ModuleAnalyzerConstructor, TargetSupport},
     build::{
-        package_compiler, package_compiler::PackageCompiler, package_loader::StaleTracker,
-        project_compiler, telemetry::Telemetry, Mode, Module, Origin, Package, Target,
+        module_loader, package_compiler, package_compiler::PackageCompiler,
+        package_loader::StaleTracker, project_compiler, telemetry::Telemetry, Mode, Module, Origin,
+        Package, Target,
     },
     codegen::{self, ErlangApp},
     config::PackageConfig,
     dep_tree,
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
+    line_numbers::LineNumbers,
     manifest::{ManifestPackage, ManifestPackageSource},
     metadata,
     paths::{self, ProjectPaths},
-    type_::{self, ModuleFunction},
+    type_::{self, ModuleFunction, TestFunction},
     uid::UniqueIdGenerator,
     version::COMPILER_VERSION,
-    warning::{self, WarningEmitter, WarningEmitterIO},
+    warning::{self, TypeWarningEmitter, WarningEmitter, WarningEmitterIO},
     Error, Result, Warning,
 };
 use ecow::EcoString;
@@ -44,7 +46,7 @@ const ELIXIR_EXECUTABLE: &str = "elixir";
 #[cfg(target_os = "windows")]
 const ELIXIR_EXECUTABLE: &str = "elixir.bat";
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Options {
     pub mode: Mode,
     pub target: Option<Target>,
@@ -74,6 +76,28 @@ impl Built {
             }),
         }
     }
+
+    /// The modules compiled by this build, from the root package and from
+    /// dependencies that were not loaded from the cache.
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.root_package
+            .modules
+            .iter()
+            .chain(&self.compiled_dependency_modules)
+    }
+
+    /// The tests in the `test` directory of the root package, ordered by
+    /// module name.
+    pub fn test_functions(&self, target: Target) -> Vec<TestFunction> {
+        self.module_interfaces
+            .values()
+            .filter(|module| {
+                module.origin == Origin::Test && module.package == self.root_package.config.name
+            })
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .flat_map(|module| module.test_functions(target))
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -90,7 +114,7 @@ pub struct ProjectCompiler<IO> {
     warnings: WarningEmitter,
     telemetry: Box<dyn Telemetry>,
     options: Options,
-    paths: ProjectPaths,
+    pub(crate) paths: ProjectPaths,
     ids: UniqueIdGenerator,
     pub(crate) io: IO,
     /// We may want to silence subprocess stdout if we are running in LSP mode.
@@ -114,8 +138,12 @@ where
         paths: ProjectPaths,
         io: IO,
     ) -> Self {
+        // The manifest of a workspace includes its members, one of which is
+        // the package being compiled.
+        let in_workspace = paths.workspace_root().is_some();
         let packages = packages
             .into_iter()
+            .filter(|p| !in_workspace || p.name != config.name)
             .map(|p| (p.name.to_string(), p))
             .collect();
 
@@ -151,6 +179,13 @@ where
     /// Compiles all packages in the project and returns the compiled
     /// information from the root package
     pub fn compile(mut self) -> Result<Built> {
+        self.recompile()
+    }
+
+    /// Compile the project, reusing the state of any previous compilation.
+    /// Modules that have not changed since they were last compiled are loaded
+    /// from the cache in the build directory rather than being compiled again.
+    pub fn recompile(&mut self) -> Result<Built> {
         // We make sure the stale module tracker is empty before we start, to
         // avoid mistakenly thinking a module is stale due to outdated state
         // from a previous build. A ProjectCompiler instance is re-used by the
@@ -180,9 +215,15 @@ where
             });
         }
 
+        if self.warnings.denied_count() > 0 {
+            return Err(Error::DeniedWarnings {
+                count: self.warnings.denied_count(),
+            });
+        }
+
         Ok(Built {
             root_package,
-            module_interfaces: self.importable_modules,
+            module_interfaces: self.importable_modules.clone(),
             compiled_dependency_modules,
         })
     }
@@ -193,6 +234,62 @@ where
             .map(|modules| Package { config, modules })
     }
 
+    /// Type checks a single module of a dependency, using the interfaces of
+    /// the modules that have already been compiled. Nothing is written to the
+    /// build directory, so the dependency's caches are left as they are.
+    ///
+    /// The caches don't contain the typed AST of a module, so the language
+    /// server uses this to serve requests for the source of dependencies.
+    ///
+    pub fn analyse_dependency_module(
+        &self,
+        package_root: &Utf8Path,
+        path: &Utf8Path,
+    ) -> Option<Module> {
+        let config = PackageConfig::read(package_root.join("gleam.toml"), &self.io).ok()?;
+        let direct_dependencies = config.dependencies_for(Mode::Prod).ok()?;
+        let mtime = self.io.modification_time(path).ok()?;
+        let name = package_compiler::module_name(&package_root.join("src"), path);
+        let module = module_loader::read_source(
+            self.io.clone(),
+            self.target(),
+            Origin::Src,
+            path.to_path_buf(),
+            name,
+            config.name.clone(),
+            mtime,
+        )
+        .ok()?;
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let analysis = ModuleAnalyzerConstructor {
+            target: self.target(),
+            ids: &self.ids,
+            origin: Origin::Src,
+            importable_modules: &self.importable_modules,
+            warnings: &TypeWarningEmitter::null(),
+            direct_dependencies: &direct_dependencies,
+            target_support: TargetSupport::NotEnforced,
+            package_config: &config,
+        }
+        .infer_module(module.ast, line_numbers, module.path.clone());
+        let ast = match analysis {
+            Outcome::Ok(ast) | Outcome::PartialFailure(ast, _) => ast,
+            Outcome::TotalFailure(_) => return None,
+        };
+
+        Some(Module {
+            name: module.name,
+            code: module.code,
+            mtime: module.mtime,
+            input_path: module.path,
+            origin: module.origin,
+            ast,
+            extra: module.extra,
+            dependencies: module.dependencies,
+        })
+    }
+
     /// Checks that version file found in the build directory matches the
     /// current version of gleam. If not, we will clear the build directory
     /// before continuing. This will ensure that upgrading gleam will not leave
@@ -474,12 +571,12 @@ where
     ) -> Result<Vec<Module>, Error> {
         // TODO: Test
         let package_root = match &package.source {
-            // If the path is relative it is relative to the root of the
-            // project, not to the current working directory. The language server
-            // could have the working directory and the project root in different
-            // places.
+            // If the path is relative it is relative to the directory of the
+            // manifest, not to the current working directory. The language
+            // server could have the working directory and the project root in
+            // different places.
             ManifestPackageSource::Local { path } if path.is_relative() => {
-                self.io.canonicalise(&self.paths.root().join(path))?
+                self.io.canonicalise(&self.paths.shared_root().join(path))?
             }
 
             // If the path is absolute we can use it as-is.
@@ -1,6 +1,20 @@
-use crate::{manifest::ManifestPackage, Error};
+use std::{collections::HashSet, sync::Arc};
 
-use super::project_compiler::{usable_build_tools, BuildTool};
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    manifest::ManifestPackage,
+    uid::UniqueIdGenerator,
+    warning::{VectorWarningEmitterIO, WarningEmitter},
+    Error,
+};
+
+use super::{
+    project_compiler::{usable_build_tools, BuildTool},
+    Mode, NullTelemetry, Outcome, PackageCompiler, StaleTracker, TargetCodegenConfiguration,
+};
 
 #[test]
 fn usable_build_tool_unknown() {
@@ -55,3 +69,55 @@ fn usable_build_tool_mix_then_rebar3() {
         Ok(vec![BuildTool::Mix, BuildTool::Rebar3])
     )
 }
+
+#[test]
+fn denied_warnings_are_emitted_again_when_rebuilding() {
+    let config: PackageConfig = toml::from_str(
+        r#"
+name = "wibble"
+version = "1.0.0"
+
+[warnings]
+todo = "deny"
+"#,
+    )
+    .unwrap();
+    let io = InMemoryFileSystem::new();
+    io.write(Utf8Path::new("/src/wibble.gleam"), "pub fn main() { todo }")
+        .unwrap();
+    let target = TargetCodegenConfiguration::JavaScript {
+        emit_typescript_definitions: false,
+        prelude_location: Utf8PathBuf::from("../prelude.mjs"),
+    };
+
+    // The module is built twice with the same file system, so the second
+    // build would reuse any caches written by the first one.
+    let build = || {
+        let warnings = VectorWarningEmitterIO::default();
+        let emitter = WarningEmitter::new(Arc::new(warnings.clone()));
+        let mut compiler = PackageCompiler::new(
+            &config,
+            Mode::Dev,
+            Utf8Path::new("/"),
+            Utf8Path::new("/build/lib/wibble"),
+            Utf8Path::new("/build/lib"),
+            &target,
+            UniqueIdGenerator::new(),
+            io.clone(),
+        );
+        compiler.copy_native_files = false;
+        let outcome = compiler.compile(
+            &emitter,
+            &mut im::HashMap::new(),
+            &mut im::HashMap::new(),
+            &mut StaleTracker::default(),
+            &mut HashSet::new(),
+            &NullTelemetry,
+        );
+        assert!(matches!(outcome, Outcome::Ok(_)));
+        (emitter.denied_count(), warnings.take().len())
+    };
+
+    assert_eq!(build(), (1, 1));
+    assert_eq!(build(), (1, 1));
+}
@@ -3,6 +3,7 @@ use crate::io::FileSystemReader;
 use crate::manifest::Manifest;
 use crate::requirement::Requirement;
 use crate::version::COMPILER_VERSION;
+use crate::warning;
 use crate::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use ecow::EcoString;
@@ -77,6 +78,10 @@ pub struct PackageConfig {
     pub gleam_version: Option<EcoString>,
     #[serde(default, alias = "licenses")]
     pub licences: Vec<SpdxLicense>,
+    /// The licences dependencies may have, checked by `gleam deps licences`.
+    /// Any licence is allowed when this is empty.
+    #[serde(default, rename = "allowed-licences", alias = "allowed-licenses")]
+    pub allowed_licences: Vec<SpdxLicense>,
     #[serde(default)]
     pub description: EcoString,
     #[serde(default, alias = "docs")]
@@ -97,6 +102,14 @@ pub struct PackageConfig {
     pub target: Target,
     #[serde(default)]
     pub internal_modules: Option<Vec<Glob>>,
+    #[serde(default)]
+    pub warnings: WarningsConfig,
+    #[serde(
+        default,
+        rename = "hex-repositories",
+        deserialize_with = "hex_repositories::deserialize"
+    )]
+    pub hex_repositories: HashMap<EcoString, HexRepositoryConfig>,
 }
 
 impl PackageConfig {
@@ -590,6 +603,7 @@ fn manifest_package(
         requirements: requirements.iter().map(|e| (*e).into()).collect(),
         source: crate::manifest::ManifestPackageSource::Hex {
             outer_checksum: Base16Checksum(vec![]),
+            repository: None,
         },
     }
 }
@@ -613,19 +627,254 @@ impl Default for PackageConfig {
             repository: Default::default(),
             dev_dependencies: Default::default(),
             licences: Default::default(),
+            allowed_licences: Default::default(),
             links: Default::default(),
             internal_modules: Default::default(),
             target: Target::Erlang,
+            warnings: Default::default(),
+            hex_repositories: Default::default(),
+        }
+    }
+}
+
+/// The level each kind of warning is reported at for a package, set in the
+/// `[warnings]` section of `gleam.toml`:
+///
+/// ```toml
+/// [warnings]
+/// todo = "deny"
+/// deprecated_item = "allow"
+/// ```
+///
+/// Kinds of warning that are not given are reported as normal.
+///
+/// Warnings are not emitted for modules that are reused from the build cache,
+/// so modules with denied warnings are never cached. They are compiled again on
+/// each build until the warnings are fixed.
+///
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+#[serde(try_from = "HashMap<EcoString, WarningLevel>")]
+pub struct WarningsConfig {
+    levels: HashMap<EcoString, WarningLevel>,
+}
+
+impl WarningsConfig {
+    pub fn level(&self, kind: &str) -> WarningLevel {
+        self.levels.get(kind).copied().unwrap_or_default()
+    }
+}
+
+impl TryFrom<HashMap<EcoString, WarningLevel>> for WarningsConfig {
+    type Error = String;
+
+    fn try_from(levels: HashMap<EcoString, WarningLevel>) -> Result<Self, Self::Error> {
+        match levels.keys().find(|kind| !warning::is_kind(kind)) {
+            Some(kind) => Err(format!("unknown kind of warning `{kind}`")),
+            None => Ok(Self { levels }),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningLevel {
+    /// The warning is not reported.
+    Allow,
+    #[default]
+    Warn,
+    /// The warning is reported and the build fails.
+    Deny,
+}
+
+/// A Hex repository that packages can be fetched from besides hex.pm, set in
+/// the `[hex-repositories]` section of `gleam.toml` or of the user config:
+///
+/// ```toml
+/// [hex-repositories.acme]
+/// url = "https://hex.acme.com/repo"
+/// api_url = "https://hex.acme.com/api"
+/// public_key = """
+/// -----BEGIN PUBLIC KEY-----
+/// ...
+/// -----END PUBLIC KEY-----
+/// """
+/// auth_key_env = "ACME_HEX_KEY"
+/// ```
+///
+/// Dependencies select a repository with the `repo` key. A repository named
+/// `hexpm` replaces hex.pm itself, so a mirror of it can be used.
+///
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct HexRepositoryConfig {
+    #[serde(with = "uri_serde")]
+    pub url: Uri,
+    /// The URL of the repository's HTTP API. Defaults to `url`.
+    #[serde(default, deserialize_with = "uri_serde::deserialize_option")]
+    pub api_url: Option<Uri>,
+    /// The PEM encoded public key the repository's registry is signed with.
+    /// Only a mirror of hex.pm, named `hexpm`, can leave it out to use the key
+    /// of hex.pm.
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// The environment variable holding the key used to authenticate with
+    /// the repository.
+    #[serde(default)]
+    pub auth_key_env: Option<String>,
+}
+
+/// The configuration shared by every project of a user, read from
+/// `config.toml` in the user's Gleam config directory. Hex repositories
+/// declared here can be used by any project, and a project's own
+/// `[hex-repositories]` take precedence over them.
+///
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct UserConfig {
+    #[serde(
+        default,
+        rename = "hex-repositories",
+        deserialize_with = "hex_repositories::deserialize"
+    )]
+    pub hex_repositories: HashMap<EcoString, HexRepositoryConfig>,
+}
+
+impl UserConfig {
+    /// Reads the user config, which is empty if the file does not exist.
+    pub fn read<FS: FileSystemReader, P: AsRef<Utf8Path>>(
+        path: P,
+        fs: &FS,
+    ) -> Result<UserConfig, Error> {
+        if !fs.is_file(path.as_ref()) {
+            return Ok(UserConfig::default());
+        }
+        let toml = fs.read(path.as_ref())?;
+        toml::from_str(&toml).map_err(|e| Error::FileIo {
+            action: FileIoAction::Parse,
+            kind: FileKind::File,
+            path: path.as_ref().to_path_buf(),
+            err: Some(e.to_string()),
+        })
+    }
+}
+
+/// The `[workspace]` section of the `gleam.toml` at the root of a repository
+/// of several packages:
+///
+/// ```toml
+/// [workspace]
+/// members = ["packages/app", "packages/app_core"]
+/// ```
+///
+/// The members share one manifest, resolved with the requirements of every
+/// member, and one build directory, both at the root of the workspace.
+///
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct WorkspaceConfig {
+    /// The directories of the member packages, relative to the root of the
+    /// workspace.
+    #[serde(default)]
+    pub members: Vec<Utf8PathBuf>,
+}
+
+impl WorkspaceConfig {
+    /// The workspace config in the `gleam.toml` at the given path, if that
+    /// file is the root of a workspace.
+    pub fn read<FS: FileSystemReader, P: AsRef<Utf8Path>>(
+        path: P,
+        fs: &FS,
+    ) -> Result<Option<WorkspaceConfig>, Error> {
+        #[derive(Deserialize)]
+        struct Root {
+            workspace: Option<WorkspaceConfig>,
         }
+
+        let toml = fs.read(path.as_ref())?;
+        if !declares_workspace(&toml) {
+            return Ok(None);
+        }
+        let root: Root = toml::from_str(&toml).map_err(|e| Error::FileIo {
+            action: FileIoAction::Parse,
+            kind: FileKind::File,
+            path: path.as_ref().to_path_buf(),
+            err: Some(e.to_string()),
+        })?;
+        Ok(root.workspace)
+    }
+
+    /// The root of the workspace that the project at the given directory is
+    /// the root or a member of, if it is in one. Only the nearest workspace
+    /// is considered, and `gleam.toml` files that do not declare a workspace
+    /// are not parsed, so a broken unrelated config cannot cause an error.
+    pub fn find_root<FS: FileSystemReader>(
+        project_root: &Utf8Path,
+        fs: &FS,
+    ) -> Result<Option<Utf8PathBuf>, Error> {
+        let project = fs.canonicalise(project_root)?;
+        for directory in project_root.ancestors() {
+            let config_path = directory.join("gleam.toml");
+            if !fs.is_file(&config_path) {
+                continue;
+            }
+            let Some(workspace) = Self::read(&config_path, fs)? else {
+                continue;
+            };
+            if directory == project_root {
+                return Ok(Some(directory.to_path_buf()));
+            }
+            for member in &workspace.members {
+                let member = directory.join(member);
+                if fs.is_directory(&member) && fs.canonicalise(&member)? == project {
+                    return Ok(Some(directory.to_path_buf()));
+                }
+            }
+            return Ok(None);
+        }
+        Ok(None)
     }
 }
 
+/// Whether a `gleam.toml` has a `workspace` table, checked without parsing it.
+fn declares_workspace(toml: &str) -> bool {
+    toml.lines().any(|line| {
+        let line = line.trim_start();
+        let rest = line
+            .strip_prefix("[workspace")
+            .or_else(|| line.strip_prefix("workspace"));
+        rest.is_some_and(|rest| rest.trim_start().starts_with([']', '.', '=']))
+    })
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
 pub struct ErlangConfig {
     #[serde(default)]
     pub application_start_module: Option<EcoString>,
     #[serde(default)]
     pub extra_applications: Vec<EcoString>,
+    #[serde(default)]
+    pub release: ErlangReleaseConfig,
+}
+
+/// The `[erlang.release]` section of `gleam.toml`, used by `gleam export
+/// erlang-release`:
+///
+/// ```toml
+/// [erlang.release]
+/// vm_args = "config/vm.args"
+/// sys_config = "config/sys.config"
+/// ```
+///
+/// The files are templates in which `$RELEASE_NAME` and `$RELEASE_VERSION`
+/// are replaced with the name and version of the release.
+///
+#[derive(Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct ErlangReleaseConfig {
+    /// The template for the flags the Erlang VM is started with, relative to
+    /// the root of the project.
+    #[serde(default)]
+    pub vm_args: Option<Utf8PathBuf>,
+    /// The template for the configuration of the applications in the
+    /// release, relative to the root of the project.
+    #[serde(default)]
+    pub sys_config: Option<Utf8PathBuf>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Default, Clone)]
@@ -857,6 +1106,47 @@ mod uri_serde_default_https {
     }
 }
 
+mod hex_repositories {
+    use super::HexRepositoryConfig;
+    use crate::hex::HEXPM_REPOSITORY;
+    use ecow::EcoString;
+    use serde::{Deserialize, Deserializer};
+    use std::collections::HashMap;
+
+    // Repository names are used as directory names in the package cache, so
+    // they are held to the same rules as package names. Registries are only
+    // trusted when signed with a known key, so any repository other than a
+    // mirror of hex.pm must give its own.
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<HashMap<EcoString, HexRepositoryConfig>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repositories: HashMap<EcoString, HexRepositoryConfig> =
+            HashMap::deserialize(deserializer)?;
+        for (name, repository) in &repositories {
+            let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                return Err(serde::de::Error::custom(format!(
+                    "invalid Hex repository name `{name}`, repository names may only \
+                    contain lowercase letters, numbers, and underscores"
+                )));
+            }
+            if name != HEXPM_REPOSITORY && repository.public_key.is_none() {
+                return Err(serde::de::Error::custom(format!(
+                    "Hex repository `{name}` has no `public_key`, the key its \
+                    registry is signed with must be given"
+                )));
+            }
+        }
+        Ok(repositories)
+    }
+}
+
 mod package_name {
     use ecow::EcoString;
     use regex::Regex;
@@ -908,3 +1198,278 @@ name = "1"
         "Package names may only container lowercase letters, numbers, and underscores for key `name` at line 1 column 1"
     )
 }
+
+#[test]
+fn warning_levels() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+todo = "deny"
+deprecated_item = "allow"
+unused_variable = "warn"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).unwrap();
+    assert_eq!(config.warnings.level("todo"), WarningLevel::Deny);
+    assert_eq!(
+        config.warnings.level("deprecated_item"),
+        WarningLevel::Allow
+    );
+    assert_eq!(config.warnings.level("unused_variable"), WarningLevel::Warn);
+    assert_eq!(config.warnings.level("unused_literal"), WarningLevel::Warn);
+}
+
+#[test]
+fn unknown_warning_kind() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+unused_var = "deny"
+"#;
+    let error = toml::from_str::<PackageConfig>(input)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("unknown kind of warning `unused_var`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn unknown_warning_level() {
+    let input = r#"
+name = "wibble"
+
+[warnings]
+todo = "forbid"
+"#;
+    assert!(toml::from_str::<PackageConfig>(input).is_err());
+}
+
+#[test]
+fn hex_repositories() {
+    let input = r#"
+name = "wibble"
+
+[dependencies]
+acme_auth = { version = "~> 1.0", repo = "acme" }
+
+[hex-repositories.acme]
+url = "https://hex.acme.com/repo"
+public_key = "acme key"
+auth_key_env = "ACME_HEX_KEY"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).unwrap();
+    assert_eq!(
+        config.dependencies["acme_auth"],
+        Requirement::hex_from_repository("~> 1.0", "acme")
+    );
+    assert_eq!(
+        config.hex_repositories["acme"],
+        HexRepositoryConfig {
+            url: "https://hex.acme.com/repo".parse().unwrap(),
+            api_url: None,
+            public_key: Some("acme key".into()),
+            auth_key_env: Some("ACME_HEX_KEY".into()),
+        }
+    );
+}
+
+#[test]
+fn hex_repository_without_public_key() {
+    let input = r#"
+name = "wibble"
+
+[hex-repositories.acme]
+url = "https://hex.acme.com/repo"
+"#;
+    let error = toml::from_str::<PackageConfig>(input).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Hex repository `acme` has no `public_key`"));
+}
+
+#[test]
+fn hexpm_mirror_without_public_key() {
+    let input = r#"
+name = "wibble"
+
+[hex-repositories.hexpm]
+url = "https://hex-mirror.acme.com/repo"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).unwrap();
+    assert_eq!(config.hex_repositories["hexpm"].public_key, None);
+}
+
+#[test]
+fn user_config() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    let path = Utf8Path::new("/home/config.toml");
+    assert_eq!(UserConfig::read(path, &fs).unwrap(), UserConfig::default());
+
+    fs.write(
+        path,
+        r#"
+[hex-repositories.acme]
+url = "https://hex.acme.com/repo"
+public_key = "acme key"
+"#,
+    )
+    .unwrap();
+    let config = UserConfig::read(path, &fs).unwrap();
+    assert_eq!(
+        config.hex_repositories["acme"].url,
+        "https://hex.acme.com/repo".parse::<Uri>().unwrap()
+    );
+}
+
+#[test]
+fn invalid_hex_repository_name() {
+    let input = r#"
+name = "wibble"
+
+[hex-repositories."../acme"]
+url = "https://hex.acme.com/repo"
+"#;
+    assert!(toml::from_str::<PackageConfig>(input).is_err());
+}
+
+#[test]
+fn workspace_config() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    fs.write(
+        Utf8Path::new("/repo/gleam.toml"),
+        r#"
+[workspace]
+members = ["packages/app", "packages/app_core"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        WorkspaceConfig::read("/repo/gleam.toml", &fs).unwrap(),
+        Some(WorkspaceConfig {
+            members: vec!["packages/app".into(), "packages/app_core".into()],
+        })
+    );
+}
+
+#[test]
+fn package_config_is_not_workspace_config() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    fs.write(Utf8Path::new("/app/gleam.toml"), "name = \"wibble\"\n")
+        .unwrap();
+    assert_eq!(WorkspaceConfig::read("/app/gleam.toml", &fs).unwrap(), None);
+}
+
+#[test]
+fn find_workspace_root() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    fs.write(
+        Utf8Path::new("/repo/gleam.toml"),
+        "[workspace]\nmembers = [\"packages/app\"]\n",
+    )
+    .unwrap();
+    fs.write(
+        Utf8Path::new("/repo/packages/app/gleam.toml"),
+        "name = \"app\"\n",
+    )
+    .unwrap();
+    fs.write(
+        Utf8Path::new("/repo/other/gleam.toml"),
+        "name = \"other\"\n",
+    )
+    .unwrap();
+
+    let root = Some(Utf8PathBuf::from("/repo"));
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/repo/packages/app"), &fs).unwrap(),
+        root
+    );
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/repo"), &fs).unwrap(),
+        root
+    );
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/repo/other"), &fs).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn find_workspace_root_ignores_other_configs() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    fs.write(Utf8Path::new("/gleam.toml"), "name = \"broken")
+        .unwrap();
+    fs.write(
+        Utf8Path::new("/repo/gleam.toml"),
+        "[workspace]\nmembers = [\"packages/app\"]\n",
+    )
+    .unwrap();
+    fs.write(
+        Utf8Path::new("/repo/packages/app/gleam.toml"),
+        "name = \"app\"\n",
+    )
+    .unwrap();
+    fs.write(Utf8Path::new("/other/app/gleam.toml"), "name = \"app\"\n")
+        .unwrap();
+
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/repo/packages/app"), &fs).unwrap(),
+        Some(Utf8PathBuf::from("/repo"))
+    );
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/other/app"), &fs).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn find_workspace_root_stops_at_nearest_workspace() {
+    use crate::io::FileSystemWriter;
+    let fs = crate::io::memory::InMemoryFileSystem::new();
+    fs.write(
+        Utf8Path::new("/outer/gleam.toml"),
+        "[workspace]\nmembers = [\"inner/app\"]\n",
+    )
+    .unwrap();
+    fs.write(
+        Utf8Path::new("/outer/inner/gleam.toml"),
+        "[workspace]\nmembers = []\n",
+    )
+    .unwrap();
+    fs.write(
+        Utf8Path::new("/outer/inner/app/gleam.toml"),
+        "name = \"app\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        WorkspaceConfig::find_root(Utf8Path::new("/outer/inner/app"), &fs).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn erlang_release_config() {
+    let input = r#"
+name = "wibble"
+
+[erlang.release]
+vm_args = "config/vm.args"
+sys_config = "config/sys.config"
+"#;
+    let config = toml::from_str::<PackageConfig>(input).unwrap();
+    assert_eq!(
+        config.erlang.release,
+        ErlangReleaseConfig {
+            vm_args: Some("config/vm.args".into()),
+            sys_config: Some("config/sys.config".into()),
+        }
+    );
+}
@@ -1,12 +1,17 @@
 use std::{borrow::Borrow, cell::RefCell, collections::HashMap, error::Error as StdError};
 
-use crate::{Error, Result};
+use crate::{
+    manifest::{Manifest, ManifestPackageSource},
+    requirement::Requirement,
+    Error, Result,
+};
 
 use ecow::EcoString;
 use hexpm::{
     version::{Range, Version},
-    Dependency, Release,
+    Dependency, Release, RetirementStatus,
 };
+use itertools::Itertools;
 use pubgrub::{
     error::PubGrubError,
     solver::{choose_package_with_fewest_versions, Dependencies},
@@ -147,6 +152,125 @@ but it is locked to {version}, which is incompatible.",
     Ok(requirements)
 }
 
+/// A Hex package locked in the manifest, along with the newer releases of it
+/// that are available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedPackage {
+    pub name: EcoString,
+    /// The version locked in the manifest.
+    pub locked: Version,
+    /// The newest release allowed by the requirements of the root package and
+    /// of the locked versions of the packages that depend on this one.
+    pub compatible: Option<Version>,
+    /// The newest release, regardless of any requirements.
+    pub latest: Option<Version>,
+    /// Set if the locked release has been retired.
+    pub retirement: Option<RetirementStatus>,
+}
+
+impl OutdatedPackage {
+    pub fn is_outdated(&self) -> bool {
+        let newer = |version: &Option<Version>| version.as_ref().is_some_and(|v| v > &self.locked);
+        newer(&self.compatible) || newer(&self.latest) || self.retirement.is_some()
+    }
+}
+
+/// Compares each Hex package locked in the manifest with the releases of it
+/// in the registry, sorted by package name.
+///
+/// As when resolving versions, retired releases are never suggested, and nor
+/// are pre-releases unless the locked version is a pre-release. Requirements
+/// on a package are only known for the root package and for the packages
+/// that come from Hex. The root package may have several requirements on one
+/// package, such as those of the members of a workspace, and compatible
+/// versions meet all of them.
+pub fn outdated_packages(
+    package_fetcher: &dyn PackageFetcher,
+    root_requirements: &[(EcoString, Requirement)],
+    manifest: &Manifest,
+) -> Result<Vec<OutdatedPackage>> {
+    let hex_packages = manifest
+        .packages
+        .iter()
+        .filter(|package| matches!(package.source, ManifestPackageSource::Hex { .. }))
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .map(|package| {
+            let releases = package_fetcher
+                .get_dependencies(&package.name)
+                .map_err(|error| Error::Hex(error.to_string()))?;
+            Ok((package, releases))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The ranges each package must be in, from the root package and from the
+    // locked releases of the packages that depend on it.
+    let mut ranges: HashMap<&str, Vec<PubgrubRange>> = HashMap::new();
+    let root_ranges =
+        root_requirements
+            .iter()
+            .filter_map(|(name, requirement)| match requirement {
+                Requirement::Hex { version, .. } => Some((name.as_str(), version)),
+                Requirement::Path { .. } | Requirement::Git { .. } => None,
+            });
+    let dependency_ranges = hex_packages
+        .iter()
+        .filter_map(|(package, releases)| {
+            releases
+                .releases
+                .iter()
+                .find(|release| release.version == package.version)
+        })
+        .flat_map(|release| {
+            release
+                .requirements
+                .iter()
+                .map(|(name, dependency)| (name.as_str(), &dependency.requirement))
+        });
+    for (name, range) in root_ranges.chain(dependency_ranges) {
+        // A range that can't be parsed would have failed resolution, so it
+        // would not be in the manifest.
+        if let Ok(range) = range.to_pubgrub() {
+            ranges.entry(name).or_default().push(range);
+        }
+    }
+
+    let outdated = hex_packages
+        .iter()
+        .map(|(package, releases)| {
+            let retirement = releases
+                .releases
+                .iter()
+                .find(|release| release.version == package.version)
+                .and_then(|release| release.retirement_status.clone());
+            let candidates = releases
+                .releases
+                .iter()
+                .filter(|release| !release.is_retired())
+                .filter(|release| !release.version.is_pre() || package.version.is_pre())
+                .map(|release| &release.version);
+            let ranges = ranges.get(package.name.as_str());
+            let compatible = candidates
+                .clone()
+                .filter(|version| {
+                    ranges
+                        .into_iter()
+                        .flatten()
+                        .all(|range| range.contains(version))
+                })
+                .max()
+                .cloned();
+            OutdatedPackage {
+                name: package.name.clone(),
+                locked: package.version.clone(),
+                compatible,
+                latest: candidates.max().cloned(),
+                retirement,
+            }
+        })
+        .collect();
+    Ok(outdated)
+}
+
 pub trait PackageFetcher {
     fn get_dependencies(&self, package: &str) -> Result<hexpm::Package, Box<dyn StdError>>;
 }
@@ -423,7 +547,7 @@ mod tests {
                     Release {
                         version: Version::try_from("0.2.0").unwrap(),
                         requirements: [].into(),
-                        retirement_status: Some(hexpm::RetirementStatus {
+                        retirement_status: Some(RetirementStatus {
                             reason: hexpm::RetirementReason::Security,
                             message: "It's bad".into(),
                         }),
@@ -697,4 +821,138 @@ mod tests {
         assert_eq!(parse_exact_version("~> 1.0.0"), None);
         assert_eq!(parse_exact_version(">= 1.0.0"), None);
     }
+
+    fn locked_manifest(packages: &[(&str, &str)]) -> Manifest {
+        Manifest {
+            requirements: HashMap::new(),
+            packages: packages
+                .iter()
+                .map(|(name, version)| crate::manifest::ManifestPackage {
+                    name: (*name).into(),
+                    version: Version::parse(version).unwrap(),
+                    build_tools: ["gleam".into()].into(),
+                    otp_app: None,
+                    requirements: vec![],
+                    source: ManifestPackageSource::Hex {
+                        outer_checksum: crate::manifest::Base16Checksum(vec![1, 2, 3]),
+                        repository: None,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn outdated_packages_respect_requirements() {
+        let manifest = locked_manifest(&[
+            ("gleam_otp", "0.1.0"),
+            ("gleam_stdlib", "0.1.0"),
+            ("package_with_retired", "0.1.0"),
+        ]);
+        let requirements = [
+            ("gleam_otp".into(), Requirement::hex("~> 0.1.0")),
+            ("gleam_stdlib".into(), Requirement::hex("~> 0.2.0")),
+            ("package_with_retired".into(), Requirement::hex(">= 0.1.0")),
+        ];
+        let result = outdated_packages(make_remote().as_ref(), &requirements, &manifest).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                // Pre-releases are not suggested
+                OutdatedPackage {
+                    name: "gleam_otp".into(),
+                    locked: Version::parse("0.1.0").unwrap(),
+                    compatible: Some(Version::parse("0.1.0").unwrap()),
+                    latest: Some(Version::parse("0.2.0").unwrap()),
+                    retirement: None,
+                },
+                OutdatedPackage {
+                    name: "gleam_stdlib".into(),
+                    locked: Version::parse("0.1.0").unwrap(),
+                    compatible: Some(Version::parse("0.2.2").unwrap()),
+                    latest: Some(Version::parse("0.3.0").unwrap()),
+                    retirement: None,
+                },
+                // Retired releases are not suggested
+                OutdatedPackage {
+                    name: "package_with_retired".into(),
+                    locked: Version::parse("0.1.0").unwrap(),
+                    compatible: Some(Version::parse("0.1.0").unwrap()),
+                    latest: Some(Version::parse("0.1.0").unwrap()),
+                    retirement: None,
+                },
+            ]
+        );
+        assert!(!result.last().unwrap().is_outdated());
+    }
+
+    #[test]
+    fn outdated_packages_use_requirements_of_dependents() {
+        // gleam_otp 0.2.0 requires gleam_stdlib >= 0.1.0, which is combined
+        // with the requirement of the root package
+        let manifest = locked_manifest(&[("gleam_otp", "0.2.0"), ("gleam_stdlib", "0.2.0")]);
+        let requirements = [
+            ("gleam_otp".into(), Requirement::hex("~> 0.2")),
+            ("gleam_stdlib".into(), Requirement::hex("< 0.3.0")),
+        ];
+        let result = outdated_packages(make_remote().as_ref(), &requirements, &manifest).unwrap();
+        assert_eq!(
+            result.iter().map(|p| p.compatible.clone()).collect_vec(),
+            vec![
+                Some(Version::parse("0.2.0").unwrap()),
+                Some(Version::parse("0.2.2").unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn outdated_packages_meet_every_root_requirement() {
+        let manifest = locked_manifest(&[("gleam_stdlib", "0.1.0")]);
+        let requirements = [
+            ("gleam_stdlib".into(), Requirement::hex(">= 0.2.0")),
+            ("gleam_stdlib".into(), Requirement::hex("< 0.2.2")),
+        ];
+        let result = outdated_packages(make_remote().as_ref(), &requirements, &manifest).unwrap();
+        assert_eq!(
+            result.iter().map(|p| p.compatible.clone()).collect_vec(),
+            vec![Some(Version::parse("0.2.0").unwrap())]
+        );
+    }
+
+    #[test]
+    fn outdated_packages_flag_retired_locked_release() {
+        let manifest = locked_manifest(&[("package_with_retired", "0.2.0")]);
+        let result = outdated_packages(make_remote().as_ref(), &[], &manifest).unwrap();
+        let package = result.first().unwrap();
+        assert_eq!(
+            package.retirement,
+            Some(RetirementStatus {
+                reason: hexpm::RetirementReason::Security,
+                message: "It's bad".into(),
+            })
+        );
+        assert_eq!(package.latest, Some(Version::parse("0.1.0").unwrap()));
+        assert!(package.is_outdated());
+    }
+
+    #[test]
+    fn outdated_packages_skips_non_hex_packages() {
+        let mut manifest = locked_manifest(&[("gleam_stdlib", "0.3.0")]);
+        manifest.packages.push(crate::manifest::ManifestPackage {
+            name: "local".into(),
+            version: Version::parse("1.0.0").unwrap(),
+            build_tools: ["gleam".into()].into(),
+            otp_app: None,
+            requirements: vec![],
+            source: ManifestPackageSource::Local {
+                path: "../local".into(),
+            },
+        });
+        let result = outdated_packages(make_remote().as_ref(), &[], &manifest).unwrap();
+        assert_eq!(
+            result.iter().map(|p| p.name.as_str()).collect_vec(),
+            vec!["gleam_stdlib"]
+        );
+        assert!(!result.first().unwrap().is_outdated());
+    }
 }
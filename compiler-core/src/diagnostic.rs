@@ -1,4 +1,10 @@
-use camino::Utf8PathBuf;
+pub mod json;
+pub mod sarif;
+
+#[cfg(test)]
+mod tests;
+
+use camino::{Utf8Path, Utf8PathBuf};
 
 pub use codespan_reporting::diagnostic::{LabelStyle, Severity};
 use codespan_reporting::{diagnostic::Label as CodespanLabel, files::SimpleFile};
@@ -39,6 +45,8 @@ pub struct Diagnostic {
     pub title: String,
     pub text: String,
     pub level: Level,
+    /// The stable code identifying the kind of error or warning, if any.
+    pub code: Option<&'static str>,
     pub location: Option<Location>,
     pub hint: Option<String>,
 }
@@ -82,9 +90,12 @@ impl Diagnostic {
             Level::Warning => Severity::Warning,
         };
 
-        let diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
+        let mut diagnostic = codespan_reporting::diagnostic::Diagnostic::new(severity)
             .with_message(&self.title)
             .with_labels(labels);
+        if let Some(code) = self.code {
+            diagnostic = diagnostic.with_code(code);
+        }
         let config = codespan_reporting::term::Config::default();
         codespan_reporting::term::emit(buffer, &config, &file, &diagnostic)
             .expect("write_diagnostic");
@@ -101,6 +112,9 @@ impl Diagnostic {
             .set_color(ColorSpec::new().set_bold(true).set_fg(Some(colour)))
             .expect("write_title_color1");
         write!(buffer, "{kind}").expect("write_title_kind");
+        if let Some(code) = self.code {
+            write!(buffer, "[{code}]").expect("write_title_code");
+        }
         buffer
             .set_color(ColorSpec::new().set_bold(true))
             .expect("write_title_color2");
@@ -110,3 +124,12 @@ impl Diagnostic {
             .expect("write_title_reset");
     }
 }
+
+/// The path of a diagnostic's file relative to the project root, for use in
+/// machine readable output. Paths outside of the root are left as they are.
+fn relative_path(path: &Utf8Path, root: &Utf8Path) -> String {
+    path.strip_prefix(root)
+        .or_else(|_| path.strip_prefix("."))
+        .unwrap_or(path)
+        .to_string()
+}
@@ -0,0 +1,131 @@
+//! Renders diagnostics as JSON so they can be consumed by other tools, such
+//! as editors and CI systems.
+//!
+//! The output is an object with a single `diagnostics` field holding a list
+//! of diagnostics:
+//!
+//! ```json
+//! {
+//!   "diagnostics": [
+//!     {
+//!       "code": "E2001",
+//!       "level": "error",
+//!       "title": "Unknown variable",
+//!       "text": "",
+//!       "hint": null,
+//!       "file": "src/wibble.gleam",
+//!       "labels": [
+//!         {
+//!           "text": "Did you mean `wobble`?",
+//!           "primary": true,
+//!           "start": { "byte": 20, "line": 2, "column": 3 },
+//!           "end": { "byte": 26, "line": 2, "column": 9 }
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Lines and columns are 1-indexed, byte offsets are 0-indexed.
+//!
+
+use camino::Utf8Path;
+use serde::Serialize;
+
+use crate::line_numbers::LineNumbers;
+
+use super::{relative_path, Diagnostic, Label, Level};
+
+#[derive(Serialize, Debug)]
+struct Output<'a> {
+    diagnostics: Vec<JsonDiagnostic<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct JsonDiagnostic<'a> {
+    code: Option<&'static str>,
+    level: &'static str,
+    title: &'a str,
+    text: &'a str,
+    hint: Option<&'a str>,
+    /// The path of the file the diagnostic is for, relative to the project
+    /// root. This is absent for diagnostics that are not about a file.
+    file: Option<String>,
+    labels: Vec<JsonLabel<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct JsonLabel<'a> {
+    text: Option<&'a str>,
+    /// The first label of a diagnostic is the primary one, any others give
+    /// additional context.
+    primary: bool,
+    start: Position,
+    end: Position,
+}
+
+#[derive(Serialize, Debug)]
+struct Position {
+    byte: u32,
+    line: u32,
+    column: u32,
+}
+
+impl Position {
+    fn new(byte: u32, line_numbers: &LineNumbers) -> Self {
+        let position = line_numbers.line_and_column_number(byte);
+        Self {
+            byte,
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
+
+pub fn render(diagnostics: &[Diagnostic], root: &Utf8Path) -> String {
+    let output = Output {
+        diagnostics: diagnostics
+            .iter()
+            .map(|diagnostic| json_diagnostic(diagnostic, root))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&output).expect("diagnostics to json")
+}
+
+fn json_diagnostic<'a>(diagnostic: &'a Diagnostic, root: &Utf8Path) -> JsonDiagnostic<'a> {
+    let (file, labels) = match &diagnostic.location {
+        None => (None, vec![]),
+        Some(location) => {
+            let line_numbers = LineNumbers::new(&location.src);
+            let labels = location
+                .labels()
+                .enumerate()
+                .map(|(index, label)| json_label(label, index == 0, &line_numbers))
+                .collect();
+            (Some(relative_path(&location.path, root)), labels)
+        }
+    };
+
+    JsonDiagnostic {
+        code: diagnostic.code,
+        level: match diagnostic.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        },
+        title: &diagnostic.title,
+        text: &diagnostic.text,
+        hint: diagnostic.hint.as_deref(),
+        file,
+        labels,
+    }
+}
+
+fn json_label<'a>(label: &'a Label, primary: bool, line_numbers: &LineNumbers) -> JsonLabel<'a> {
+    JsonLabel {
+        text: label.text.as_deref(),
+        primary,
+        start: Position::new(label.span.start, line_numbers),
+        end: Position::new(label.span.end, line_numbers),
+    }
+}
@@ -0,0 +1,182 @@
+//! Renders diagnostics in the Static Analysis Results Interchange Format
+//! (SARIF) version 2.1.0, which is understood by code scanning tools such as
+//! GitHub's.
+//!
+//! <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+//!
+
+use camino::Utf8Path;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{line_numbers::LineNumbers, version::COMPILER_VERSION};
+
+use super::{relative_path, Diagnostic, Level, Location};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://gleam.run";
+
+#[derive(Serialize, Debug)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Run<'a> {
+    tool: Tool<'a>,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Tool<'a> {
+    driver: Driver<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Driver<'a> {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule<'a>>,
+}
+
+/// A rule describes a kind of diagnostic, identified by its code. Each is
+/// listed once, described by the title of the first diagnostic with its code.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Rule<'a> {
+    id: &'static str,
+    short_description: Message<'a>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<&'static str>,
+    level: &'static str,
+    message: Message<'a>,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize, Debug)]
+struct Message<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize, Debug)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+pub fn render(diagnostics: &[Diagnostic], root: &Utf8Path) -> String {
+    let rules = diagnostics
+        .iter()
+        .filter_map(|diagnostic| {
+            diagnostic.code.map(|id| Rule {
+                id,
+                short_description: Message {
+                    text: &diagnostic.title,
+                },
+            })
+        })
+        .unique_by(|rule| rule.id)
+        .collect();
+
+    let results = diagnostics
+        .iter()
+        .map(|diagnostic| SarifResult {
+            rule_id: diagnostic.code,
+            level: match diagnostic.level {
+                Level::Error => "error",
+                Level::Warning => "warning",
+            },
+            message: Message {
+                text: message_text(diagnostic),
+            },
+            locations: diagnostic
+                .location
+                .iter()
+                .map(|location| sarif_location(location, root))
+                .collect(),
+        })
+        .collect();
+
+    let log = Log {
+        schema: SCHEMA,
+        version: VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "gleam",
+                    version: COMPILER_VERSION,
+                    information_uri: INFORMATION_URI,
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+    serde_json::to_string_pretty(&log).expect("diagnostics to sarif")
+}
+
+/// SARIF requires every result to have a message, so diagnostics without a
+/// body of text are described by their title.
+fn message_text(diagnostic: &Diagnostic) -> &str {
+    if diagnostic.text.trim().is_empty() {
+        &diagnostic.title
+    } else {
+        &diagnostic.text
+    }
+}
+
+fn sarif_location(location: &Location, root: &Utf8Path) -> SarifLocation {
+    let line_numbers = LineNumbers::new(&location.src);
+    let span = location.label.span;
+    let start = line_numbers.line_and_column_number(span.start);
+    let end = line_numbers.line_and_column_number(span.end);
+    SarifLocation {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: relative_path(&location.path, root).replace('\\', "/"),
+            },
+            region: Region {
+                start_line: start.line,
+                start_column: start.column,
+                end_line: end.line,
+                end_column: end.column,
+                byte_offset: span.start,
+                byte_length: span.end.saturating_sub(span.start),
+            },
+        },
+    }
+}
@@ -0,0 +1,84 @@
+use camino::Utf8PathBuf;
+
+use super::{json, sarif, Diagnostic, Label, Level, Location};
+use crate::{ast::SrcSpan, error::Unformatted};
+
+fn diagnostics() -> Vec<Diagnostic> {
+    let src = "pub fn main() {\n  wibble\n}\n";
+    vec![
+        Diagnostic {
+            title: "Unknown variable".into(),
+            text: "".into(),
+            level: Level::Error,
+            code: Some("E2001"),
+            location: Some(Location {
+                src: src.into(),
+                path: Utf8PathBuf::from("/project/src/main.gleam"),
+                label: Label {
+                    text: Some("Did you mean `wobble`?".into()),
+                    span: SrcSpan::new(18, 24),
+                },
+                extra_labels: vec![Label {
+                    text: None,
+                    span: SrcSpan::new(0, 13),
+                }],
+            }),
+            hint: None,
+        },
+        Diagnostic {
+            title: "Unused variable".into(),
+            text: "This variable is never used.".into(),
+            level: Level::Warning,
+            code: Some("W1001"),
+            location: Some(Location {
+                src: src.into(),
+                path: Utf8PathBuf::from("/project/src/main.gleam"),
+                label: Label {
+                    text: None,
+                    span: SrcSpan::new(18, 24),
+                },
+                extra_labels: vec![],
+            }),
+            hint: Some("You can safely remove it.".into()),
+        },
+        Diagnostic {
+            title: "These files have not been formatted".into(),
+            text: "  - src/main.gleam\n".into(),
+            level: Level::Error,
+            code: None,
+            location: None,
+            hint: None,
+        },
+    ]
+}
+
+#[test]
+fn json_output() {
+    insta::assert_snapshot!(json::render(&diagnostics(), "/project".into()));
+}
+
+#[test]
+fn json_output_no_diagnostics() {
+    insta::assert_snapshot!(json::render(&[], "/project".into()));
+}
+
+#[test]
+fn sarif_output() {
+    let output = sarif::render(&diagnostics(), "/project".into())
+        .replace(crate::version::COMPILER_VERSION, "<version>");
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn unformatted_file_points_at_first_difference() {
+    let unformatted = Unformatted {
+        source: "/project/src/main.gleam".into(),
+        destination: "/project/src/main.gleam".into(),
+        input: "pub fn main() {\n    1\n}\n".into(),
+        output: "pub fn main() {\n  1\n}\n".into(),
+    };
+    insta::assert_snapshot!(json::render(
+        &[unformatted.to_diagnostic()],
+        "/project".into()
+    ));
+}
@@ -3,6 +3,7 @@
 // formatter for examples.
 
 mod pattern;
+pub mod term;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,366 @@
+//! A parser for files of Erlang terms as read by `file:consult/1`, such as
+//! the `metadata.config` of a Hex package or the `.app.src` file of an OTP
+//! application.
+
+use std::fmt;
+
+use ecow::EcoString;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Atom(EcoString),
+    /// A binary written as a string, such as `<<"wibble">>` or
+    /// `<<"wibble"/utf8>>`.
+    Binary(EcoString),
+    String(EcoString),
+    /// An integer or float, as it is written.
+    Number(EcoString),
+    List(Vec<Term>),
+    Tuple(Vec<Term>),
+}
+
+impl Term {
+    /// The text of an atom, binary, or string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Term::Atom(text) | Term::Binary(text) | Term::String(text) => Some(text),
+            Term::Number(_) | Term::List(_) | Term::Tuple(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Term]> {
+        match self {
+            Term::List(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// The value of the `{Key, Value}` pair with the given key in a list of
+    /// pairs, where the key can be an atom, binary, or string.
+    pub fn get(&self, key: &str) -> Option<&Term> {
+        self.as_list()?.iter().find_map(|element| match element {
+            Term::Tuple(pair) => match pair.as_slice() {
+                [name, value] if name.as_str() == Some(key) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset of the problem in the parsed text.
+    pub position: usize,
+    pub problem: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.problem, self.position)
+    }
+}
+
+/// Parses a sequence of terms, each ended by a full stop.
+pub fn consult(text: &str) -> Result<Vec<Term>, ParseError> {
+    let mut parser = Parser { text, position: 0 };
+    let mut terms = vec![];
+    loop {
+        parser.skip_whitespace();
+        if parser.peek().is_none() {
+            return Ok(terms);
+        }
+        terms.push(parser.term()?);
+        parser.skip_whitespace();
+        parser.expect('.', "expected a full stop after the term")?;
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text.get(self.position..)?.chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+
+    fn error<T>(&self, problem: &'static str) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position,
+            problem,
+        })
+    }
+
+    fn expect(&mut self, expected: char, problem: &'static str) -> Result<(), ParseError> {
+        if self.peek() == Some(expected) {
+            self.position += expected.len_utf8();
+            Ok(())
+        } else {
+            self.error(problem)
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> EcoString {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            let _ = self.next();
+        }
+        self.text
+            .get(start..self.position)
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(char) if char.is_whitespace() => {
+                    let _ = self.next();
+                }
+                Some('%') => {
+                    let _ = self.take_while(|char| char != '\n');
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                let _ = self.next();
+                Ok(Term::Tuple(self.elements('}')?))
+            }
+            Some('[') => {
+                let _ = self.next();
+                Ok(Term::List(self.elements(']')?))
+            }
+            Some('<') => self.binary(),
+            Some('"') => Ok(Term::String(self.quoted('"')?)),
+            Some('\'') => Ok(Term::Atom(self.quoted('\'')?)),
+            Some(char) if char.is_ascii_lowercase() => {
+                Ok(Term::Atom(self.take_while(|char| {
+                    char.is_alphanumeric() || char == '_' || char == '@'
+                })))
+            }
+            Some(char) if char.is_ascii_digit() || char == '-' => Ok(self.number()),
+            _ => self.error("expected a term"),
+        }
+    }
+
+    fn elements(&mut self, close: char) -> Result<Vec<Term>, ParseError> {
+        let mut elements = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            let _ = self.next();
+            return Ok(elements);
+        }
+        loop {
+            elements.push(self.term()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => (),
+                Some(char) if char == close => {
+                    let _ = self.next();
+                    return Ok(elements);
+                }
+                _ => return self.error("expected a comma or the end of the term"),
+            }
+            let _ = self.next();
+        }
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<EcoString, ParseError> {
+        let _ = self.next();
+        let mut text = EcoString::new();
+        loop {
+            match self.next() {
+                None => return self.error("unterminated quoted text"),
+                Some(char) if char == quote => return Ok(text),
+                Some('\\') => match self.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(char) => text.push(char),
+                    None => return self.error("unterminated quoted text"),
+                },
+                Some(char) => text.push(char),
+            }
+        }
+    }
+
+    fn binary(&mut self) -> Result<Term, ParseError> {
+        let problem = "expected a binary";
+        self.expect('<', problem)?;
+        self.expect('<', problem)?;
+        self.skip_whitespace();
+        let text = if self.peek() == Some('"') {
+            self.quoted('"')?
+        } else {
+            EcoString::new()
+        };
+        self.skip_whitespace();
+        if self.peek() == Some('/') {
+            let _ = self.next();
+            let _ = self.take_while(|char| char.is_ascii_alphanumeric());
+            self.skip_whitespace();
+        }
+        self.expect('>', problem)?;
+        self.expect('>', problem)?;
+        Ok(Term::Binary(text))
+    }
+
+    fn number(&mut self) -> Term {
+        let start = self.position;
+        if self.peek() == Some('-') {
+            let _ = self.next();
+        }
+        let digits = |char: char| char.is_ascii_digit() || char == '_';
+        let _ = self.take_while(digits);
+        // A full stop is only part of a number when a digit follows it, as
+        // it also ends the term.
+        let rest = self.text.get(self.position..).unwrap_or_default();
+        let fraction = rest.strip_prefix('.');
+        if fraction.is_some_and(|rest| rest.starts_with(|char: char| char.is_ascii_digit())) {
+            let _ = self.next();
+            let _ = self.take_while(digits);
+            if matches!(self.peek(), Some('e' | 'E')) {
+                let _ = self.next();
+                if matches!(self.peek(), Some('-' | '+')) {
+                    let _ = self.next();
+                }
+                let _ = self.take_while(digits);
+            }
+        }
+        Term::Number(
+            self.text
+                .get(start..self.position)
+                .unwrap_or_default()
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary(text: &str) -> Term {
+        Term::Binary(text.into())
+    }
+
+    #[test]
+    fn consult_hex_metadata() {
+        let text = r#"{<<"name">>, <<"wibble">>}.
+{<<"description">>, <<"Wobble ✨"/utf8>>}.
+{<<"licenses">>, [<<"Apache-2.0">>]}.
+{<<"requirements">>, [
+  {<<"gleam_stdlib">>, [
+    {<<"app">>, <<"gleam_stdlib">>},
+    {<<"optional">>, false},
+    {<<"requirement">>, <<"~> 0.34">>}
+  ]}
+]}.
+"#;
+        let terms = consult(text).unwrap();
+        assert_eq!(
+            terms.first(),
+            Some(&Term::Tuple(vec![binary("name"), binary("wibble")]))
+        );
+        assert_eq!(
+            terms.get(1),
+            Some(&Term::Tuple(vec![
+                binary("description"),
+                binary("Wobble ✨")
+            ]))
+        );
+        let requirements = Term::List(terms);
+        let requirement = requirements
+            .get("requirements")
+            .and_then(|requirements| requirements.get("gleam_stdlib"))
+            .unwrap();
+        assert_eq!(
+            requirement.get("optional"),
+            Some(&Term::Atom("false".into()))
+        );
+        assert_eq!(requirement.get("requirement"), Some(&binary("~> 0.34")));
+    }
+
+    #[test]
+    fn consult_app_src() {
+        let text = r#"
+% An application resource file.
+{application, 'my app', [
+    {vsn, "1.0.0"},
+    {registered, []},
+    {licenses , [ "Apache 2.0" , "MIT" ]},
+    {env, [{port, 8080}, {ratio, 1.5e-3}, {offset, -1}]}
+]}.
+"#;
+        let terms = consult(text).unwrap();
+        let Some(Term::Tuple(application)) = terms.first() else {
+            panic!("expected a tuple: {terms:?}");
+        };
+        assert_eq!(application.get(1), Some(&Term::Atom("my app".into())));
+        let properties = application.get(2).unwrap();
+        assert_eq!(
+            properties.get("licenses"),
+            Some(&Term::List(vec![
+                Term::String("Apache 2.0".into()),
+                Term::String("MIT".into())
+            ]))
+        );
+        let env = properties.get("env").unwrap();
+        assert_eq!(env.get("port"), Some(&Term::Number("8080".into())));
+        assert_eq!(env.get("ratio"), Some(&Term::Number("1.5e-3".into())));
+        assert_eq!(env.get("offset"), Some(&Term::Number("-1".into())));
+    }
+
+    #[test]
+    fn consult_number_before_full_stop() {
+        assert_eq!(consult("1."), Ok(vec![Term::Number("1".into())]));
+    }
+
+    #[test]
+    fn consult_escapes() {
+        assert_eq!(
+            consult(r#""a\"b\\c"."#),
+            Ok(vec![Term::String(r#"a"b\c"#.into())])
+        );
+    }
+
+    #[test]
+    fn consult_empty_binary() {
+        assert_eq!(consult("<<>>."), Ok(vec![binary("")]));
+    }
+
+    #[test]
+    fn consult_missing_full_stop() {
+        assert_eq!(
+            consult("{wibble, wobble}"),
+            Err(ParseError {
+                position: 16,
+                problem: "expected a full stop after the term"
+            })
+        );
+    }
+
+    #[test]
+    fn consult_unclosed_list() {
+        assert_eq!(
+            consult("[wibble wobble]."),
+            Err(ParseError {
+                position: 8,
+                problem: "expected a comma or the end of the term"
+            })
+        );
+    }
+}
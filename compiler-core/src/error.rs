@@ -9,9 +9,14 @@ use crate::{
     bit_array,
     diagnostic::Level,
     javascript,
+    requirement::GitReference,
     type_::{pretty::Printer, UnifyErrorSituation},
 };
+
+mod explanations;
+
 use ecow::EcoString;
+pub use explanations::explanation;
 use hexpm::version::ResolutionError;
 use itertools::Itertools;
 use pubgrub::package::Package;
@@ -202,8 +207,26 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
     #[error("{0}")]
     Http(String),
 
-    #[error("Git dependencies are currently unsupported")]
-    GitDependencyUnsupported,
+    #[error("The git command for dependency {package} failed")]
+    GitCommandFailed {
+        package: EcoString,
+        repo: EcoString,
+        output: String,
+    },
+
+    #[error("The reference {reference} of git dependency {package} was not found")]
+    GitReferenceNotFound {
+        package: EcoString,
+        repo: EcoString,
+        reference: GitReference,
+    },
+
+    #[error("The locked commit {commit} of git dependency {package} was not found")]
+    GitLockedCommitNotFound {
+        package: EcoString,
+        repo: EcoString,
+        commit: EcoString,
+    },
 
     #[error("Failed to create canonical path for package {0}")]
     DependencyCanonicalizationFailed(String),
@@ -273,6 +296,85 @@ file_names.iter().map(|x| x.as_str()).join(", "))]
 
     #[error("Version already published")]
     HexPublishReplaceRequired { version: String },
+
+    #[error("There is no error or warning with the code {code}")]
+    UnknownErrorCode { code: EcoString },
+
+    #[error("Warnings denied by the package configuration were generated")]
+    DeniedWarnings { count: usize },
+
+    #[error("The package {name} is not a dependency")]
+    UnknownDependency { name: EcoString },
+
+    #[error("The package {package} is fetched from the unknown Hex repository {repository}")]
+    UnknownHexRepository {
+        package: EcoString,
+        repository: EcoString,
+    },
+
+    #[error("Invalid arguments for gleam add: {problem}")]
+    InvalidAddArguments { problem: String },
+
+    #[error("The package {package} {version} is not available offline")]
+    OfflinePackageUnavailable {
+        package: EcoString,
+        version: hexpm::version::Version,
+    },
+
+    #[error("The vendored tarball of {package} {version} does not match its checksum")]
+    VendoredPackageChecksumMismatch {
+        package: EcoString,
+        version: hexpm::version::Version,
+        path: Utf8PathBuf,
+    },
+
+    #[error("The package {package} is not a member of the workspace")]
+    UnknownWorkspaceMember {
+        package: EcoString,
+        members: Vec<EcoString>,
+    },
+
+    #[error("The JavaScript module {path} could not be bundled")]
+    JavaScriptBundle { path: Utf8PathBuf, problem: String },
+
+    #[error("dependencies have no allowed licence")]
+    DisallowedLicences {
+        packages: Vec<(EcoString, Vec<EcoString>)>,
+    },
+
+    #[error("version bump is too small for the API changes")]
+    InsufficientVersionBump {
+        previous: EcoString,
+        version: EcoString,
+        /// The smallest version that could be used instead.
+        suggested: EcoString,
+        /// The bump needed, which is `None` when the version has not been
+        /// increased at all.
+        required: Option<crate::package_interface::diff::Bump>,
+        changes: Vec<EcoString>,
+    },
+
+    #[error("no package interface to compare with")]
+    MissingPackageInterface {
+        package: EcoString,
+        version: EcoString,
+    },
+
+    #[error("project template does not support target")]
+    TemplateDoesNotSupportTarget { template: EcoString, target: Target },
+
+    #[error("no tests found")]
+    NoTestsFound {
+        /// Whether the tests were selected by name or module.
+        filtered: bool,
+    },
+
+    #[error("workspace members define the Hex repository {repository} differently")]
+    ConflictingHexRepositories {
+        repository: EcoString,
+        /// The two members with different definitions of the repository.
+        packages: (EcoString, EcoString),
+    },
 }
 
 impl Error {
@@ -664,6 +766,98 @@ fn did_you_mean(name: &str, options: &[EcoString]) -> Option<String> {
 }
 
 impl Error {
+    /// A code identifying the kind of error, such as `E0001`, which is shown
+    /// alongside the error and included in machine-readable diagnostics.
+    ///
+    /// Codes are stable across compiler versions so that they can be searched
+    /// for and linked to. Once released a code must never be changed or
+    /// reused; new kinds of error are given the next unused code, and an
+    /// explanation of it is added to the `error/explanations` directory for
+    /// `gleam explain`.
+    ///
+    /// - `E0xxx` are errors from the build tool.
+    /// - `E1xxx` are syntax errors.
+    /// - `E2xxx` are type errors.
+    /// - `E3xxx` are code generation errors.
+    /// - `W0xxx` are warnings from the build tool.
+    /// - `W1xxx` are warnings from the type checker.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Parse { error, .. } => error.error.code(),
+            Error::Type { errors, .. } => errors.first().code(),
+            Error::UnknownImport { .. } => "E0001",
+            Error::DuplicateModule { .. } => "E0002",
+            Error::DuplicateSourceFile { .. } => "E0003",
+            Error::ImportCycle { .. } => "E0004",
+            Error::PackageCycle { .. } => "E0005",
+            Error::FileIo { .. } => "E0006",
+            Error::NonUtf8Path { .. } => "E0007",
+            Error::GitInitialization { .. } => "E0008",
+            Error::StandardIo { .. } => "E0009",
+            Error::Format { .. } => "E0010",
+            Error::Hex(..) => "E0011",
+            Error::ExpandTar { .. } => "E0012",
+            Error::AddTar { .. } => "E0013",
+            Error::TarFinish(..) => "E0014",
+            Error::Gzip(..) => "E0015",
+            Error::ShellProgramNotFound { .. } => "E0016",
+            Error::ShellCommand { .. } => "E0017",
+            Error::InvalidProjectName { .. } => "E0018",
+            Error::InvalidModuleName { .. } => "E0019",
+            Error::ModuleDoesNotExist { .. } => "E0020",
+            Error::ModuleDoesNotHaveMainFunction { .. } => "E0021",
+            Error::MainFunctionHasWrongArity { .. } => "E0022",
+            Error::MainFunctionDoesNotSupportTarget { .. } => "E0023",
+            Error::InvalidVersionFormat { .. } => "E0024",
+            Error::ProjectRootAlreadyExist { .. } => "E0025",
+            Error::OutputFilesAlreadyExist { .. } => "E0026",
+            Error::UnableToFindProjectRoot { .. } => "E0027",
+            Error::VersionDoesNotMatch { .. } => "E0028",
+            Error::MetadataDecodeError { .. } => "E0029",
+            Error::ForbiddenWarnings { .. } => "E0030",
+            Error::JavaScript { error, .. } => error.code(),
+            Error::InvalidRuntime { .. } => "E0031",
+            Error::DownloadPackageError { .. } => "E0032",
+            Error::Http(..) => "E0033",
+            Error::DependencyCanonicalizationFailed(..) => "E0035",
+            Error::DependencyResolutionFailed(..) => "E0036",
+            Error::DuplicateDependency(..) => "E0037",
+            Error::WrongDependencyProvided { .. } => "E0038",
+            Error::ProvidedDependencyConflict { .. } => "E0039",
+            Error::MissingHexPublishFields { .. } => "E0040",
+            Error::PublishNonHexDependencies { .. } => "E0041",
+            Error::UnsupportedBuildTool { .. } => "E0042",
+            Error::FailedToOpenDocs { .. } => "E0043",
+            Error::IncompatibleCompilerVersion { .. } => "E0044",
+            Error::JavaScriptPreludeRequired => "E0045",
+            Error::CannotPublishTodo { .. } => "E0046",
+            Error::CannotPublishLeakedInternalType { .. } => "E0047",
+            Error::HexPackageSquatting => "E0048",
+            Error::CorruptManifest => "E0049",
+            Error::GleamModuleWouldOverwriteStandardErlangModule { .. } => "E0050",
+            Error::HexPublishReplaceRequired { .. } => "E0051",
+            Error::UnknownErrorCode { .. } => "E0052",
+            Error::DeniedWarnings { .. } => "E0053",
+            Error::UnknownDependency { .. } => "E0054",
+            Error::GitCommandFailed { .. } => "E0055",
+            Error::GitReferenceNotFound { .. } => "E0056",
+            Error::GitLockedCommitNotFound { .. } => "E0057",
+            Error::UnknownHexRepository { .. } => "E0058",
+            Error::InvalidAddArguments { .. } => "E0059",
+            Error::OfflinePackageUnavailable { .. } => "E0060",
+            Error::VendoredPackageChecksumMismatch { .. } => "E0061",
+            Error::UnknownWorkspaceMember { .. } => "E0062",
+            Error::JavaScriptBundle { .. } => "E0063",
+            Error::DisallowedLicences { .. } => "E0064",
+            Error::InsufficientVersionBump { .. } => "E0065",
+            Error::MissingPackageInterface { .. } => "E0066",
+            Error::TemplateDoesNotSupportTarget { .. } => "E0067",
+            Error::NoTestsFound { .. } => "E0068",
+            Error::ConflictingHexRepositories { .. } => "E0069",
+        }
+    }
+
     pub fn pretty_string(&self) -> String {
         let mut nocolor = Buffer::no_color();
         self.pretty(&mut nocolor);
@@ -679,6 +873,7 @@ impl Error {
 
     pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
         use crate::type_::Error as TypeError;
+        let code = Some(self.code());
         match self {
             Error::HexPackageSquatting => {
                 let text =
@@ -692,6 +887,7 @@ package deletion or account suspension.
                     title: "Invalid Hex package".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }]
@@ -710,6 +906,7 @@ of the Gleam dependency modules."
                     title: "Failed to decode module metadata".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }]
@@ -745,6 +942,7 @@ This prefix is intended for official Gleam packages only.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -757,6 +955,7 @@ Module names can only contain lowercase letters, underscore, and
 forward slash and must not end with a slash."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             }],
@@ -770,6 +969,7 @@ forward slash and must not end with a slash."
                     title: "Module does not exist".into(),
                     text: format!("Module `{module}` was not found."),
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: Some(hint),
                 }]
@@ -781,6 +981,7 @@ forward slash and must not end with a slash."
                     "`{module}` does not have a main function so the module can not be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some(format!(
                     "Add a public `main` function to \
@@ -795,6 +996,7 @@ to `src/{module}.gleam`."
 target, so it cannot be run."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             }],
@@ -805,6 +1007,7 @@ target, so it cannot be run."
                     "`{module}:main` should have an arity of 0 to be run but its arity is {arity}."
                 ),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Change the function signature of main to `pub fn main() {}`.".into()),
             }],
@@ -813,6 +1016,7 @@ target, so it cannot be run."
                 title: "Project folder already exists".into(),
                 text: format!("Project folder root:\n\n  {path}"),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             }],
@@ -837,6 +1041,7 @@ If you want to overwrite these files, delete them and run the command again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             }],
@@ -856,6 +1061,7 @@ Please remove them and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             }],
@@ -875,6 +1081,7 @@ Please make sure internal types do not appear in public functions and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                code,
                 hint: None,
                 location: None,
             }],
@@ -890,6 +1097,7 @@ We searched in {path} and all parent directories."
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -904,6 +1112,7 @@ your app.src file \"{app_ver}\"."
                     hint: None,
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -942,6 +1151,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -957,6 +1167,7 @@ You can also install rebar3 via homebrew using \"brew install rebar3\"",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -979,6 +1190,7 @@ The error from the shell command library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -996,6 +1208,7 @@ This was error from the gzip library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1014,6 +1227,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1031,6 +1245,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1048,6 +1263,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1065,6 +1281,7 @@ This was error from the Hex client library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1086,6 +1303,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1095,6 +1313,7 @@ Second: {second}"
                 text: format!("The file `{file}` is defined multiple times."),
                 hint: None,
                 level: Level::Error,
+                code,
                 location: None,
             }],
 
@@ -1125,6 +1344,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1138,6 +1358,7 @@ Second: {second}"
                     title: "Non UTF-8 Path Encountered".into(),
                     text,
                     level: Level::Error,
+                    code,
                     location: None,
                     hint: None,
                 }]
@@ -1154,6 +1375,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -1161,6 +1383,7 @@ Second: {second}"
             Error::Type { path, src, errors: error } => error
                 .iter()
                 .map(|error| {
+                    let code = Some(error.code());
                     match error {
                 TypeError::SrcImportingTest {
                     location,
@@ -1179,6 +1402,7 @@ modules cannot import them. Perhaps move the `{test_module}` module to the src d
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Imported here".into()),
@@ -1238,6 +1462,7 @@ constructor accepts."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label,
                             path: path.clone(),
@@ -1257,6 +1482,7 @@ not expect any. Please remove the label `{label}`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1279,6 +1505,7 @@ also be labelled."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1305,6 +1532,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Reimported here".into()),
@@ -1340,6 +1568,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1370,6 +1599,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1393,6 +1623,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1412,6 +1643,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1435,6 +1667,7 @@ Hint: Add some type annotations and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1458,6 +1691,7 @@ Hint: Add some type annotations and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1535,6 +1769,7 @@ to call a method on this value you may want to use the function syntax instead."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -1578,6 +1813,7 @@ But this argument has this type:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1629,6 +1865,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This function does not accept the piped type".into()),
@@ -1667,6 +1904,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1698,6 +1936,7 @@ number of arguments."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected}, got {given}")),
@@ -1737,6 +1976,7 @@ number of arguments."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -1759,6 +1999,7 @@ assigned variables to all of them."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1785,6 +2026,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("I can't tell this is always the right constructor".into()),
@@ -1826,6 +2068,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: label_text,
@@ -1854,6 +2097,7 @@ constructing a new record with its values."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, variables),
@@ -1887,6 +2131,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1908,6 +2153,7 @@ Private types can only be used within the module that defines them.",
                     text: format!("No module has been found with the name `{name}`."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: did_you_mean(name, imported_modules),
@@ -1936,6 +2182,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: if *imported_type_as_value {
@@ -1969,6 +2216,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: if *imported_value_as_type {
@@ -1999,6 +2247,7 @@ Each clause must have a pattern for every subject value.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected} patterns, got {given}")),
@@ -2021,6 +2270,7 @@ function, or be an argument to the function. The variable `{name}` is not define
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Is not locally defined".into()),
@@ -2043,6 +2293,7 @@ This variable `{name}` has not been previously defined.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Has not been previously defined".into()),
@@ -2065,6 +2316,7 @@ as the initial pattern, but the `{name}` variable is missing.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This does not define all required variables".into()),
@@ -2090,6 +2342,7 @@ e.g. (x, y) if x == y -> ...",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This has already been used".into()),
@@ -2109,6 +2362,7 @@ e.g. (x, y) if x == y -> ...",
                     text: "This tuple has no elements so it cannot be indexed at all.".into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2137,6 +2391,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This index is too large".into()),
@@ -2162,6 +2417,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("This is not a tuple".into()),
@@ -2184,6 +2440,7 @@ we can continue."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("What type is this?".into()),
@@ -2206,6 +2463,7 @@ function and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("I don't know what type this is".into()),
@@ -2303,6 +2561,7 @@ function and try again."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label.into()),
@@ -2320,6 +2579,7 @@ function and try again."
                     text: "Only record constructors can be used with the update syntax.".into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some("This is not a record constructor".into()),
@@ -2336,6 +2596,7 @@ function and try again."
                     text: "We need to know the exact type here so type holes cannot be used.".into(),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some("I need to know what this is".into()),
@@ -2358,6 +2619,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        code,
                     }
                 }
 
@@ -2373,6 +2635,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        code,
                     }
                 }
 
@@ -2406,6 +2669,7 @@ value, or use `let assert` rather than `let`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2423,6 +2687,7 @@ value, or use `let assert` rather than `let`."
                     text: format!("Two `{name}` arguments have been defined for this function."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2439,6 +2704,7 @@ value, or use `let assert` rather than `let`."
                     text: wrap("All unlabelled arguments must come before any labelled arguments."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2462,6 +2728,7 @@ forever in a loop, and we'd never get the final type.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2490,6 +2757,7 @@ so we can tell what type of values they accept and return.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2512,6 +2780,7 @@ using the `@external` attribute."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2538,6 +2807,7 @@ implementation but the module path `{module}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2564,6 +2834,7 @@ implementation but the function name `{function}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2595,6 +2866,7 @@ The missing patterns are:\n"
                         text,
                         hint: Some("Use a more general pattern or use `let assert` instead.".into()),
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -2623,6 +2895,7 @@ The missing patterns are:\n"
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -2653,6 +2926,7 @@ and there is no implementation for the {} target.\n",
                         text,
                         hint: Some(hint),
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2684,6 +2958,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2705,6 +2980,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2727,6 +3003,7 @@ Rename or remove one of them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -2756,6 +3033,7 @@ See: https://tour.gleam.run/advanced-features/use/",
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2779,6 +3057,7 @@ See: https://tour.gleam.run/advanced-features/use/");
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some("Expected no arguments, got 1".into()),
@@ -2826,6 +3105,7 @@ so it cannot take the the `use` callback function as a final argument.\n")
                         text: wrap(&text),
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -2854,6 +3134,7 @@ See: https://tour.gleam.run/advanced-features/use/",
                         text: wrap(&text),
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2888,6 +3169,7 @@ See: https://tour.gleam.run/advanced-features/use/");
                         text,
                         hint: None,
                         level: Level::Error,
+                        code,
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2926,6 +3208,7 @@ See: https://tour.gleam.run/advanced-features/use/");
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: Some(label.to_string()),
@@ -2952,6 +3235,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -2970,6 +3254,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -2991,6 +3276,7 @@ but it cannot be found."
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: did_you_mean(import, modules),
@@ -3023,6 +3309,7 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3041,6 +3328,7 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3058,6 +3346,7 @@ Fix the warnings and try again."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3067,6 +3356,7 @@ Fix the warnings and try again."
                     text: format!("{feature} is not supported for JavaScript compilation."),
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -3096,6 +3386,7 @@ The error from the package manager client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3112,6 +3403,7 @@ The error from the HTTP client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3128,6 +3420,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3140,6 +3433,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3158,15 +3452,62 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
-            Error::GitDependencyUnsupported => vec![Diagnostic {
-                title: "Git dependencies are not currently supported".into(),
-                text: "Please remove all git dependencies from the gleam.toml file".into(),
+            Error::GitCommandFailed {
+                package,
+                repo,
+                output,
+            } => vec![Diagnostic {
+                title: "Failed to fetch git dependency".into(),
+                text: format!(
+                    "The git repository `{repo}` of the dependency `{package}` could not be
+fetched. The output from git was:
+
+{}",
+                    output.trim_end()
+                ),
                 hint: None,
                 location: None,
                 level: Level::Error,
+                code,
+            }],
+
+            Error::GitReferenceNotFound {
+                package,
+                repo,
+                reference,
+            } => vec![Diagnostic {
+                title: "Git reference not found".into(),
+                text: format!(
+                    "The dependency `{package}` is taken from {reference} of the git
+repository `{repo}`, but that could not be found in the repository."
+                ),
+                hint: Some("Check the git requirement in gleam.toml.".into()),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::GitLockedCommitNotFound {
+                package,
+                repo,
+                commit,
+            } => vec![Diagnostic {
+                title: "Locked git commit not found".into(),
+                text: format!(
+                    "The dependency `{package}` is locked to commit {commit} of the git
+repository `{repo}` in manifest.toml, but that commit could not be found in
+the repository. It may have been removed from the repository's history."
+                ),
+                hint: Some(format!(
+                    "Run `gleam update {package}` to lock it to a commit that exists."
+                )),
+                location: None,
+                level: Level::Error,
+                code,
             }],
 
             Error::WrongDependencyProvided {
@@ -3184,6 +3525,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3202,6 +3544,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3216,6 +3559,7 @@ dev-dependencies sections of the gleam.toml file."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3247,6 +3591,7 @@ licences = ["Apache-2.0"]"#
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3260,6 +3605,7 @@ because dependency `{package}` is not a Hex dependency.",
                 hint: None,
                 location: None,
                 level: Level::Error,
+                code,
             }],
 
             Error::UnsupportedBuildTool {
@@ -3281,6 +3627,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3297,6 +3644,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     text,
                     hint: None,
                     level: Level::Error,
+                    code,
                     location: None,
                 }]
             }
@@ -3316,6 +3664,7 @@ but you are using v{gleam_version}.",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3341,6 +3690,7 @@ but you are using v{gleam_version}.",
                     hint,
                     location: None,
                     level: Level::Error,
+                    code,
                 }]
             }
 
@@ -3349,6 +3699,7 @@ but you are using v{gleam_version}.",
                 text: "The --javascript-prelude flag must be given when compiling to JavaScript."
                     .into(),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: None,
             }],
@@ -3356,6 +3707,7 @@ but you are using v{gleam_version}.",
                 title: "Corrupt manifest.toml".into(),
                 text: "The `manifest.toml` file is corrupt.".into(),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Please run `gleam update` to fix it.".into()),
             }],
@@ -3371,6 +3723,7 @@ to compile and load your module it would overwrite the Erlang one, potentially \
 causing confusing errors and crashes.
 "),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Rename this module and try again.".into()),
             }],
@@ -3381,9 +3734,274 @@ causing confusing errors and crashes.
 This release has been recently published so you can replace it \
 or you can publish it using a different version number"),
                 level: Level::Error,
+                code,
                 location: None,
                 hint: Some("Please add the --replace flag if you want to replace the release.".into()),
             }],
+
+            Error::UnknownErrorCode { code: unknown } => vec![Diagnostic {
+                title: "Unknown error code".into(),
+                text: wrap_format!("There is no error or warning with the code `{unknown}`."),
+                level: Level::Error,
+                code,
+                location: None,
+                hint: Some("Codes look like `E2004` or `W1014` and are shown with each \
+error and warning.".into()),
+            }],
+
+            Error::DeniedWarnings { count } => {
+                let word_warning = match count {
+                    1 => "warning",
+                    _ => "warnings",
+                };
+                vec![Diagnostic {
+                    title: format!("{count} denied {word_warning} generated."),
+                    text: "The `[warnings]` section of your gleam.toml denies these kinds of
+warning. Fix the warnings and try again."
+                        .into(),
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::UnknownDependency { name } => vec![Diagnostic {
+                title: "Unknown dependency".into(),
+                text: format!("The package `{name}` is not a dependency of this project."),
+                hint: Some("Run `gleam deps list` to see the packages in the manifest.".into()),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::UnknownHexRepository {
+                package,
+                repository,
+            } => vec![Diagnostic {
+                title: "Unknown Hex repository".into(),
+                text: format!(
+                    "The package `{package}` is fetched from the Hex repository `{repository}`,
+but no repository with that name is declared in gleam.toml."
+                ),
+                hint: Some(format!(
+                    "Add a `[hex-repositories.{repository}]` section with the URL of the repository."
+                )),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::InvalidAddArguments { problem } => vec![Diagnostic {
+                title: "Invalid arguments".into(),
+                text: problem.clone(),
+                hint: Some("Run `gleam add --help` to see how packages can be added.".into()),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::OfflinePackageUnavailable { package, version } => vec![Diagnostic {
+                title: "Package not available offline".into(),
+                text: format!(
+                    "The package `{package}` {version} is needed, but it is neither in the
+vendor directory nor in the global package cache, and it cannot be downloaded
+from Hex because GLEAM_OFFLINE is set."
+                ),
+                hint: Some(
+                    "Run `gleam deps vendor` with network access to vendor every package in manifest.toml."
+                        .into(),
+                ),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::VendoredPackageChecksumMismatch {
+                package,
+                version,
+                path,
+            } => vec![Diagnostic {
+                title: "Vendored package checksum mismatch".into(),
+                text: format!(
+                    "The tarball of `{package}` {version} at {path} does not match the
+checksum recorded for it in manifest.toml. The file may have been modified or
+be from a different release of the package."
+                ),
+                hint: Some("Run `gleam deps vendor` to vendor the package again.".into()),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::UnknownWorkspaceMember { package, members } => {
+                let (text, hint) = if members.is_empty() {
+                    (
+                        format!(
+                            "The package `{package}` was selected, but this project is not
+part of a workspace."
+                        ),
+                        None,
+                    )
+                } else {
+                    let members = members.iter().map(|member| format!("`{member}`")).join(", ");
+                    (
+                        format!("The package `{package}` is not a member of this workspace."),
+                        Some(format!("The members of this workspace are {members}.")),
+                    )
+                };
+                vec![Diagnostic {
+                    title: "Unknown workspace member".into(),
+                    text,
+                    hint,
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::JavaScriptBundle { path, problem } => vec![Diagnostic {
+                title: "Module could not be bundled".into(),
+                text: format!(
+                    "The JavaScript module {path} could not be bundled.
+
+{problem}"
+                ),
+                hint: None,
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::DisallowedLicences { packages } => {
+                let mut text = "These dependencies have no licence that is in the
+`allowed-licences` list of your gleam.toml:
+"
+                .to_string();
+                for (package, licences) in packages {
+                    text.push_str(&format!("\n  - {package}: {}", licences.join(", ")));
+                }
+                vec![Diagnostic {
+                    title: "Licence not allowed".into(),
+                    text,
+                    hint: Some(
+                        "Add the licences to `allowed-licences` if they are acceptable, \
+or remove the dependencies."
+                            .into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::InsufficientVersionBump {
+                previous,
+                version,
+                suggested,
+                required,
+                changes,
+            } => {
+                let mut text = match required {
+                    None => format!(
+                        "The version in gleam.toml, {version}, is not greater than the \
+version of the previous release, {previous}."
+                    ),
+                    Some(required) => format!(
+                        "The version in gleam.toml, {version}, is too small an increase \
+from the version of the previous release, {previous}. Its API has changed in a way that \
+needs a {required} version bump."
+                    ),
+                };
+                if !changes.is_empty() {
+                    text.push('\n');
+                    for change in changes {
+                        text.push_str(&format!("\n  - {change}"));
+                    }
+                }
+                vec![Diagnostic {
+                    title: "Version bump too small".into(),
+                    text,
+                    hint: Some(format!(
+                        "Change the version in gleam.toml to {suggested}."
+                    )),
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::MissingPackageInterface { package, version } => vec![Diagnostic {
+                title: "No package interface".into(),
+                text: format!(
+                    "The documentation of {package} {version} on Hex has no package
+interface to compare the API with. It may have been published by a version
+of Gleam that did not include one."
+                ),
+                hint: Some(
+                    "Create one with `gleam export package-interface` for that version \
+of the package and give it with `--baseline`."
+                        .into(),
+                ),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
+
+            Error::TemplateDoesNotSupportTarget { template, target } => {
+                let target = match target {
+                    Target::Erlang => "Erlang",
+                    Target::JavaScript => "JavaScript",
+                };
+                vec![Diagnostic {
+                    title: "Target not supported".into(),
+                    text: format!(
+                        "The {template} project template can't be used with the {target} target."
+                    ),
+                    hint: Some(
+                        "Leave out `--target` to use the target the template is made for.".into(),
+                    ),
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::NoTestsFound { filtered } => {
+                let text = if *filtered {
+                    "No tests match the given filters and modules."
+                } else {
+                    "No tests were found in the `test` directory. Tests are public
+functions with names ending in `_test` that take no arguments."
+                };
+                vec![Diagnostic {
+                    title: "No tests found".into(),
+                    text: text.into(),
+                    hint: None,
+                    location: None,
+                    level: Level::Error,
+                    code,
+                }]
+            }
+
+            Error::ConflictingHexRepositories {
+                repository,
+                packages: (first, second),
+            } => vec![Diagnostic {
+                title: "Conflicting Hex repositories".into(),
+                text: format!(
+                    "The workspace members `{first}` and `{second}` both define the Hex
+repository `{repository}`, but their definitions are different. The
+dependencies of every member are resolved together, so they must agree on
+where the packages of each repository come from."
+                ),
+                hint: Some(format!(
+                    "Give `{repository}` the same definition in both members."
+                )),
+                location: None,
+                level: Level::Error,
+                code,
+            }],
         }
     }
 }
@@ -3476,6 +4094,46 @@ pub struct Unformatted {
     pub output: String,
 }
 
+impl Unformatted {
+    /// A diagnostic for this file alone, pointing at the first line that
+    /// differs from the formatted output. This is used by machine readable
+    /// output, which reports each unformatted file separately.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let mut start = 0;
+        let mut input_lines = self.input.split_inclusive('\n');
+        let mut output_lines = self.output.split_inclusive('\n');
+        let end = loop {
+            match (input_lines.next(), output_lines.next()) {
+                (Some(input), Some(output)) if input == output => start += input.len(),
+                (Some(input), _) => break start + input.trim_end_matches(['\r', '\n']).len(),
+                (None, _) => break start,
+            }
+        };
+
+        Diagnostic {
+            title: "This file has not been formatted".into(),
+            text: "".into(),
+            level: Level::Error,
+            code: Some(
+                Error::Format {
+                    problem_files: vec![],
+                }
+                .code(),
+            ),
+            location: Some(Location {
+                src: self.input.clone(),
+                path: self.source.clone(),
+                label: Label {
+                    text: Some("This differs from the formatted code".into()),
+                    span: crate::ast::SrcSpan::new(start as u32, end as u32),
+                },
+                extra_labels: vec![],
+            }),
+            hint: Some("Run `gleam format` to format it.".into()),
+        }
+    }
+}
+
 pub fn wrap(text: &str) -> String {
     textwrap::fill(text, std::cmp::min(75, textwrap::termwidth()))
 }
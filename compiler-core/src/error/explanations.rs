@@ -0,0 +1,150 @@
+//! Long-form explanations of the errors and warnings that the compiler can
+//! emit, looked up by their code and shown by `gleam explain`.
+//!
+//! Each explanation is a Markdown file in the `explanations` directory, named
+//! after its code. When a new kind of error or warning is added its
+//! explanation must be added here too.
+//!
+
+macro_rules! explanations {
+    ($($code:ident),* $(,)?) => {
+        /// Every code with its explanation, ordered by code.
+        const EXPLANATIONS: &[(&str, &str)] = &[
+            $((
+                stringify!($code),
+                include_str!(concat!("explanations/", stringify!($code), ".md")),
+            )),*
+        ];
+    };
+}
+
+explanations! {
+    // Build tool errors
+    E0001, E0002, E0003, E0004, E0005, E0006, E0007, E0008, E0009, E0010,
+    E0011, E0012, E0013, E0014, E0015, E0016, E0017, E0018, E0019, E0020,
+    E0021, E0022, E0023, E0024, E0025, E0026, E0027, E0028, E0029, E0030,
+    E0031, E0032, E0033, E0034, E0035, E0036, E0037, E0038, E0039, E0040,
+    E0041, E0042, E0043, E0044, E0045, E0046, E0047, E0048, E0049, E0050,
+    E0051, E0052, E0053, E0054, E0055, E0056, E0057, E0058, E0059, E0060,
+    E0061, E0062, E0063, E0064, E0065, E0066, E0067, E0068, E0069,
+    // Syntax errors
+    E1001, E1002, E1003, E1004, E1005, E1006, E1007, E1008, E1009, E1010,
+    E1011, E1012, E1013, E1014, E1015, E1016, E1017, E1018, E1019, E1020,
+    E1021, E1022, E1023, E1024, E1025, E1026, E1027, E1028, E1029, E1030,
+    E1031, E1032, E1033, E1034, E1035, E1036, E1037, E1038, E1039, E1040,
+    E1041, E1042, E1043, E1044, E1045,
+    // Type errors
+    E2001, E2002, E2003, E2004, E2005, E2006, E2007, E2008, E2009, E2010,
+    E2011, E2012, E2013, E2014, E2015, E2016, E2017, E2018, E2019, E2020,
+    E2021, E2022, E2023, E2024, E2025, E2026, E2027, E2028, E2029, E2030,
+    E2031, E2032, E2033, E2034, E2035, E2036, E2037, E2038, E2039, E2040,
+    E2041, E2042, E2043, E2044, E2045, E2046, E2047, E2048, E2049, E2050,
+    E2051, E2052, E2053, E2054, E2055,
+    // Code generation errors
+    E3001,
+    // Build tool warnings
+    W0001,
+    // Type checker warnings
+    W1001, W1002, W1003, W1004, W1005, W1006, W1007, W1008, W1009, W1010,
+    W1011, W1012, W1013, W1014, W1015, W1016, W1017, W1018, W1019, W1020,
+    W1021, W1022, W1023, W1024, W1025, W1026, W1027, W1028, W1029,
+}
+
+/// The explanation for an error or warning code, such as `E2004`. Codes are
+/// matched case-insensitively.
+///
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(code.trim()))
+        .map(|(_, explanation)| *explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn explanation_is_case_insensitive() {
+        assert_eq!(explanation("e2004"), explanation("E2004"));
+        assert!(explanation("E2004").is_some());
+    }
+
+    #[test]
+    fn unknown_code_has_no_explanation() {
+        assert_eq!(explanation("E9999"), None);
+        assert_eq!(explanation("wibble"), None);
+    }
+
+    #[test]
+    fn codes_are_sorted_and_unique() {
+        let codes: Vec<_> = EXPLANATIONS.iter().map(|(code, _)| *code).collect();
+        let mut sorted = codes.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(codes, sorted);
+    }
+
+    #[test]
+    fn every_explanation_file_is_included() {
+        let directory =
+            camino::Utf8Path::new(env!("CARGO_MANIFEST_DIR")).join("src/error/explanations");
+        let mut files: Vec<_> = std::fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        let expected: Vec<_> = EXPLANATIONS
+            .iter()
+            .map(|(code, _)| format!("{code}.md"))
+            .collect();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn every_code_has_an_explanation() {
+        // The errors and warnings cannot be enumerated, so the codes are read
+        // from the `code` methods that return them, each of which matches on
+        // the variants and returns a literal code.
+        let code = regex::Regex::new(r#"=> "([EW][0-9]{4})""#).unwrap();
+        let mut codes = vec![];
+        for file in [
+            "src/error.rs",
+            "src/javascript.rs",
+            "src/parse/error.rs",
+            "src/type_/error.rs",
+            "src/warning.rs",
+        ] {
+            let path = camino::Utf8Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+            let source = std::fs::read_to_string(path).unwrap();
+            codes.extend(
+                code.captures_iter(&source)
+                    .map(|captures| captures.get(1).unwrap().as_str().to_string()),
+            );
+        }
+        let missing: Vec<_> = codes
+            .iter()
+            .filter(|code| explanation(code).is_none())
+            .collect();
+        assert_eq!(missing, Vec::<&String>::new());
+    }
+
+    #[test]
+    fn codes_in_each_range_are_contiguous() {
+        // Codes are given out in order within each range, so a gap means a
+        // code is missing its explanation.
+        for (range, codes) in &EXPLANATIONS
+            .iter()
+            .map(|(code, _)| code)
+            .group_by(|code| code.get(..2))
+        {
+            let numbers: Vec<u32> = codes
+                .map(|code| code.get(2..).unwrap().parse().unwrap())
+                .collect();
+            let first = numbers.first().copied().unwrap() / 1000 * 1000 + 1;
+            let expected: Vec<u32> = (first..first + numbers.len() as u32).collect();
+            assert_eq!(numbers, expected, "{range:?}");
+        }
+    }
+}
@@ -143,10 +143,19 @@ impl<'comments> Formatter<'comments> {
 
     fn targeted_definition<'a>(&mut self, definition: &'a TargetedDefinition) -> Document<'a> {
         let target = definition.target;
+        let allowed_warnings = &definition.allowed_warnings;
         let definition = &definition.definition;
         let start = definition.location().start;
         let comments = self.pop_comments(start);
         let document = self.documented_definition(definition);
+        let document = if allowed_warnings.is_empty() {
+            document
+        } else {
+            let names = allowed_warnings
+                .iter()
+                .map(|allowed| allowed.name.as_str().to_doc());
+            docvec!["@allow(", join(names, ", ".to_doc()), ")", line(), document]
+        };
         let document = match target {
             None => document,
             Some(Target::Erlang) => docvec!["@target(erlang)", line(), document],
@@ -6156,3 +6156,52 @@ fn newlines_are_not_stripped_if_two_consecutive_anonymous_function_are_passed_as
 "#
     );
 }
+
+#[test]
+fn allow_attribute() {
+    assert_format!(
+        "@allow(todo)
+pub fn main() {
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_with_several_warnings() {
+    assert_format!(
+        "@allow(todo, unused_variable)
+pub fn main() {
+  let x = 1
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_with_target() {
+    assert_format!(
+        "@target(erlang)
+@allow(unused_imported_module)
+import wibble
+"
+    );
+}
+
+#[test]
+fn allow_attribute_trailing_comma_is_removed() {
+    assert_format_rewrite!(
+        "@allow(todo,)
+pub fn main() {
+  todo
+}
+",
+        "@allow(todo)
+pub fn main() {
+  todo
+}
+"
+    );
+}
@@ -1,11 +1,21 @@
-use camino::Utf8Path;
+use std::{collections::HashMap, io::Read};
+
+use camino::{Utf8Path, Utf8PathBuf};
 use debug_ignore::DebugIgnore;
+use ecow::EcoString;
 use flate2::read::GzDecoder;
 use futures::future;
-use hexpm::{version::Version, ApiError};
+use hexpm::{
+    version::{Range, Version},
+    ApiError,
+};
+use sha2::Digest;
 use tar::Archive;
 
 use crate::{
+    config::{HexRepositoryConfig, PackageConfig, UserConfig},
+    erlang::term::{self, Term},
+    error::{FileIoAction, FileKind},
     io::{FileSystemReader, FileSystemWriter, HttpClient, TarUnpacker},
     manifest::{ManifestPackage, ManifestPackageSource},
     paths::{self, ProjectPaths},
@@ -23,6 +33,121 @@ J1i2xWFndWa6nfFnRxZmCStCOZWYYPlaxr+FZceFbpMwzTNs4g3d4tLNUcbKAIH4
 -----END PUBLIC KEY-----
 ";
 
+/// The name of the public Hex repository, hex.pm.
+pub const HEXPM_REPOSITORY: &str = "hexpm";
+
+/// A Hex repository that packages are fetched from.
+#[derive(Debug, Clone)]
+pub struct HexRepository {
+    pub name: EcoString,
+    pub config: hexpm::Config,
+    /// The key the repository's registry is signed with. Registry responses
+    /// that are not signed with it are rejected.
+    pub public_key: Vec<u8>,
+    pub auth_key: Option<String>,
+}
+
+impl HexRepository {
+    pub fn hexpm() -> Self {
+        Self {
+            name: HEXPM_REPOSITORY.into(),
+            config: hexpm::Config::new(),
+            public_key: HEXPM_PUBLIC_KEY.to_vec(),
+            auth_key: None,
+        }
+    }
+
+    fn from_config(
+        name: EcoString,
+        config: &HexRepositoryConfig,
+        auth_key: Option<String>,
+    ) -> Self {
+        let repository_base = with_trailing_slash(&config.url);
+        let api_base = match &config.api_url {
+            Some(url) => with_trailing_slash(url),
+            None => repository_base.clone(),
+        };
+        // Only a mirror of hex.pm may leave out its key, which is checked when
+        // the config is parsed.
+        let public_key = match &config.public_key {
+            Some(key) => key.as_bytes().to_vec(),
+            None => HEXPM_PUBLIC_KEY.to_vec(),
+        };
+        Self {
+            name,
+            config: hexpm::Config {
+                api_base,
+                repository_base,
+            },
+            public_key,
+            auth_key,
+        }
+    }
+
+    pub fn auth_key(&self) -> Option<&str> {
+        self.auth_key.as_deref()
+    }
+}
+
+// Request paths are appended to the base URLs, so they must end with a slash.
+fn with_trailing_slash(uri: &http::Uri) -> http::Uri {
+    let uri = uri.to_string();
+    if uri.ends_with('/') {
+        return uri.parse().expect("Hex repository URL");
+    }
+    format!("{uri}/").parse().expect("Hex repository URL")
+}
+
+/// The Hex repositories a project can fetch packages from: hex.pm and any
+/// declared in the `[hex-repositories]` section of the user config or of its
+/// `gleam.toml`.
+#[derive(Debug, Clone)]
+pub struct HexRepositories {
+    repositories: HashMap<EcoString, HexRepository>,
+}
+
+impl HexRepositories {
+    /// `auth_key` looks up the value of the environment variable a repository
+    /// takes its authentication key from. A repository declared by the
+    /// project replaces one of the same name from the user config.
+    pub fn new(
+        user_config: &UserConfig,
+        config: &PackageConfig,
+        auth_key: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut repositories = Self::default();
+        let declared = user_config
+            .hex_repositories
+            .iter()
+            .chain(&config.hex_repositories);
+        for (name, repository) in declared {
+            let key = repository.auth_key_env.as_deref().and_then(&auth_key);
+            let repository = HexRepository::from_config(name.clone(), repository, key);
+            let _ = repositories.repositories.insert(name.clone(), repository);
+        }
+        repositories
+    }
+
+    /// The repository with the given name, or hex.pm when there is no name.
+    pub fn get(&self, package: &str, name: Option<&EcoString>) -> Result<&HexRepository> {
+        let name = name.map(EcoString::as_str).unwrap_or(HEXPM_REPOSITORY);
+        self.repositories
+            .get(name)
+            .ok_or_else(|| Error::UnknownHexRepository {
+                package: package.into(),
+                repository: name.into(),
+            })
+    }
+}
+
+impl Default for HexRepositories {
+    fn default() -> Self {
+        Self {
+            repositories: [(HEXPM_REPOSITORY.into(), HexRepository::hexpm())].into(),
+        }
+    }
+}
+
 fn key_name(hostname: &str) -> String {
     format!("gleam-{hostname}")
 }
@@ -132,8 +257,11 @@ pub struct Downloader {
     fs_writer: DebugIgnore<Box<dyn FileSystemWriter>>,
     http: DebugIgnore<Box<dyn HttpClient>>,
     untar: DebugIgnore<Box<dyn TarUnpacker>>,
-    hex_config: hexpm::Config,
+    repositories: HexRepositories,
     paths: ProjectPaths,
+    /// When set packages are only taken from the vendor directory and the
+    /// package cache, never downloaded.
+    offline: bool,
 }
 
 impl Downloader {
@@ -142,15 +270,18 @@ impl Downloader {
         fs_writer: Box<dyn FileSystemWriter>,
         http: Box<dyn HttpClient>,
         untar: Box<dyn TarUnpacker>,
+        repositories: HexRepositories,
         paths: ProjectPaths,
+        offline: bool,
     ) -> Self {
         Self {
             fs_reader: DebugIgnore(fs_reader),
             fs_writer: DebugIgnore(fs_writer),
             http: DebugIgnore(http),
             untar: DebugIgnore(untar),
-            hex_config: hexpm::Config::new(),
+            repositories,
             paths,
+            offline,
         }
     }
 
@@ -158,14 +289,17 @@ impl Downloader {
         &self,
         package: &ManifestPackage,
     ) -> Result<bool, Error> {
-        let outer_checksum = if let ManifestPackageSource::Hex { outer_checksum } = &package.source
-        {
-            outer_checksum
-        } else {
+        let ManifestPackageSource::Hex {
+            outer_checksum,
+            repository,
+        } = &package.source
+        else {
             panic!("Attempt to download non-hex package from hex")
         };
+        let repository = self.repositories.get(&package.name, repository.as_ref())?;
 
         let tarball_path = paths::global_package_cache_package_tarball(
+            &repository.name,
             &package.name,
             &package.version.to_string(),
         );
@@ -177,6 +311,37 @@ impl Downloader {
             );
             return Ok(false);
         }
+
+        let vendored_path = self.paths.vendor_package_tarball(
+            &repository.name,
+            &package.name,
+            &package.version.to_string(),
+        );
+        if self.fs_reader.is_file(&vendored_path) {
+            tracing::info!(
+                package = package.name.as_str(),
+                version = %package.version,
+                "copying_vendored_package_to_cache"
+            );
+            let tarball = self.fs_reader.read_bytes(&vendored_path)?;
+            if sha2::Sha256::digest(&tarball).as_slice() != outer_checksum.0.as_slice() {
+                return Err(Error::VendoredPackageChecksumMismatch {
+                    package: package.name.clone(),
+                    version: package.version.clone(),
+                    path: vendored_path,
+                });
+            }
+            self.fs_writer.write_bytes(&tarball_path, &tarball)?;
+            return Ok(true);
+        }
+
+        if self.offline {
+            return Err(Error::OfflinePackageUnavailable {
+                package: package.name.clone(),
+                version: package.version.clone(),
+            });
+        }
+
         tracing::info!(
             package = &package.name.as_str(),
             version = %package.version,
@@ -186,8 +351,8 @@ impl Downloader {
         let request = hexpm::get_package_tarball_request(
             &package.name,
             &package.version.to_string(),
-            None,
-            &self.hex_config,
+            repository.auth_key(),
+            &repository.config,
         );
         let response = self.http.send(request).await?;
 
@@ -203,16 +368,46 @@ impl Downloader {
         Ok(true)
     }
 
+    /// Copies the tarball of a package into the vendor directory of the
+    /// project, downloading it into the package cache first if needed.
+    pub async fn vendor_package(&self, package: &ManifestPackage) -> Result<()> {
+        let _ = self.ensure_package_downloaded(package).await?;
+        let tarball = self.fs_reader.read_bytes(&self.cached_tarball(package)?)?;
+        let destination = self.paths.vendor_package_tarball(
+            &self.repository(package)?.name,
+            &package.name,
+            &package.version.to_string(),
+        );
+        self.fs_writer.write_bytes(&destination, &tarball)
+    }
+
+    fn repository(&self, package: &ManifestPackage) -> Result<&HexRepository> {
+        let repository = match &package.source {
+            ManifestPackageSource::Hex { repository, .. } => repository.as_ref(),
+            _ => None,
+        };
+        self.repositories.get(&package.name, repository)
+    }
+
+    fn cached_tarball(&self, package: &ManifestPackage) -> Result<Utf8PathBuf> {
+        Ok(paths::global_package_cache_package_tarball(
+            &self.repository(package)?.name,
+            &package.name,
+            &package.version.to_string(),
+        ))
+    }
+
     pub async fn ensure_package_in_build_directory(
         &self,
         package: &ManifestPackage,
     ) -> Result<bool> {
         let _ = self.ensure_package_downloaded(package).await?;
-        self.extract_package_from_cache(&package.name, &package.version)
+        self.extract_package_from_cache(package)
     }
 
     // It would be really nice if this was async but the library is sync
-    pub fn extract_package_from_cache(&self, name: &str, version: &Version) -> Result<bool> {
+    pub fn extract_package_from_cache(&self, package: &ManifestPackage) -> Result<bool> {
+        let name = package.name.as_str();
         let contents_path = Utf8Path::new("contents.tar.gz");
         let destination = self.paths.build_packages_package(name);
 
@@ -223,7 +418,7 @@ impl Downloader {
         }
 
         tracing::info!(package = name, "writing_package_to_target");
-        let tarball = paths::global_package_cache_package_tarball(name, &version.to_string());
+        let tarball = self.cached_tarball(package)?;
         let reader = self.fs_reader.reader(&tarball)?;
         let mut archive = Archive::new(reader);
 
@@ -275,6 +470,125 @@ impl Downloader {
     }
 }
 
+/// The releases of a package that can be used without network access, read
+/// from its tarballs in the vendor directory and in the package cache. The
+/// vendored tarball of a release is used if it is in both.
+pub fn offline_releases(
+    fs: &dyn FileSystemReader,
+    paths: &ProjectPaths,
+    repository: &str,
+    package: &str,
+) -> Result<Vec<hexpm::Release<hexpm::ReleaseMeta>>> {
+    let directories = [
+        paths.vendor_repository_directory(repository),
+        paths::global_packages_cache(repository),
+    ];
+    let prefix = format!("{package}-");
+    let mut releases: Vec<hexpm::Release<hexpm::ReleaseMeta>> = vec![];
+    for directory in directories {
+        if !fs.is_directory(&directory) {
+            continue;
+        }
+        for entry in fs.read_dir(&directory)?.into_iter().filter_map(Result::ok) {
+            let path = entry.into_path();
+            let is_release = path.parent() == Some(&directory)
+                && path
+                    .file_name()
+                    .and_then(|name| name.strip_prefix(&prefix))
+                    .and_then(|name| name.strip_suffix(".tar"))
+                    .is_some_and(|version| Version::parse(version).is_ok());
+            if !is_release {
+                continue;
+            }
+            let release = release_from_tarball(&path, &fs.read_bytes(&path)?)?;
+            if releases
+                .iter()
+                .all(|other| other.version != release.version)
+            {
+                releases.push(release);
+            }
+        }
+    }
+    Ok(releases)
+}
+
+/// The release described by the `metadata.config` file of a package tarball.
+fn release_from_tarball(
+    path: &Utf8Path,
+    tarball: &[u8],
+) -> Result<hexpm::Release<hexpm::ReleaseMeta>> {
+    let invalid = |problem: String| Error::FileIo {
+        action: FileIoAction::Parse,
+        kind: FileKind::File,
+        path: path.to_path_buf(),
+        err: Some(problem),
+    };
+
+    let mut metadata = None;
+    let mut archive = Archive::new(tarball);
+    for entry in archive.entries().map_err(Error::expand_tar)? {
+        let mut entry = entry.map_err(Error::expand_tar)?;
+        if entry.path().map_err(Error::expand_tar)?.as_os_str() == "metadata.config" {
+            let mut text = String::new();
+            let _ = entry.read_to_string(&mut text).map_err(Error::expand_tar)?;
+            metadata = Some(text);
+            break;
+        }
+    }
+    let metadata = metadata.ok_or_else(|| invalid("The tarball has no metadata.config".into()))?;
+    let metadata =
+        Term::List(term::consult(&metadata).map_err(|error| invalid(error.to_string()))?);
+
+    let text = |key| metadata.get(key).and_then(Term::as_str);
+    let list = |key| {
+        metadata
+            .get(key)
+            .and_then(Term::as_list)
+            .unwrap_or_default()
+    };
+    let version = text("version")
+        .and_then(|version| Version::parse(version).ok())
+        .ok_or_else(|| invalid("The metadata.config has no valid version".into()))?;
+    Ok(hexpm::Release {
+        version,
+        requirements: list("requirements")
+            .iter()
+            .filter_map(requirement_from_term)
+            .collect(),
+        retirement_status: None,
+        outer_checksum: sha2::Sha256::digest(tarball).to_vec(),
+        meta: hexpm::ReleaseMeta {
+            app: text("app").or(text("name")).unwrap_or_default().into(),
+            build_tools: list("build_tools")
+                .iter()
+                .filter_map(Term::as_str)
+                .map(String::from)
+                .collect(),
+        },
+    })
+}
+
+/// A requirement in `metadata.config`, which is either `{Name, Properties}`
+/// or, in older packages, a list of properties including the name.
+fn requirement_from_term(requirement: &Term) -> Option<(String, hexpm::Dependency)> {
+    let (name, properties) = match requirement {
+        Term::Tuple(pair) => match pair.as_slice() {
+            [name, properties] => (name.as_str()?, properties),
+            _ => return None,
+        },
+        Term::List(_) => (requirement.get("name")?.as_str()?, requirement),
+        _ => return None,
+    };
+    let text = |key| properties.get(key).and_then(Term::as_str);
+    let dependency = hexpm::Dependency {
+        requirement: Range::new(text("requirement")?.into()),
+        optional: text("optional") == Some("true"),
+        app: text("app").map(String::from),
+        repository: text("repository").map(String::from),
+    };
+    Some((name.into(), dependency))
+}
+
 pub async fn publish_documentation<Http: HttpClient>(
     name: &str,
     version: &Version,
@@ -293,6 +607,7 @@ pub async fn publish_documentation<Http: HttpClient>(
 pub async fn get_package_release<Http: HttpClient>(
     name: &str,
     version: &Version,
+    api_key: Option<&str>,
     config: &hexpm::Config,
     http: &Http,
 ) -> Result<hexpm::Release<hexpm::ReleaseMeta>> {
@@ -302,7 +617,349 @@ pub async fn get_package_release<Http: HttpClient>(
         version = version.as_str(),
         "looking_up_package_release"
     );
-    let request = hexpm::get_package_release_request(name, &version, None, config);
+    let request = hexpm::get_package_release_request(name, &version, api_key, config);
     let response = http.send(request).await?;
     hexpm::get_package_release_response(response).map_err(Error::hex)
 }
+
+/// The versions of a package that have been published to a Hex repository.
+/// There are none if the package has never been published.
+pub async fn get_published_versions<Http: HttpClient>(
+    name: &str,
+    repository: &HexRepository,
+    http: &Http,
+) -> Result<Vec<Version>> {
+    tracing::info!(name = name, "looking_up_published_versions");
+    let request = hexpm::get_package_request(name, repository.auth_key(), &repository.config);
+    let response = http.send(request).await?;
+    match hexpm::get_package_response(response, &repository.public_key) {
+        Ok(package) => Ok(package
+            .releases
+            .into_iter()
+            .map(|release| release.version)
+            .collect()),
+        Err(ApiError::NotFound) => Ok(vec![]),
+        Err(error) => Err(Error::hex(error)),
+    }
+}
+
+/// The package interface included in the documentation published for a
+/// release. This is `None` if the release has no documentation, or if it was
+/// published by a version of Gleam that did not include the interface.
+pub async fn get_published_package_interface<Http: HttpClient>(
+    name: &str,
+    version: &Version,
+    config: &hexpm::Config,
+    http: &Http,
+) -> Result<Option<String>> {
+    tracing::info!(
+        name = name,
+        version = version.to_string().as_str(),
+        "downloading_published_documentation"
+    );
+    let uri = format!("{}docs/{name}-{version}.tar.gz", config.repository_base);
+    let request = http::Request::get(uri)
+        .body(vec![])
+        .expect("documentation request");
+    let response = http.send(request).await?;
+    let status = response.status();
+    if status == http::StatusCode::NOT_FOUND || status == http::StatusCode::FORBIDDEN {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(Error::hex(ApiError::UnexpectedResponse(
+            status,
+            String::from_utf8_lossy(response.body()).to_string(),
+        )));
+    }
+
+    let mut archive = Archive::new(GzDecoder::new(response.body().as_slice()));
+    for entry in archive.entries().map_err(Error::expand_tar)? {
+        let mut entry = entry.map_err(Error::expand_tar)?;
+        let path = entry.path().map_err(Error::expand_tar)?;
+        if path.as_os_str() != "package-interface.json" {
+            continue;
+        }
+        let mut interface = String::new();
+        let _ = entry
+            .read_to_string(&mut interface)
+            .map_err(Error::expand_tar)?;
+        return Ok(Some(interface));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{HexRepositoryConfig, UserConfig},
+        io::memory::InMemoryFileSystem,
+        manifest::Base16Checksum,
+    };
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    /// Packages are never downloaded in these tests, so any request fails
+    /// them.
+    struct NoHttp;
+
+    #[async_trait]
+    impl HttpClient for NoHttp {
+        async fn send(
+            &self,
+            request: http::Request<Vec<u8>>,
+        ) -> Result<http::Response<Vec<u8>>, Error> {
+            panic!("unexpected request to {}", request.uri())
+        }
+    }
+
+    struct NoUntar;
+
+    impl TarUnpacker for NoUntar {
+        fn io_result_entries<'a>(
+            &self,
+            _archive: &'a mut Archive<crate::io::WrappedReader>,
+        ) -> std::io::Result<tar::Entries<'a, crate::io::WrappedReader>> {
+            unreachable!("packages are not unpacked in these tests")
+        }
+
+        fn io_result_unpack(
+            &self,
+            _path: &Utf8Path,
+            _archive: Archive<GzDecoder<tar::Entry<'_, crate::io::WrappedReader>>>,
+        ) -> std::io::Result<()> {
+            unreachable!("packages are not unpacked in these tests")
+        }
+    }
+
+    fn tarball(name: &str, version: &str, requirements: &str) -> Vec<u8> {
+        let metadata = format!(
+            r#"{{<<"name">>, <<"{name}">>}}.
+{{<<"app">>, <<"{name}">>}}.
+{{<<"version">>, <<"{version}">>}}.
+{{<<"build_tools">>, [<<"gleam">>]}}.
+{{<<"requirements">>, [{requirements}]}}.
+"#
+        );
+        let mut builder = tar::Builder::new(vec![]);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(metadata.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "metadata.config", metadata.as_bytes())
+            .unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    fn manifest_package(tarball: &[u8], repository: Option<&str>) -> ManifestPackage {
+        ManifestPackage {
+            name: "wibble".into(),
+            version: Version::new(1, 0, 0),
+            build_tools: vec!["gleam".into()],
+            otp_app: None,
+            requirements: vec![],
+            source: ManifestPackageSource::Hex {
+                outer_checksum: Base16Checksum(sha2::Sha256::digest(tarball).to_vec()),
+                repository: repository.map(EcoString::from),
+            },
+        }
+    }
+
+    fn repositories() -> HexRepositories {
+        let acme = HexRepositoryConfig {
+            url: "https://hex.acme.com/repo".parse().unwrap(),
+            api_url: None,
+            public_key: Some("acme key".into()),
+            auth_key_env: None,
+        };
+        let user_config = UserConfig {
+            hex_repositories: [("acme".into(), acme)].into(),
+        };
+        HexRepositories::new(&user_config, &PackageConfig::default(), |_| None)
+    }
+
+    fn downloader(fs: &InMemoryFileSystem, offline: bool) -> Downloader {
+        Downloader::new(
+            Box::new(fs.clone()),
+            Box::new(fs.clone()),
+            Box::new(NoHttp),
+            Box::new(NoUntar),
+            repositories(),
+            ProjectPaths::new("/app".into()),
+            offline,
+        )
+    }
+
+    fn cached_tarball(repository: &str) -> Utf8PathBuf {
+        paths::global_package_cache_package_tarball(repository, "wibble", "1.0.0")
+    }
+
+    #[test]
+    fn vendored_package_is_copied_into_the_cache() {
+        let fs = InMemoryFileSystem::new();
+        let tarball = tarball("wibble", "1.0.0", "");
+        let vendored = Utf8Path::new("/app/vendor/hexpm/wibble-1.0.0.tar");
+        fs.write_bytes(vendored, &tarball).unwrap();
+
+        let package = manifest_package(&tarball, None);
+        let downloaded = block_on(downloader(&fs, true).ensure_package_downloaded(&package));
+        assert_eq!(downloaded, Ok(true));
+        assert_eq!(fs.read_bytes(&cached_tarball("hexpm")), Ok(tarball));
+    }
+
+    #[test]
+    fn vendored_package_with_wrong_checksum() {
+        let fs = InMemoryFileSystem::new();
+        let vendored = Utf8Path::new("/app/vendor/hexpm/wibble-1.0.0.tar");
+        fs.write_bytes(vendored, &tarball("wibble", "1.0.0", ""))
+            .unwrap();
+
+        let package = manifest_package(b"another release", None);
+        let downloaded = block_on(downloader(&fs, true).ensure_package_downloaded(&package));
+        assert_eq!(
+            downloaded,
+            Err(Error::VendoredPackageChecksumMismatch {
+                package: "wibble".into(),
+                version: Version::new(1, 0, 0),
+                path: vendored.into(),
+            })
+        );
+        assert!(!fs.is_file(&cached_tarball("hexpm")));
+    }
+
+    #[test]
+    fn offline_package_is_not_downloaded() {
+        let fs = InMemoryFileSystem::new();
+        let package = manifest_package(b"", Some("acme"));
+        let downloaded = block_on(downloader(&fs, true).ensure_package_downloaded(&package));
+        assert_eq!(
+            downloaded,
+            Err(Error::OfflinePackageUnavailable {
+                package: "wibble".into(),
+                version: Version::new(1, 0, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn vendor_packages_from_different_repositories() {
+        let fs = InMemoryFileSystem::new();
+        let hexpm = tarball("wibble", "1.0.0", "");
+        let acme = tarball("wibble", "1.0.0", r#"{<<"wobble">>, []}"#);
+        fs.write_bytes(&cached_tarball("hexpm"), &hexpm).unwrap();
+        fs.write_bytes(&cached_tarball("acme"), &acme).unwrap();
+
+        let downloader = downloader(&fs, false);
+        block_on(downloader.vendor_package(&manifest_package(&hexpm, None))).unwrap();
+        block_on(downloader.vendor_package(&manifest_package(&acme, Some("acme")))).unwrap();
+        assert_eq!(
+            fs.read_bytes(Utf8Path::new("/app/vendor/hexpm/wibble-1.0.0.tar")),
+            Ok(hexpm)
+        );
+        assert_eq!(
+            fs.read_bytes(Utf8Path::new("/app/vendor/acme/wibble-1.0.0.tar")),
+            Ok(acme)
+        );
+    }
+
+    #[test]
+    fn offline_releases_from_vendor_directory_and_cache() {
+        let fs = InMemoryFileSystem::new();
+        let paths = ProjectPaths::new("/app".into());
+        let vendored = tarball(
+            "wibble",
+            "1.0.0",
+            r#"{<<"gleam_stdlib">>, [
+  {<<"app">>, <<"gleam_stdlib">>},
+  {<<"optional">>, false},
+  {<<"requirement">>, <<"~> 0.34">>}
+]}"#,
+        );
+        fs.write_bytes(
+            &paths.vendor_package_tarball("hexpm", "wibble", "1.0.0"),
+            &vendored,
+        )
+        .unwrap();
+        fs.write_bytes(
+            &paths::global_package_cache_package_tarball("hexpm", "wibble", "1.1.0"),
+            &tarball("wibble", "1.1.0", ""),
+        )
+        .unwrap();
+        fs.write_bytes(
+            &paths.vendor_package_tarball("hexpm", "wibble_extra", "1.0.0"),
+            &tarball("wibble_extra", "1.0.0", ""),
+        )
+        .unwrap();
+
+        let mut releases = offline_releases(&fs, &paths, "hexpm", "wibble").unwrap();
+        releases.sort_by(|one, other| one.version.cmp(&other.version));
+        let versions = releases
+            .iter()
+            .map(|release| release.version.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec!["1.0.0", "1.1.0"]);
+
+        let release = releases.first().unwrap();
+        assert_eq!(
+            release.outer_checksum,
+            sha2::Sha256::digest(&vendored).to_vec()
+        );
+        assert_eq!(release.meta.app, "wibble");
+        assert_eq!(release.meta.build_tools, vec!["gleam".to_string()]);
+        assert_eq!(
+            release.requirements.get("gleam_stdlib"),
+            Some(&hexpm::Dependency {
+                requirement: Range::new("~> 0.34".into()),
+                optional: false,
+                app: Some("gleam_stdlib".into()),
+                repository: None,
+            })
+        );
+
+        assert!(offline_releases(&fs, &paths, "acme", "wibble")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn release_with_requirements_as_lists() {
+        let requirements = r#"[
+  {<<"name">>, <<"wobble">>},
+  {<<"optional">>, true},
+  {<<"repository">>, <<"acme">>},
+  {<<"requirement">>, <<">= 1.0.0">>}
+]"#;
+        let tarball = tarball("wibble", "1.0.0", requirements);
+        let release = release_from_tarball(Utf8Path::new("wibble.tar"), &tarball).unwrap();
+        assert_eq!(
+            release.requirements.get("wobble"),
+            Some(&hexpm::Dependency {
+                requirement: Range::new(">= 1.0.0".into()),
+                optional: true,
+                app: None,
+                repository: Some("acme".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn release_without_metadata() {
+        let mut builder = tar::Builder::new(vec![]);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "contents.tar.gz", [].as_slice())
+            .unwrap();
+        let tarball = builder.into_inner().unwrap();
+        assert!(matches!(
+            release_from_tarball(Utf8Path::new("wibble.tar"), &tarball),
+            Err(Error::FileIo {
+                action: FileIoAction::Parse,
+                ..
+            })
+        ));
+    }
+}
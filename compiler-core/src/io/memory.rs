@@ -2,7 +2,7 @@ use super::*;
 use std::ops::Deref;
 use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
 
-use camino::{Utf8Path, Utf8PathBuf};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 
 // An in memory sharable collection of pretend files that can be used in place
 // of a real file system. It is a shared reference to a set of buffer than can
@@ -130,8 +130,20 @@ impl FileSystemWriter for InMemoryFileSystem {
 }
 
 impl FileSystemReader for InMemoryFileSystem {
+    /// There are no links in memory, so a path is canonicalised by resolving
+    /// its `.` and `..` components.
     fn canonicalise(&self, path: &Utf8Path) -> Result<Utf8PathBuf, Error> {
-        Ok(path.to_path_buf())
+        let mut canonical = Utf8PathBuf::new();
+        for component in path.components() {
+            match component {
+                Utf8Component::CurDir => (),
+                Utf8Component::ParentDir => _ = canonical.pop(),
+                Utf8Component::Prefix(_) | Utf8Component::RootDir | Utf8Component::Normal(_) => {
+                    canonical.push(component)
+                }
+            }
+        }
+        Ok(canonical)
     }
 
     fn gleam_source_files(&self, dir: &Utf8Path) -> Vec<Utf8PathBuf> {
@@ -1,3 +1,4 @@
+mod bundle;
 mod expression;
 mod import;
 mod pattern;
@@ -21,6 +22,8 @@ use itertools::Itertools;
 
 use self::import::{Imports, Member};
 
+pub use self::bundle::{bundle, Bundle};
+
 const INDENT: isize = 2;
 
 pub const PRELUDE: &str = include_str!("../templates/prelude.mjs");
@@ -583,6 +586,13 @@ pub enum Error {
 }
 
 impl Error {
+    /// The stable code identifying this kind of error. See [`crate::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Unsupported { .. } => "E3001",
+        }
+    }
+
     /// Returns `true` if the error is [`Unsupported`].
     ///
     /// [`Unsupported`]: Error::Unsupported
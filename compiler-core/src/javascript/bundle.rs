@@ -0,0 +1,1598 @@
+//! Bundling of the JavaScript modules of a project into a single ES module,
+//! for platforms that cannot load the tree of modules in the build directory.
+//!
+//! Functions that cannot be reached from the public functions and constants
+//! of the entry module, or from the FFI modules, are left out of the generated
+//! modules, then every module that the entry module imports, directly or not,
+//! is wrapped in a function scope and concatenated. This includes the prelude
+//! and the FFI modules of each package.
+//!
+//! The modules are not parsed as JavaScript, they are split into tokens only
+//! far enough to find and rewrite their top level `import` and `export`
+//! statements. Each module exports into a namespace object, and the bindings
+//! it imports are assigned from the namespace objects of other modules once
+//! before the module is evaluated and once more after every module has been,
+//! so that modules which import each other see each other's exports.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use itertools::Itertools;
+
+use crate::{
+    analyse::TargetSupport,
+    ast::{
+        visit::{self, Visit},
+        Constant, Definition, Function, Import, ModuleConstant, SrcSpan, TypedModule,
+    },
+    build::Module,
+    codegen::TypeScriptDeclarations,
+    io::FileSystemReader,
+    line_numbers::LineNumbers,
+    type_::{ModuleValueConstructor, ValueConstructor, ValueConstructorVariant},
+    Error, Result,
+};
+
+/// A project bundled into a single ES module, along with the TypeScript
+/// declarations for its entry module if they were asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    pub javascript: String,
+    pub typescript_declarations: Option<String>,
+}
+
+/// Bundle the given modules into a single ES module that exports everything
+/// the `entry` module exports. FFI modules and the prelude are read from the
+/// build directory the modules were compiled into.
+pub fn bundle<IO: FileSystemReader>(
+    io: &IO,
+    build_directory: &Utf8Path,
+    modules: &[&Module],
+    entry: &str,
+    target_support: TargetSupport,
+    typescript: TypeScriptDeclarations,
+) -> Result<Bundle> {
+    let entry_module = modules
+        .iter()
+        .find(|module| module.name == entry)
+        .ok_or_else(|| Error::ModuleDoesNotExist {
+            module: entry.into(),
+            suggestion: None,
+        })?;
+    let ffi_roots = ffi_references(io, build_directory, modules);
+    let reachable = reachable_functions(modules, &entry_module.ast, ffi_roots);
+
+    let mut sources = HashMap::new();
+    let _ = sources.insert(Utf8PathBuf::from("prelude.mjs"), super::PRELUDE.to_string());
+    for module in modules {
+        let mut ast = module.ast.clone();
+        ast.definitions.retain(|definition| match definition {
+            Definition::Function(Function { name, .. }) => {
+                reachable.contains(&(module.name.clone(), name.clone()))
+            }
+            Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_)
+            | Definition::ModuleConstant(_) => true,
+        });
+        let javascript = super::module(
+            &ast,
+            &LineNumbers::new(&module.code),
+            &module.input_path,
+            &module.code,
+            target_support,
+            TypeScriptDeclarations::None,
+        )?;
+        let _ = sources.insert(module_path(module, "mjs"), javascript);
+    }
+    let mut linker = Linker::new(io, build_directory, sources, Language::JavaScript);
+    let javascript = linker.link_entry(&module_path(entry_module, "mjs"))?;
+
+    let typescript_declarations = match typescript {
+        TypeScriptDeclarations::None => None,
+        TypeScriptDeclarations::Emit => {
+            let mut sources = HashMap::new();
+            let _ = sources.insert(
+                Utf8PathBuf::from("prelude.d.mts"),
+                super::PRELUDE_TS_DEF.to_string(),
+            );
+            for module in modules {
+                let declarations =
+                    super::ts_declaration(&module.ast, &module.input_path, &module.code)?;
+                let _ = sources.insert(module_path(module, "d.mts"), declarations);
+            }
+            let mut linker = Linker::new(io, build_directory, sources, Language::TypeScript);
+            Some(linker.link_entry(&module_path(entry_module, "d.mts"))?)
+        }
+    };
+
+    Ok(Bundle {
+        javascript,
+        typescript_declarations,
+    })
+}
+
+/// The path of a module in the build directory, relative to the directory.
+fn module_path(module: &Module, extension: &str) -> Utf8PathBuf {
+    Utf8PathBuf::from(module.ast.type_info.package.as_str())
+        .join(format!("{}.{extension}", module.name))
+}
+
+type FunctionName = (EcoString, EcoString);
+
+/// The functions that can be reached from the public functions of the entry
+/// module, from any module constant, or from the given functions imported by
+/// FFI modules, by following the functions each of them references. Module
+/// constants and custom types are always kept, so only functions are left out
+/// of the bundle.
+fn reachable_functions(
+    modules: &[&Module],
+    entry: &TypedModule,
+    ffi_roots: Vec<FunctionName>,
+) -> HashSet<FunctionName> {
+    let modules: HashMap<&str, &TypedModule> = modules
+        .iter()
+        .map(|module| (module.name.as_str(), &module.ast))
+        .collect();
+
+    let mut queue = ffi_roots;
+    for definition in &entry.definitions {
+        if let Definition::Function(Function {
+            name, publicity, ..
+        }) = definition
+        {
+            if publicity.is_importable() {
+                queue.push((entry.name.clone(), name.clone()));
+            }
+        }
+    }
+    for module in modules.values() {
+        let mut references = References::new(module);
+        for definition in &module.definitions {
+            if let Definition::ModuleConstant(ModuleConstant { value, .. }) = definition {
+                references.constant(value);
+            }
+        }
+        queue.extend(references.functions);
+    }
+
+    let mut reachable = HashSet::new();
+    while let Some(function) = queue.pop() {
+        if reachable.contains(&function) {
+            continue;
+        }
+        let Some(module) = modules.get(function.0.as_str()) else {
+            continue;
+        };
+        let definition = module
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Function(definition) if definition.name == function.1 => {
+                    Some(definition)
+                }
+                _ => None,
+            });
+        if let Some(definition) = definition {
+            let mut references = References::new(module);
+            references.visit_typed_function(definition);
+            queue.extend(references.functions);
+        }
+        let _ = reachable.insert(function);
+    }
+    reachable
+}
+
+/// The functions of Gleam modules that are imported or re-exported by the FFI
+/// modules the Gleam modules use, and by the FFI modules those import in turn.
+/// Nothing on the Gleam side need reference them, so they must be kept in the
+/// bundle. A module imported as a namespace could have any of its functions
+/// used, so all of them are kept.
+///
+/// FFI modules that cannot be read or parsed are skipped here, as linking the
+/// bundle reports the problem.
+fn ffi_references<IO: FileSystemReader>(
+    io: &IO,
+    build_directory: &Utf8Path,
+    modules: &[&Module],
+) -> Vec<FunctionName> {
+    let gleam_modules: HashMap<Utf8PathBuf, &TypedModule> = modules
+        .iter()
+        .map(|module| (module_path(module, "mjs"), &module.ast))
+        .collect();
+    let mut queue = Vec::new();
+    for module in modules {
+        let path = module_path(module, "mjs");
+        for definition in &module.ast.definitions {
+            if let Definition::Function(Function {
+                external_javascript: Some((specifier, _)),
+                ..
+            }) = definition
+            {
+                queue.extend(resolve(&path, specifier));
+            }
+        }
+    }
+
+    let mut scanned = HashSet::new();
+    let mut references = Vec::new();
+    while let Some(path) = queue.pop() {
+        if gleam_modules.contains_key(&path) || !scanned.insert(path.clone()) {
+            continue;
+        }
+        let Ok(source) = io.read(&build_directory.join(&path)) else {
+            continue;
+        };
+        let Ok(statements) = parse_statements(&tokenise(&source)) else {
+            continue;
+        };
+        for statement in &statements {
+            let Some(imported) = statement.specifier().and_then(|s| resolve(&path, s)) else {
+                continue;
+            };
+            let Some(module) = gleam_modules.get(&imported) else {
+                queue.push(imported);
+                continue;
+            };
+            let names: Vec<&str> = match statement {
+                Statement::Import { bindings, .. }
+                    if bindings
+                        .iter()
+                        .all(|binding| matches!(binding, Binding::Named { .. })) =>
+                {
+                    bindings
+                        .iter()
+                        .filter_map(|binding| match binding {
+                            Binding::Named { imported, .. } => Some(imported.as_str()),
+                            Binding::Namespace(_) => None,
+                        })
+                        .collect()
+                }
+                Statement::ExportFrom {
+                    exports: Reexport::Named(names),
+                    ..
+                } => names
+                    .iter()
+                    .map(|(imported, _)| imported.as_str())
+                    .collect(),
+                _ => {
+                    references.extend(
+                        function_names(module).map(|name| (module.name.clone(), name.clone())),
+                    );
+                    continue;
+                }
+            };
+            // Functions are imported by their JavaScript names, which are
+            // escaped if they are reserved words.
+            references.extend(
+                function_names(module)
+                    .filter(|name| {
+                        names.contains(&super::maybe_escape_identifier_string(name).as_str())
+                    })
+                    .map(|name| (module.name.clone(), name.clone())),
+            );
+        }
+    }
+    references
+}
+
+fn function_names(module: &TypedModule) -> impl Iterator<Item = &EcoString> {
+    module
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(Function { name, .. }) => Some(name),
+            _ => None,
+        })
+}
+
+/// Collects the module functions referenced by the definitions of a module.
+#[derive(Debug)]
+struct References<'a> {
+    /// The modules imported by the module, by the name they are referred to
+    /// with.
+    aliases: HashMap<EcoString, &'a EcoString>,
+    /// The functions that can be referred to without a qualifier: those
+    /// defined by the module and those imported unqualified.
+    unqualified: HashMap<&'a EcoString, FunctionName>,
+    functions: Vec<FunctionName>,
+}
+
+impl<'a> References<'a> {
+    fn new(module: &'a TypedModule) -> Self {
+        let mut aliases = HashMap::new();
+        let mut unqualified = HashMap::new();
+        for definition in &module.definitions {
+            match definition {
+                Definition::Function(Function { name, .. }) => {
+                    let _ = unqualified.insert(name, (module.name.clone(), name.clone()));
+                }
+                Definition::Import(
+                    import @ Import {
+                        module: imported,
+                        unqualified_values,
+                        ..
+                    },
+                ) => {
+                    if let Some(alias) = import.used_name() {
+                        let _ = aliases.insert(alias, imported);
+                    }
+                    for value in unqualified_values {
+                        let _ = unqualified
+                            .insert(value.used_name(), (imported.clone(), value.name.clone()));
+                    }
+                }
+                Definition::TypeAlias(_)
+                | Definition::CustomType(_)
+                | Definition::ModuleConstant(_) => (),
+            }
+        }
+        Self {
+            aliases,
+            unqualified,
+            functions: Vec::new(),
+        }
+    }
+
+    fn name(&mut self, module: Option<&EcoString>, name: &EcoString) {
+        let function = match module {
+            None => self.unqualified.get(name).cloned(),
+            Some(alias) => self
+                .aliases
+                .get(alias)
+                .map(|module| ((*module).clone(), name.clone())),
+        };
+        self.functions.extend(function);
+    }
+
+    fn constant<T, RecordTag>(&mut self, constant: &Constant<T, RecordTag>) {
+        match constant {
+            Constant::Int { .. }
+            | Constant::Float { .. }
+            | Constant::String { .. }
+            | Constant::Invalid { .. } => (),
+            Constant::Tuple { elements, .. } | Constant::List { elements, .. } => {
+                elements.iter().for_each(|element| self.constant(element))
+            }
+            Constant::Record { args, .. } => args.iter().for_each(|arg| self.constant(&arg.value)),
+            Constant::BitArray { segments, .. } => segments
+                .iter()
+                .for_each(|segment| self.constant(&segment.value)),
+            Constant::Var {
+                module,
+                name,
+                constructor,
+                ..
+            } => {
+                if constructor.as_ref().is_some_and(|constructor| {
+                    matches!(
+                        constructor.variant,
+                        ValueConstructorVariant::ModuleFn { .. }
+                    )
+                }) {
+                    self.name(module.as_ref(), name);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for References<'_> {
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        // The unqualified names of the module may be shadowed by local
+        // variables, so only module functions are looked up.
+        if let ValueConstructorVariant::ModuleFn { .. } = constructor.variant {
+            self.name(None, name);
+        }
+        visit::visit_typed_expr_var(self, location, constructor, name);
+    }
+
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast std::sync::Arc<crate::type_::Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        if let ModuleValueConstructor::Fn { .. } = constructor {
+            self.functions.push((module_name.clone(), label.clone()));
+        }
+        visit::visit_typed_expr_module_select(
+            self,
+            location,
+            typ,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    JavaScript,
+    TypeScript,
+}
+
+/// Concatenates a module and the modules it imports, in an order where each
+/// module comes after the modules it imports, unless they import each other.
+#[derive(Debug)]
+struct Linker<'a, IO> {
+    io: &'a IO,
+    build_directory: &'a Utf8Path,
+    /// Modules generated for the bundle rather than read from the build
+    /// directory, by their path relative to it.
+    sources: HashMap<Utf8PathBuf, String>,
+    language: Language,
+    /// The index of each module, used to name its namespace object. Indexes
+    /// are given out as modules are first imported.
+    indexes: HashMap<Utf8PathBuf, usize>,
+    /// Imports of modules outside of the build directory, such as those of
+    /// the runtime, which are imported by the bundle as they are.
+    externals: Vec<String>,
+    rendered: Vec<String>,
+    export_star_used: bool,
+}
+
+impl<'a, IO: FileSystemReader> Linker<'a, IO> {
+    fn new(
+        io: &'a IO,
+        build_directory: &'a Utf8Path,
+        sources: HashMap<Utf8PathBuf, String>,
+        language: Language,
+    ) -> Self {
+        Self {
+            io,
+            build_directory,
+            sources,
+            language,
+            indexes: HashMap::new(),
+            externals: Vec::new(),
+            rendered: Vec::new(),
+            export_star_used: false,
+        }
+    }
+
+    fn link_entry(&mut self, path: &Utf8Path) -> Result<String> {
+        let (index, exports) = self.link(path, true)?;
+        let mut output = String::new();
+        for (index, specifier) in self.externals.iter().enumerate() {
+            output.push_str(&format!(
+                "import * as $$external{index} from \"{specifier}\";\n"
+            ));
+        }
+        if !self.externals.is_empty() {
+            output.push('\n');
+        }
+
+        if self.language == Language::TypeScript {
+            output.push_str(&self.rendered.join("\n"));
+            return Ok(output);
+        }
+
+        output.push_str(
+            "\
+const $$links = [];
+function $$export(target, getters) {
+  for (const key of Object.keys(getters)) {
+    Object.defineProperty(target, key, { get: getters[key], enumerable: true });
+  }
+}
+function $$import(source, key) {
+  try {
+    return source[key];
+  } catch {
+    // The export is not initialised yet as the modules import each other.
+    // It is imported again once every module has been evaluated.
+    return undefined;
+  }
+}
+",
+        );
+        if self.export_star_used {
+            output.push_str(
+                "\
+function $$exportStar(target, source) {
+  for (const key of Object.keys(source)) {
+    if (key !== \"default\" && !(key in target)) {
+      Object.defineProperty(target, key, { get: () => source[key], enumerable: true });
+    }
+  }
+}
+",
+            );
+        }
+        for index in 0..self.indexes.len() {
+            output.push_str(&format!("const $$module{index} = {{}};\n"));
+        }
+        for module in &self.rendered {
+            output.push('\n');
+            output.push_str(module);
+        }
+        output.push_str("\nfor (const $$link of $$links) $$link();\n\n");
+        if exports.is_empty() {
+            output.push_str("export {};\n");
+        } else {
+            output.push_str(&format!(
+                "export const {{ {} }} = $$module{index};\n",
+                exports.join(", ")
+            ));
+        }
+        Ok(output)
+    }
+
+    /// Link the module at the given path and the modules it imports,
+    /// returning the index of the module and the names it exports.
+    fn link(&mut self, path: &Utf8Path, entry: bool) -> Result<(usize, Vec<String>)> {
+        if let Some(index) = self.indexes.get(path) {
+            return Ok((*index, vec![]));
+        }
+        let index = self.indexes.len();
+        let _ = self.indexes.insert(path.to_path_buf(), index);
+
+        let source = match self.sources.get(path) {
+            Some(source) => source.clone(),
+            None => self.io.read(&self.build_directory.join(path))?,
+        };
+        let tokens = tokenise(&source);
+        let statements = parse_statements(&tokens).map_err(|problem| Error::JavaScriptBundle {
+            path: path.to_path_buf(),
+            problem,
+        })?;
+
+        let mut namespaces = HashMap::new();
+        for statement in &statements {
+            if let Some(specifier) = statement.specifier() {
+                if !namespaces.contains_key(specifier) {
+                    let namespace = self.namespace(path, specifier)?;
+                    let _ = namespaces.insert(specifier.to_string(), namespace);
+                }
+            }
+        }
+
+        let module = Rendered {
+            path,
+            source: &source,
+            statements: &statements,
+            namespaces: &namespaces,
+            namespace: format!("$$module{index}"),
+        };
+        let (rendered, exports) = match (self.language, entry) {
+            (Language::JavaScript, _) => {
+                self.export_star_used |= statements.iter().any(|statement| {
+                    matches!(
+                        statement,
+                        Statement::ExportFrom {
+                            exports: Reexport::All,
+                            ..
+                        }
+                    )
+                });
+                module.javascript()
+            }
+            (Language::TypeScript, entry) => (module.typescript(entry)?, vec![]),
+        };
+        self.rendered.push(rendered);
+        Ok((index, exports))
+    }
+
+    /// The name of the namespace object of the module imported with the
+    /// given specifier.
+    fn namespace(&mut self, importer: &Utf8Path, specifier: &str) -> Result<String> {
+        let Some(path) = resolve(importer, specifier) else {
+            let index = match self.externals.iter().position(|name| name == specifier) {
+                Some(index) => index,
+                None => {
+                    self.externals.push(specifier.to_string());
+                    self.externals.len() - 1
+                }
+            };
+            return Ok(format!("$$external{index}"));
+        };
+        let (index, _) = self.link(&path, false)?;
+        Ok(format!("$$module{index}"))
+    }
+}
+
+/// The path of an imported module relative to the build directory, or `None`
+/// if it is not imported with a relative path. Every package has a `gleam`
+/// module that re-exports the prelude, which is imported directly instead.
+fn resolve(importer: &Utf8Path, specifier: &str) -> Option<Utf8PathBuf> {
+    if !specifier.starts_with("./") && !specifier.starts_with("../") {
+        return None;
+    }
+    let mut path = Utf8PathBuf::new();
+    let directory = importer.parent().unwrap_or(Utf8Path::new(""));
+    for component in directory.join(specifier).components() {
+        match component {
+            Utf8Component::ParentDir => {
+                let _ = path.pop();
+            }
+            Utf8Component::CurDir => (),
+            component => path.push(component),
+        }
+    }
+    if path.components().count() == 2 {
+        match path.file_name() {
+            Some("gleam.mjs") => return Some("prelude.mjs".into()),
+            Some("gleam.d.mts") => return Some("prelude.d.mts".into()),
+            _ => (),
+        }
+    }
+    Some(path)
+}
+
+struct Rendered<'a> {
+    path: &'a Utf8Path,
+    source: &'a str,
+    statements: &'a [Statement],
+    namespaces: &'a HashMap<String, String>,
+    namespace: String,
+}
+
+impl Rendered<'_> {
+    fn imported_namespace(&self, specifier: &str) -> &str {
+        self.namespaces
+            .get(specifier)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    /// The source of the module with each of its top level statements
+    /// replaced. Statements that are removed take their line with them.
+    fn replace_statements(&self, mut replacement: impl FnMut(&Statement) -> String) -> String {
+        let mut output = String::new();
+        let mut position = 0;
+        for statement in self.statements {
+            let span = statement.span();
+            let replacement = replacement(statement);
+            output.push_str(self.source.get(position..span.start).unwrap_or_default());
+            output.push_str(&replacement);
+            position = span.end;
+            if replacement.is_empty() && self.source.get(position..position + 1) == Some("\n") {
+                position += 1;
+            }
+        }
+        output.push_str(self.source.get(position..).unwrap_or_default());
+        output
+    }
+
+    /// The module wrapped in a function that defines its exports on its
+    /// namespace object, along with the names it exports. Exports are defined
+    /// as getters before the module is evaluated, so that modules importing
+    /// each other can use them and changes to `let` variables are seen by
+    /// the importing modules.
+    fn javascript(&self) -> (String, Vec<String>) {
+        let namespace = &self.namespace;
+        let mut bindings = Vec::new();
+        let mut link = Vec::new();
+        let mut getters = Vec::new();
+
+        for statement in self.statements {
+            match statement {
+                Statement::Import {
+                    specifier,
+                    bindings: imported,
+                    ..
+                } => {
+                    let source = self.imported_namespace(specifier);
+                    for binding in imported {
+                        match binding {
+                            Binding::Namespace(local) => {
+                                link.push(format!("{local} = {source};"));
+                                bindings.push(local.clone());
+                            }
+                            Binding::Named { imported, local } => {
+                                link.push(format!("{local} = $$import({source}, \"{imported}\");"));
+                                bindings.push(local.clone());
+                            }
+                        }
+                    }
+                }
+                Statement::ExportFrom {
+                    specifier,
+                    exports: reexport,
+                    ..
+                } => {
+                    let source = self.imported_namespace(specifier);
+                    match reexport {
+                        Reexport::All => link.push(format!("$$exportStar({namespace}, {source});")),
+                        Reexport::Namespace(exported) => {
+                            getters.push((exported.clone(), source.to_string()));
+                        }
+                        Reexport::Named(reexports) => {
+                            getters.extend(reexports.iter().map(|(imported, exported)| {
+                                (exported.clone(), format!("{source}[\"{imported}\"]"))
+                            }))
+                        }
+                    }
+                }
+                Statement::ExportDeclaration { exports, .. }
+                | Statement::ExportList { exports, .. } => getters.extend(
+                    exports
+                        .iter()
+                        .map(|(local, exported)| (exported.clone(), local.clone())),
+                ),
+                Statement::ExportDefaultExpression { .. } => {
+                    getters.push(("default".into(), "$$default".into()));
+                }
+            }
+        }
+
+        let mut output = format!("// {}\n(() => {{\n", self.path);
+        if !bindings.is_empty() {
+            output.push_str(&format!("let {};\n", bindings.join(", ")));
+        }
+        if !getters.is_empty() {
+            let properties = getters
+                .iter()
+                .map(|(exported, value)| format!("{exported}: () => {value}"))
+                .join(", ");
+            output.push_str(&format!("$$export({namespace}, {{ {properties} }});\n"));
+        }
+        if !link.is_empty() {
+            output.push_str("const $$link = () => {\n");
+            for line in link {
+                output.push_str("  ");
+                output.push_str(&line);
+                output.push('\n');
+            }
+            output.push_str("};\n$$link();\n$$links.push($$link);\n");
+        }
+        let body = self.replace_statements(|statement| match statement {
+            Statement::ExportDefaultExpression { .. } => "const $$default =".into(),
+            Statement::Import { .. }
+            | Statement::ExportFrom { .. }
+            | Statement::ExportDeclaration { .. }
+            | Statement::ExportList { .. } => String::new(),
+        });
+        let body = body.trim_end();
+        if !body.is_empty() {
+            output.push_str(body);
+            output.push('\n');
+        }
+        output.push_str("})();\n");
+
+        let mut names = getters
+            .into_iter()
+            .map(|(exported, _)| exported)
+            .collect_vec();
+        names.sort();
+        (output, names)
+    }
+
+    /// The declarations of the module in a namespace named after its
+    /// namespace object, or left at the top level for the entry module.
+    fn typescript(&self, entry: bool) -> Result<String> {
+        let mut imports = Vec::new();
+        for statement in self.statements {
+            match statement {
+                Statement::Import {
+                    specifier,
+                    bindings,
+                    ..
+                } => {
+                    let source = self.imported_namespace(specifier);
+                    for binding in bindings {
+                        imports.push(match binding {
+                            Binding::Namespace(local) => format!("import {local} = {source};"),
+                            Binding::Named { imported, local } => {
+                                format!("import {local} = {source}.{imported};")
+                            }
+                        });
+                    }
+                }
+                Statement::ExportDeclaration { .. } => (),
+                Statement::ExportList { exports, .. } if exports.is_empty() => (),
+                Statement::ExportList { .. }
+                | Statement::ExportFrom { .. }
+                | Statement::ExportDefaultExpression { .. } => {
+                    return Err(Error::JavaScriptBundle {
+                        path: self.path.to_path_buf(),
+                        problem: "Declarations can only be bundled if they are exported \
+where they are declared."
+                            .into(),
+                    })
+                }
+            }
+        }
+
+        let body = self.replace_statements(|statement| match statement {
+            Statement::ExportDeclaration { span, .. } => {
+                self.source.get(span.clone()).unwrap_or_default().into()
+            }
+            Statement::Import { .. }
+            | Statement::ExportList { .. }
+            | Statement::ExportFrom { .. }
+            | Statement::ExportDefaultExpression { .. } => String::new(),
+        });
+        let lines = imports
+            .iter()
+            .map(String::as_str)
+            .chain(body.trim().lines())
+            .collect_vec();
+
+        if entry {
+            return Ok(format!("{}\n", lines.join("\n")));
+        }
+        let mut output = format!("declare namespace {} {{\n", self.namespace);
+        for line in lines {
+            if !line.trim().is_empty() {
+                output.push_str("  ");
+                output.push_str(line);
+            }
+            output.push('\n');
+        }
+        output.push_str("}\n");
+        Ok(output)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Binding {
+    Namespace(String),
+    Named { imported: String, local: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reexport {
+    All,
+    Namespace(String),
+    /// The names imported from the other module and exported as.
+    Named(Vec<(String, String)>),
+}
+
+/// A top level statement of a module that imports or exports something.
+/// Export names are pairs of the local name and the name exported as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Statement {
+    Import {
+        span: Range<usize>,
+        specifier: String,
+        bindings: Vec<Binding>,
+    },
+    /// An exported declaration. The span covers only the `export` keyword,
+    /// along with any `default` keyword.
+    ExportDeclaration {
+        span: Range<usize>,
+        exports: Vec<(String, String)>,
+    },
+    /// The `export default` keywords before an expression.
+    ExportDefaultExpression { span: Range<usize> },
+    ExportList {
+        span: Range<usize>,
+        exports: Vec<(String, String)>,
+    },
+    ExportFrom {
+        span: Range<usize>,
+        specifier: String,
+        exports: Reexport,
+    },
+}
+
+impl Statement {
+    fn span(&self) -> Range<usize> {
+        match self {
+            Statement::Import { span, .. }
+            | Statement::ExportDeclaration { span, .. }
+            | Statement::ExportDefaultExpression { span }
+            | Statement::ExportList { span, .. }
+            | Statement::ExportFrom { span, .. } => span.clone(),
+        }
+    }
+
+    fn specifier(&self) -> Option<&str> {
+        match self {
+            Statement::Import { specifier, .. } | Statement::ExportFrom { specifier, .. } => {
+                Some(specifier)
+            }
+            Statement::ExportDeclaration { .. }
+            | Statement::ExportDefaultExpression { .. }
+            | Statement::ExportList { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Identifier,
+    String,
+    Punctuation,
+    /// Numbers, regular expressions, and template literals.
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Split JavaScript source into tokens, skipping whitespace and comments.
+/// Operators made of several characters are split into a token for each
+/// character, which is all that is needed to find statements.
+fn tokenise(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens: Vec<Token<'_>> = Vec::new();
+    let mut position = 0;
+    // The depth of curly braces, and the depth at which each template
+    // literal substitution being tokenised was opened.
+    let mut braces = 0;
+    let mut substitutions = Vec::new();
+
+    while let Some(&byte) = bytes.get(position) {
+        let start = position;
+        let next = bytes.get(position + 1).copied();
+        let kind = match byte {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                position += 1;
+                continue;
+            }
+            b'/' if next == Some(b'/') => {
+                position = find(bytes, position, b"\n");
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                position = find(bytes, position + 2, b"*/") + 2;
+                continue;
+            }
+            b'"' | b'\'' => {
+                position = skip_string(bytes, position + 1, byte);
+                TokenKind::String
+            }
+            b'`' => {
+                position = skip_template(bytes, position + 1, &mut substitutions, braces);
+                TokenKind::Other
+            }
+            b'}' if substitutions.last() == Some(&braces) => {
+                let _ = substitutions.pop();
+                position = skip_template(bytes, position + 1, &mut substitutions, braces);
+                TokenKind::Other
+            }
+            b'/' if regular_expression_allowed(tokens.last()) => {
+                position = skip_regular_expression(bytes, position + 1);
+                TokenKind::Other
+            }
+            b'0'..=b'9' => {
+                position = skip_identifier(bytes, position);
+                TokenKind::Other
+            }
+            _ if is_identifier_byte(byte) => {
+                position = skip_identifier(bytes, position);
+                TokenKind::Identifier
+            }
+            b'{' => {
+                braces += 1;
+                position += 1;
+                TokenKind::Punctuation
+            }
+            b'}' => {
+                braces = braces.saturating_sub(1);
+                position += 1;
+                TokenKind::Punctuation
+            }
+            _ => {
+                position += 1;
+                TokenKind::Punctuation
+            }
+        };
+        let position = position.min(source.len());
+        tokens.push(Token {
+            kind,
+            text: source.get(start..position).unwrap_or_default(),
+            start,
+            end: position,
+        });
+    }
+    tokens
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte == b'\\' || byte >= 0x80
+}
+
+/// The position of the given bytes, or the end of the source.
+fn find(bytes: &[u8], from: usize, needle: &[u8]) -> usize {
+    bytes
+        .get(from..)
+        .and_then(|rest| {
+            rest.windows(needle.len())
+                .position(|window| window == needle)
+        })
+        .map_or(bytes.len(), |offset| from + offset)
+}
+
+fn skip_identifier(bytes: &[u8], mut position: usize) -> usize {
+    while bytes
+        .get(position)
+        .is_some_and(|byte| is_identifier_byte(*byte))
+    {
+        position += 1;
+    }
+    position
+}
+
+fn skip_string(bytes: &[u8], mut position: usize, quote: u8) -> usize {
+    while let Some(&byte) = bytes.get(position) {
+        position += 1;
+        match byte {
+            b'\\' => position += 1,
+            b'\n' => break,
+            _ if byte == quote => break,
+            _ => (),
+        }
+    }
+    position
+}
+
+/// Skip to the end of a template literal, or to the start of its next
+/// substitution.
+fn skip_template(
+    bytes: &[u8],
+    mut position: usize,
+    substitutions: &mut Vec<usize>,
+    braces: usize,
+) -> usize {
+    while let Some(&byte) = bytes.get(position) {
+        position += 1;
+        match byte {
+            b'\\' => position += 1,
+            b'`' => break,
+            b'$' if bytes.get(position) == Some(&b'{') => {
+                substitutions.push(braces);
+                return position + 1;
+            }
+            _ => (),
+        }
+    }
+    position
+}
+
+fn skip_regular_expression(bytes: &[u8], mut position: usize) -> usize {
+    let mut in_class = false;
+    while let Some(&byte) = bytes.get(position) {
+        position += 1;
+        match byte {
+            b'\\' => position += 1,
+            b'[' => in_class = true,
+            b']' => in_class = false,
+            b'/' if !in_class => break,
+            b'\n' => break,
+            _ => (),
+        }
+    }
+    skip_identifier(bytes, position)
+}
+
+/// Whether a `/` after the given token starts a regular expression rather
+/// than being a division.
+fn regular_expression_allowed(previous: Option<&Token<'_>>) -> bool {
+    match previous {
+        None => true,
+        Some(token) => match token.kind {
+            TokenKind::Punctuation => !matches!(token.text, ")" | "]" | "}"),
+            TokenKind::Identifier => matches!(
+                token.text,
+                "return"
+                    | "typeof"
+                    | "instanceof"
+                    | "in"
+                    | "of"
+                    | "new"
+                    | "delete"
+                    | "void"
+                    | "throw"
+                    | "case"
+                    | "do"
+                    | "else"
+                    | "yield"
+                    | "await"
+            ),
+            TokenKind::String | TokenKind::Other => false,
+        },
+    }
+}
+
+/// Find the top level `import` and `export` statements of a module.
+fn parse_statements(tokens: &[Token<'_>]) -> Result<Vec<Statement>, String> {
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    let mut previous: Option<&str> = None;
+
+    while let Some(token) = parser.peek().copied() {
+        let at_top_level =
+            depth == 0 && token.kind == TokenKind::Identifier && previous != Some(".");
+        let statement = match token.text {
+            "import" if at_top_level => parser.import()?,
+            "export" if at_top_level => Some(parser.export()?),
+            _ => None,
+        };
+        match statement {
+            Some(statement) => {
+                statements.push(statement);
+                previous = None;
+            }
+            None => {
+                let _ = parser.next();
+                match token.text {
+                    "{" | "(" | "[" if token.kind == TokenKind::Punctuation => depth += 1,
+                    "}" | ")" | "]" if token.kind == TokenKind::Punctuation => {
+                        depth = depth.saturating_sub(1)
+                    }
+                    _ => (),
+                }
+                previous = Some(token.text);
+            }
+        }
+    }
+    Ok(statements)
+}
+
+struct Parser<'a, 'b> {
+    tokens: &'b [Token<'a>],
+    position: usize,
+}
+
+impl<'a> Parser<'a, '_> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_text(&self, offset: usize) -> Option<&'a str> {
+        self.tokens
+            .get(self.position + offset)
+            .map(|token| token.text)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    /// The end of the last token taken.
+    fn end(&self) -> usize {
+        self.position
+            .checked_sub(1)
+            .and_then(|position| self.tokens.get(position))
+            .map_or(0, |token| token.end)
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        if self.peek_text(0) == Some(text) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), String> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{text}`")))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        match self.peek().copied() {
+            Some(token) if token.kind == TokenKind::Identifier => {
+                self.position += 1;
+                Ok(token.text.into())
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        match self.peek().copied() {
+            Some(token) if token.kind == TokenKind::String => {
+                self.position += 1;
+                let text = token.text;
+                Ok(text.get(1..text.len() - 1).unwrap_or_default().into())
+            }
+            _ => Err(self.unexpected("a module path")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> String {
+        match self.peek() {
+            Some(token) => format!(
+                "Expected {expected} at byte {}, found `{}`.",
+                token.start, token.text
+            ),
+            None => format!("Expected {expected}, found the end of the module."),
+        }
+    }
+
+    /// The end of a statement, after any semicolon.
+    fn statement_end(&mut self) -> Result<usize, String> {
+        if let Some("with" | "assert") = self.peek_text(0) {
+            return Err("Import attributes are not supported.".into());
+        }
+        let _ = self.eat(";");
+        Ok(self.end())
+    }
+
+    /// A list of names in braces, such as `{ a, b as c }`, as pairs of the
+    /// name and the name it is given.
+    fn names(&mut self) -> Result<Vec<(String, String)>, String> {
+        self.expect("{")?;
+        let mut names = Vec::new();
+        while !self.eat("}") {
+            // Type only imports and exports of TypeScript
+            if self.peek_text(0) == Some("type")
+                && !matches!(self.peek_text(1), Some("," | "}" | "as"))
+            {
+                self.position += 1;
+            }
+            let name = self.identifier()?;
+            let alias = if self.eat("as") {
+                self.identifier()?
+            } else {
+                name.clone()
+            };
+            names.push((name, alias));
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    fn import(&mut self) -> Result<Option<Statement>, String> {
+        // Dynamic imports and `import.meta` are expressions
+        if matches!(self.peek_text(1), Some("(" | ".")) {
+            return Ok(None);
+        }
+        let start = self.next().map_or(0, |token| token.start);
+        if self.peek_text(0) == Some("type") && !matches!(self.peek_text(1), Some("from" | ",")) {
+            self.position += 1;
+        }
+
+        let mut bindings = Vec::new();
+        if self.peek().map(|token| token.kind) != Some(TokenKind::String) {
+            if self.peek().map(|token| token.kind) == Some(TokenKind::Identifier) {
+                bindings.push(Binding::Named {
+                    imported: "default".into(),
+                    local: self.identifier()?,
+                });
+                let _ = self.eat(",");
+            }
+            if self.eat("*") {
+                self.expect("as")?;
+                bindings.push(Binding::Namespace(self.identifier()?));
+            } else if self.peek_text(0) == Some("{") {
+                bindings.extend(
+                    self.names()?
+                        .into_iter()
+                        .map(|(imported, local)| Binding::Named { imported, local }),
+                );
+            }
+            self.expect("from")?;
+        }
+        let specifier = self.string()?;
+        let end = self.statement_end()?;
+        Ok(Some(Statement::Import {
+            span: start..end,
+            specifier,
+            bindings,
+        }))
+    }
+
+    fn export(&mut self) -> Result<Statement, String> {
+        let start = self.next().map_or(0, |token| token.start);
+
+        if self.eat("default") {
+            let keyword_start = self.peek().map_or(0, |token| token.start);
+            let offset = usize::from(self.peek_text(0) == Some("async"));
+            let name = match self.peek_text(offset) {
+                Some("function") => {
+                    let offset = offset + 1 + usize::from(self.peek_text(offset + 1) == Some("*"));
+                    self.tokens.get(self.position + offset)
+                }
+                Some("class") => self.tokens.get(self.position + offset + 1),
+                _ => None,
+            };
+            return Ok(match name {
+                Some(name) if name.kind == TokenKind::Identifier && name.text != "extends" => {
+                    Statement::ExportDeclaration {
+                        span: start..keyword_start,
+                        exports: vec![(name.text.into(), "default".into())],
+                    }
+                }
+                _ => Statement::ExportDefaultExpression {
+                    span: start..self.end(),
+                },
+            });
+        }
+
+        if self.peek_text(0) == Some("{") {
+            let names = self.names()?;
+            if self.eat("from") {
+                let specifier = self.string()?;
+                return Ok(Statement::ExportFrom {
+                    specifier,
+                    exports: Reexport::Named(names),
+                    span: start..self.statement_end()?,
+                });
+            }
+            return Ok(Statement::ExportList {
+                exports: names,
+                span: start..self.statement_end()?,
+            });
+        }
+
+        if self.eat("*") {
+            let exports = if self.eat("as") {
+                Reexport::Namespace(self.identifier()?)
+            } else {
+                Reexport::All
+            };
+            self.expect("from")?;
+            let specifier = self.string()?;
+            return Ok(Statement::ExportFrom {
+                specifier,
+                exports,
+                span: start..self.statement_end()?,
+            });
+        }
+
+        let span = start..self.peek().map_or(0, |token| token.start);
+        let mut parser = Parser {
+            tokens: self.tokens,
+            position: self.position,
+        };
+        let _ = parser.eat("declare");
+        let _ = parser.eat("async");
+        let keyword = parser.identifier()?;
+        let _ = parser.eat("*");
+        let names = match keyword.as_str() {
+            "const" | "let" | "var" => parser.declared_names()?,
+            "function" | "class" | "type" | "interface" | "enum" | "namespace" | "abstract" => {
+                let _ = parser.eat("class");
+                vec![parser.identifier()?]
+            }
+            _ => return Err(format!("Unsupported export of `{keyword}`.")),
+        };
+        Ok(Statement::ExportDeclaration {
+            span,
+            exports: names.into_iter().map(|name| (name.clone(), name)).collect(),
+        })
+    }
+
+    /// The names declared by a `const`, `let`, or `var` statement.
+    fn declared_names(&mut self) -> Result<Vec<String>, String> {
+        if matches!(self.peek_text(0), Some("{" | "[")) {
+            return Err("Exports of destructured variables are not supported.".into());
+        }
+        let mut names = vec![self.identifier()?];
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token.text {
+                "{" | "(" | "[" => depth += 1,
+                "}" | ")" | "]" => depth = depth.saturating_sub(1),
+                ";" if depth == 0 => break,
+                "," if depth == 0
+                    && self.peek().map(|token| token.kind) == Some(TokenKind::Identifier)
+                    && self.peek_text(1) == Some("=") =>
+                {
+                    names.push(self.identifier()?);
+                }
+                _ => (),
+            }
+        }
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statements(source: &str) -> Vec<Statement> {
+        parse_statements(&tokenise(source)).expect("parse statements")
+    }
+
+    fn texts(source: &str) -> Vec<(TokenKind, &str)> {
+        tokenise(source)
+            .into_iter()
+            .map(|token| (token.kind, token.text))
+            .collect()
+    }
+
+    fn names(names: &[(&str, &str)]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|(local, exported)| ((*local).into(), (*exported).into()))
+            .collect()
+    }
+
+    #[test]
+    fn imports_in_comments_and_strings_are_ignored() {
+        let source = r#"// import { a } from "./a.mjs";
+/* export const b = 1; */
+const c = "import d from './d.mjs'";
+const e = 'export * from "./e.mjs"';
+const f = `import g from "./g.mjs"`;
+import { h } from "./h.mjs";
+"#;
+        let start = source.find("import { h }").expect("import");
+        assert_eq!(
+            statements(source),
+            vec![Statement::Import {
+                span: start..start + 28,
+                specifier: "./h.mjs".into(),
+                bindings: vec![Binding::Named {
+                    imported: "h".into(),
+                    local: "h".into()
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn dynamic_imports_and_import_meta_are_ignored() {
+        let source = r#"const a = import("./a.mjs");
+const b = import.meta.url;
+const c = { import: 1 }.import;
+"#;
+        assert_eq!(statements(source), vec![]);
+    }
+
+    #[test]
+    fn regular_expressions_and_division() {
+        assert_eq!(
+            texts("a = b / c / d"),
+            vec![
+                (TokenKind::Identifier, "a"),
+                (TokenKind::Punctuation, "="),
+                (TokenKind::Identifier, "b"),
+                (TokenKind::Punctuation, "/"),
+                (TokenKind::Identifier, "c"),
+                (TokenKind::Punctuation, "/"),
+                (TokenKind::Identifier, "d"),
+            ]
+        );
+        assert_eq!(
+            texts("(1) / 2 / x[0] / y"),
+            vec![
+                (TokenKind::Punctuation, "("),
+                (TokenKind::Other, "1"),
+                (TokenKind::Punctuation, ")"),
+                (TokenKind::Punctuation, "/"),
+                (TokenKind::Other, "2"),
+                (TokenKind::Punctuation, "/"),
+                (TokenKind::Identifier, "x"),
+                (TokenKind::Punctuation, "["),
+                (TokenKind::Other, "0"),
+                (TokenKind::Punctuation, "]"),
+                (TokenKind::Punctuation, "/"),
+                (TokenKind::Identifier, "y"),
+            ]
+        );
+        assert_eq!(
+            texts(r#"a = /"[/"]'}/gu; return /`/"#),
+            vec![
+                (TokenKind::Identifier, "a"),
+                (TokenKind::Punctuation, "="),
+                (TokenKind::Other, r#"/"[/"]'}/gu"#),
+                (TokenKind::Punctuation, ";"),
+                (TokenKind::Identifier, "return"),
+                (TokenKind::Other, "/`/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_template_substitutions() {
+        let source = r#"const a = `x ${`y ${ { b: "}" }.b } z`} ${c}`;
+export const d = 1;
+"#;
+        assert_eq!(
+            texts(source).get(3..).unwrap_or_default().first(),
+            Some(&(TokenKind::Other, "`x ${"))
+        );
+        assert_eq!(
+            statements(source),
+            vec![Statement::ExportDeclaration {
+                span: 47..54,
+                exports: names(&[("d", "d")]),
+            }]
+        );
+    }
+
+    #[test]
+    fn export_star() {
+        assert_eq!(
+            statements(
+                r#"export * from "./a.mjs";
+export * as b from "./b.mjs"
+"#
+            ),
+            vec![
+                Statement::ExportFrom {
+                    span: 0..24,
+                    specifier: "./a.mjs".into(),
+                    exports: Reexport::All,
+                },
+                Statement::ExportFrom {
+                    span: 25..53,
+                    specifier: "./b.mjs".into(),
+                    exports: Reexport::Namespace("b".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn export_default() {
+        let declaration = |span, name: &str| Statement::ExportDeclaration {
+            span,
+            exports: names(&[(name, "default")]),
+        };
+        assert_eq!(
+            statements("export default function a() {}"),
+            vec![declaration(0..15, "a")]
+        );
+        assert_eq!(
+            statements("export default async function* b() {}"),
+            vec![declaration(0..15, "b")]
+        );
+        assert_eq!(
+            statements("export default class C extends D {}"),
+            vec![declaration(0..15, "C")]
+        );
+        assert_eq!(
+            statements("export default class extends D {}"),
+            vec![Statement::ExportDefaultExpression { span: 0..14 }]
+        );
+        assert_eq!(
+            statements("export default function () {}"),
+            vec![Statement::ExportDefaultExpression { span: 0..14 }]
+        );
+        assert_eq!(
+            statements("export default { a: 1 };"),
+            vec![Statement::ExportDefaultExpression { span: 0..14 }]
+        );
+    }
+
+    #[test]
+    fn modules_importing_each_other_are_linked_once() {
+        let io = crate::io::memory::InMemoryFileSystem::new();
+        let mut sources = HashMap::new();
+        let _ = sources.insert(
+            Utf8PathBuf::from("p/a.mjs"),
+            "import { b } from \"./b.mjs\";\nexport const a = () => b;\n".into(),
+        );
+        let _ = sources.insert(
+            Utf8PathBuf::from("p/b.mjs"),
+            "import { a } from \"./a.mjs\";\nexport let b = 1;\n".into(),
+        );
+        let mut linker = Linker::new(&io, Utf8Path::new("/build"), sources, Language::JavaScript);
+        let output = linker.link_entry(Utf8Path::new("p/a.mjs")).expect("link");
+        assert_eq!(output.matches("// p/a.mjs").count(), 1);
+        assert_eq!(output.matches("// p/b.mjs").count(), 1);
+        assert!(output.contains("b = $$import($$module1, \"b\");"));
+        assert!(output.contains("a = $$import($$module0, \"a\");"));
+        assert!(output.ends_with("export const { a } = $$module0;\n"));
+    }
+}
@@ -12,6 +12,7 @@ mod assignments;
 mod bit_arrays;
 mod blocks;
 mod bools;
+mod bundle;
 mod case;
 mod case_clause_guards;
 mod custom_types;
@@ -0,0 +1,308 @@
+use std::time::SystemTime;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    analyse::TargetSupport,
+    build::{Module, Origin, Target},
+    codegen::TypeScriptDeclarations,
+    config::PackageConfig,
+    io::{memory::InMemoryFileSystem, FileSystemWriter},
+    javascript::{bundle, Bundle},
+    line_numbers::LineNumbers,
+    type_::PRELUDE_MODULE_NAME,
+    uid::UniqueIdGenerator,
+    warning::TypeWarningEmitter,
+    Error,
+};
+
+/// Compile each of the modules, in order, for a bundle of the last one.
+/// Files given with a path are written to the build directory.
+fn bundle_modules(
+    modules: &[(&str, &str, &str)],
+    files: &[(&str, &str)],
+    typescript: TypeScriptDeclarations,
+) -> Result<Bundle, Error> {
+    let ids = UniqueIdGenerator::new();
+    let mut importable_modules = im::HashMap::new();
+    let _ = importable_modules.insert(
+        PRELUDE_MODULE_NAME.into(),
+        crate::type_::build_prelude(&ids),
+    );
+    let direct_dependencies = modules
+        .iter()
+        .map(|(package, _, _)| ((*package).into(), ()))
+        .collect();
+
+    let mut compiled = Vec::new();
+    for (package, name, src) in modules {
+        let config = PackageConfig {
+            name: (*package).into(),
+            ..Default::default()
+        };
+        let mut ast = crate::parse::parse_module(src)
+            .expect("syntax error")
+            .module;
+        ast.name = (*name).into();
+        let ast = crate::analyse::ModuleAnalyzerConstructor::<()> {
+            target: Target::JavaScript,
+            ids: &ids,
+            origin: Origin::Src,
+            importable_modules: &importable_modules,
+            warnings: &TypeWarningEmitter::null(),
+            direct_dependencies: &direct_dependencies,
+            target_support: TargetSupport::Enforced,
+            package_config: &config,
+        }
+        .infer_module(ast, LineNumbers::new(src), "".into())
+        .expect("should successfully infer");
+        let _ = importable_modules.insert((*name).into(), ast.type_info.clone());
+        compiled.push(Module {
+            name: (*name).into(),
+            code: (*src).into(),
+            mtime: SystemTime::UNIX_EPOCH,
+            input_path: Utf8PathBuf::from(format!("{name}.gleam")),
+            origin: Origin::Src,
+            ast,
+            extra: Default::default(),
+            dependencies: vec![],
+        });
+    }
+
+    let io = InMemoryFileSystem::new();
+    let build = Utf8Path::new("/build");
+    for (path, content) in files {
+        io.write(&build.join(path), content).expect("write file");
+    }
+    let modules = compiled.iter().collect::<Vec<_>>();
+    let entry = compiled.last().map(|module| module.name.clone());
+    bundle(
+        &io,
+        build,
+        &modules,
+        &entry.expect("entry module"),
+        TargetSupport::Enforced,
+        typescript,
+    )
+}
+
+/// The output without the part starting with `start` and ending before
+/// `end`, as the prelude is the same in every bundle.
+fn without_prelude(output: &str, start: &str, end: &str) -> String {
+    let prelude_start = output.find(start).expect("prelude start") + start.len();
+    let prelude_end = prelude_start + output[prelude_start..].find(end).expect("prelude end");
+    format!("{}\n{}", &output[..prelude_start], &output[prelude_end..])
+}
+
+fn bundle_javascript(modules: &[(&str, &str, &str)], files: &[(&str, &str)]) -> String {
+    let bundle = bundle_modules(modules, files, TypeScriptDeclarations::None).expect("bundle");
+    without_prelude(&bundle.javascript, "// prelude.mjs\n(() => {", "})();\n")
+}
+
+#[test]
+fn unreachable_functions_are_left_out() {
+    let output = bundle_javascript(
+        &[
+            (
+                "other_package",
+                "wibble",
+                r#"
+pub fn used() { helper() }
+fn helper() { 1 }
+pub fn unused() { 2 }
+pub fn used_by_constant() { 3 }
+"#,
+            ),
+            (
+                "thepackage",
+                "my/mod",
+                r#"
+import wibble.{used_by_constant}
+
+pub const functions = [used_by_constant]
+
+pub fn main() { wibble.used() + private() }
+fn private() { 4 }
+fn unused() { wibble.unused() }
+"#,
+            ),
+        ],
+        &[],
+    );
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn ffi_modules_are_bundled() {
+    let output = bundle_javascript(
+        &[(
+            "thepackage",
+            "my/mod",
+            r#"
+@external(javascript, "./ffi.mjs", "describe")
+pub fn describe(x: Int) -> String
+
+@external(javascript, "./ffi.mjs", "read")
+pub fn read(path: String) -> String
+"#,
+        )],
+        &[(
+            "thepackage/my/ffi.mjs",
+            r#"import { Ok } from "../gleam.mjs";
+import { readFileSync } from "node:fs";
+import * as $mod from "./mod.mjs";
+
+// Not a statement: } export const comment = 1;
+const pattern = /[`'"}]/g;
+
+export function describe(x) {
+  return `number ${`${x}`.replace(pattern, "")}`;
+}
+
+export const read = (path) => new Ok(readFileSync(path));
+
+export default $mod;
+"#,
+        )],
+    );
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn functions_imported_by_ffi_modules_are_kept() {
+    // Nothing imports the prelude, so it is not in the bundle
+    let output = bundle_modules(
+        &[
+            (
+                "other_package",
+                "wibble",
+                r#"
+pub fn used_by_ffi() { helper() }
+fn helper() { 1 }
+pub fn reexported_by_ffi() { 2 }
+pub fn unused() { 3 }
+"#,
+            ),
+            (
+                "thepackage",
+                "my/mod",
+                r#"
+@external(javascript, "./ffi.mjs", "call")
+pub fn call() -> Int
+"#,
+            ),
+        ],
+        &[
+            (
+                "thepackage/my/ffi.mjs",
+                r#"import { used_by_ffi } from "./other.mjs";
+export function call() { return used_by_ffi(); }
+"#,
+            ),
+            (
+                "thepackage/my/other.mjs",
+                r#"export { used_by_ffi, reexported_by_ffi } from "../../other_package/wibble.mjs";
+"#,
+            ),
+        ],
+        TypeScriptDeclarations::None,
+    )
+    .expect("bundle")
+    .javascript;
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn typescript_declarations() {
+    let bundle = bundle_modules(
+        &[
+            (
+                "other_package",
+                "wibble",
+                r#"
+pub type Wibble { Wibble(Int) }
+"#,
+            ),
+            (
+                "thepackage",
+                "my/mod",
+                r#"
+import wibble.{type Wibble}
+
+pub fn main(wibble: Wibble) -> List(Wibble) { [wibble] }
+"#,
+            ),
+        ],
+        &[],
+        TypeScriptDeclarations::Emit,
+    )
+    .expect("bundle");
+    let declarations = bundle.typescript_declarations.expect("declarations");
+    insta::assert_snapshot!(without_prelude(
+        &declarations,
+        "declare namespace $$module2 {",
+        "\n}\n"
+    ));
+}
+
+#[test]
+fn destructured_exports_are_not_supported() {
+    let result = bundle_modules(
+        &[(
+            "thepackage",
+            "my/mod",
+            r#"
+@external(javascript, "./ffi.mjs", "wibble")
+pub fn wibble() -> Int
+"#,
+        )],
+        &[(
+            "thepackage/my/ffi.mjs",
+            "export const { wibble } = { wibble: () => 1 };\n",
+        )],
+        TypeScriptDeclarations::None,
+    );
+    assert!(matches!(result, Err(Error::JavaScriptBundle { .. })));
+}
+
+#[test]
+fn exports_are_live_bindings() {
+    let bundle = bundle_modules(
+        &[(
+            "thepackage",
+            "my/mod",
+            r#"
+@external(javascript, "./counter.mjs", "increment")
+pub fn increment() -> Nil
+
+@external(javascript, "./counter.mjs", "current")
+pub fn current() -> Int
+"#,
+        )],
+        &[
+            (
+                "thepackage/my/counter.mjs",
+                r#"export * from "./current.mjs";
+
+export let count = 0;
+
+export function increment() {
+  count += 1;
+}
+"#,
+            ),
+            (
+                "thepackage/my/current.mjs",
+                r#"import * as counter from "./counter.mjs";
+
+export function current() {
+  return counter.count;
+}
+"#,
+            ),
+        ],
+        TypeScriptDeclarations::None,
+    )
+    .expect("bundle");
+    insta::assert_snapshot!(bundle.javascript);
+}
@@ -7,6 +7,7 @@ mod messages;
 mod progress;
 mod router;
 mod server;
+mod typed_hole;
 
 #[cfg(test)]
 mod tests;
@@ -17,7 +18,7 @@ use crate::{
     ast::SrcSpan, build::Target, line_numbers::LineNumbers, manifest::Manifest,
     paths::ProjectPaths, Result,
 };
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use lsp_types::{Position, Range, Url};
 use std::any::Any;
 
@@ -55,3 +56,26 @@ fn path(uri: &Url) -> Utf8PathBuf {
     #[cfg(not(any(unix, windows, target_os = "redox", target_os = "wasi")))]
     return Utf8PathBuf::from_path_buf(uri.path().into()).expect("Non Utf8 Path");
 }
+
+/// Hex and Git dependencies are downloaded into the `build/packages` directory
+/// of the project that depends on them. Given the path of a file, return the
+/// root of the project using it if the file is a module of such a dependency.
+///
+/// This source is read-only as far as the language server is concerned, any
+/// edits made to it would be lost the next time the dependencies are
+/// downloaded.
+///
+fn dependency_module_project_root(path: &Utf8Path) -> Option<&Utf8Path> {
+    path.ancestors().skip(1).find_map(|src| {
+        if src.file_name() != Some("src") {
+            return None;
+        }
+        let packages = src.parent()?.parent()?;
+        let build = packages.parent()?;
+        if packages.file_name() == Some("packages") && build.file_name() == Some("build") {
+            build.parent()
+        } else {
+            None
+        }
+    })
+}
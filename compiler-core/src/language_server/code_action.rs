@@ -8,10 +8,10 @@ use crate::{
     build,
     line_numbers::LineNumbers,
     parse::extra::ModuleExtra,
-    type_::Type,
+    type_::{ModuleInterface, Type},
 };
 
-use super::{engine::overlaps, src_span_to_lsp_range};
+use super::{engine::overlaps, src_span_to_lsp_range, typed_hole};
 
 #[derive(Debug)]
 pub struct CodeActionBuilder {
@@ -264,3 +264,164 @@ impl<'a> RedundantTupleInCaseSubject<'a> {
         edits
     }
 }
+
+/// Code actions to replace a `todo` expression with one of the values in scope
+/// that has the type expected at that position.
+///
+pub struct FillTodo<'a> {
+    module: &'a build::Module,
+    line_numbers: LineNumbers,
+    params: &'a CodeActionParams,
+    importable_modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    actions: Vec<CodeAction>,
+}
+
+impl<'ast> ast::visit::Visit<'ast> for FillTodo<'_> {
+    fn visit_typed_expr_todo(
+        &mut self,
+        location: &'ast SrcSpan,
+        message: &'ast Option<Box<ast::TypedExpr>>,
+        type_: &'ast Arc<Type>,
+    ) {
+        let range = src_span_to_lsp_range(*location, &self.line_numbers);
+        if overlaps(self.params.range, range) {
+            let fillers =
+                typed_hole::fillers(self.module, *location, type_, self.importable_modules);
+            for filler in fillers {
+                CodeActionBuilder::new(&format!("Replace `todo` with `{}`", filler.name))
+                    .kind(CodeActionKind::REFACTOR_REWRITE)
+                    .changes(
+                        self.params.text_document.uri.clone(),
+                        vec![TextEdit {
+                            range,
+                            new_text: filler.name.to_string(),
+                        }],
+                    )
+                    .preferred(false)
+                    .push_to(&mut self.actions);
+            }
+        }
+
+        ast::visit::visit_typed_expr_todo(self, location, message, type_)
+    }
+}
+
+impl<'a> FillTodo<'a> {
+    pub fn new(
+        module: &'a build::Module,
+        params: &'a CodeActionParams,
+        importable_modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    ) -> Self {
+        Self {
+            module,
+            line_numbers: LineNumbers::new(&module.code),
+            params,
+            importable_modules,
+            actions: vec![],
+        }
+    }
+
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        self.visit_typed_module(&self.module.ast);
+        self.actions
+    }
+}
+
+/// Code actions to replace a `_` hole in a type annotation with the type that
+/// was inferred for it.
+///
+pub struct FillTypeHole<'a> {
+    module: &'a build::Module,
+    line_numbers: LineNumbers,
+    params: &'a CodeActionParams,
+    actions: Vec<CodeAction>,
+}
+
+impl<'ast> ast::visit::Visit<'ast> for FillTypeHole<'_> {
+    fn visit_typed_function(&mut self, fun: &'ast ast::TypedFunction) {
+        for argument in &fun.arguments {
+            self.fill_annotation(argument.annotation.as_ref(), &argument.type_);
+        }
+        self.fill_annotation(fun.return_annotation.as_ref(), &fun.return_type);
+
+        ast::visit::visit_typed_function(self, fun)
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        typ: &'ast Arc<Type>,
+        is_capture: &'ast bool,
+        args: &'ast [ast::TypedArg],
+        body: &'ast [ast::TypedStatement],
+        return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        for argument in args {
+            self.fill_annotation(argument.annotation.as_ref(), &argument.type_);
+        }
+        if let Some((_, return_type)) = typ.fn_types() {
+            self.fill_annotation(return_annotation.as_ref(), &return_type);
+        }
+
+        ast::visit::visit_typed_expr_fn(
+            self,
+            location,
+            typ,
+            is_capture,
+            args,
+            body,
+            return_annotation,
+        )
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast ast::TypedAssignment) {
+        self.fill_annotation(assignment.annotation.as_ref(), &assignment.pattern.type_());
+
+        ast::visit::visit_typed_assignment(self, assignment)
+    }
+}
+
+impl<'a> FillTypeHole<'a> {
+    pub fn new(module: &'a build::Module, params: &'a CodeActionParams) -> Self {
+        Self {
+            module,
+            line_numbers: LineNumbers::new(&module.code),
+            params,
+            actions: vec![],
+        }
+    }
+
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        self.visit_typed_module(&self.module.ast);
+        self.actions
+    }
+
+    fn fill_annotation(&mut self, annotation: Option<&ast::TypeAst>, type_: &Arc<Type>) {
+        let Some(annotation) = annotation else {
+            return;
+        };
+        let mut holes = vec![];
+        typed_hole::type_holes(annotation, type_, &mut holes);
+
+        for hole in holes {
+            let range = src_span_to_lsp_range(hole.location, &self.line_numbers);
+            if !overlaps(self.params.range, range) {
+                continue;
+            }
+            let Some(filler) = typed_hole::type_hole_filler(&hole, self.module) else {
+                continue;
+            };
+            CodeActionBuilder::new(&format!("Replace `{}` with `{filler}`", hole.name))
+                .kind(CodeActionKind::REFACTOR_REWRITE)
+                .changes(
+                    self.params.text_document.uri.clone(),
+                    vec![TextEdit {
+                        range,
+                        new_text: filler,
+                    }],
+                )
+                .preferred(false)
+                .push_to(&mut self.actions);
+        }
+    }
+}
@@ -1,6 +1,5 @@
 use debug_ignore::DebugIgnore;
 use ecow::EcoString;
-use itertools::Itertools;
 
 use crate::{
     analyse::TargetSupport,
@@ -17,7 +16,7 @@ use crate::{
 };
 use std::{collections::HashMap, sync::Arc};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 
 /// A wrapper around the project compiler which makes it possible to repeatedly
 /// recompile the top level package, reusing the information about the already
@@ -27,7 +26,9 @@ use camino::Utf8PathBuf;
 pub struct LspProjectCompiler<IO> {
     pub project_compiler: ProjectCompiler<IO>,
 
-    /// Information on compiled modules.
+    /// Information on compiled modules, both from the root package and from
+    /// any dependencies that have been compiled or type checked in this
+    /// session.
     pub modules: HashMap<EcoString, Module>,
     pub sources: HashMap<EcoString, ModuleSourceInformation>,
 
@@ -111,8 +112,11 @@ where
             Err(err) => return err.into(),
         };
 
+        // Record the compiled dependency modules
+        let mut compiled_modules = Vec::with_capacity(compiled_dependencies.len());
+
         // Store the compiled dependency module information
-        for module in &compiled_dependencies {
+        for module in compiled_dependencies {
             let path = module.input_path.as_os_str().to_string_lossy().to_string();
             // strip canonicalised windows prefix
             #[cfg(target_family = "windows")]
@@ -122,7 +126,9 @@ where
                 .unwrap_or(path);
             let line_numbers = LineNumbers::new(&module.code);
             let source = ModuleSourceInformation { path, line_numbers };
+            compiled_modules.push(module.input_path.clone());
             _ = self.sources.insert(module.name.clone(), source);
+            _ = self.modules.insert(module.name.clone(), module);
         }
 
         // Since cached modules are not recompiled we need to manually add them
@@ -158,12 +164,6 @@ where
             Outcome::TotalFailure(error) => (vec![], Some(error)),
         };
 
-        // Record the compiled dependency modules
-        let mut compiled_modules = compiled_dependencies
-            .into_iter()
-            .map(|m| m.input_path)
-            .collect_vec();
-
         // Store the compiled module information
         for module in modules {
             let path = module.input_path.as_os_str().to_string_lossy().to_string();
@@ -183,6 +183,20 @@ where
         }
     }
 
+    /// Type checks a module of a dependency downloaded into `build/packages`
+    /// so that requests can be served for its source. Dependency modules
+    /// loaded from the cache have no typed AST, so they are checked when
+    /// first needed rather than deleting their caches.
+    pub fn load_dependency_module(&mut self, package: &str, path: &Utf8Path) {
+        let package_root = self.project_compiler.paths.build_packages_package(package);
+        if let Some(module) = self
+            .project_compiler
+            .analyse_dependency_module(&package_root, path)
+        {
+            _ = self.modules.insert(module.name.clone(), module);
+        }
+    }
+
     pub fn get_module_inferface(&self, name: &str) -> Option<&ModuleInterface> {
         self.project_compiler.get_importable_modules().get(name)
     }
@@ -3,7 +3,10 @@ use crate::{
         Arg, Definition, Import, ModuleConstant, Publicity, SrcSpan, TypedDefinition, TypedExpr,
         TypedFunction, TypedModule, TypedPattern,
     },
-    build::{type_constructor_from_modules, Located, Module, UnqualifiedImport},
+    build::{
+        type_constructor_from_modules, BitArraySegmentOption, Located, Module, StringPrefixSide,
+        UnqualifiedImport,
+    },
     config::PackageConfig,
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
@@ -25,8 +28,10 @@ use std::sync::Arc;
 use strum::IntoEnumIterator;
 
 use super::{
-    code_action::{CodeActionBuilder, RedundantTupleInCaseSubject},
-    src_span_to_lsp_range, DownloadDependencies, MakeLocker,
+    code_action::{CodeActionBuilder, FillTodo, FillTypeHole, RedundantTupleInCaseSubject},
+    src_span_to_lsp_range,
+    typed_hole::{self, Filler},
+    DownloadDependencies, MakeLocker,
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -154,6 +159,7 @@ where
     ) -> Response<Option<lsp::Location>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
+            this.load_dependency_module(&params.text_document.uri);
             let (line_numbers, node) = match this.node_at_position(&params) {
                 Some(location) => location,
                 None => return Ok(None),
@@ -242,6 +248,8 @@ where
 
                 Located::Arg(_) => None,
 
+                Located::BitArraySegmentOption(_) | Located::StringPrefix(_) => None,
+
                 Located::Annotation(_, _) => Some(this.completion_types(module)),
             };
 
@@ -256,8 +264,23 @@ where
                 return Ok(None);
             };
 
+            // Dependency source is read-only, any edits would be lost the next
+            // time the dependencies are downloaded.
+            if this.is_dependency_module(module) {
+                return Ok(None);
+            }
+
             code_action_unused_imports(module, &params, &mut actions);
             actions.extend(RedundantTupleInCaseSubject::new(module, &params).code_actions());
+            actions.extend(
+                FillTodo::new(
+                    module,
+                    &params,
+                    this.compiler.project_compiler.get_importable_modules(),
+                )
+                .code_actions(),
+            );
+            actions.extend(FillTypeHole::new(module, &params).code_actions());
 
             Ok(if actions.is_empty() {
                 None
@@ -288,6 +311,7 @@ where
     pub fn hover(&mut self, params: lsp::HoverParams) -> Response<Option<Hover>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
+            this.load_dependency_module(&params.text_document.uri);
 
             let (lines, found) = match this.node_at_position(&params) {
                 Some(value) => value,
@@ -328,6 +352,19 @@ where
                         }
                     }),
                 Located::Pattern(pattern) => Some(hover_for_pattern(pattern, lines)),
+                Located::Expression(TypedExpr::Todo {
+                    location, type_, ..
+                }) => this
+                    .module_for_uri(&params.text_document.uri)
+                    .map(|module| {
+                        let fillers = typed_hole::fillers(
+                            module,
+                            *location,
+                            type_,
+                            this.compiler.project_compiler.get_importable_modules(),
+                        );
+                        hover_for_todo(*location, type_, &fillers, lines)
+                    }),
                 Located::Expression(expression) => {
                     let module = this.module_for_uri(&params.text_document.uri);
 
@@ -339,6 +376,10 @@ where
                     ))
                 }
                 Located::Arg(arg) => Some(hover_for_function_argument(arg, lines)),
+                Located::BitArraySegmentOption(option) => {
+                    Some(hover_for_bit_array_segment_option(&option, lines))
+                }
+                Located::StringPrefix(side) => Some(hover_for_string_prefix(&side, lines)),
                 Located::FunctionBody(_) => None,
                 Located::Annotation(annotation, type_) => {
                     let type_constructor = type_constructor_from_modules(
@@ -386,12 +427,24 @@ where
         #[cfg(not(any(unix, windows, target_os = "redox", target_os = "wasi")))]
         let path: Utf8PathBuf = uri.path().into();
 
-        let components = path
-            .strip_prefix(self.paths.root())
-            .ok()?
-            .components()
-            .skip(1)
-            .map(|c| c.as_os_str().to_string_lossy());
+        // Modules of downloaded dependencies are found in the `src` directory
+        // of the package within `build/packages`, while modules of the root
+        // package are found in either its `src` or `test` directory.
+        let components = match path.strip_prefix(self.paths.build_packages_directory()) {
+            Ok(path) => {
+                let mut components = path.components().skip(1);
+                if components.next()?.as_os_str() != "src" {
+                    return None;
+                }
+                components
+            }
+            Err(_) => path
+                .strip_prefix(self.paths.root())
+                .ok()?
+                .components()
+                .skip(1),
+        }
+        .map(|c| c.as_os_str().to_string_lossy());
         let module_name: EcoString = Itertools::intersperse(components, "/".into())
             .collect::<String>()
             .strip_suffix(".gleam")?
@@ -400,6 +453,30 @@ where
         self.compiler.modules.get(&module_name)
     }
 
+    /// Type checks the module of a dependency in `build/packages` if it has
+    /// not been already, so that it can be navigated.
+    fn load_dependency_module(&mut self, uri: &Url) {
+        if self.module_for_uri(uri).is_some() {
+            return;
+        }
+        let path = super::path(uri);
+        let Some(package) = path
+            .strip_prefix(self.paths.build_packages_directory())
+            .ok()
+            .and_then(|path| path.components().next())
+        else {
+            return;
+        };
+        self.compiler
+            .load_dependency_module(package.as_str(), &path);
+    }
+
+    fn is_dependency_module(&self, module: &Module) -> bool {
+        module
+            .input_path
+            .starts_with(self.paths.build_packages_directory())
+    }
+
     /// checks based on the publicity if something should be suggested for import from root package
     fn is_suggestable_import(&self, publicity: &Publicity, package: &str) -> bool {
         match publicity {
@@ -877,6 +954,90 @@ fn hover_for_expression(
     }
 }
 
+fn hover_for_bit_array_segment_option(
+    option: &BitArraySegmentOption,
+    line_numbers: LineNumbers,
+) -> Hover {
+    let type_ = Printer::new().pretty_print(&option.segment_type, 0);
+    let documentation = option.documentation;
+    let size = match option.segment_size {
+        Some(1) => "\nThe segment is 1 bit long.\n".into(),
+        Some(size) => format!("\nThe segment is {size} bits long.\n"),
+        None => String::new(),
+    };
+    let contents = format!(
+        "```gleam
+{type_}
+```
+{documentation}
+{size}"
+    );
+    Hover {
+        contents: HoverContents::Scalar(MarkedString::String(contents)),
+        range: Some(src_span_to_lsp_range(option.location, &line_numbers)),
+    }
+}
+
+fn hover_for_string_prefix(side: &StringPrefixSide<'_>, line_numbers: LineNumbers) -> Hover {
+    let documentation = match side {
+        StringPrefixSide::Prefix {
+            prefix,
+            assignment: None,
+            ..
+        } => format!("Matches strings starting with `\"{prefix}\"`."),
+        StringPrefixSide::Prefix {
+            prefix,
+            assignment: Some(name),
+            ..
+        } => format!("Matches strings starting with `\"{prefix}\"`, assigning it to `{name}`."),
+        StringPrefixSide::Rest { prefix, .. } => {
+            format!("The rest of the string after the prefix `\"{prefix}\"`.")
+        }
+    };
+    let contents = format!(
+        "```gleam
+String
+```
+{documentation}
+"
+    );
+    Hover {
+        contents: HoverContents::Scalar(MarkedString::String(contents)),
+        range: Some(src_span_to_lsp_range(side.location(), &line_numbers)),
+    }
+}
+
+fn hover_for_todo(
+    location: SrcSpan,
+    type_: &Arc<Type>,
+    fillers: &[Filler],
+    line_numbers: LineNumbers,
+) -> Hover {
+    // The same printer is used for all the types so that type variables are
+    // named consistently.
+    let mut printer = Printer::new();
+    let type_ = printer.pretty_print(type_, 0);
+    let mut contents = format!(
+        "```gleam
+{type_}
+```
+"
+    );
+
+    if !fillers.is_empty() {
+        contents.push_str("\nValues in scope with this type:\n");
+        for filler in fillers {
+            let type_ = printer.pretty_print(&filler.type_, 0);
+            contents.push_str(&format!("- `{}: {type_}`\n", filler.name));
+        }
+    }
+
+    Hover {
+        contents: HoverContents::Scalar(MarkedString::String(contents)),
+        range: Some(src_span_to_lsp_range(location, &line_numbers)),
+    }
+}
+
 fn hover_for_imported_value(
     value: &crate::type_::ValueConstructor,
     location: &SrcSpan,
@@ -1,4 +1,11 @@
-use crate::{diagnostic::Diagnostic, Error, Warning};
+use crate::{
+    ast::SrcSpan,
+    diagnostic::{Diagnostic, Label, Location},
+    error::FileIoAction,
+    io::FileSystemReader,
+    paths::ProjectPaths,
+    Error, Warning,
+};
 use std::collections::{HashMap, HashSet};
 
 use camino::Utf8PathBuf;
@@ -116,7 +123,19 @@ impl FeedbackBookKeeper {
         compilation: Compilation,
         warnings: Vec<Warning>,
     ) -> Feedback {
-        let diagnostics = error.to_diagnostics();
+        self.build_with_diagnostics(error.to_diagnostics(), compilation, warnings)
+    }
+
+    /// Compilation failed with an error that has already been converted into
+    /// diagnostics, for example to give it a location in the project's config
+    /// files. See `project_error_diagnostics`.
+    ///
+    pub fn build_with_diagnostics(
+        &mut self,
+        diagnostics: Vec<Diagnostic>,
+        compilation: Compilation,
+        warnings: Vec<Warning>,
+    ) -> Feedback {
         let mut feedback = self.response(compilation, warnings);
 
         // A new error means that any existing errors are no longer valid. Unset them.
@@ -151,6 +170,119 @@ impl FeedbackBookKeeper {
     }
 }
 
+/// Errors caused by the configuration of a project or by its dependencies have
+/// no location in Gleam source code. Where possible we attribute them to the
+/// relevant line of the project's `gleam.toml` or `manifest.toml` so that they
+/// are shown as diagnostics in the editor rather than as messages.
+///
+pub fn project_error_diagnostics<IO>(error: Error, paths: &ProjectPaths, io: &IO) -> Vec<Diagnostic>
+where
+    IO: FileSystemReader,
+{
+    let mut diagnostics = error.to_diagnostics();
+    let Some(location) = config_location(&error, paths, io) else {
+        return diagnostics;
+    };
+    for diagnostic in diagnostics.iter_mut() {
+        if diagnostic.location.is_none() {
+            diagnostic.location = Some(location.clone());
+        }
+    }
+    diagnostics
+}
+
+fn config_location<IO>(error: &Error, paths: &ProjectPaths, io: &IO) -> Option<Location>
+where
+    IO: FileSystemReader,
+{
+    match error {
+        Error::DownloadPackageError {
+            package_name: package,
+            ..
+        }
+        | Error::DependencyCanonicalizationFailed(package)
+        | Error::WrongDependencyProvided {
+            expected: package, ..
+        }
+        | Error::ProvidedDependencyConflict { package, .. }
+        | Error::UnsupportedBuildTool { package, .. }
+        | Error::IncompatibleCompilerVersion { package, .. } => {
+            package_location(package, paths, io)
+        }
+
+        Error::DuplicateDependency(package) => package_location(package, paths, io),
+
+        Error::GitCommandFailed { package, .. }
+        | Error::GitReferenceNotFound { package, .. }
+        | Error::GitLockedCommitNotFound { package, .. }
+        | Error::UnknownHexRepository { package, .. } => package_location(package, paths, io),
+
+        Error::DependencyResolutionFailed(_) => {
+            let config = paths.root_config();
+            line_location(&config, |line| line.trim() == "[dependencies]", io)
+                .or_else(|| line_location(&config, |_| true, io))
+        }
+
+        Error::CorruptManifest => line_location(&paths.manifest(), |_| true, io),
+
+        Error::FileIo {
+            action: FileIoAction::Parse,
+            path,
+            ..
+        } if *path == paths.root_config() || *path == paths.manifest() => {
+            line_location(path, |_| true, io)
+        }
+
+        _ => None,
+    }
+}
+
+/// The line where a package is required in `gleam.toml`, or where it is locked
+/// in `manifest.toml` if it is a transitive dependency.
+///
+fn package_location<IO>(package: &str, paths: &ProjectPaths, io: &IO) -> Option<Location>
+where
+    IO: FileSystemReader,
+{
+    let table = format!(".{package}]");
+    let is_requirement = |line: &str| {
+        let line = line.trim();
+        let is_key = line
+            .strip_prefix(package)
+            .map(|rest| rest.trim_start().starts_with('='));
+        is_key.unwrap_or(false) || line.ends_with(&table)
+    };
+    let locked = format!("name = \"{package}\"");
+
+    line_location(&paths.root_config(), is_requirement, io)
+        .or_else(|| line_location(&paths.manifest(), |line| line.contains(&locked), io))
+}
+
+/// The location of the first line of the file for which the predicate holds.
+///
+fn line_location<IO>(
+    path: &Utf8PathBuf,
+    is_relevant: impl Fn(&str) -> bool,
+    io: &IO,
+) -> Option<Location>
+where
+    IO: FileSystemReader,
+{
+    let src = io.read(path).ok()?;
+    let mut start = 0;
+    let span = src.split_inclusive('\n').find_map(|line| {
+        let span = SrcSpan::new(start, start + line.trim_end().len() as u32);
+        start += line.len() as u32;
+        is_relevant(line).then_some(span)
+    })?;
+    Some(Location {
+        src: src.into(),
+        path: path.clone(),
+        label: Label { text: None, span },
+        extra_labels: vec![],
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -158,11 +290,12 @@ mod tests {
 
     use super::*;
     use crate::{
-        ast::SrcSpan,
         diagnostic::Level,
+        io::{memory::InMemoryFileSystem, FileSystemWriter},
         parse::error::{ParseError, ParseErrorType},
         type_,
     };
+    use camino::Utf8Path;
 
     #[test]
     fn feedback() {
@@ -374,7 +507,7 @@ mod tests {
         let file1 = Utf8PathBuf::from("src/file1.gleam");
         let file2 = Utf8PathBuf::from("src/file2.gleam");
 
-        let error = |file: &camino::Utf8Path| Error::Parse {
+        let error = |file: &Utf8Path| Error::Parse {
             path: file.to_path_buf(),
             src: "blah".into(),
             error: ParseError {
@@ -462,6 +595,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -470,6 +604,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -481,6 +616,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -497,6 +633,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                     (
@@ -507,6 +644,7 @@ mod tests {
                             text: "Error 3".to_string(),
                             title: "Error 3".to_string(),
                             level: Level::Error,
+                            code: None,
                         }],
                     ),
                 ]),
@@ -516,6 +654,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -532,6 +671,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -540,6 +680,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -551,6 +692,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![],
@@ -566,6 +708,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }],
                 ),]),
                 messages: vec![Diagnostic {
@@ -574,6 +717,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -590,6 +734,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -598,6 +743,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -608,6 +754,7 @@ mod tests {
                 text: "Error 3".to_string(),
                 title: "Error 3".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         });
         assert_eq!(
@@ -621,6 +768,7 @@ mod tests {
                         text: "Error 1".to_string(),
                         title: "Error 1".to_string(),
                         level: Level::Error,
+                        code: None,
                     },],
                 ),]),
                 messages: vec![
@@ -630,6 +778,7 @@ mod tests {
                         text: "Error 2".to_string(),
                         title: "Error 2".to_string(),
                         level: Level::Error,
+                        code: None,
                     },
                     Diagnostic {
                         location: None,
@@ -637,6 +786,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        code: None,
                     }
                 ],
             }
@@ -654,6 +804,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -662,6 +813,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -680,6 +832,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            code: None,
                         },],
                     ),
                     (Utf8PathBuf::from("src/file2.gleam"), vec![],),
@@ -690,6 +843,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
@@ -706,6 +860,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    code: None,
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -714,6 +869,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                code: None,
             }],
         };
         feedback.append_feedback(Feedback {
@@ -730,8 +886,101 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    code: None,
                 },],
             }
         );
     }
+
+    fn error_location(error: Error, files: &[(&str, &str)]) -> Option<(Utf8PathBuf, SrcSpan)> {
+        let io = InMemoryFileSystem::new();
+        for (path, src) in files {
+            io.write(Utf8Path::new(path), src).unwrap();
+        }
+        let paths = ProjectPaths::new("/app".into());
+        let diagnostics = project_error_diagnostics(error, &paths, &io);
+        diagnostics
+            .first()?
+            .location
+            .as_ref()
+            .map(|location| (location.path.clone(), location.label.span))
+    }
+
+    const GLEAM_TOML: &str = r#"name = "app"
+
+[dependencies]
+gleam_stdlib = ">= 0.34.0 and < 2.0.0"
+gleam = "~> 1.0"
+
+[dev-dependencies.gleeunit]
+version = "~> 1.0"
+"#;
+
+    const MANIFEST_TOML: &str = r#"packages = [
+  { name = "gleam_stdlib", version = "0.38.0", build_tools = ["gleam"], requirements = [], otp_app = "gleam_stdlib", source = "hex", outer_checksum = "" },
+  { name = "thoas", version = "1.0.0", build_tools = ["rebar3"], requirements = [], otp_app = "thoas", source = "hex", outer_checksum = "" },
+]
+"#;
+
+    #[test]
+    fn download_error_located_at_requirement() {
+        let error = Error::DownloadPackageError {
+            package_name: "gleam".into(),
+            package_version: "1.0.0".into(),
+            error: "Oh no".into(),
+        };
+        assert_eq!(
+            error_location(error, &[("/app/gleam.toml", GLEAM_TOML)]),
+            Some(("/app/gleam.toml".into(), SrcSpan::new(68, 84)))
+        );
+    }
+
+    #[test]
+    fn error_located_at_dependency_table() {
+        let error = Error::IncompatibleCompilerVersion {
+            package: "gleeunit".into(),
+            required_version: "2.0.0".into(),
+            gleam_version: "1.0.0".into(),
+        };
+        assert_eq!(
+            error_location(error, &[("/app/gleam.toml", GLEAM_TOML)]),
+            Some(("/app/gleam.toml".into(), SrcSpan::new(86, 113)))
+        );
+    }
+
+    #[test]
+    fn transitive_dependency_error_located_in_manifest() {
+        let error = Error::UnsupportedBuildTool {
+            package: "thoas".into(),
+            build_tools: vec![],
+        };
+        assert_eq!(
+            error_location(
+                error,
+                &[
+                    ("/app/gleam.toml", GLEAM_TOML),
+                    ("/app/manifest.toml", MANIFEST_TOML)
+                ]
+            ),
+            Some(("/app/manifest.toml".into(), SrcSpan::new(169, 310)))
+        );
+    }
+
+    #[test]
+    fn resolution_error_located_at_dependencies() {
+        let error = Error::DependencyResolutionFailed("Oh no".into());
+        assert_eq!(
+            error_location(error, &[("/app/gleam.toml", GLEAM_TOML)]),
+            Some(("/app/gleam.toml".into(), SrcSpan::new(14, 28)))
+        );
+    }
+
+    #[test]
+    fn unrelated_error_has_no_location() {
+        let error = Error::Gzip("Hello!".into());
+        assert_eq!(
+            error_location(error, &[("/app/gleam.toml", GLEAM_TOML)]),
+            None
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use camino::Utf8PathBuf;
 use lsp::{
-    notification::{DidChangeWatchedFiles, DidOpenTextDocument},
+    notification::{DidChangeWatchedFiles, DidChangeWorkspaceFolders, DidOpenTextDocument},
     request::GotoDefinition,
 };
 use lsp_types::{
@@ -62,6 +62,8 @@ pub enum Notification {
     SourceFileMatchesDisc { path: Utf8PathBuf },
     /// gleam.toml has changed.
     ConfigFileChanged { path: Utf8PathBuf },
+    /// Folders have been removed from the editor's workspace.
+    WorkspaceFoldersRemoved { paths: Vec<Utf8PathBuf> },
     /// It's time to compile all open projects.
     CompilePlease,
 }
@@ -108,6 +110,21 @@ impl Notification {
                 };
                 Some(Message::Notification(notification))
             }
+
+            "workspace/didChangeWorkspaceFolders" => {
+                let params = cast_notification::<DidChangeWorkspaceFolders>(notification);
+                let paths = params
+                    .event
+                    .removed
+                    .iter()
+                    .map(|folder| super::path(&folder.uri))
+                    .collect::<Vec<_>>();
+                if paths.is_empty() {
+                    return None;
+                }
+                let notification = Notification::WorkspaceFoldersRemoved { paths };
+                Some(Message::Notification(notification))
+            }
             _ => None,
         }
     }
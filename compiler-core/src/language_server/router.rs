@@ -1,22 +1,27 @@
 use crate::{
     build::SourceFingerprint,
+    config::WorkspaceConfig,
     error::{FileIoAction, FileKind},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
-        engine::LanguageServerEngine, files::FileSystemProxy, progress::ProgressReporter,
-        DownloadDependencies, MakeLocker,
+        dependency_module_project_root, engine::LanguageServerEngine, files::FileSystemProxy,
+        progress::ProgressReporter, DownloadDependencies, MakeLocker,
     },
+    manifest::ManifestPackageSource,
     paths::ProjectPaths,
     Error, Result,
 };
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     time::SystemTime,
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
 
-use super::feedback::FeedbackBookKeeper;
+use super::{
+    engine::Compilation,
+    feedback::{project_error_diagnostics, Feedback, FeedbackBookKeeper},
+};
 
 /// The language server instance serves a language client, typically a text
 /// editor. The editor could have multiple Gleam projects open at once, so run
@@ -29,6 +34,11 @@ use super::feedback::FeedbackBookKeeper;
 pub(crate) struct Router<IO, Reporter> {
     io: FileSystemProxy<IO>,
     engines: HashMap<Utf8PathBuf, Project<IO, Reporter>>,
+    /// The feedback book keepers of projects that do not currently have an
+    /// engine, either because creating one failed or because it was discarded.
+    /// These are given to the next engine created for the project so that any
+    /// diagnostics already sent to the client can be cleared once fixed.
+    feedback: HashMap<Utf8PathBuf, FeedbackBookKeeper>,
     progress_reporter: Reporter,
 }
 
@@ -48,6 +58,7 @@ where
         Self {
             io,
             engines: HashMap::new(),
+            feedback: HashMap::new(),
             progress_reporter,
         }
     }
@@ -76,7 +87,7 @@ where
         // account.
         if let Some(project) = self.engines.get(&path) {
             if Self::gleam_toml_changed(&path, project, &self.io)? {
-                let _ = self.engines.remove(&path);
+                self.delete_engine(&path);
             }
         }
 
@@ -84,13 +95,31 @@ where
         Ok(Some(match self.engines.entry(path.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
-                let project =
-                    Self::new_project(path, self.io.clone(), self.progress_reporter.clone())?;
+                let feedback = self.feedback.remove(&path).unwrap_or_default();
+                let project = Self::new_project(
+                    path,
+                    feedback,
+                    self.io.clone(),
+                    self.progress_reporter.clone(),
+                )?;
                 entry.insert(project)
             }
         }))
     }
 
+    /// Feedback for an error that prevented the engine for the project at the
+    /// given root from being created, such as an invalid `gleam.toml` or
+    /// dependencies that could not be downloaded.
+    pub fn project_error(&mut self, root: Utf8PathBuf, error: Error) -> Feedback {
+        let paths = project_paths(root.clone(), &self.io)
+            .unwrap_or_else(|_| ProjectPaths::new(root.clone()));
+        let diagnostics = project_error_diagnostics(error, &paths, &self.io);
+        self.feedback
+            .entry(root)
+            .or_default()
+            .build_with_diagnostics(diagnostics, Compilation::No, vec![])
+    }
+
     /// Has gleam.toml changed since the last time we saw this project?
     fn gleam_toml_changed(
         path: &Utf8PathBuf,
@@ -115,17 +144,85 @@ where
 
     pub fn delete_engine_for_path(&mut self, path: &Utf8Path) {
         if let Some(path) = find_gleam_project_parent(&self.io, path) {
-            _ = self.engines.remove(&path);
+            self.delete_engine(&path);
+        }
+    }
+
+    fn delete_engine(&mut self, path: &Utf8Path) {
+        if let Some(project) = self.engines.remove(path) {
+            _ = self.feedback.insert(path.to_path_buf(), project.feedback);
         }
     }
 
+    /// Discard the engines of all projects found within the given directory,
+    /// for example when it is removed from the editor's workspace.
+    pub fn delete_engines_within(&mut self, directory: &Utf8Path) {
+        self.engines.retain(|path, _| !path.starts_with(directory));
+        self.feedback.retain(|path, _| !path.starts_with(directory));
+    }
+
+    /// Find the open projects which depend on the project at the given root,
+    /// either directly or transitively, via `path` dependencies. These need
+    /// to be recompiled when the project changes.
+    pub fn dependent_projects(&self, root: &Utf8Path) -> HashSet<Utf8PathBuf> {
+        let mut dependents = HashSet::new();
+        let root = self.canonicalise(root);
+        let mut queue = vec![root.clone()];
+
+        while let Some(dependency) = queue.pop() {
+            for (path, project) in &self.engines {
+                if dependents.contains(path) {
+                    continue;
+                }
+                let canonical_path = self.canonicalise(path);
+                if canonical_path == root {
+                    continue;
+                }
+                if self.has_path_dependency(path, project, &dependency) {
+                    _ = dependents.insert(path.clone());
+                    queue.push(canonical_path);
+                }
+            }
+        }
+
+        dependents
+    }
+
+    fn has_path_dependency(
+        &self,
+        path: &Utf8Path,
+        project: &Project<IO, Reporter>,
+        dependency: &Utf8Path,
+    ) -> bool {
+        let project_compiler = &project.engine.compiler.project_compiler;
+        // Relative paths are relative to the root of the project, or to the
+        // root of its workspace if it is in one
+        let root = project_compiler.paths.workspace_root().unwrap_or(path);
+        project_compiler
+            .packages
+            .values()
+            .any(|package| match &package.source {
+                ManifestPackageSource::Local { path: package_path } => {
+                    self.canonicalise(&root.join(package_path)) == dependency
+                }
+                ManifestPackageSource::Hex { .. } | ManifestPackageSource::Git { .. } => false,
+            })
+    }
+
+    fn canonicalise(&self, path: &Utf8Path) -> Utf8PathBuf {
+        self.io
+            .canonicalise(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
     fn new_project(
         path: Utf8PathBuf,
+        feedback: FeedbackBookKeeper,
         io: FileSystemProxy<IO>,
         progress_reporter: Reporter,
     ) -> Result<Project<IO, Reporter>, Error> {
         tracing::info!(?path, "creating_new_language_server_engine");
-        let paths = ProjectPaths::new(path);
+        let paths = project_paths(path, &io)?;
         let config_path = paths.root_config();
         let modification_time = io.modification_time(&config_path)?;
         let toml = io.read(&config_path)?;
@@ -138,7 +235,7 @@ where
         let engine = LanguageServerEngine::new(config, progress_reporter, io, paths)?;
         let project = Project {
             engine,
-            feedback: FeedbackBookKeeper::default(),
+            feedback,
             gleam_toml_modification_time: modification_time,
             gleam_toml_fingerprint: SourceFingerprint::new(&toml),
         };
@@ -146,15 +243,38 @@ where
     }
 }
 
+/// The paths of the project at the given root. A project in a workspace uses
+/// the manifest and build directory at the root of the workspace.
+fn project_paths<IO>(root: Utf8PathBuf, io: &IO) -> Result<ProjectPaths>
+where
+    IO: FileSystemReader,
+{
+    Ok(match WorkspaceConfig::find_root(&root, io)? {
+        Some(workspace_root) => ProjectPaths::in_workspace(root, workspace_root),
+        None => ProjectPaths::new(root),
+    })
+}
+
 /// Given a given path, find the nearest parent directory containing a
 /// `gleam.toml` file.
 ///
 /// The file must be in either the `src` or `test` directory if it is not a
 /// `.gleam` file.
+///
+/// Modules of dependencies downloaded into `build/packages` belong to the
+/// project that downloaded them rather than to the dependency's own
+/// `gleam.toml`, so that they are served using the already compiled
+/// dependency modules instead of starting a new engine for the dependency.
 fn find_gleam_project_parent<IO>(io: &IO, path: &Utf8Path) -> Option<Utf8PathBuf>
 where
     IO: FileSystemReader,
 {
+    if let Some(root) = dependency_module_project_root(path) {
+        if io.is_file(&root.join("gleam.toml")) {
+            return Some(root.to_path_buf());
+        }
+    }
+
     let is_module = path.extension().map(|x| x == "gleam").unwrap_or(false);
     let mut directory = path.to_path_buf();
 
@@ -276,4 +396,19 @@ mod find_gleam_project_parent_tests {
             Some(Utf8PathBuf::from("/app/examples/wibble"))
         );
     }
+
+    #[test]
+    fn dependency_module() {
+        let io = InMemoryFileSystem::new();
+        io.write(Utf8Path::new("/app/gleam.toml"), "").unwrap();
+        io.write(Utf8Path::new("/app/build/packages/wibble/gleam.toml"), "")
+            .unwrap();
+        assert_eq!(
+            find_gleam_project_parent(
+                &io,
+                Utf8Path::new("/app/build/packages/wibble/src/one/two.gleam")
+            ),
+            Some(Utf8PathBuf::from("/app"))
+        );
+    }
 }
@@ -6,8 +6,9 @@ use crate::{
     diagnostic::{Diagnostic, Level},
     io::{CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
+        dependency_module_project_root,
         engine::{self, LanguageServerEngine},
-        feedback::{Feedback, FeedbackBookKeeper},
+        feedback::{project_error_diagnostics, Feedback, FeedbackBookKeeper},
         files::FileSystemProxy,
         router::Router,
         src_span_to_lsp_range, DownloadDependencies, MakeLocker,
@@ -87,7 +88,7 @@ where
         Ok(())
     }
 
-    fn handle_message(&mut self, message: Message) {
+    pub(crate) fn handle_message(&mut self, message: Message) {
         match message {
             Message::Request(id, request) => self.handle_request(id, request),
             Message::Notification(notification) => self.handle_notification(notification),
@@ -124,6 +125,9 @@ where
                 self.cache_file_in_memory(path, text)
             }
             Notification::ConfigFileChanged { path } => self.watched_files_changed(path),
+            Notification::WorkspaceFoldersRemoved { paths } => {
+                self.workspace_folders_removed(paths)
+            }
         };
         self.publish_feedback(feedback);
     }
@@ -234,7 +238,7 @@ where
             &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
         ) -> engine::Response<T>,
     {
-        match self.router.project_for_path(path) {
+        match self.router.project_for_path(path.clone()) {
             Ok(Some(project)) => {
                 let engine::Response {
                     result,
@@ -248,7 +252,13 @@ where
                         (json, feedback)
                     }
                     Err(e) => {
-                        let feedback = project.feedback.build_with_error(e, compilation, warnings);
+                        let diagnostics =
+                            project_error_diagnostics(e, &project.engine.paths, &self.io);
+                        let feedback = project.feedback.build_with_diagnostics(
+                            diagnostics,
+                            compilation,
+                            warnings,
+                        );
                         (Json::Null, feedback)
                     }
                 }
@@ -256,7 +266,10 @@ where
 
             Ok(None) => (Json::Null, Feedback::default()),
 
-            Err(error) => (Json::Null, self.outside_of_project_feedback.error(error)),
+            Err(error) => match self.router.project_path(&path) {
+                Some(root) => (Json::Null, self.router.project_error(root, error)),
+                None => (Json::Null, self.outside_of_project_feedback.error(error)),
+            },
         }
     }
 
@@ -272,6 +285,12 @@ where
         let path = super::path(&params.text_document.uri);
         let mut new_text = String::new();
 
+        // Dependency source is read-only, any edits would be lost the next
+        // time the dependencies are downloaded.
+        if dependency_module_project_root(&path).is_some() {
+            return (Json::Null, Feedback::default());
+        }
+
         let src = match self.io.read(&path) {
             Ok(src) => src.into(),
             Err(error) => return self.path_error_response(path, error),
@@ -320,6 +339,11 @@ where
     }
 
     fn cache_file_in_memory(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
+        // Edits to dependency source are not compiled as they would be lost
+        // the next time the dependencies are downloaded.
+        if dependency_module_project_root(&path).is_some() {
+            return Feedback::none();
+        }
         self.project_changed(&path);
         if let Err(error) = self.io.write_mem_cache(&path, &text) {
             return self.outside_of_project_feedback.error(error);
@@ -340,6 +364,15 @@ where
         Feedback::none()
     }
 
+    fn workspace_folders_removed(&mut self, paths: Vec<Utf8PathBuf>) -> Feedback {
+        for path in paths {
+            self.router.delete_engines_within(&path);
+            self.changed_projects
+                .retain(|project| !project.starts_with(&path));
+        }
+        Feedback::none()
+    }
+
     fn compile_please(&mut self) -> Feedback {
         let mut accumulator = Feedback::none();
         let projects = std::mem::take(&mut self.changed_projects);
@@ -353,6 +386,10 @@ where
     fn project_changed(&mut self, path: &Utf8Path) {
         let project_path = self.router.project_path(path);
         if let Some(project_path) = project_path {
+            // Open projects that use this one as a path dependency are
+            // recompiled too so that their diagnostics reflect the change.
+            self.changed_projects
+                .extend(self.router.dependent_projects(&project_path));
             _ = self.changed_projects.insert(project_path);
         }
     }
@@ -403,7 +440,13 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
         folding_range_provider: None,
         declaration_provider: None,
         execute_command_provider: None,
-        workspace: None,
+        workspace: Some(lsp::WorkspaceServerCapabilities {
+            workspace_folders: Some(lsp::WorkspaceFoldersServerCapabilities {
+                supported: Some(true),
+                change_notifications: Some(lsp::OneOf::Left(true)),
+            }),
+            file_operations: None,
+        }),
         call_hierarchy_provider: None,
         semantic_tokens_provider: None,
         moniker_provider: None,
@@ -459,7 +502,9 @@ fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
     let main = lsp::Diagnostic {
         range: src_span_to_lsp_range(location.label.span, &line_numbers),
         severity: Some(severity),
-        code: None,
+        code: diagnostic
+            .code
+            .map(|code| lsp::NumberOrString::String(code.into())),
         code_description: None,
         source: None,
         message: text,
@@ -3,6 +3,8 @@ mod compilation;
 mod completion;
 mod definition;
 mod hover;
+mod router;
+mod server;
 
 use std::{
     collections::HashMap,
@@ -104,6 +106,7 @@ impl LanguageServerTestIO {
             name: name.into(),
             source: ManifestPackageSource::Hex {
                 outer_checksum: Base16Checksum(vec![]),
+                repository: None,
             },
             build_tools: vec!["gleam".into()],
             ..Default::default()
@@ -290,9 +293,10 @@ fn add_package_from_manifest<B>(
         match package.source {
             ManifestPackageSource::Hex { .. } => Requirement::Hex {
                 version: Range::new("1.0.0".into()),
+                repository: None,
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
-            ManifestPackageSource::Git { ref repo, .. } => Requirement::Git { git: repo.clone() },
+            ManifestPackageSource::Git { ref repo, .. } => Requirement::git(repo),
         },
     );
     write_toml_from_manifest(engine, toml_path, package);
@@ -309,9 +313,10 @@ fn add_dev_package_from_manifest<B>(
         match package.source {
             ManifestPackageSource::Hex { .. } => Requirement::Hex {
                 version: Range::new("1.0.0".into()),
+                repository: None,
             },
             ManifestPackageSource::Local { ref path } => Requirement::Path { path: path.into() },
-            ManifestPackageSource::Git { ref repo, .. } => Requirement::Git { git: repo.clone() },
+            ManifestPackageSource::Git { ref repo, .. } => Requirement::git(repo),
         },
     );
     write_toml_from_manifest(engine, toml_path, package);
@@ -464,6 +469,7 @@ impl<'a> TestProject<'a> {
                 name: "indirect_hex".into(),
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
                 build_tools: vec!["gleam".into()],
                 ..Default::default()
@@ -479,6 +485,7 @@ impl<'a> TestProject<'a> {
                 name: "dev_hex".into(),
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
                 build_tools: vec!["gleam".into()],
                 ..Default::default()
@@ -67,6 +67,40 @@ fn apply_first_code_action_with_title(src: &str, line: u32, title: &str) -> Stri
     }
 }
 
+// Replacing a `todo` or a type hole is a single edit, which may be longer
+// than the text it replaces.
+fn apply_first_replacement_with_title(src: &str, line: u32, title: &str) -> String {
+    let action = engine_response(src, line)
+        .result
+        .unwrap()
+        .and_then(|actions| actions.into_iter().find(|action| action.title == title))
+        .expect("No code action produced by the engine");
+    let changes = action
+        .edit
+        .and_then(|edit| edit.changes)
+        .expect("No workspace edit found");
+    let Some([edit]) = changes.get(&test_file_url()).map(Vec::as_slice) else {
+        panic!("Expected a single text edit")
+    };
+
+    let line_numbers = LineNumbers::new(src);
+    let start = line_numbers.byte_index(edit.range.start.line, edit.range.start.character);
+    let end = line_numbers.byte_index(edit.range.end.line, edit.range.end.character);
+    let mut result = src.to_string();
+    result.replace_range(start as usize..end as usize, &edit.new_text);
+    result
+}
+
+fn code_action_titles(src: &str, line: u32) -> Vec<String> {
+    engine_response(src, line)
+        .result
+        .unwrap()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|action| action.title)
+        .collect()
+}
+
 fn apply_code_action(src: &str, url: &Url, action: &lsp_types::CodeAction) -> String {
     match &action.edit {
         Some(WorkspaceEdit { changes, .. }) => match changes {
@@ -299,6 +333,239 @@ pub fn main() {
     );
 }
 
+#[test]
+fn fill_todo_with_local_variable() {
+    let code = "
+pub fn main() {
+  let x = 1
+  let y = \"Hello\"
+  let z: Int = todo
+}
+";
+
+    let expected = "
+pub fn main() {
+  let x = 1
+  let y = \"Hello\"
+  let z: Int = x
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 5, "Replace `todo` with `x`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_todo_with_todo_message() {
+    let code = "
+pub fn main(name: String) -> String {
+  todo as \"the name\"
+}
+";
+
+    let expected = "
+pub fn main(name: String) -> String {
+  name
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 3, "Replace `todo` with `name`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_todo_with_imported_value() {
+    let code = "
+import result
+
+pub fn main() -> fn() -> Nil {
+  todo
+}
+";
+
+    let expected = "
+import result
+
+pub fn main() -> fn() -> Nil {
+  result.is_ok
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 5, "Replace `todo` with `result.is_ok`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_todo_offers_only_values_of_the_expected_type() {
+    let code = "
+pub fn main() {
+  let x = 1
+  let y = \"Hello\"
+  let z: String = todo
+}
+";
+
+    assert_eq!(
+        code_action_titles(code, 5),
+        vec!["Replace `todo` with `y`".to_string()]
+    );
+}
+
+#[test]
+fn fill_todo_of_unknown_type_offers_nothing() {
+    let code = "
+pub fn main() {
+  let x = 1
+  let y = todo
+  y
+}
+";
+
+    assert_eq!(code_action_titles(code, 3), Vec::<String>::new());
+}
+
+#[test]
+fn fill_type_hole_in_assignment() {
+    let code = "
+pub fn main() {
+  let x: List(_) = [#(1, \"one\")]
+  x
+}
+";
+
+    let expected = "
+pub fn main() {
+  let x: List(#(Int, String)) = [#(1, \"one\")]
+  x
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 2, "Replace `_` with `#(Int, String)`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_type_hole_in_function_signature() {
+    let code = "
+type Wibble {
+  Wibble
+}
+
+fn wibble(x: _, f: fn(Int) -> _wobble) -> _ {
+  f(x)
+  Wibble
+}
+";
+
+    assert_eq!(
+        code_action_titles(code, 5),
+        vec![
+            "Replace `_` with `Int`".to_string(),
+            "Replace `_` with `Wibble`".to_string(),
+        ]
+    );
+
+    let expected = "
+type Wibble {
+  Wibble
+}
+
+fn wibble(x: _, f: fn(Int) -> _wobble) -> Wibble {
+  f(x)
+  Wibble
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 5, "Replace `_` with `Wibble`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_type_hole_in_anonymous_function() {
+    let code = "
+pub fn main() {
+  let f = fn(x: _) { x + 1 }
+  f
+}
+";
+
+    let expected = "
+pub fn main() {
+  let f = fn(x: Int) { x + 1 }
+  f
+}
+";
+
+    assert_eq!(
+        apply_first_replacement_with_title(code, 2, "Replace `_` with `Int`"),
+        expected
+    );
+}
+
+#[test]
+fn fill_type_hole_not_offered_for_imported_types() {
+    let code = "
+import map
+
+pub fn main(x: map.Map(Int, Int), y: _) -> Nil {
+  let z: _ = x
+  let w: _ = y
+  Nil
+}
+";
+
+    assert_eq!(code_action_titles(code, 5), Vec::<String>::new());
+}
+
+#[test]
+fn no_code_actions_for_dependency_modules() {
+    let dep = "
+import example_module/other
+
+pub fn main() {
+  Nil
+}
+";
+    let (mut engine, _) = TestProject::for_source("import example_module")
+        .add_hex_module("example_module", dep)
+        .add_hex_module("example_module/other", "")
+        .positioned_with_io(Position::new(0, 0));
+
+    let path = Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\build\packages\hex\src\example_module.gleam"
+    } else {
+        "/build/packages/hex/src/example_module.gleam"
+    });
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier::new(Url::from_file_path(path).unwrap()),
+        context: CodeActionContext {
+            diagnostics: vec![],
+            only: None,
+            trigger_kind: None,
+        },
+        range: Range::new(Position::new(0, 0), Position::new(6, 0)),
+        work_done_progress_params: WorkDoneProgressParams {
+            work_done_token: None,
+        },
+        partial_result_params: PartialResultParams {
+            partial_result_token: None,
+        },
+    };
+
+    assert!(engine.compiler.modules.contains_key("example_module"));
+    assert_eq!(engine.action(params).result, Ok(None));
+}
+
 /* TODO: implement qualified unused location
 #[test]
 fn test_remove_unused_qualified_action() {
@@ -940,3 +940,166 @@ fn main() -> MyType {
         })
     )
 }
+
+#[test]
+fn goto_definition_from_within_dependency_module() {
+    let dep = "
+fn wibble() { Nil }
+
+pub fn wobble() {
+  wibble()
+}
+";
+    let (mut engine, _) = TestProject::for_source("import example_module")
+        .add_hex_module("example_module", dep)
+        .positioned_with_io(Position::new(0, 0));
+
+    let uri = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\build\packages\hex\src\example_module.gleam"
+    } else {
+        "/build/packages/hex/src/example_module.gleam"
+    }))
+    .unwrap();
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(4, 3),
+        ),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    assert_eq!(
+        engine.goto_definition(params).result.unwrap(),
+        Some(Location {
+            uri,
+            range: Range {
+                start: Position {
+                    line: 1,
+                    character: 0
+                },
+                end: Position {
+                    line: 1,
+                    character: 11
+                }
+            }
+        })
+    )
+}
+
+#[test]
+fn goto_definition_bit_array_size_variable() {
+    let code = "
+pub fn main() {
+  let size = 8
+  <<1:size(size)>>
+}";
+
+    assert_eq!(
+        definition(TestProject::for_source(code), Position::new(3, 12)),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\app.gleam"
+            } else {
+                "/src/app.gleam"
+            }))
+            .unwrap(),
+            range: Range {
+                start: Position {
+                    line: 2,
+                    character: 6
+                },
+                end: Position {
+                    line: 2,
+                    character: 10
+                }
+            }
+        })
+    )
+}
+
+#[test]
+fn goto_definition_bit_array_pattern_size_variable() {
+    let code = "
+pub fn main(bits: BitArray) {
+  case bits {
+    <<size, data:bits-size(size)>> -> data
+    _ -> bits
+  }
+}";
+
+    assert_eq!(
+        definition(TestProject::for_source(code), Position::new(3, 28)),
+        Some(Location {
+            uri: Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+                r"\\?\C:\src\app.gleam"
+            } else {
+                "/src/app.gleam"
+            }))
+            .unwrap(),
+            range: Range {
+                start: Position {
+                    line: 3,
+                    character: 6
+                },
+                end: Position {
+                    line: 3,
+                    character: 10
+                }
+            }
+        })
+    )
+}
+
+#[test]
+fn goto_definition_from_within_cached_dependency_module() {
+    let dep = "
+fn wibble() { Nil }
+
+pub fn wobble() {
+  wibble()
+}
+";
+    let (mut engine, _) = TestProject::for_source("import example_module")
+        .add_hex_module("example_module", dep)
+        .positioned_with_io(Position::new(0, 0));
+
+    // Dependency modules loaded from the build cache have no typed AST, so
+    // the module is type checked when it is first needed.
+    _ = engine.compiler.modules.remove("example_module");
+
+    let uri = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\build\packages\hex\src\example_module.gleam"
+    } else {
+        "/build/packages/hex/src/example_module.gleam"
+    }))
+    .unwrap();
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(4, 3),
+        ),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    assert_eq!(
+        engine.goto_definition(params).result.unwrap(),
+        Some(Location {
+            uri,
+            range: Range {
+                start: Position {
+                    line: 1,
+                    character: 0
+                },
+                end: Position {
+                    line: 1,
+                    character: 11
+                }
+            }
+        })
+    );
+    assert!(engine.compiler.modules.contains_key("example_module"));
+}
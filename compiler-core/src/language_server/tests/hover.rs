@@ -867,3 +867,354 @@ fn valid() { Nil }
         })
     );
 }
+
+#[test]
+fn hover_todo_shows_expected_type_and_values_in_scope() {
+    let code = "
+pub fn main(a: Int, b: String) -> Int {
+  let c = 1
+  let d = [1]
+  todo
+}
+
+fn one() -> Int {
+  1
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(4, 3)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Int
+```
+
+Values in scope with this type:
+- `c: Int`
+- `a: Int`
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(4, 2),
+                end: Position::new(4, 6)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_todo_with_generic_module_values() {
+    let code = "
+pub fn main() -> fn() -> List(Int) {
+  todo
+}
+
+fn empty() -> List(a) {
+  []
+}
+
+fn strings() -> List(String) {
+  []
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(2, 3)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+fn() -> List(Int)
+```
+
+Values in scope with this type:
+- `empty: fn() -> List(a)`
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(2, 2),
+                end: Position::new(2, 6)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_todo_of_unknown_type_suggests_nothing() {
+    let code = "
+pub fn main(a: Int, b: String) {
+  let c = todo
+  c
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(2, 11)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+a
+```
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(2, 10),
+                end: Position::new(2, 14)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_todo_suggests_at_most_ten_values() {
+    let code = "
+pub fn main(a: Int, b: Int, c: Int, d: Int, e: Int, f: Int) -> Int {
+  let g = 1
+  let h = 1
+  let i = 1
+  let j = 1
+  let k = 1
+  let l = 1
+  todo
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(8, 3)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Int
+```
+
+Values in scope with this type:
+- `l: Int`
+- `k: Int`
+- `j: Int`
+- `i: Int`
+- `h: Int`
+- `g: Int`
+- `f: Int`
+- `e: Int`
+- `d: Int`
+- `c: Int`
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(8, 2),
+                end: Position::new(8, 6)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_type_hole_shows_inferred_type() {
+    let code = "
+pub fn main() {
+  let x: List(_) = [1.0]
+  x
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(2, 14)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Float
+```
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(2, 14),
+                end: Position::new(2, 15)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_bit_array_segment_option() {
+    let code = "
+pub fn main() {
+  let x = 1
+  <<x:size(8)-unit(2)>>
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 8)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Int
+```
+The number of units in the segment.
+
+The segment is 16 bits long.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 6),
+                end: Position::new(3, 13)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_bit_array_pattern_segment_option() {
+    let code = "
+pub fn main(bits: BitArray) {
+  case bits {
+    <<n:size(16)-little, rest:bytes>> -> n
+    _ -> 0
+  }
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 18)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Int
+```
+The value is stored in little-endian byte order.
+
+The segment is 16 bits long.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 17),
+                end: Position::new(3, 23)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_bit_array_pattern_segment_option_without_size() {
+    let code = "
+pub fn main(bits: BitArray) {
+  case bits {
+    <<n:size(16)-little, rest:bytes>> -> n
+    _ -> 0
+  }
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 32)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+BitArray
+```
+The segment is a bit array with a whole number of bytes.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 30),
+                end: Position::new(3, 35)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_string_prefix() {
+    let code = r#"
+pub fn main(name: String) {
+  case name {
+    "Hello, " as greeting <> rest -> rest
+    _ -> name
+  }
+}
+"#;
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 6)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+String
+```
+Matches strings starting with `\"Hello, \"`, assigning it to `greeting`.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 4),
+                end: Position::new(3, 25)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_string_prefix_rest() {
+    let code = r#"
+pub fn main(name: String) {
+  case name {
+    "Hello, " <> rest -> rest
+    _ -> name
+  }
+}
+"#;
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 18)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+String
+```
+The rest of the string after the prefix `\"Hello, \"`.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 17),
+                end: Position::new(3, 21)
+            }),
+        })
+    );
+}
+
+#[test]
+fn hover_bit_array_segment_option_with_overflowing_size() {
+    let code = "
+pub fn main() {
+  let x = 1
+  <<x:size(9223372036854775807)-unit(2)>>
+}
+";
+
+    assert_eq!(
+        hover(TestProject::for_source(code), Position::new(3, 32)),
+        Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(
+                "```gleam
+Int
+```
+The number of bits in each unit of the segment's size.
+"
+                .to_string()
+            )),
+            range: Some(Range {
+                start: Position::new(3, 32),
+                end: Position::new(3, 39)
+            }),
+        })
+    );
+}
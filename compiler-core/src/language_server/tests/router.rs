@@ -0,0 +1,96 @@
+use crate::language_server::router::Router;
+
+use std::collections::HashSet;
+
+use super::*;
+
+fn router_with_projects(
+    io: &LanguageServerTestIO,
+    projects: &[&str],
+) -> Router<LanguageServerTestIO, LanguageServerTestIO> {
+    let mut router = Router::new(io.clone(), FileSystemProxy::new(io.clone()));
+    for project in projects {
+        let root = Utf8PathBuf::from(*project);
+        io.io
+            .write(
+                &root.join("gleam.toml"),
+                &format!("name = \"{}\"", root.file_name().unwrap()),
+            )
+            .unwrap();
+        _ = router.project_for_path(root).unwrap().unwrap();
+    }
+    router
+}
+
+fn add_local_package(io: &mut LanguageServerTestIO, name: &str, path: &str) {
+    io.manifest.packages.push(ManifestPackage {
+        name: name.into(),
+        source: ManifestPackageSource::Local { path: path.into() },
+        build_tools: vec!["gleam".into()],
+        ..Default::default()
+    });
+}
+
+#[test]
+fn dependent_projects_via_path_dependency() {
+    let mut io = LanguageServerTestIO::new();
+    add_local_package(&mut io, "lib", "lib");
+    let router = router_with_projects(&io, &["/app/lib", "/app", "/other/app"]);
+
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/app/lib")),
+        HashSet::from([Utf8PathBuf::from("/app")])
+    );
+}
+
+#[test]
+fn dependent_projects_of_unused_project() {
+    let mut io = LanguageServerTestIO::new();
+    add_local_package(&mut io, "lib", "/lib");
+    let router = router_with_projects(&io, &["/lib", "/app"]);
+
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/app")),
+        HashSet::new()
+    );
+}
+
+#[test]
+fn transitive_dependent_projects() {
+    let mut io = LanguageServerTestIO::new();
+    add_local_package(&mut io, "lib", "lib");
+    let router = router_with_projects(&io, &["/app/lib/lib", "/app/lib", "/app"]);
+
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/app/lib/lib")),
+        HashSet::from([Utf8PathBuf::from("/app/lib"), Utf8PathBuf::from("/app")])
+    );
+}
+
+#[test]
+fn dependent_projects_of_non_canonical_root() {
+    let mut io = LanguageServerTestIO::new();
+    add_local_package(&mut io, "app", "/app");
+    let router = router_with_projects(&io, &["/app", "/lib"]);
+
+    // Every project depends on `/app`, including itself, but the project
+    // that changed is never one of its own dependents.
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/lib/../app/.")),
+        HashSet::from([Utf8PathBuf::from("/lib")])
+    );
+}
+
+#[test]
+fn delete_engines_within_removed_workspace_folder() {
+    let mut io = LanguageServerTestIO::new();
+    add_local_package(&mut io, "lib", "/one/lib");
+    let mut router = router_with_projects(&io, &["/one/lib", "/one/app", "/two/app"]);
+
+    router.delete_engines_within(Utf8Path::new("/two"));
+
+    assert_eq!(
+        router.dependent_projects(Utf8Path::new("/one/lib")),
+        HashSet::from([Utf8PathBuf::from("/one/app")])
+    );
+}
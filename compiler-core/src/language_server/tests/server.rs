@@ -0,0 +1,111 @@
+use lsp_server::Connection;
+use lsp_types::{
+    DocumentFormattingParams, FormattingOptions, InitializeParams, InitializedParams,
+    TextDocumentIdentifier, Url,
+};
+use serde_json::Value as Json;
+
+use super::*;
+use crate::language_server::{
+    messages::{Message, Notification, Request},
+    server::LanguageServer,
+};
+
+const DEPENDENCY_MODULE: &str = "/build/packages/hex/src/wibble.gleam";
+const ROOT_MODULE: &str = "/src/app.gleam";
+const UNFORMATTED: &str = "pub fn main(){Nil}";
+
+/// Starts a language server with the client's half of the initialisation
+/// handshake already sent, so that it can be driven one message at a time.
+fn server<'a>(
+    connection: &'a Connection,
+    client: &Connection,
+    io: &LanguageServerTestIO,
+) -> LanguageServer<'a, LanguageServerTestIO> {
+    let initialize =
+        lsp_server::Request::new(0.into(), "initialize".into(), InitializeParams::default());
+    let initialized = lsp_server::Notification::new("initialized".into(), InitializedParams {});
+    client.sender.send(initialize.into()).unwrap();
+    client.sender.send(initialized.into()).unwrap();
+
+    io.io
+        .write(
+            Utf8Path::new("/gleam.toml"),
+            "name = \"app\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+    _ = io.src_module("app", UNFORMATTED);
+    _ = io.hex_dep_module("hex", "wibble", UNFORMATTED);
+
+    LanguageServer::new(connection, io.clone()).unwrap()
+}
+
+fn format(
+    server: &mut LanguageServer<'_, LanguageServerTestIO>,
+    client: &Connection,
+    path: &str,
+) -> Json {
+    let params = DocumentFormattingParams {
+        text_document: TextDocumentIdentifier::new(Url::from_file_path(path).unwrap()),
+        options: FormattingOptions {
+            tab_size: 2,
+            insert_spaces: true,
+            ..Default::default()
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let id: lsp_server::RequestId = path.to_string().into();
+    server.handle_message(Message::Request(id.clone(), Request::Format(params)));
+
+    client
+        .receiver
+        .try_iter()
+        .find_map(|message| match message {
+            lsp_server::Message::Response(response) if response.id == id => response.result,
+            _ => None,
+        })
+        .expect("format response")
+}
+
+#[cfg(not(target_family = "windows"))]
+#[test]
+fn dependency_modules_are_not_formatted() {
+    let (connection, client) = Connection::memory();
+    let io = LanguageServerTestIO::new();
+    let mut server = server(&connection, &client, &io);
+
+    assert_eq!(format(&mut server, &client, DEPENDENCY_MODULE), Json::Null);
+    assert_ne!(format(&mut server, &client, ROOT_MODULE), Json::Null);
+}
+
+#[cfg(not(target_family = "windows"))]
+#[test]
+fn dependency_module_changes_are_not_compiled() {
+    let (connection, client) = Connection::memory();
+    let io = LanguageServerTestIO::new();
+    let mut server = server(&connection, &client, &io);
+
+    server.handle_message(Message::Notification(
+        Notification::SourceFileChangedInMemory {
+            path: DEPENDENCY_MODULE.into(),
+            text: "pub fn main() { 1 }".into(),
+        },
+    ));
+    server.handle_message(Message::Notification(Notification::CompilePlease));
+    assert_eq!(io.actions.lock().unwrap().clone(), vec![]);
+
+    // A change to a module of the project itself starts the project's
+    // engine, which first downloads its dependencies.
+    server.handle_message(Message::Notification(
+        Notification::SourceFileChangedInMemory {
+            path: ROOT_MODULE.into(),
+            text: "pub fn main() { 1 }".into(),
+        },
+    ));
+    server.handle_message(Message::Notification(Notification::CompilePlease));
+    assert!(io
+        .actions
+        .lock()
+        .unwrap()
+        .contains(&Action::DownloadDependencies));
+}
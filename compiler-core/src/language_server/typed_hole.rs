@@ -0,0 +1,429 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ecow::EcoString;
+
+use crate::{
+    ast::{
+        self, visit::Visit, AssignName, Definition, SrcSpan, Statement, TypeAst, TypedArg,
+        TypedClause, TypedExpr, TypedFunction, TypedPattern, TypedStatement,
+    },
+    build::Module,
+    type_::{
+        self, collapse_links, prelude::is_prelude_module, pretty::Printer, unbound_var, unify,
+        ModuleInterface, Type, TypeVar,
+    },
+    uid::UniqueIdGenerator,
+};
+
+/// The most fillers suggested for a single `todo`. When the expected type is
+/// very general, such as a function taking a type variable, a great many
+/// values could fit and listing them all is of no help.
+///
+const MAX_FILLERS: usize = 10;
+
+/// A value that could be used in place of a `todo` expression as its type
+/// unifies with the type expected at that position.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filler {
+    /// The code to replace the `todo` with, e.g. `wibble` or `wobble.wubble`.
+    pub name: EcoString,
+    pub type_: Arc<Type>,
+}
+
+/// Find the values in scope at a `todo` expression whose type unifies with the
+/// type expected of the `todo`.
+///
+/// These are, in order:
+/// - The local variables in scope at the `todo`, most recently bound first.
+/// - The values defined in the module.
+/// - The values imported from other modules, both qualified and unqualified.
+///
+/// Nothing is suggested when the expected type is a type variable, as any
+/// value would do, nor are values whose type is a type variable. At most
+/// `MAX_FILLERS` values are returned.
+///
+pub fn fillers(
+    module: &Module,
+    todo: SrcSpan,
+    type_: &Arc<Type>,
+    importable_modules: &im::HashMap<EcoString, ModuleInterface>,
+) -> Vec<Filler> {
+    if is_type_variable(type_) {
+        return vec![];
+    }
+
+    let mut scope = LocalScope {
+        position: todo.start,
+        variables: vec![],
+    };
+    scope.visit_typed_module(&module.ast);
+
+    let mut fillers: Vec<Filler> = vec![];
+    let mut add = |name: EcoString, candidate: &Arc<Type>, is_local: bool| {
+        if fillers.len() >= MAX_FILLERS
+            || is_type_variable(candidate)
+            || fillers.iter().any(|filler| filler.name == name)
+        {
+            return;
+        }
+        if could_unify(type_, candidate, is_local) {
+            fillers.push(Filler {
+                name,
+                type_: candidate.clone(),
+            });
+        }
+    };
+
+    // Later bindings shadow earlier ones, so they take precedence.
+    for (name, candidate) in scope.variables.iter().rev() {
+        add(name.clone(), candidate, true);
+    }
+
+    for (name, value) in &module.ast.type_info.values {
+        add(name.clone(), &value.type_, false);
+    }
+
+    for definition in &module.ast.definitions {
+        let Definition::Import(import) = definition else {
+            continue;
+        };
+        let Some(interface) = importable_modules.get(&import.module) else {
+            continue;
+        };
+
+        for unqualified in &import.unqualified_values {
+            if let Some(value) = interface.get_public_value(&unqualified.name) {
+                add(unqualified.used_name().clone(), &value.type_, false);
+            }
+        }
+
+        let Some(module_name) = import.used_name() else {
+            continue;
+        };
+        for (name, value) in &interface.values {
+            if value.publicity.is_importable() {
+                add(format!("{module_name}.{name}").into(), &value.type_, false);
+            }
+        }
+    }
+
+    fillers
+}
+
+fn is_type_variable(type_: &Arc<Type>) -> bool {
+    matches!(collapse_links(type_.clone()).as_ref(), Type::Var { .. })
+}
+
+/// A `_` hole in a type annotation, along with the type inferred for it.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHole {
+    pub location: SrcSpan,
+    /// The name of the hole as written, e.g. `_` or `_wibble`.
+    pub name: EcoString,
+    pub type_: Arc<Type>,
+}
+
+/// Find the holes in a type annotation, pairing each with the part of the
+/// inferred type that it stands for.
+///
+pub fn type_holes(annotation: &TypeAst, type_: &Arc<Type>, holes: &mut Vec<TypeHole>) {
+    let type_ = collapse_links(type_.clone());
+    match annotation {
+        TypeAst::Hole(hole) => holes.push(TypeHole {
+            location: hole.location,
+            name: hole.name.clone(),
+            type_,
+        }),
+
+        TypeAst::Var(_) => (),
+
+        TypeAst::Constructor(constructor) => {
+            if let Type::Named { args, .. } = type_.as_ref() {
+                for (argument, type_) in constructor.arguments.iter().zip(args) {
+                    type_holes(argument, type_, holes);
+                }
+            }
+        }
+
+        TypeAst::Fn(fn_) => {
+            if let Type::Fn { args, retrn } = type_.as_ref() {
+                for (argument, type_) in fn_.arguments.iter().zip(args) {
+                    type_holes(argument, type_, holes);
+                }
+                type_holes(&fn_.return_, retrn, holes);
+            }
+        }
+
+        TypeAst::Tuple(tuple) => {
+            if let Type::Tuple { elems } = type_.as_ref() {
+                for (elem, type_) in tuple.elems.iter().zip(elems) {
+                    type_holes(elem, type_, holes);
+                }
+            }
+        }
+    }
+}
+
+/// The annotation to write in place of a type hole, if the inferred type can
+/// be written in the module.
+///
+/// Types containing type variables are not filled as their printed names could
+/// clash with the names of type variables elsewhere in the annotation, and
+/// types from other modules are not filled as the module may not be imported,
+/// or may be imported under another name.
+///
+pub fn type_hole_filler(hole: &TypeHole, module: &Module) -> Option<String> {
+    if is_writable_type(&hole.type_, &module.name) {
+        Some(Printer::new().pretty_print(&hole.type_, 0))
+    } else {
+        None
+    }
+}
+
+fn is_writable_type(type_: &Arc<Type>, module_name: &str) -> bool {
+    match collapse_links(type_.clone()).as_ref() {
+        Type::Var { .. } => false,
+        Type::Named { module, args, .. } => {
+            (is_prelude_module(module) || module == module_name)
+                && args.iter().all(|arg| is_writable_type(arg, module_name))
+        }
+        Type::Fn { args, retrn } => {
+            args.iter().all(|arg| is_writable_type(arg, module_name))
+                && is_writable_type(retrn, module_name)
+        }
+        Type::Tuple { elems } => elems.iter().all(|elem| is_writable_type(elem, module_name)),
+    }
+}
+
+/// Whether a value of the candidate type could be used where a value of the
+/// expected type is needed.
+///
+/// Neither type is modified: the check is performed on copies of them. Type
+/// variables of the expected type, and of local variables, belong to the
+/// function being checked so they must match exactly, while the generic type
+/// variables of a module value are instantiated afresh as they would be when
+/// the value is referenced.
+///
+fn could_unify(expected: &Arc<Type>, candidate: &Arc<Type>, is_local: bool) -> bool {
+    let ids = UniqueIdGenerator::new();
+    let mut unbound = HashMap::new();
+    let expected = copy_type(expected, &ids, &mut unbound, None);
+    let candidate = if is_local {
+        copy_type(candidate, &ids, &mut unbound, None)
+    } else {
+        copy_type(candidate, &ids, &mut unbound, Some(&mut HashMap::new()))
+    };
+    unify(expected, candidate).is_ok()
+}
+
+/// Copy a type, replacing each unbound type variable with a new one so that
+/// unifying the copy does not affect the original. If a map of generic type
+/// variables is given then generic variables are also replaced, instantiating
+/// the type.
+///
+fn copy_type(
+    type_: &Arc<Type>,
+    ids: &UniqueIdGenerator,
+    unbound: &mut HashMap<u64, Arc<Type>>,
+    mut generic: Option<&mut HashMap<u64, Arc<Type>>>,
+) -> Arc<Type> {
+    let type_ = collapse_links(type_.clone());
+    match type_.as_ref() {
+        Type::Var { type_: var } => match *var.borrow() {
+            TypeVar::Unbound { id } => unbound
+                .entry(id)
+                .or_insert_with(|| unbound_var(ids.next()))
+                .clone(),
+            TypeVar::Generic { id } => match generic {
+                Some(generic) => generic
+                    .entry(id)
+                    .or_insert_with(|| unbound_var(ids.next()))
+                    .clone(),
+                None => type_.clone(),
+            },
+            TypeVar::Link { .. } => type_.clone(),
+        },
+
+        Type::Named {
+            publicity,
+            package,
+            module,
+            name,
+            args,
+        } => Arc::new(Type::Named {
+            publicity: *publicity,
+            package: package.clone(),
+            module: module.clone(),
+            name: name.clone(),
+            args: args
+                .iter()
+                .map(|arg| copy_type(arg, ids, unbound, generic.as_deref_mut()))
+                .collect(),
+        }),
+
+        Type::Fn { args, retrn } => type_::fn_(
+            args.iter()
+                .map(|arg| copy_type(arg, ids, unbound, generic.as_deref_mut()))
+                .collect(),
+            copy_type(retrn, ids, unbound, generic.as_deref_mut()),
+        ),
+
+        Type::Tuple { elems } => type_::tuple(
+            elems
+                .iter()
+                .map(|elem| copy_type(elem, ids, unbound, generic.as_deref_mut()))
+                .collect(),
+        ),
+    }
+}
+
+/// Collects the local variables in scope at a position in a module, in the
+/// order in which they are bound.
+///
+struct LocalScope {
+    position: u32,
+    variables: Vec<(EcoString, Arc<Type>)>,
+}
+
+impl LocalScope {
+    fn visit_statements(&mut self, statements: &[TypedStatement]) {
+        for statement in statements {
+            let location = statement.location();
+            if location.end <= self.position {
+                // Variables assigned by earlier statements are in scope for
+                // the rest of the block.
+                if let Statement::Assignment(assignment) = statement {
+                    self.bind_pattern(&assignment.pattern);
+                }
+            } else if location.start <= self.position {
+                self.visit_typed_statement(statement);
+                return;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn bind_arguments(&mut self, arguments: &[TypedArg]) {
+        for argument in arguments {
+            if let Some(name) = argument.get_variable_name() {
+                self.bind(name, argument.type_.clone());
+            }
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &TypedPattern) {
+        match pattern {
+            ast::Pattern::Int { .. }
+            | ast::Pattern::Float { .. }
+            | ast::Pattern::String { .. }
+            | ast::Pattern::VarUsage { .. }
+            | ast::Pattern::Discard { .. } => (),
+
+            ast::Pattern::Variable { name, type_, .. } => self.bind(name, type_.clone()),
+
+            ast::Pattern::Assign { name, pattern, .. } => {
+                self.bind_pattern(pattern);
+                self.bind(name, pattern.type_());
+            }
+
+            ast::Pattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.bind_pattern(element);
+                }
+                if let Some(tail) = tail {
+                    self.bind_pattern(tail);
+                }
+            }
+
+            ast::Pattern::Constructor { arguments, .. } => {
+                for argument in arguments {
+                    self.bind_pattern(&argument.value);
+                }
+            }
+
+            ast::Pattern::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.bind_pattern(elem);
+                }
+            }
+
+            ast::Pattern::BitArray { segments, .. } => {
+                for segment in segments {
+                    self.bind_pattern(&segment.value);
+                }
+            }
+
+            ast::Pattern::StringPrefix {
+                left_side_assignment,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some((name, _)) = left_side_assignment {
+                    self.bind(name, type_::string());
+                }
+                if let AssignName::Variable(name) = right_side_assignment {
+                    self.bind(name, type_::string());
+                }
+            }
+        }
+    }
+
+    fn bind(&mut self, name: &EcoString, type_: Arc<Type>) {
+        // Variables introduced by the compiler, such as those used when
+        // desugaring pipelines, are not valid names to write.
+        if name.starts_with('_') {
+            return;
+        }
+        self.variables.push((name.clone(), type_));
+    }
+}
+
+impl<'ast> Visit<'ast> for LocalScope {
+    fn visit_typed_function(&mut self, fun: &'ast TypedFunction) {
+        if !SrcSpan::new(fun.location.start, fun.end_position).contains(self.position) {
+            return;
+        }
+        self.bind_arguments(&fun.arguments);
+        self.visit_statements(&fun.body);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        if expr.location().contains(self.position) {
+            ast::visit::visit_typed_expr(self, expr);
+        }
+    }
+
+    fn visit_typed_expr_block(
+        &mut self,
+        _location: &'ast SrcSpan,
+        statements: &'ast [TypedStatement],
+    ) {
+        self.visit_statements(statements);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _typ: &'ast Arc<Type>,
+        _is_capture: &'ast bool,
+        args: &'ast [TypedArg],
+        body: &'ast [TypedStatement],
+        _return_annotation: &'ast Option<TypeAst>,
+    ) {
+        self.bind_arguments(args);
+        self.visit_statements(body);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        if !clause.then.location().contains(self.position) {
+            return;
+        }
+        for pattern in &clause.pattern {
+            self.bind_pattern(pattern);
+        }
+        self.visit_typed_expr(&clause.then);
+    }
+}
@@ -82,6 +82,7 @@ pub mod parse;
 pub mod paths;
 pub mod pretty;
 pub mod requirement;
+pub mod sbom;
 pub mod strings;
 pub mod type_;
 pub mod uid;
@@ -79,10 +79,18 @@ impl Manifest {
             }
 
             match source {
-                ManifestPackageSource::Hex { outer_checksum } => {
+                ManifestPackageSource::Hex {
+                    outer_checksum,
+                    repository,
+                } => {
                     buffer.push_str(r#", source = "hex", outer_checksum = ""#);
                     buffer.push_str(&outer_checksum.to_string());
                     buffer.push('"');
+                    if let Some(repository) = repository {
+                        buffer.push_str(r#", repository = ""#);
+                        buffer.push_str(repository);
+                        buffer.push('"');
+                    }
                 }
                 ManifestPackageSource::Git { repo, commit } => {
                     buffer.push_str(r#", source = "git", repo = ""#);
@@ -186,7 +194,13 @@ impl ManifestPackage {
 #[serde(tag = "source")]
 pub enum ManifestPackageSource {
     #[serde(rename = "hex")]
-    Hex { outer_checksum: Base16Checksum },
+    Hex {
+        outer_checksum: Base16Checksum,
+        /// The Hex repository the package was fetched from, `None` for
+        /// hex.pm.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        repository: Option<EcoString>,
+    },
     #[serde(rename = "git")]
     Git { repo: EcoString, commit: EcoString },
     #[serde(rename = "local")]
@@ -250,6 +264,7 @@ mod tests {
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -260,6 +275,7 @@ mod tests {
                     requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -270,6 +286,7 @@ mod tests {
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -301,6 +318,7 @@ mod tests {
                     requirements: vec!["gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 46]),
+                        repository: None,
                     },
                 },
             ],
@@ -360,6 +378,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![1, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -370,6 +389,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec!["zzz".into(), "gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -380,6 +400,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec![],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 22]),
+                        repository: None,
                     },
                 },
                 ManifestPackage {
@@ -411,6 +432,7 @@ zzz = { version = "> 0.0.0" }
                     requirements: vec!["gleam_stdlib".into()],
                     source: ManifestPackageSource::Hex {
                         outer_checksum: Base16Checksum(vec![3, 46]),
+                        repository: None,
                     },
                 },
             ],
@@ -442,6 +464,44 @@ zzz = { version = "> 0.0.0" }
         );
     }
 
+    #[test]
+    fn manifest_hex_repository_round_trip() {
+        let manifest = Manifest {
+            requirements: [(
+                "acme_auth".into(),
+                Requirement::hex_from_repository("~> 1.0", "acme"),
+            )]
+            .into(),
+            packages: vec![ManifestPackage {
+                name: "acme_auth".into(),
+                version: Version::new(1, 2, 0),
+                build_tools: ["gleam".into()].into(),
+                otp_app: None,
+                requirements: vec![],
+                source: ManifestPackageSource::Hex {
+                    outer_checksum: Base16Checksum(vec![3, 22]),
+                    repository: Some("acme".into()),
+                },
+            }],
+        };
+        let buffer = manifest.to_toml(HOME.into());
+        assert_eq!(
+            buffer,
+            r#"# This file was generated by Gleam
+# You typically do not need to edit this file
+
+packages = [
+  { name = "acme_auth", version = "1.2.0", build_tools = ["gleam"], requirements = [], source = "hex", outer_checksum = "0316", repository = "acme" },
+]
+
+[requirements]
+acme_auth = { version = "~> 1.0", repo = "acme" }
+"#
+        );
+        let read: Manifest = toml::from_str(&buffer).unwrap();
+        assert_eq!(read, manifest);
+    }
+
     impl Default for ManifestPackage {
         fn default() -> Self {
             Self {
@@ -452,6 +512,7 @@ zzz = { version = "> 0.0.0" }
                 version: Version::new(1, 0, 0),
                 source: ManifestPackageSource::Hex {
                     outer_checksum: Base16Checksum(vec![]),
+                    repository: None,
                 },
             }
         }
@@ -4,6 +4,8 @@ use ecow::EcoString;
 use itertools::Itertools;
 use serde::Serialize;
 
+pub mod diff;
+
 #[cfg(test)]
 mod tests;
 
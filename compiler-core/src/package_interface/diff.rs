@@ -0,0 +1,317 @@
+//! Compares two versions of the public interface of a package, as serialised
+//! to JSON, to find the changes made to its API.
+//!
+//! The interfaces are compared as JSON rather than as `PackageInterface`s so
+//! that the previous one can be taken from anywhere it was saved, such as the
+//! documentation published to Hex.
+//!
+
+use std::fmt;
+
+use ecow::EcoString;
+use hexpm::version::Version;
+use itertools::Itertools;
+use serde_json::{Map, Value};
+
+use crate::{Error, Result};
+
+/// The kinds of item in a module's interface, along with the name of the
+/// field they are kept in.
+const ITEM_KINDS: [(ItemKind, &str); 4] = [
+    (ItemKind::Type, "types"),
+    (ItemKind::TypeAlias, "type-aliases"),
+    (ItemKind::Constant, "constants"),
+    (ItemKind::Function, "functions"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemKind {
+    Module,
+    Type,
+    TypeAlias,
+    Constant,
+    Function,
+}
+
+impl ItemKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            ItemKind::Module => "module",
+            ItemKind::Type => "type",
+            ItemKind::TypeAlias => "type alias",
+            ItemKind::Constant => "constant",
+            ItemKind::Function => "function",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Removed,
+    Changed,
+    Deprecated,
+    Added,
+}
+
+impl ChangeKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Changed => "Changed",
+            ChangeKind::Deprecated => "Deprecated",
+            ChangeKind::Added => "Added",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub change: ChangeKind,
+    pub kind: ItemKind,
+    pub module: EcoString,
+    /// The name of the item in the module, which is `None` when the change
+    /// is to the module as a whole.
+    pub name: Option<EcoString>,
+}
+
+impl ApiChange {
+    /// The smallest version bump that can be made for a release with this
+    /// change. Anything removed or changed may break code using it, while
+    /// anything added or deprecated only gives users something new.
+    pub fn bump(&self) -> Bump {
+        match self.change {
+            ChangeKind::Removed | ChangeKind::Changed => Bump::Major,
+            ChangeKind::Deprecated | ChangeKind::Added => Bump::Minor,
+        }
+    }
+}
+
+/// The parts of a semantic version that can be increased for a release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+impl Bump {
+    /// The bump made from one version to another, or `None` if the version
+    /// has not increased.
+    ///
+    /// Before 1.0.0 there is no major version to increase, so as with Cargo
+    /// an increase of the minor version is a major bump, and an increase of
+    /// the patch version is a minor bump.
+    pub fn between(previous: &Version, version: &Version) -> Option<Bump> {
+        if version <= previous {
+            None
+        } else if version.major != previous.major {
+            Some(Bump::Major)
+        } else if version.minor != previous.minor {
+            Some(if version.major == 0 {
+                Bump::Major
+            } else {
+                Bump::Minor
+            })
+        } else if version.major == 0 {
+            Some(Bump::Minor)
+        } else {
+            Some(Bump::Patch)
+        }
+    }
+
+    /// The smallest version after `previous` with this bump.
+    pub fn apply(self, previous: &Version) -> Version {
+        let Version {
+            major,
+            minor,
+            patch,
+            ..
+        } = *previous;
+        match (self, major) {
+            (Bump::Major, 0) => Version::new(0, minor + 1, 0),
+            (Bump::Major, _) => Version::new(major + 1, 0, 0),
+            (Bump::Minor, 0) | (Bump::Patch, _) => Version::new(major, minor, patch + 1),
+            (Bump::Minor, _) => Version::new(major, minor + 1, 0),
+        }
+    }
+}
+
+/// The smallest bump needed for a release with the given changes. Any
+/// release needs at least a patch bump.
+pub fn required_bump(changes: &[ApiChange]) -> Bump {
+    changes
+        .iter()
+        .map(ApiChange::bump)
+        .max()
+        .unwrap_or(Bump::Patch)
+}
+
+/// Returns an error if the bump from `previous` to `version` is too small
+/// for the changes made to the API.
+pub fn check_version(previous: &Version, version: &Version, changes: &[ApiChange]) -> Result<()> {
+    let required = required_bump(changes);
+    let bump = Bump::between(previous, version);
+    match bump {
+        Some(bump) if bump >= required => Ok(()),
+        _ => Err(Error::InsufficientVersionBump {
+            previous: previous.to_string().into(),
+            version: version.to_string().into(),
+            suggested: required.apply(previous).to_string().into(),
+            // When the version has not increased any bump at all is needed
+            required: bump.map(|_| required),
+            changes: changes
+                .iter()
+                .filter(|change| change.bump() == required)
+                .map(|change| change.to_string().into())
+                .collect(),
+        }),
+    }
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = self.change.describe();
+        let kind = self.kind.describe();
+        match &self.name {
+            Some(name) => write!(f, "{change} {kind} {}.{name}", self.module),
+            None => write!(f, "{change} {kind} {}", self.module),
+        }
+    }
+}
+
+/// The changes from the `previous` interface to the `current` one, ordered by
+/// module and then with the removals, which are the most likely to break
+/// users of the package, first.
+///
+/// Changes to documentation are not part of the API, so they are ignored. Nor
+/// are changes to how an item is implemented, unless it can no longer be used
+/// on a target it could be used on before.
+pub fn diff(previous: &Value, current: &Value) -> Vec<ApiChange> {
+    let empty = Map::new();
+    let previous_modules = field(previous, "modules").unwrap_or(&empty);
+    let current_modules = field(current, "modules").unwrap_or(&empty);
+
+    let mut changes = vec![];
+    for (module, previous_module) in previous_modules {
+        let Some(current_module) = current_modules.get(module) else {
+            changes.push(ApiChange {
+                change: ChangeKind::Removed,
+                kind: ItemKind::Module,
+                module: module.into(),
+                name: None,
+            });
+            continue;
+        };
+        for (kind, items) in ITEM_KINDS {
+            let previous_items = field(previous_module, items).unwrap_or(&empty);
+            let current_items = field(current_module, items).unwrap_or(&empty);
+            changes.extend(diff_items(module, kind, previous_items, current_items));
+        }
+    }
+    for module in current_modules.keys() {
+        if !previous_modules.contains_key(module) {
+            changes.push(ApiChange {
+                change: ChangeKind::Added,
+                kind: ItemKind::Module,
+                module: module.into(),
+                name: None,
+            });
+        }
+    }
+
+    changes
+        .into_iter()
+        .sorted_by(|a, b| {
+            (&a.module, a.change, a.kind, &a.name).cmp(&(&b.module, b.change, b.kind, &b.name))
+        })
+        .collect()
+}
+
+fn diff_items(
+    module: &str,
+    kind: ItemKind,
+    previous: &Map<String, Value>,
+    current: &Map<String, Value>,
+) -> Vec<ApiChange> {
+    let change = |change, name: &str| ApiChange {
+        change,
+        kind,
+        module: module.into(),
+        name: Some(name.into()),
+    };
+    let mut changes = vec![];
+    for (name, previous_item) in previous {
+        let Some(current_item) = current.get(name) else {
+            changes.push(change(ChangeKind::Removed, name));
+            continue;
+        };
+        if without_details(previous_item) != without_details(current_item)
+            || drops_target(previous_item, current_item)
+        {
+            changes.push(change(ChangeKind::Changed, name));
+        } else if is_deprecated(current_item) && !is_deprecated(previous_item) {
+            changes.push(change(ChangeKind::Deprecated, name));
+        }
+    }
+    for name in current.keys() {
+        if !previous.contains_key(name) {
+            changes.push(change(ChangeKind::Added, name));
+        }
+    }
+    changes
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Map<String, Value>> {
+    value.get(name).and_then(Value::as_object)
+}
+
+fn is_deprecated(item: &Value) -> bool {
+    item.get("deprecation")
+        .is_some_and(|deprecation| !deprecation.is_null())
+}
+
+/// Whether the item could run on a target before but no longer can.
+fn drops_target(previous: &Value, current: &Value) -> bool {
+    ["can-run-on-erlang", "can-run-on-javascript"]
+        .iter()
+        .any(|target| can_run_on(previous, target) && !can_run_on(current, target))
+}
+
+fn can_run_on(item: &Value, target: &str) -> bool {
+    item.get("implementations")
+        .and_then(|implementations| implementations.get(target))
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+}
+
+/// The item with its documentation, deprecation, and implementations removed,
+/// at any depth, so only the parts that affect how it can be used are
+/// compared.
+fn without_details(item: &Value) -> Value {
+    match item {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(name, _)| {
+                    !matches!(
+                        name.as_str(),
+                        "documentation" | "deprecation" | "implementations"
+                    )
+                })
+                .map(|(name, value)| (name.clone(), without_details(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(without_details).collect()),
+        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => item.clone(),
+    }
+}
@@ -2,7 +2,7 @@ use std::time::SystemTime;
 
 use ecow::EcoString;
 use globset::GlobBuilder;
-use hexpm::version::Identifier;
+use hexpm::version::{Identifier, Version};
 
 use crate::{
     analyse::TargetSupport,
@@ -12,6 +12,7 @@ use crate::{
     type_::PRELUDE_MODULE_NAME,
     uid::UniqueIdGenerator,
     warning::TypeWarningEmitter,
+    Error,
 };
 
 use super::PackageInterface;
@@ -134,7 +135,7 @@ fn package_from_module(module: Module) -> Package {
     Package {
         config: PackageConfig {
             name: "my_package".into(),
-            version: hexpm::version::Version {
+            version: Version {
                 major: 11,
                 minor: 10,
                 patch: 9,
@@ -146,6 +147,7 @@ fn package_from_module(module: Module) -> Package {
             },
             gleam_version: Some("1.0.0".into()),
             licences: vec![],
+            allowed_licences: vec![],
             description: "description".into(),
             documentation: Docs { pages: vec![] },
             dependencies: std::collections::HashMap::new(),
@@ -158,6 +160,8 @@ fn package_from_module(module: Module) -> Package {
             internal_modules: Some(vec![GlobBuilder::new("internals/*")
                 .build()
                 .expect("internals glob")]),
+            warnings: Default::default(),
+            hex_repositories: std::collections::HashMap::new(),
         },
         modules: vec![module],
     }
@@ -293,3 +297,150 @@ pub type Box(a, b) {
 pub fn internal_modules_are_not_exported() {
     assert_package_interface_with_name!("internals/internal_module", "pub fn main() { 1 }");
 }
+
+fn api_changes(previous: &str, current: &str) -> Vec<String> {
+    let interface =
+        |src| serde_json::from_str(&compile_package(None, src, None)).expect("interface json");
+    super::diff::diff(&interface(previous), &interface(current))
+        .iter()
+        .map(|change| change.to_string())
+        .collect()
+}
+
+#[test]
+pub fn api_changes_between_interfaces() {
+    let changes = api_changes(
+        r#"
+pub type Wibble { Wibble(Int) }
+pub const wobble = 1
+pub fn removed() { 1 }
+pub fn changed(x: Int) -> Int { x }
+pub fn deprecated() { 1 }
+"#,
+        r#"
+pub type Wibble { Wibble(Int) Wobble }
+pub const wobble = 1
+pub fn added() { 1 }
+pub fn changed(x: Float) -> Float { x }
+@deprecated("Use added instead")
+pub fn deprecated() { 1 }
+"#,
+    );
+    assert_eq!(
+        changes,
+        vec![
+            "Removed function my/module.removed",
+            "Changed type my/module.Wibble",
+            "Changed function my/module.changed",
+            "Deprecated function my/module.deprecated",
+            "Added function my/module.added",
+        ]
+    );
+}
+
+#[test]
+pub fn documentation_is_not_an_api_change() {
+    let changes = api_changes(
+        r#"
+/// Wibble
+pub fn wibble(x: Int) { x }
+"#,
+        r#"
+/// Wobble!
+pub fn wibble(y: Int) { y }
+"#,
+    );
+    assert!(changes.is_empty(), "{changes:?}");
+}
+
+#[test]
+pub fn implementation_is_not_an_api_change() {
+    let changes = api_changes(
+        r#"
+pub fn wibble() -> Int { 1 }
+pub fn wobble() -> Int { 1 }
+"#,
+        r#"
+@external(erlang, "wibble", "wibble")
+pub fn wibble() -> Int { 1 }
+
+@external(erlang, "wibble", "wobble")
+pub fn wobble() -> Int
+"#,
+    );
+    // `wobble` can no longer be used on JavaScript
+    assert_eq!(changes, vec!["Changed function my/module.wobble"]);
+}
+
+#[test]
+pub fn removed_and_added_modules_are_api_changes() {
+    let previous = serde_json::json!({ "modules": { "wibble": {}, "wobble": {} } });
+    let current = serde_json::json!({ "modules": { "wobble": {}, "wubble": {} } });
+    let changes = super::diff::diff(&previous, &current)
+        .iter()
+        .map(|change| change.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec!["Removed module wibble", "Added module wubble"]
+    );
+}
+
+#[test]
+pub fn version_bumps() {
+    use super::diff::Bump;
+    let version = |version| Version::parse(version).expect("version");
+    let between = |previous, current| Bump::between(&version(previous), &version(current));
+    assert_eq!(between("1.2.3", "2.0.0"), Some(Bump::Major));
+    assert_eq!(between("1.2.3", "1.3.0"), Some(Bump::Minor));
+    assert_eq!(between("1.2.3", "1.2.4"), Some(Bump::Patch));
+    assert_eq!(between("0.2.3", "0.3.0"), Some(Bump::Major));
+    assert_eq!(between("0.2.3", "0.2.4"), Some(Bump::Minor));
+    assert_eq!(between("0.2.3", "1.0.0"), Some(Bump::Major));
+    assert_eq!(between("1.2.3", "1.2.3"), None);
+    assert_eq!(between("1.2.3", "1.0.0"), None);
+
+    assert_eq!(Bump::Major.apply(&version("1.2.3")), version("2.0.0"));
+    assert_eq!(Bump::Minor.apply(&version("1.2.3")), version("1.3.0"));
+    assert_eq!(Bump::Patch.apply(&version("1.2.3")), version("1.2.4"));
+    assert_eq!(Bump::Major.apply(&version("0.2.3")), version("0.3.0"));
+    assert_eq!(Bump::Minor.apply(&version("0.2.3")), version("0.2.4"));
+}
+
+#[test]
+pub fn version_check() {
+    use super::diff::{check_version, ApiChange, Bump, ChangeKind, ItemKind};
+    let change = |change| ApiChange {
+        change,
+        kind: ItemKind::Function,
+        module: "wibble".into(),
+        name: Some("wobble".into()),
+    };
+    let version = |version| Version::parse(version).expect("version");
+    let added = [change(ChangeKind::Added)];
+    let removed = [change(ChangeKind::Added), change(ChangeKind::Removed)];
+
+    assert_eq!(
+        check_version(&version("1.0.0"), &version("1.0.1"), &[]),
+        Ok(())
+    );
+    assert_eq!(
+        check_version(&version("1.0.0"), &version("1.1.0"), &added),
+        Ok(())
+    );
+    assert_eq!(
+        check_version(&version("1.0.0"), &version("2.0.0"), &removed),
+        Ok(())
+    );
+    assert_eq!(
+        check_version(&version("1.0.0"), &version("1.1.0"), &removed),
+        Err(Error::InsufficientVersionBump {
+            previous: "1.0.0".into(),
+            version: "1.1.0".into(),
+            suggested: "2.0.0".into(),
+            required: Some(Bump::Major),
+            changes: vec!["Removed function wibble.wobble".into()],
+        })
+    );
+    assert!(check_version(&version("1.0.0"), &version("1.0.0"), &[]).is_err());
+}
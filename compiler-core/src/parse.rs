@@ -56,19 +56,20 @@ mod token;
 
 use crate::analyse::Inferred;
 use crate::ast::{
-    Arg, ArgNames, AssignName, Assignment, AssignmentKind, BinOp, BitArrayOption, BitArraySegment,
-    CallArg, Clause, ClauseGuard, Constant, CustomType, Definition, Function, HasLocation, Import,
-    Module, ModuleConstant, Pattern, Publicity, RecordConstructor, RecordConstructorArg,
-    RecordUpdateSpread, SrcSpan, Statement, TargetedDefinition, TodoKind, TypeAlias, TypeAst,
-    TypeAstConstructor, TypeAstFn, TypeAstHole, TypeAstTuple, TypeAstVar, UnqualifiedImport,
-    UntypedArg, UntypedClause, UntypedClauseGuard, UntypedConstant, UntypedDefinition, UntypedExpr,
-    UntypedModule, UntypedPattern, UntypedRecordUpdateArg, UntypedStatement, Use, UseAssignment,
-    CAPTURE_VARIABLE,
+    AllowedWarning, Arg, ArgNames, AssignName, Assignment, AssignmentKind, BinOp, BitArrayOption,
+    BitArraySegment, CallArg, Clause, ClauseGuard, Constant, CustomType, Definition, Function,
+    HasLocation, Import, Module, ModuleConstant, Pattern, Publicity, RecordConstructor,
+    RecordConstructorArg, RecordUpdateSpread, SrcSpan, Statement, TargetedDefinition, TodoKind,
+    TypeAlias, TypeAst, TypeAstConstructor, TypeAstFn, TypeAstHole, TypeAstTuple, TypeAstVar,
+    UnqualifiedImport, UntypedArg, UntypedClause, UntypedClauseGuard, UntypedConstant,
+    UntypedDefinition, UntypedExpr, UntypedModule, UntypedPattern, UntypedRecordUpdateArg,
+    UntypedStatement, Use, UseAssignment, CAPTURE_VARIABLE,
 };
 use crate::build::Target;
 use crate::parse::extra::ModuleExtra;
 use crate::type_::expression::Implementations;
 use crate::type_::Deprecation;
+use crate::warning;
 use ecow::EcoString;
 use error::{LexicalError, ParseError, ParseErrorType};
 use lexer::{LexResult, Spanned};
@@ -116,6 +117,7 @@ struct Attributes {
     external_erlang: Option<(EcoString, EcoString)>,
     external_javascript: Option<(EcoString, EcoString)>,
     internal: InternalAttribute,
+    allowed_warnings: Vec<AllowedWarning>,
 }
 
 impl Attributes {
@@ -317,11 +319,13 @@ where
             (Some(definition), _) if definition.is_function() => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (Some(definition), None) => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (_, Some(location)) if attributes.has_function_only() => {
@@ -331,6 +335,7 @@ where
             (Some(definition), _) => Ok(Some(TargetedDefinition {
                 definition,
                 target: attributes.target,
+                allowed_warnings: attributes.allowed_warnings,
             })),
 
             (_, Some(location)) => parse_error(ParseErrorType::ExpectedDefinition, location),
@@ -3015,6 +3020,10 @@ where
                 self.parse_deprecated_attribute(start, end, attributes)
             }
             "internal" => self.parse_internal_attribute(start, end, attributes),
+            "allow" => {
+                let _ = self.expect_one(&Token::LeftParen)?;
+                self.parse_allow_attribute(attributes)
+            }
             _ => parse_error(ParseErrorType::UnknownAttribute, SrcSpan { start, end }),
         }?;
 
@@ -3095,6 +3104,32 @@ where
         Ok(end)
     }
 
+    fn parse_allow_attribute(&mut self, attributes: &mut Attributes) -> Result<u32, ParseError> {
+        loop {
+            if let Some((_, end)) = self.maybe_one(&Token::RightParen) {
+                return Ok(end);
+            }
+
+            // `todo` is a keyword so it is not lexed as a name.
+            let (start, name, end) = match self.maybe_one(&Token::Todo) {
+                Some((start, end)) => (start, "todo".into(), end),
+                None => self.expect_name()?,
+            };
+            let location = SrcSpan::new(start, end);
+            if !warning::is_kind(&name) {
+                return parse_error(ParseErrorType::UnknownWarningKind { name }, location);
+            }
+            attributes
+                .allowed_warnings
+                .push(AllowedWarning { location, name });
+
+            if self.maybe_one(&Token::Comma).is_none() {
+                let (_, end) = self.expect_one(&Token::RightParen)?;
+                return Ok(end);
+            }
+        }
+    }
+
     fn parse_internal_attribute(
         &mut self,
         start: u32,
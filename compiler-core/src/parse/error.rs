@@ -212,7 +212,7 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
             ),
             ParseErrorType::UnknownAttribute => (
                 "I don't recognise this attribute",
-                vec!["Try `deprecated`, `external` or `target` instead.".into()],
+                vec!["Try `allow`, `deprecated`, `external` or `target` instead.".into()],
             ),
             ParseErrorType::DuplicateAttribute => (
                 "Duplicate attribute",
@@ -232,6 +232,14 @@ utf16_codepoint, utf32_codepoint, signed, unsigned, big, little, native, size, u
                     "See: https://tour.gleam.run/flow-control/case-expressions/".into(),
                 ],
             ),
+            ParseErrorType::UnknownWarningKind { name } => (
+                "I don't recognise this kind of warning",
+                vec![
+                    format!("There is no kind of warning called `{name}`."),
+                    "Hint: warning kinds are written in snake_case, such as `unused_variable`."
+                        .into(),
+                ],
+            ),
         }
     }
 }
@@ -288,6 +296,62 @@ pub enum ParseErrorType {
     ExpectedFunctionBody,       // let x = fn()
     RedundantInternalAttribute, // for a private definition marked as internal
     InvalidModuleTypePattern,   // for patterns that have a dot like: `name.thing`
+    UnknownWarningKind {
+        name: EcoString,
+    }, // an unknown kind of warning was given to the `@allow` attribute
+}
+
+impl ParseErrorType {
+    /// The stable code identifying this kind of error. See [`crate::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorType::ExpectedEqual => "E1001",
+            ParseErrorType::ExpectedExpr => "E1002",
+            ParseErrorType::ExpectedName => "E1003",
+            ParseErrorType::ExpectedPattern => "E1004",
+            ParseErrorType::ExpectedType => "E1005",
+            ParseErrorType::ExpectedUpName => "E1006",
+            ParseErrorType::ExpectedValue => "E1007",
+            ParseErrorType::ExpectedStatement => "E1008",
+            ParseErrorType::ExpectedDefinition => "E1009",
+            ParseErrorType::ExpectedFunctionDefinition => "E1010",
+            ParseErrorType::ExprLparStart => "E1011",
+            ParseErrorType::ExtraSeparator => "E1012",
+            ParseErrorType::IncorrectName => "E1013",
+            ParseErrorType::IncorrectUpName => "E1014",
+            ParseErrorType::InvalidBitArraySegment => "E1015",
+            ParseErrorType::InvalidBitArrayUnit => "E1016",
+            ParseErrorType::InvalidTailPattern => "E1017",
+            ParseErrorType::InvalidTupleAccess => "E1018",
+            ParseErrorType::LexError { .. } => "E1019",
+            ParseErrorType::NestedBitArrayPattern => "E1020",
+            ParseErrorType::NoExpression => "E1021",
+            ParseErrorType::NoLetBinding => "E1022",
+            ParseErrorType::NoValueAfterEqual => "E1023",
+            ParseErrorType::NotConstType => "E1024",
+            ParseErrorType::OpNakedRight => "E1025",
+            ParseErrorType::OpaqueTypeAlias => "E1026",
+            ParseErrorType::TooManyArgHoles => "E1027",
+            ParseErrorType::DuplicateAttribute => "E1028",
+            ParseErrorType::UnknownAttribute => "E1029",
+            ParseErrorType::UnknownTarget => "E1030",
+            ParseErrorType::ListSpreadWithoutElements => "E1031",
+            ParseErrorType::ListSpreadFollowedByElements => "E1032",
+            ParseErrorType::LowcaseBooleanPattern => "E1033",
+            ParseErrorType::UnexpectedLabel => "E1034",
+            ParseErrorType::UnexpectedEof => "E1035",
+            ParseErrorType::UnexpectedReservedWord => "E1036",
+            ParseErrorType::UnexpectedToken { .. } => "E1037",
+            ParseErrorType::ExpectedBoolean => "E1038",
+            ParseErrorType::UnexpectedFunction => "E1039",
+            ParseErrorType::ConcatPatternVariableLeftHandSide => "E1040",
+            ParseErrorType::ListSpreadWithoutTail => "E1041",
+            ParseErrorType::ExpectedFunctionBody => "E1042",
+            ParseErrorType::RedundantInternalAttribute => "E1043",
+            ParseErrorType::InvalidModuleTypePattern => "E1044",
+            ParseErrorType::UnknownWarningKind { .. } => "E1045",
+        }
+    }
 }
 
 impl LexicalError {
@@ -626,6 +626,14 @@ pub fn main() { 1 }"#
     );
 }
 
+#[test]
+fn unknown_allowed_warning() {
+    assert_module_error!(
+        r#"@allow(unused_var)
+pub fn main(x) { 1 }"#
+    );
+}
+
 #[test]
 fn incomplete_function() {
     assert_error!("fn()");
@@ -7,11 +7,24 @@ pub const ARTEFACT_DIRECTORY_NAME: &str = "_gleam_artefacts";
 #[derive(Debug, Clone)]
 pub struct ProjectPaths {
     root: Utf8PathBuf,
+    workspace_root: Option<Utf8PathBuf>,
 }
 
 impl ProjectPaths {
     pub fn new(root: Utf8PathBuf) -> Self {
-        Self { root }
+        Self {
+            root,
+            workspace_root: None,
+        }
+    }
+
+    /// The paths of a project in a workspace, which shares the manifest and
+    /// build directory at the root of the workspace with the other members.
+    pub fn in_workspace(root: Utf8PathBuf, workspace_root: Utf8PathBuf) -> Self {
+        Self {
+            root,
+            workspace_root: Some(workspace_root),
+        }
     }
 
     pub fn at_filesystem_root() -> Self {
@@ -28,6 +41,18 @@ impl ProjectPaths {
         &self.root
     }
 
+    pub fn workspace_root(&self) -> Option<&Utf8Path> {
+        self.workspace_root.as_deref()
+    }
+
+    /// The directory containing the manifest and the build directory. This is
+    /// the root of the workspace if the project is in one, and the root of the
+    /// project otherwise. The paths of local packages in the manifest are
+    /// relative to this directory.
+    pub fn shared_root(&self) -> &Utf8Path {
+        self.workspace_root().unwrap_or(&self.root)
+    }
+
     pub fn root_config(&self) -> Utf8PathBuf {
         self.root.join("gleam.toml")
     }
@@ -37,7 +62,7 @@ impl ProjectPaths {
     }
 
     pub fn manifest(&self) -> Utf8PathBuf {
-        self.root.join("manifest.toml")
+        self.shared_root().join("manifest.toml")
     }
 
     pub fn src_directory(&self) -> Utf8PathBuf {
@@ -49,7 +74,7 @@ impl ProjectPaths {
     }
 
     pub fn build_directory(&self) -> Utf8PathBuf {
-        self.root.join("build")
+        self.shared_root().join("build")
     }
 
     pub fn build_packages_directory(&self) -> Utf8PathBuf {
@@ -64,6 +89,28 @@ impl ProjectPaths {
         self.build_packages_directory().join(package_name)
     }
 
+    /// The directory `gleam deps vendor` copies the tarballs of Hex packages
+    /// into, so they can be used without network access.
+    pub fn vendor_directory(&self) -> Utf8PathBuf {
+        self.shared_root().join("vendor")
+    }
+
+    /// Packages of the same name can come from different Hex repositories, so
+    /// each repository has a directory of its own, like in the package cache.
+    pub fn vendor_repository_directory(&self, repository: &str) -> Utf8PathBuf {
+        self.vendor_directory().join(repository)
+    }
+
+    pub fn vendor_package_tarball(
+        &self,
+        repository: &str,
+        package_name: &str,
+        version: &str,
+    ) -> Utf8PathBuf {
+        self.vendor_repository_directory(repository)
+            .join(format!("{package_name}-{version}.tar"))
+    }
+
     // build_deps_package_config
     pub fn build_packages_package_config(&self, package_name: &str) -> Utf8PathBuf {
         self.build_packages_package(package_name).join("gleam.toml")
@@ -74,6 +121,16 @@ impl ProjectPaths {
             .join(format!("{package_name}-{version}.tar"))
     }
 
+    pub fn build_export_escript(&self, package_name: &str) -> Utf8PathBuf {
+        self.build_directory().join("escript").join(package_name)
+    }
+
+    pub fn build_export_javascript_bundle(&self, module: &str) -> Utf8PathBuf {
+        self.build_directory()
+            .join("javascript-bundle")
+            .join(format!("{}.mjs", module.replace('/', "@")))
+    }
+
     pub fn build_directory_for_mode(&self, mode: Mode) -> Utf8PathBuf {
         self.build_directory().join(mode.to_string())
     }
@@ -82,6 +139,10 @@ impl ProjectPaths {
         self.build_directory().join("erlang-shipment")
     }
 
+    pub fn erlang_release_directory(&self) -> Utf8PathBuf {
+        self.build_directory().join("erlang-release")
+    }
+
     pub fn build_documentation_directory(&self, package: &str) -> Utf8PathBuf {
         self.build_directory_for_mode(Mode::Dev)
             .join("docs")
@@ -122,17 +183,39 @@ impl ProjectPaths {
     }
 }
 
-pub fn global_package_cache_package_tarball(package_name: &str, version: &str) -> Utf8PathBuf {
-    global_packages_cache().join(format!("{package_name}-{version}.tar"))
+pub fn global_package_cache_package_tarball(
+    repository: &str,
+    package_name: &str,
+    version: &str,
+) -> Utf8PathBuf {
+    global_packages_cache(repository).join(format!("{package_name}-{version}.tar"))
 }
 
-fn global_packages_cache() -> Utf8PathBuf {
+pub fn global_packages_cache(repository: &str) -> Utf8PathBuf {
     default_global_gleam_cache()
         .join("hex")
-        .join("hexpm")
+        .join(repository)
         .join("packages")
 }
 
+/// The bare repository that the commits of a git dependency are fetched into.
+pub fn global_git_repository_cache(directory_name: &str) -> Utf8PathBuf {
+    default_global_gleam_cache()
+        .join("git")
+        .join(directory_name)
+}
+
+/// The config shared by every project of the user.
+pub fn global_user_config() -> Utf8PathBuf {
+    Utf8PathBuf::from_path_buf(
+        dirs_next::config_dir()
+            .expect("Failed to determine user config directory")
+            .join("gleam")
+            .join("config.toml"),
+    )
+    .expect("Non Utf8 Path")
+}
+
 pub fn default_global_gleam_cache() -> Utf8PathBuf {
     Utf8PathBuf::from_path_buf(
         dirs_next::cache_dir()
@@ -154,13 +237,51 @@ pub fn unnest(within: &Utf8Path) -> Utf8PathBuf {
 fn paths() {
     assert!(default_global_gleam_cache().ends_with("gleam"));
 
-    assert!(global_packages_cache().ends_with("hex/hexpm/packages"));
+    assert!(global_packages_cache("hexpm").ends_with("hex/hexpm/packages"));
 
     assert!(
-        global_package_cache_package_tarball("gleam_stdlib", "0.17.1")
+        global_package_cache_package_tarball("hexpm", "gleam_stdlib", "0.17.1")
             .ends_with("hex/hexpm/packages/gleam_stdlib-0.17.1.tar")
     );
 
-    assert!(global_package_cache_package_tarball("elli", "1.0.0")
-        .ends_with("hex/hexpm/packages/elli-1.0.0.tar"));
+    assert!(
+        global_package_cache_package_tarball("hexpm", "elli", "1.0.0")
+            .ends_with("hex/hexpm/packages/elli-1.0.0.tar")
+    );
+
+    assert!(
+        global_package_cache_package_tarball("acme", "elli", "1.0.0")
+            .ends_with("hex/acme/packages/elli-1.0.0.tar")
+    );
+
+    assert!(global_git_repository_cache("wibble-0123").ends_with("gleam/git/wibble-0123"));
+}
+
+#[test]
+fn workspace_member_paths() {
+    let paths = ProjectPaths::in_workspace("/repo/packages/app".into(), "/repo".into());
+    assert_eq!(
+        paths.root_config(),
+        Utf8PathBuf::from("/repo/packages/app/gleam.toml")
+    );
+    assert_eq!(
+        paths.src_directory(),
+        Utf8PathBuf::from("/repo/packages/app/src")
+    );
+    assert_eq!(paths.manifest(), Utf8PathBuf::from("/repo/manifest.toml"));
+    assert_eq!(paths.build_directory(), Utf8PathBuf::from("/repo/build"));
+    assert_eq!(paths.vendor_directory(), Utf8PathBuf::from("/repo/vendor"));
+}
+
+#[test]
+fn vendor_package_tarballs() {
+    let paths = ProjectPaths::new("/app".into());
+    assert_eq!(
+        paths.vendor_package_tarball("hexpm", "wibble", "1.0.0"),
+        Utf8PathBuf::from("/app/vendor/hexpm/wibble-1.0.0.tar")
+    );
+    assert_eq!(
+        paths.vendor_package_tarball("acme", "wibble", "1.0.0"),
+        Utf8PathBuf::from("/app/vendor/acme/wibble-1.0.0.tar")
+    );
 }
@@ -10,18 +10,69 @@ use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::ser::{Serialize, SerializeMap, Serializer};
 use serde::Deserialize;
 
-#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
-#[serde(untagged, remote = "Self")]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Requirement {
-    Hex { version: Range },
-    Path { path: Utf8PathBuf },
-    Git { git: EcoString },
+    Hex {
+        version: Range,
+        /// The name of the Hex repository the package is fetched from. `None`
+        /// for the default repository, hex.pm.
+        repository: Option<EcoString>,
+    },
+    Path {
+        path: Utf8PathBuf,
+    },
+    Git {
+        git: EcoString,
+        reference: GitReference,
+    },
+}
+
+/// Which commit of a git repository a dependency is taken from.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub enum GitReference {
+    /// The commit at the head of the repository's default branch.
+    #[default]
+    Head,
+    Branch(EcoString),
+    Tag(EcoString),
+    Rev(EcoString),
+    Ref(EcoString),
+}
+
+impl GitReference {
+    /// The key used for this reference in `gleam.toml`, along with its value.
+    pub fn key_and_value(&self) -> Option<(&'static str, &EcoString)> {
+        match self {
+            GitReference::Head => None,
+            GitReference::Branch(branch) => Some(("branch", branch)),
+            GitReference::Tag(tag) => Some(("tag", tag)),
+            GitReference::Rev(rev) => Some(("rev", rev)),
+            GitReference::Ref(ref_) => Some(("ref", ref_)),
+        }
+    }
+}
+
+impl fmt::Display for GitReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.key_and_value() {
+            None => f.write_str("the default branch"),
+            Some((key, value)) => write!(f, "{key} `{value}`"),
+        }
+    }
 }
 
 impl Requirement {
     pub fn hex(range: &str) -> Requirement {
         Requirement::Hex {
             version: Range::new(range.to_string()),
+            repository: None,
+        }
+    }
+
+    pub fn hex_from_repository(range: &str, repository: &str) -> Requirement {
+        Requirement::Hex {
+            version: Range::new(range.to_string()),
+            repository: Some(repository.into()),
         }
     }
 
@@ -30,25 +81,57 @@ impl Requirement {
     }
 
     pub fn git(url: &str) -> Requirement {
-        Requirement::Git { git: url.into() }
+        Requirement::Git {
+            git: url.into(),
+            reference: GitReference::Head,
+        }
     }
 
     pub fn to_toml(&self, root_path: &Utf8Path) -> String {
         match self {
-            Requirement::Hex { version: range } => {
-                format!(r#"{{ version = "{}" }}"#, range)
+            Requirement::Hex {
+                version: range,
+                repository: None,
+            } => {
+                format!(r#"{{ version = {} }}"#, toml_string(&range.to_string()))
+            }
+            Requirement::Hex {
+                version: range,
+                repository: Some(repository),
+            } => {
+                format!(
+                    r#"{{ version = {}, repo = {} }}"#,
+                    toml_string(&range.to_string()),
+                    toml_string(repository)
+                )
             }
             Requirement::Path { path } => {
                 format!(
-                    r#"{{ path = "{}" }}"#,
-                    make_relative(root_path, path).as_str().replace('\\', "/")
+                    r#"{{ path = {} }}"#,
+                    toml_string(&make_relative(root_path, path).as_str().replace('\\', "/"))
                 )
             }
-            Requirement::Git { git: url } => format!(r#"{{ git = "{}" }}"#, url),
+            Requirement::Git {
+                git: url,
+                reference,
+            } => match reference.key_and_value() {
+                None => format!(r#"{{ git = {} }}"#, toml_string(url)),
+                Some((key, value)) => format!(
+                    r#"{{ git = {}, {} = {} }}"#,
+                    toml_string(url),
+                    key,
+                    toml_string(value)
+                ),
+            },
         }
     }
 }
 
+/// A TOML string with the given value, quoted and escaped as needed.
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.into()).to_string()
+}
+
 // Serialization
 
 impl Serialize for Requirement {
@@ -56,11 +139,27 @@ impl Serialize for Requirement {
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(1))?;
+        let mut map = serializer.serialize_map(None)?;
         match self {
-            Requirement::Hex { version: range } => map.serialize_entry("version", range)?,
+            Requirement::Hex {
+                version: range,
+                repository,
+            } => {
+                map.serialize_entry("version", range)?;
+                if let Some(repository) = repository {
+                    map.serialize_entry("repo", repository)?;
+                }
+            }
             Requirement::Path { path } => map.serialize_entry("path", path)?,
-            Requirement::Git { git: url } => map.serialize_entry("git", url)?,
+            Requirement::Git {
+                git: url,
+                reference,
+            } => {
+                map.serialize_entry("git", url)?;
+                if let Some((key, value)) = reference.key_and_value() {
+                    map.serialize_entry(key, value)?;
+                }
+            }
         }
         map.end()
     }
@@ -99,7 +198,72 @@ impl<'de> Visitor<'de> for RequirementVisitor {
     where
         M: MapAccess<'de>,
     {
-        Requirement::deserialize(de::value::MapAccessDeserializer::new(visitor))
+        RawRequirement::deserialize(de::value::MapAccessDeserializer::new(visitor))?
+            .try_into()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// The fields a requirement can have in a table, before it is known which
+/// kind of requirement it is.
+#[derive(Deserialize)]
+struct RawRequirement {
+    version: Option<Range>,
+    repo: Option<EcoString>,
+    path: Option<Utf8PathBuf>,
+    git: Option<EcoString>,
+    branch: Option<EcoString>,
+    tag: Option<EcoString>,
+    rev: Option<EcoString>,
+    #[serde(rename = "ref")]
+    ref_: Option<EcoString>,
+}
+
+impl TryFrom<RawRequirement> for Requirement {
+    type Error = &'static str;
+
+    fn try_from(raw: RawRequirement) -> Result<Self, Self::Error> {
+        let references = [
+            raw.branch.map(GitReference::Branch),
+            raw.tag.map(GitReference::Tag),
+            raw.rev.map(GitReference::Rev),
+            raw.ref_.map(GitReference::Ref),
+        ];
+        let mut references = references.into_iter().flatten();
+        let reference = references.next();
+        let has_many_references = references.next().is_some();
+
+        match (raw.version, raw.path, raw.git) {
+            (Some(version), _, _) => Ok(Requirement::Hex {
+                version,
+                repository: raw.repo,
+            }),
+            (None, _, _) if raw.repo.is_some() => {
+                Err("`repo` can only be given with a `version` requirement")
+            }
+            (None, Some(path), _) => Ok(Requirement::Path { path }),
+            (None, None, Some(_)) if has_many_references => {
+                Err("only one of `branch`, `tag`, `rev`, and `ref` can be given")
+            }
+            // These are given to git as arguments, so they must not be
+            // mistaken for its options.
+            (None, None, Some(git)) if git.starts_with('-') => {
+                Err("a `git` location cannot start with `-`")
+            }
+            (None, None, Some(_))
+                if reference
+                    .as_ref()
+                    .and_then(GitReference::key_and_value)
+                    .is_some_and(|(_, value)| value.starts_with('-')) =>
+            {
+                Err("a `branch`, `tag`, `rev`, or `ref` cannot start with `-`")
+            }
+            (None, None, Some(git)) => Ok(Requirement::Git {
+                git,
+                reference: reference.unwrap_or_default(),
+            }),
+            (None, None, None) => Err("expected a `version`, `path`, or `git` requirement"),
+        }
     }
 }
 
@@ -135,4 +299,136 @@ mod tests {
             Requirement::git("https://github.com/gleam-lang/otp.git")
         );
     }
+
+    #[test]
+    fn read_hex_requirement_with_repository() {
+        let toml = r#"
+            private = { version = "~> 1.0", repo = "acme" }
+        "#;
+        let deps: HashMap<String, Requirement> = toml::from_str(toml).unwrap();
+        assert_eq!(
+            deps["private"],
+            Requirement::hex_from_repository("~> 1.0", "acme")
+        );
+    }
+
+    #[test]
+    fn read_repository_without_version() {
+        let toml = r#"
+            wibble = { path = "../wibble", repo = "acme" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("`repo` can only be given with a `version` requirement"));
+    }
+
+    #[test]
+    fn hex_requirement_with_repository_round_trip() {
+        let requirement = Requirement::hex_from_repository("~> 1.0", "acme");
+        assert_eq!(
+            requirement.to_toml(Utf8Path::new("/")),
+            r#"{ version = "~> 1.0", repo = "acme" }"#
+        );
+        let toml = format!("wibble = {}", requirement.to_toml(Utf8Path::new("/")));
+        let deps: HashMap<String, Requirement> = toml::from_str(&toml).unwrap();
+        assert_eq!(deps["wibble"], requirement);
+    }
+
+    #[test]
+    fn read_git_requirement_references() {
+        let toml = r#"
+            branch = { git = "../repo.git", branch = "main" }
+            tag = { git = "../repo.git", tag = "v1.0.0" }
+            rev = { git = "../repo.git", rev = "4e1c9b3" }
+            ref = { git = "../repo.git", ref = "refs/pull/1/head" }
+        "#;
+        let deps: HashMap<String, Requirement> = toml::from_str(toml).unwrap();
+        let git = |reference| Requirement::Git {
+            git: "../repo.git".into(),
+            reference,
+        };
+        assert_eq!(deps["branch"], git(GitReference::Branch("main".into())));
+        assert_eq!(deps["tag"], git(GitReference::Tag("v1.0.0".into())));
+        assert_eq!(deps["rev"], git(GitReference::Rev("4e1c9b3".into())));
+        assert_eq!(
+            deps["ref"],
+            git(GitReference::Ref("refs/pull/1/head".into()))
+        );
+    }
+
+    #[test]
+    fn read_git_requirement_with_many_references() {
+        let toml = r#"
+            wibble = { git = "../repo.git", branch = "main", tag = "v1.0.0" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("only one of `branch`, `tag`, `rev`, and `ref` can be given"));
+    }
+
+    #[test]
+    fn read_git_requirement_with_option_like_location() {
+        let toml = r#"
+            wibble = { git = "--upload-pack=touch /tmp/wobble" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("a `git` location cannot start with `-`"));
+    }
+
+    #[test]
+    fn read_git_requirement_with_option_like_reference() {
+        let toml = r#"
+            wibble = { git = "../repo.git", rev = "--output=/tmp/wobble" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("a `branch`, `tag`, `rev`, or `ref` cannot start with `-`"));
+    }
+
+    #[test]
+    fn read_requirement_without_source() {
+        let toml = r#"
+            wibble = { tag = "v1.0.0" }
+        "#;
+        let error = toml::from_str::<HashMap<String, Requirement>>(toml).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected a `version`, `path`, or `git` requirement"));
+    }
+
+    #[test]
+    fn git_requirement_round_trip() {
+        let requirement = Requirement::Git {
+            git: "https://example.com/repo.git".into(),
+            reference: GitReference::Tag("v1.0.0".into()),
+        };
+        assert_eq!(
+            requirement.to_toml(Utf8Path::new("/")),
+            r#"{ git = "https://example.com/repo.git", tag = "v1.0.0" }"#
+        );
+        let toml = format!("wibble = {}", requirement.to_toml(Utf8Path::new("/")));
+        let deps: HashMap<String, Requirement> = toml::from_str(&toml).unwrap();
+        assert_eq!(deps["wibble"], requirement);
+    }
+
+    #[test]
+    fn requirement_with_quotes_round_trip() {
+        let path = Requirement::Path {
+            path: "wib\"ble".into(),
+        };
+        let git = Requirement::Git {
+            git: "https://example.com/\"repo\".git".into(),
+            reference: GitReference::Branch("wibble\"wobble'".into()),
+        };
+        for requirement in [path, git] {
+            let toml = format!("wibble = {}", requirement.to_toml(Utf8Path::new("/")));
+            let deps: HashMap<String, Requirement> = toml::from_str(&toml).unwrap();
+            assert_eq!(deps["wibble"], requirement);
+        }
+    }
 }
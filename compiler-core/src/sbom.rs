@@ -0,0 +1,171 @@
+//! A software bill of materials (SBOM) for a package: the package itself and
+//! every package it depends on when it is built for production, along with
+//! their versions, sources, and licences.
+//!
+//! The bill can be rendered in the CycloneDX and SPDX formats, and its
+//! licences checked against those allowed by the `allowed-licences` setting
+//! of `gleam.toml`.
+//!
+
+pub mod cyclonedx;
+pub mod spdx;
+
+#[cfg(test)]
+mod tests;
+
+use ecow::EcoString;
+use hexpm::version::Version;
+
+use crate::{config::SpdxLicense, manifest::ManifestPackageSource, Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sbom {
+    pub root: Package,
+    /// Every production dependency of the root package, direct or not.
+    pub dependencies: Vec<Package>,
+    /// When the bill was created, in seconds since the Unix epoch.
+    pub created: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: EcoString,
+    pub version: Version,
+    /// The licences given in the package's metadata, as written there. These
+    /// are not always SPDX identifiers for packages that are not written in
+    /// Gleam.
+    pub licences: Vec<EcoString>,
+    /// Where the package comes from, which is `None` for the root package.
+    pub source: Option<ManifestPackageSource>,
+    /// The names of the packages this package depends on directly.
+    pub dependencies: Vec<EcoString>,
+}
+
+impl Package {
+    /// The Package URL of the package, if it is one that can be fetched
+    /// from hex.pm.
+    ///
+    /// <https://github.com/package-url/purl-spec>
+    pub fn purl(&self) -> Option<String> {
+        match &self.source {
+            Some(ManifestPackageSource::Hex {
+                repository: None, ..
+            }) => Some(format!("pkg:hex/{}@{}", self.name, self.version)),
+            _ => None,
+        }
+    }
+
+    /// The SHA-256 checksum of the package's Hex tarball, in lowercase
+    /// base16.
+    pub fn sha256(&self) -> Option<String> {
+        match &self.source {
+            Some(ManifestPackageSource::Hex { outer_checksum, .. })
+                if !outer_checksum.0.is_empty() =>
+            {
+                Some(base16::encode_lower(&outer_checksum.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// The licences of the package if none of them are allowed. The licences
+    /// of a Hex package are alternatives it can be used under, so one allowed
+    /// licence is enough. A package with no known licence is never allowed,
+    /// so its only disallowed licence is reported as `unknown`.
+    fn disallowed_licences(&self, allowed: &[SpdxLicense]) -> Vec<EcoString> {
+        if self.licences.is_empty() {
+            return vec!["unknown".into()];
+        }
+        let is_allowed = |licence: &EcoString| {
+            let id = licence_id(licence);
+            allowed.iter().any(|allowed| Some(allowed.as_ref()) == id)
+        };
+        if self.licences.iter().any(is_allowed) {
+            vec![]
+        } else {
+            self.licences.clone()
+        }
+    }
+}
+
+impl Sbom {
+    /// Returns an error listing each dependency with none of its licences in
+    /// the allowed list.
+    pub fn check_licences(&self, allowed: &[SpdxLicense]) -> Result<()> {
+        let packages: Vec<_> = self
+            .dependencies
+            .iter()
+            .filter_map(|package| {
+                let disallowed = package.disallowed_licences(allowed);
+                (!disallowed.is_empty()).then(|| (package.name.clone(), disallowed))
+            })
+            .collect();
+        if packages.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::DisallowedLicences { packages })
+        }
+    }
+
+    /// A report of the licences of each dependency, one per line.
+    pub fn licence_report(&self) -> String {
+        let width = self
+            .dependencies
+            .iter()
+            .map(|package| package.name.len() + package.version.to_string().len() + 1)
+            .max()
+            .unwrap_or_default();
+        let mut report = String::new();
+        for package in &self.dependencies {
+            let name = format!("{} {}", package.name, package.version);
+            let licences = if package.licences.is_empty() {
+                "unknown".into()
+            } else {
+                package.licences.join(", ")
+            };
+            report.push_str(&format!("{name:width$}  {licences}\n"));
+        }
+        report
+    }
+}
+
+/// The SPDX identifier for a licence. Packages that are not written in
+/// Gleam often give the name of their licence rather than its identifier,
+/// such as `Apache 2.0`, so the commonly used names are recognised too.
+pub fn licence_id(licence: &str) -> Option<&'static str> {
+    if let Some(id) = ::spdx::license_id(licence) {
+        return Some(id.name);
+    }
+    match ::spdx::imprecise_license_id(licence) {
+        Some((id, length)) if length == licence.len() => Some(id.name),
+        _ => None,
+    }
+}
+
+/// Formats a number of seconds since the Unix epoch as an RFC 3339 UTC
+/// timestamp, as used by both formats.
+fn timestamp(seconds: u64) -> String {
+    let time = seconds % 86_400;
+    // The civil date for a number of days since the epoch, from Howard
+    // Hinnant's `civil_from_days` algorithm.
+    let days = seconds / 86_400 + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
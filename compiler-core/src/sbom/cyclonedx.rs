@@ -0,0 +1,186 @@
+//! Renders a bill of materials in the CycloneDX 1.5 JSON format.
+//!
+//! <https://cyclonedx.org/docs/1.5/json/>
+//!
+
+use serde::Serialize;
+
+use crate::{manifest::ManifestPackageSource, version::COMPILER_VERSION};
+
+use super::{licence_id, timestamp, Package, Sbom};
+
+const SCHEMA: &str = "http://cyclonedx.org/schema/bom-1.5.schema.json";
+const SPEC_VERSION: &str = "1.5";
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Bom {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Serialize, Debug)]
+struct Metadata {
+    timestamp: String,
+    tools: Tools,
+    component: Component,
+}
+
+#[derive(Serialize, Debug)]
+struct Tools {
+    components: Vec<Tool>,
+}
+
+#[derive(Serialize, Debug)]
+struct Tool {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Component {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<LicenceChoice>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_references: Vec<ExternalReference>,
+}
+
+#[derive(Serialize, Debug)]
+struct LicenceChoice {
+    license: Licence,
+}
+
+/// A licence is given by its SPDX identifier where it has one, and by name
+/// otherwise.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Licence {
+    Id(&'static str),
+    Name(String),
+}
+
+#[derive(Serialize, Debug)]
+struct Hash {
+    alg: &'static str,
+    content: String,
+}
+
+#[derive(Serialize, Debug)]
+struct ExternalReference {
+    #[serde(rename = "type")]
+    type_: &'static str,
+    url: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Dependency {
+    #[serde(rename = "ref")]
+    ref_: String,
+    depends_on: Vec<String>,
+}
+
+pub fn render(sbom: &Sbom) -> String {
+    let packages = std::iter::once(&sbom.root).chain(&sbom.dependencies);
+    let dependencies = packages
+        .map(|package| Dependency {
+            ref_: bom_ref(package),
+            depends_on: package
+                .dependencies
+                .iter()
+                .filter_map(|name| sbom.dependencies.iter().find(|p| &p.name == name))
+                .map(bom_ref)
+                .collect(),
+        })
+        .collect();
+
+    let bom = Bom {
+        schema: SCHEMA,
+        bom_format: "CycloneDX",
+        spec_version: SPEC_VERSION,
+        version: 1,
+        metadata: Metadata {
+            timestamp: timestamp(sbom.created),
+            tools: Tools {
+                components: vec![Tool {
+                    type_: "application",
+                    name: "gleam",
+                    version: COMPILER_VERSION,
+                }],
+            },
+            component: component(&sbom.root, "application"),
+        },
+        components: sbom
+            .dependencies
+            .iter()
+            .map(|package| component(package, "library"))
+            .collect(),
+        dependencies,
+    };
+    serde_json::to_string_pretty(&bom).expect("sbom to cyclonedx")
+}
+
+fn component(package: &Package, type_: &'static str) -> Component {
+    let licenses = package
+        .licences
+        .iter()
+        .map(|licence| LicenceChoice {
+            license: match licence_id(licence) {
+                Some(id) => Licence::Id(id),
+                None => Licence::Name(licence.to_string()),
+            },
+        })
+        .collect();
+    let hashes = package
+        .sha256()
+        .map(|content| Hash {
+            alg: "SHA-256",
+            content,
+        })
+        .into_iter()
+        .collect();
+    let external_references = match &package.source {
+        Some(ManifestPackageSource::Git { repo, .. }) => vec![ExternalReference {
+            type_: "vcs",
+            url: repo.to_string(),
+        }],
+        _ => vec![],
+    };
+    Component {
+        type_,
+        bom_ref: bom_ref(package),
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        purl: package.purl(),
+        licenses,
+        hashes,
+        external_references,
+    }
+}
+
+/// Each component is referred to by its Package URL where it has one, as is
+/// conventional, and by its name and version otherwise.
+fn bom_ref(package: &Package) -> String {
+    package
+        .purl()
+        .unwrap_or_else(|| format!("{}@{}", package.name, package.version))
+}
@@ -0,0 +1,183 @@
+//! Renders a bill of materials in the SPDX 2.3 JSON format.
+//!
+//! <https://spdx.github.io/spdx-spec/v2.3/>
+//!
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{manifest::ManifestPackageSource, version::COMPILER_VERSION};
+
+use super::{licence_id, timestamp, Package, Sbom};
+
+const SPDX_VERSION: &str = "SPDX-2.3";
+const NO_ASSERTION: &str = "NOASSERTION";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<SpdxPackage>,
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Serialize, Debug)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SpdxPackage {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    license_concluded: &'static str,
+    license_declared: String,
+    copyright_text: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checksums: Vec<Checksum>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Checksum {
+    algorithm: &'static str,
+    checksum_value: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+pub fn render(sbom: &Sbom) -> String {
+    let packages = std::iter::once(&sbom.root)
+        .chain(&sbom.dependencies)
+        .collect_vec();
+
+    let mut relationships = vec![Relationship {
+        spdx_element_id: DOCUMENT_ID.into(),
+        relationship_type: "DESCRIBES",
+        related_spdx_element: spdx_id(&sbom.root),
+    }];
+    for package in &packages {
+        relationships.extend(
+            package
+                .dependencies
+                .iter()
+                .filter_map(|name| sbom.dependencies.iter().find(|p| &p.name == name))
+                .map(|dependency| Relationship {
+                    spdx_element_id: spdx_id(package),
+                    relationship_type: "DEPENDS_ON",
+                    related_spdx_element: spdx_id(dependency),
+                }),
+        );
+    }
+
+    let created = timestamp(sbom.created);
+    let name = format!("{}-{}", sbom.root.name, sbom.root.version);
+    let document = Document {
+        spdx_version: SPDX_VERSION,
+        data_license: "CC0-1.0",
+        spdx_id: DOCUMENT_ID,
+        document_namespace: format!("https://gleam.run/spdxdocs/{name}-{created}"),
+        name,
+        creation_info: CreationInfo {
+            created,
+            creators: vec![format!("Tool: gleam-{COMPILER_VERSION}")],
+        },
+        packages: packages.into_iter().map(package).collect(),
+        relationships,
+    };
+    serde_json::to_string_pretty(&document).expect("sbom to spdx")
+}
+
+fn package(package: &Package) -> SpdxPackage {
+    let download_location = match &package.source {
+        Some(ManifestPackageSource::Hex {
+            repository: None, ..
+        }) => format!(
+            "https://repo.hex.pm/tarballs/{}-{}.tar",
+            package.name, package.version
+        ),
+        Some(ManifestPackageSource::Git { repo, commit }) => format!("git+{repo}@{commit}"),
+        Some(ManifestPackageSource::Hex { .. } | ManifestPackageSource::Local { .. }) | None => {
+            NO_ASSERTION.into()
+        }
+    };
+    let checksums = package
+        .sha256()
+        .map(|checksum_value| Checksum {
+            algorithm: "SHA256",
+            checksum_value,
+        })
+        .into_iter()
+        .collect();
+    let external_refs = package
+        .purl()
+        .map(|reference_locator| ExternalRef {
+            reference_category: "PACKAGE-MANAGER",
+            reference_type: "purl",
+            reference_locator,
+        })
+        .into_iter()
+        .collect();
+    SpdxPackage {
+        name: package.name.to_string(),
+        spdx_id: spdx_id(package),
+        version_info: package.version.to_string(),
+        download_location,
+        files_analyzed: false,
+        license_concluded: NO_ASSERTION,
+        license_declared: license_declared(package),
+        copyright_text: NO_ASSERTION,
+        checksums,
+        external_refs,
+    }
+}
+
+/// The licences of a package as an SPDX licence expression. Licences that
+/// have no SPDX identifier can't be part of an expression, so if there are
+/// any the declared licence is unknown.
+fn license_declared(package: &Package) -> String {
+    let ids: Option<Vec<_>> = package
+        .licences
+        .iter()
+        .map(|licence| licence_id(licence))
+        .collect();
+    match ids {
+        Some(ids) if !ids.is_empty() => ids.join(" AND "),
+        _ => NO_ASSERTION.into(),
+    }
+}
+
+/// Package names may contain underscores, which are not permitted in SPDX
+/// identifiers.
+fn spdx_id(package: &Package) -> String {
+    format!("SPDXRef-Package-{}", package.name.replace("_", "-"))
+}
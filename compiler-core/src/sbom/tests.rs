@@ -0,0 +1,129 @@
+use hexpm::version::Version;
+
+use super::{cyclonedx, licence_id, spdx, timestamp, Package, Sbom};
+use crate::{
+    config::SpdxLicense,
+    manifest::{Base16Checksum, ManifestPackageSource},
+    Error,
+};
+
+fn package(name: &str, licences: &[&str], source: ManifestPackageSource) -> Package {
+    Package {
+        name: name.into(),
+        version: Version::new(1, 0, 0),
+        licences: licences.iter().map(|licence| (*licence).into()).collect(),
+        source: Some(source),
+        dependencies: vec![],
+    }
+}
+
+fn hex() -> ManifestPackageSource {
+    ManifestPackageSource::Hex {
+        outer_checksum: Base16Checksum(vec![0xAB, 0xCD, 0xEF]),
+        repository: None,
+    }
+}
+
+fn sbom() -> Sbom {
+    let mut gleam_stdlib = package("gleam_stdlib", &["Apache-2.0"], hex());
+    gleam_stdlib.version = Version::new(0, 38, 0);
+    let mut thoas = package("thoas", &["Apache 2.0"], hex());
+    thoas.dependencies = vec!["gleam_stdlib".into()];
+    let wibble = package(
+        "wibble",
+        &[],
+        ManifestPackageSource::Git {
+            repo: "https://github.com/gleam-lang/wibble".into(),
+            commit: "bd9fe02f72250e6a136967917bcb1bdccaffa3c8".into(),
+        },
+    );
+    let wobble = package(
+        "wobble",
+        &["MIT", "Custom licence"],
+        ManifestPackageSource::Local {
+            path: "../wobble".into(),
+        },
+    );
+    Sbom {
+        root: Package {
+            name: "my_app".into(),
+            version: Version::new(0, 1, 0),
+            licences: vec!["MIT".into()],
+            source: None,
+            dependencies: vec!["thoas".into(), "wibble".into(), "wobble".into()],
+        },
+        dependencies: vec![gleam_stdlib, thoas, wibble, wobble],
+        created: 1_717_243_200,
+    }
+}
+
+fn licences(ids: &[&str]) -> Vec<SpdxLicense> {
+    ids.iter()
+        .map(|id| SpdxLicense {
+            licence: (*id).into(),
+        })
+        .collect()
+}
+
+#[test]
+fn cyclonedx_output() {
+    insta::assert_snapshot!(cyclonedx::render(&sbom()));
+}
+
+#[test]
+fn spdx_output() {
+    insta::assert_snapshot!(spdx::render(&sbom()));
+}
+
+#[test]
+fn licence_report() {
+    insta::assert_snapshot!(sbom().licence_report());
+}
+
+#[test]
+fn allowed_licences() {
+    let mut sbom = sbom();
+    sbom.dependencies.truncate(2);
+    assert_eq!(sbom.check_licences(&licences(&["Apache-2.0"])), Ok(()));
+}
+
+#[test]
+fn disallowed_licences() {
+    let result = sbom().check_licences(&licences(&["Apache-2.0"]));
+    assert_eq!(
+        result,
+        Err(Error::DisallowedLicences {
+            packages: vec![
+                ("wibble".into(), vec!["unknown".into()]),
+                ("wobble".into(), vec!["MIT".into(), "Custom licence".into()]),
+            ]
+        })
+    );
+}
+
+#[test]
+fn any_allowed_licence_is_enough() {
+    let mut sbom = sbom();
+    let _ = sbom.dependencies.remove(2);
+    assert_eq!(
+        sbom.check_licences(&licences(&["Apache-2.0", "MIT"])),
+        Ok(())
+    );
+}
+
+#[test]
+fn licence_ids() {
+    assert_eq!(licence_id("MIT"), Some("MIT"));
+    assert_eq!(licence_id("Apache 2.0"), Some("Apache-2.0"));
+    assert_eq!(licence_id("apache2"), Some("Apache-2.0"));
+    assert_eq!(licence_id("Apache 2.0 or something"), None);
+    assert_eq!(licence_id("Custom licence"), None);
+}
+
+#[test]
+fn timestamps() {
+    assert_eq!(timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(timestamp(1_717_243_200), "2024-06-01T12:00:00Z");
+    assert_eq!(timestamp(4_102_444_799), "2099-12-31T23:59:59Z");
+}
@@ -13,7 +13,7 @@ pub mod tests;
 use camino::Utf8PathBuf;
 use ecow::EcoString;
 pub use environment::*;
-pub use error::{Error, UnifyErrorSituation, Warning};
+pub use error::{Error, UnifyErrorSituation, Warning, WarningKind};
 pub(crate) use expression::ExprTyper;
 pub use fields::FieldMap;
 pub use prelude::*;
@@ -586,6 +586,13 @@ pub struct ModuleFunction {
     pub package: EcoString,
 }
 
+/// A test function run by `gleam test`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFunction {
+    pub module: EcoString,
+    pub name: EcoString,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModuleInterface {
     pub name: EcoString,
@@ -729,6 +736,33 @@ impl ModuleInterface {
         })
     }
 
+    /// The tests defined in this module: public functions that take no
+    /// arguments and have a name ending in `_test`, in the order they are
+    /// defined.
+    pub fn test_functions(&self, target: Target) -> Vec<TestFunction> {
+        self.values
+            .iter()
+            .filter(|(name, value)| name.ends_with("_test") && value.publicity.is_importable())
+            .filter_map(|(name, value)| match &value.variant {
+                ValueConstructorVariant::ModuleFn {
+                    arity: 0,
+                    module,
+                    location,
+                    implementations,
+                    ..
+                } if module == &self.name && implementations.supports(target) => {
+                    Some((location.start, name))
+                }
+                _ => None,
+            })
+            .sorted()
+            .map(|(_, name)| TestFunction {
+                module: self.name.clone(),
+                name: name.clone(),
+            })
+            .collect()
+    }
+
     pub fn public_value_names(&self) -> Vec<EcoString> {
         self.values
             .iter()
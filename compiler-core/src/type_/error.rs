@@ -6,6 +6,7 @@ use crate::{
 
 use camino::Utf8PathBuf;
 use std::sync::Arc;
+use strum::{EnumDiscriminants, EnumIter};
 
 use crate::ast::Layer;
 use ecow::EcoString;
@@ -449,7 +450,8 @@ pub enum LiteralCollectionKind {
     Record,
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, EnumDiscriminants)]
+#[strum_discriminants(name(WarningKind), derive(EnumIter))]
 pub enum Warning {
     Todo {
         kind: TodoKind,
@@ -651,6 +653,19 @@ pub enum Warning {
     RedundantPipeFunctionCapture {
         location: SrcSpan,
     },
+
+    /// When an `@allow` attribute silences a kind of warning that is never
+    /// emitted for the definition it is attached to:
+    ///
+    /// ```gleam
+    /// @allow(todo)
+    /// pub fn wibble() { 1 }
+    /// ```
+    ///
+    UnusedWarningSuppression {
+        location: SrcSpan,
+        name: EcoString,
+    },
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
@@ -675,6 +690,67 @@ pub enum TodoOrPanic {
 }
 
 impl Error {
+    /// The stable code identifying this kind of error. See [`crate::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::SrcImportingTest { .. } => "E2001",
+            Error::BitArraySegmentError { .. } => "E2002",
+            Error::UnknownLabels { .. } => "E2003",
+            Error::UnknownVariable { .. } => "E2004",
+            Error::UnknownType { .. } => "E2005",
+            Error::UnknownModule { .. } => "E2006",
+            Error::UnknownModuleType { .. } => "E2007",
+            Error::UnknownModuleValue { .. } => "E2008",
+            Error::NotFn { .. } => "E2009",
+            Error::UnknownRecordField { .. } => "E2010",
+            Error::IncorrectArity { .. } => "E2011",
+            Error::UpdateMultiConstructorType { .. } => "E2012",
+            Error::UnnecessarySpreadOperator { .. } => "E2013",
+            Error::IncorrectTypeArity { .. } => "E2014",
+            Error::CouldNotUnify { .. } => "E2015",
+            Error::RecursiveType { .. } => "E2016",
+            Error::DuplicateName { .. } => "E2017",
+            Error::DuplicateImport { .. } => "E2018",
+            Error::DuplicateTypeName { .. } => "E2019",
+            Error::DuplicateArgument { .. } => "E2020",
+            Error::DuplicateField { .. } => "E2021",
+            Error::PrivateTypeLeak { .. } => "E2022",
+            Error::UnexpectedLabelledArg { .. } => "E2023",
+            Error::PositionalArgumentAfterLabelled { .. } => "E2024",
+            Error::IncorrectNumClausePatterns { .. } => "E2025",
+            Error::NonLocalClauseGuardVariable { .. } => "E2026",
+            Error::ExtraVarInAlternativePattern { .. } => "E2027",
+            Error::MissingVarInAlternativePattern { .. } => "E2028",
+            Error::DuplicateVarInPattern { .. } => "E2029",
+            Error::OutOfBoundsTupleIndex { .. } => "E2030",
+            Error::NotATuple { .. } => "E2031",
+            Error::NotATupleUnbound { .. } => "E2032",
+            Error::RecordAccessUnknownType { .. } => "E2033",
+            Error::RecordUpdateInvalidConstructor { .. } => "E2034",
+            Error::UnexpectedTypeHole { .. } => "E2035",
+            Error::ReservedModuleName { .. } => "E2036",
+            Error::KeywordInModuleName { .. } => "E2037",
+            Error::NotExhaustivePatternMatch { .. } => "E2038",
+            Error::ArgumentNameAlreadyUsed { .. } => "E2039",
+            Error::UnlabelledAfterlabelled { .. } => "E2040",
+            Error::RecursiveTypeAlias { .. } => "E2041",
+            Error::ExternalMissingAnnotation { .. } => "E2042",
+            Error::NoImplementation { .. } => "E2043",
+            Error::UnsupportedExpressionTarget { .. } => "E2044",
+            Error::InvalidExternalJavascriptModule { .. } => "E2045",
+            Error::InvalidExternalJavascriptFunction { .. } => "E2046",
+            Error::InexhaustiveCaseExpression { .. } => "E2047",
+            Error::InexhaustiveLetAssignment { .. } => "E2048",
+            Error::UnusedTypeAliasParameter { .. } => "E2049",
+            Error::DuplicateTypeParameter { .. } => "E2050",
+            Error::UnsupportedPublicFunctionTarget { .. } => "E2051",
+            Error::NotFnInUse { .. } => "E2052",
+            Error::UseFnIncorrectArity { .. } => "E2053",
+            Error::UseCallbackIncorrectArity { .. } => "E2054",
+            Error::UseFnDoesntTakeCallback { .. } => "E2055",
+        }
+    }
+
     // Location where the error started
     pub fn start_location(&self) -> u32 {
         match self {
@@ -770,7 +846,119 @@ impl Error {
     }
 }
 
+impl WarningKind {
+    /// The name of this kind of warning, as used to configure it in the
+    /// `[warnings]` section of `gleam.toml` or with the `@allow` attribute.
+    pub fn name(self) -> &'static str {
+        match self {
+            WarningKind::Todo => "todo",
+            WarningKind::ImplicitlyDiscardedResult => "implicitly_discarded_result",
+            WarningKind::UnusedLiteral => "unused_literal",
+            WarningKind::UnusedValue => "unused_value",
+            WarningKind::NoFieldsRecordUpdate => "no_fields_record_update",
+            WarningKind::AllFieldsRecordUpdate => "all_fields_record_update",
+            WarningKind::UnusedType => "unused_type",
+            WarningKind::UnusedConstructor => "unused_constructor",
+            WarningKind::UnusedImportedValue => "unused_imported_value",
+            WarningKind::UnusedImportedModule => "unused_imported_module",
+            WarningKind::UnusedImportedModuleAlias => "unused_imported_module_alias",
+            WarningKind::UnusedPrivateModuleConstant => "unused_private_module_constant",
+            WarningKind::UnusedPrivateFunction => "unused_private_function",
+            WarningKind::UnusedVariable => "unused_variable",
+            WarningKind::UnnecessaryDoubleIntNegation => "unnecessary_double_int_negation",
+            WarningKind::UnnecessaryDoubleBoolNegation => "unnecessary_double_bool_negation",
+            WarningKind::InefficientEmptyListCheck => "inefficient_empty_list_check",
+            WarningKind::TransitiveDependencyImported => "transitive_dependency_imported",
+            WarningKind::DeprecatedItem => "deprecated_item",
+            WarningKind::UnreachableCaseClause => "unreachable_case_clause",
+            WarningKind::CaseMatchOnLiteralCollection => "case_match_on_literal_collection",
+            WarningKind::CaseMatchOnLiteralValue => "case_match_on_literal_value",
+            WarningKind::OpaqueExternalType => "opaque_external_type",
+            WarningKind::InternalTypeLeak => "internal_type_leak",
+            WarningKind::RedundantAssertAssignment => "redundant_assert_assignment",
+            WarningKind::TodoOrPanicUsedAsFunction => "todo_or_panic_used_as_function",
+            WarningKind::UnreachableCodeAfterPanic => "unreachable_code_after_panic",
+            WarningKind::RedundantPipeFunctionCapture => "redundant_pipe_function_capture",
+            WarningKind::UnusedWarningSuppression => "unused_warning_suppression",
+        }
+    }
+}
+
 impl Warning {
+    /// The stable code identifying this kind of warning. See [`crate::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::Todo { .. } => "W1001",
+            Warning::ImplicitlyDiscardedResult { .. } => "W1002",
+            Warning::UnusedLiteral { .. } => "W1003",
+            Warning::UnusedValue { .. } => "W1004",
+            Warning::NoFieldsRecordUpdate { .. } => "W1005",
+            Warning::AllFieldsRecordUpdate { .. } => "W1006",
+            Warning::UnusedType { .. } => "W1007",
+            Warning::UnusedConstructor { .. } => "W1008",
+            Warning::UnusedImportedValue { .. } => "W1009",
+            Warning::UnusedImportedModule { .. } => "W1010",
+            Warning::UnusedImportedModuleAlias { .. } => "W1011",
+            Warning::UnusedPrivateModuleConstant { .. } => "W1012",
+            Warning::UnusedPrivateFunction { .. } => "W1013",
+            Warning::UnusedVariable { .. } => "W1014",
+            Warning::UnnecessaryDoubleIntNegation { .. } => "W1015",
+            Warning::UnnecessaryDoubleBoolNegation { .. } => "W1016",
+            Warning::InefficientEmptyListCheck { .. } => "W1017",
+            Warning::TransitiveDependencyImported { .. } => "W1018",
+            Warning::DeprecatedItem { .. } => "W1019",
+            Warning::UnreachableCaseClause { .. } => "W1020",
+            Warning::CaseMatchOnLiteralCollection { .. } => "W1021",
+            Warning::CaseMatchOnLiteralValue { .. } => "W1022",
+            Warning::OpaqueExternalType { .. } => "W1023",
+            Warning::InternalTypeLeak { .. } => "W1024",
+            Warning::RedundantAssertAssignment { .. } => "W1025",
+            Warning::TodoOrPanicUsedAsFunction { .. } => "W1026",
+            Warning::UnreachableCodeAfterPanic { .. } => "W1027",
+            Warning::RedundantPipeFunctionCapture { .. } => "W1028",
+            Warning::UnusedWarningSuppression { .. } => "W1029",
+        }
+    }
+
+    /// The name of this kind of warning. See [`WarningKind::name`].
+    pub fn name(&self) -> &'static str {
+        WarningKind::from(self).name()
+    }
+
+    pub fn location(&self) -> SrcSpan {
+        match self {
+            Warning::Todo { location, .. }
+            | Warning::ImplicitlyDiscardedResult { location, .. }
+            | Warning::UnusedLiteral { location, .. }
+            | Warning::UnusedValue { location, .. }
+            | Warning::NoFieldsRecordUpdate { location, .. }
+            | Warning::AllFieldsRecordUpdate { location, .. }
+            | Warning::UnusedType { location, .. }
+            | Warning::UnusedConstructor { location, .. }
+            | Warning::UnusedImportedValue { location, .. }
+            | Warning::UnusedImportedModule { location, .. }
+            | Warning::UnusedImportedModuleAlias { location, .. }
+            | Warning::UnusedPrivateModuleConstant { location, .. }
+            | Warning::UnusedPrivateFunction { location, .. }
+            | Warning::UnusedVariable { location, .. }
+            | Warning::UnnecessaryDoubleIntNegation { location, .. }
+            | Warning::UnnecessaryDoubleBoolNegation { location, .. }
+            | Warning::InefficientEmptyListCheck { location, .. }
+            | Warning::TransitiveDependencyImported { location, .. }
+            | Warning::DeprecatedItem { location, .. }
+            | Warning::UnreachableCaseClause { location, .. }
+            | Warning::CaseMatchOnLiteralCollection { location, .. }
+            | Warning::CaseMatchOnLiteralValue { location, .. }
+            | Warning::OpaqueExternalType { location, .. }
+            | Warning::InternalTypeLeak { location, .. }
+            | Warning::RedundantAssertAssignment { location, .. }
+            | Warning::TodoOrPanicUsedAsFunction { location, .. }
+            | Warning::UnreachableCodeAfterPanic { location, .. }
+            | Warning::RedundantPipeFunctionCapture { location, .. }
+            | Warning::UnusedWarningSuppression { location, .. } => *location,
+        }
+    }
+
     pub fn into_warning(self, path: Utf8PathBuf, src: EcoString) -> crate::Warning {
         crate::Warning::Type {
             path,
@@ -2201,3 +2201,28 @@ fn assert_suitable_main_function_javascript_not_supported() {
     };
     assert!(assert_suitable_main_function(&value, &"module".into(), Target::JavaScript).is_err(),);
 }
+
+#[test]
+fn test_functions() {
+    let module = compile_module(
+        "wibble_test",
+        r#"
+pub fn second_test() { Nil }
+pub fn first_test() { Nil }
+fn private_test() { Nil }
+pub fn with_argument_test(x) { x }
+pub fn helper() { private_test() }
+pub const constant_test = 1
+"#,
+        None,
+        vec![],
+    )
+    .expect("compile");
+    let names: Vec<_> = module
+        .type_info
+        .test_functions(Target::Erlang)
+        .into_iter()
+        .map(|test| test.name)
+        .collect();
+    assert_eq!(names, vec!["second_test", "first_test"]);
+}
@@ -2064,3 +2064,118 @@ fn redundant_function_capture_in_pipe_4() {
 "
     );
 }
+
+#[test]
+fn allow_attribute_silences_warning() {
+    assert_no_warnings!(
+        "
+@allow(todo)
+pub fn main() {
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_silences_several_warnings() {
+    assert_no_warnings!(
+        "
+@allow(unused_variable, unused_literal)
+pub fn main() {
+  let x = 1
+  2
+  Nil
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_silences_unused_private_function() {
+    assert_no_warnings!(
+        "
+@allow(unused_private_function)
+fn wibble() {
+  1
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_silences_unused_import() {
+    assert_no_warnings!(
+        ("thepackage", "wibble", "pub const x = 1"),
+        "
+@allow(unused_imported_module)
+import wibble
+"
+    );
+}
+
+#[test]
+fn allow_attribute_only_silences_its_own_definition() {
+    assert_warning!(
+        "
+@allow(todo)
+pub fn main() {
+  todo
+}
+
+pub fn wibble() {
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_only_silences_given_kind() {
+    assert_warning!(
+        "
+@allow(unused_variable)
+pub fn main() {
+  let x = 1
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn unused_allow_attribute() {
+    assert_warning!(
+        "
+@allow(todo)
+pub fn main() {
+  1
+}
+"
+    );
+}
+
+#[test]
+fn unused_allow_attribute_with_used_one() {
+    assert_warning!(
+        "
+@allow(todo, unused_variable)
+pub fn main() {
+  todo
+}
+"
+    );
+}
+
+#[test]
+fn allow_attribute_for_other_target_is_not_checked() {
+    assert_no_warnings!(
+        "
+@target(javascript)
+@allow(todo)
+pub fn main() {
+  1
+}
+"
+    );
+}
@@ -1,5 +1,6 @@
 use crate::{
-    ast::TodoKind,
+    ast::{AllowedWarning, SrcSpan, TodoKind},
+    config::{WarningLevel, WarningsConfig},
     diagnostic::{self, Diagnostic, Location},
     error::wrap,
     type_::{
@@ -14,8 +15,9 @@ use ecow::EcoString;
 use std::sync::atomic::AtomicUsize;
 use std::{
     io::Write,
-    sync::{atomic::Ordering, Arc},
+    sync::{atomic::Ordering, Arc, RwLock},
 };
+use strum::IntoEnumIterator;
 use termcolor::Buffer;
 
 pub trait WarningEmitterIO {
@@ -31,7 +33,7 @@ impl WarningEmitterIO for NullWarningEmitterIO {
 
 #[derive(Debug, Clone, Default)]
 pub struct VectorWarningEmitterIO {
-    pub warnings: Arc<std::sync::RwLock<Vec<Warning>>>,
+    pub warnings: Arc<RwLock<Vec<Warning>>>,
 }
 
 impl VectorWarningEmitterIO {
@@ -66,6 +68,25 @@ impl WarningEmitterIO for VectorWarningEmitterIO {
     }
 }
 
+/// The name of the warning for a module with an invalid name.
+const INVALID_SOURCE: &str = "invalid_source";
+
+/// The name of every kind of warning, as used to configure them in the
+/// `[warnings]` section of `gleam.toml` or with the `@allow` attribute, in
+/// alphabetical order.
+pub fn kinds() -> Vec<&'static str> {
+    let mut kinds: Vec<_> = type_::WarningKind::iter()
+        .map(type_::WarningKind::name)
+        .chain([INVALID_SOURCE])
+        .collect();
+    kinds.sort_unstable();
+    kinds
+}
+
+pub fn is_kind(name: &str) -> bool {
+    kinds().contains(&name)
+}
+
 #[derive(Debug, Clone)]
 pub struct WarningEmitter {
     /// The number of warnings emitted.
@@ -73,6 +94,10 @@ pub struct WarningEmitter {
     /// package only, the count is reset back to zero after the dependencies are
     /// compiled.
     count: Arc<AtomicUsize>,
+    /// The number of emitted warnings that the package configuration denies.
+    /// This is reset along with `count`.
+    denied: Arc<AtomicUsize>,
+    levels: WarningsConfig,
     emitter: DebugIgnore<Arc<dyn WarningEmitterIO>>,
 }
 
@@ -80,6 +105,8 @@ impl WarningEmitter {
     pub fn new(emitter: Arc<dyn WarningEmitterIO>) -> Self {
         Self {
             count: Arc::new(AtomicUsize::new(0)),
+            denied: Arc::new(AtomicUsize::new(0)),
+            levels: WarningsConfig::default(),
             emitter: DebugIgnore(emitter),
         }
     }
@@ -88,15 +115,34 @@ impl WarningEmitter {
         Self::new(Arc::new(NullWarningEmitterIO))
     }
 
+    /// An emitter sharing the same counts and output, but which reports each
+    /// kind of warning at the level given in a package's configuration.
+    pub fn with_levels(&self, levels: WarningsConfig) -> Self {
+        Self {
+            levels,
+            ..self.clone()
+        }
+    }
+
     pub fn reset_count(&self) {
         self.count.store(0, Ordering::Relaxed);
+        self.denied.store(0, Ordering::Relaxed);
     }
 
     pub fn count(&self) -> usize {
         self.count.load(Ordering::Relaxed)
     }
 
+    pub fn denied_count(&self) -> usize {
+        self.denied.load(Ordering::Relaxed)
+    }
+
     pub fn emit(&self, warning: Warning) {
+        match self.levels.level(warning.name()) {
+            WarningLevel::Allow => return,
+            WarningLevel::Warn => (),
+            WarningLevel::Deny => _ = self.denied.fetch_add(1, Ordering::Relaxed),
+        }
         _ = self.count.fetch_add(1, Ordering::Relaxed);
         self.emitter.emit_warning(warning);
     }
@@ -108,11 +154,22 @@ impl WarningEmitter {
     }
 }
 
+/// A kind of warning silenced for a single definition by an `@allow`
+/// attribute.
+#[derive(Debug, Clone)]
+struct Suppression {
+    allowed: AllowedWarning,
+    /// The span of the whole definition the attribute is attached to.
+    scope: SrcSpan,
+    used: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeWarningEmitter {
     module_path: Utf8PathBuf,
     module_src: EcoString,
     emitter: WarningEmitter,
+    suppressions: Arc<RwLock<Vec<Suppression>>>,
 }
 
 impl TypeWarningEmitter {
@@ -121,24 +178,78 @@ impl TypeWarningEmitter {
             module_path,
             module_src,
             emitter,
+            suppressions: Arc::new(RwLock::new(vec![])),
         }
     }
 
     pub fn null() -> Self {
+        Self::new(
+            Utf8PathBuf::new(),
+            EcoString::from(""),
+            WarningEmitter::new(Arc::new(NullWarningEmitterIO)),
+        )
+    }
+
+    /// An emitter for the same module that drops any warning silenced by the
+    /// `@allow` attributes of the definition containing it.
+    pub fn with_suppressions<'a>(
+        &self,
+        definitions: impl IntoIterator<Item = (SrcSpan, &'a [AllowedWarning])>,
+    ) -> Self {
+        let suppressions = definitions
+            .into_iter()
+            .flat_map(|(scope, allowed)| {
+                allowed.iter().map(move |allowed| Suppression {
+                    allowed: allowed.clone(),
+                    scope,
+                    used: false,
+                })
+            })
+            .collect();
         Self {
-            module_path: Utf8PathBuf::new(),
-            module_src: EcoString::from(""),
-            emitter: WarningEmitter::new(Arc::new(NullWarningEmitterIO)),
+            suppressions: Arc::new(RwLock::new(suppressions)),
+            ..self.clone()
         }
     }
 
+    /// The `@allow` attributes that have not silenced any warning so far.
+    pub fn unused_suppressions(&self) -> Vec<AllowedWarning> {
+        self.suppressions
+            .read()
+            .expect("Suppressions lock poisoned")
+            .iter()
+            .filter(|suppression| !suppression.used)
+            .map(|suppression| suppression.allowed.clone())
+            .collect()
+    }
+
     pub fn emit(&self, warning: type_::Warning) {
+        if self.is_suppressed(&warning) {
+            return;
+        }
         self.emitter.emit(Warning::Type {
             path: self.module_path.clone(),
             src: self.module_src.clone(),
             warning,
         });
     }
+
+    fn is_suppressed(&self, warning: &type_::Warning) -> bool {
+        let name = warning.name();
+        let position = warning.location().start;
+        let mut suppressed = false;
+        let mut suppressions = self
+            .suppressions
+            .write()
+            .expect("Suppressions lock poisoned");
+        for suppression in suppressions.iter_mut().filter(|suppression| {
+            suppression.allowed.name == name && suppression.scope.contains(position)
+        }) {
+            suppression.used = true;
+            suppressed = true;
+        }
+        suppressed
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -154,7 +265,24 @@ pub enum Warning {
 }
 
 impl Warning {
+    /// The stable code identifying this kind of warning. See [`crate::Error::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::Type { warning, .. } => warning.code(),
+            Warning::InvalidSource { .. } => "W0001",
+        }
+    }
+
+    /// The name of this kind of warning. See [`type_::Warning::name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Warning::Type { warning, .. } => warning.name(),
+            Warning::InvalidSource { .. } => INVALID_SOURCE,
+        }
+    }
+
     pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = Some(self.code());
         match self {
             Warning::InvalidSource { path } => Diagnostic {
                 title: "Invalid module name".into(),
@@ -163,6 +291,7 @@ Module names must begin with a lowercase letter and contain
 only lowercase alphanumeric characters or underscores."
                     .into(),
                 level: diagnostic::Level::Warning,
+                code,
                 location: None,
                 hint: Some(format!(
                     "Rename `{path}` to be valid, or remove this file from the project source."
@@ -204,6 +333,7 @@ expression.",
                         title,
                         text,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             path: path.to_path_buf(),
                             src: src.clone(),
@@ -224,6 +354,7 @@ expression.",
                         "If you are sure you don't need it you can assign it to `_`.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -240,6 +371,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -258,6 +390,7 @@ expression.",
                         "Add some fields to change or replace it with the record itself.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -274,6 +407,7 @@ expression.",
                     text: "".into(),
                     hint: Some("It is better style to use the record creation syntax.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -303,6 +437,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -333,6 +468,7 @@ expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -350,6 +486,7 @@ expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -378,6 +515,7 @@ Hint: You can safely remove it.
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -395,6 +533,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -411,6 +550,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -427,6 +567,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -443,6 +584,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some(format!("You can ignore it with an underscore: `_{name}`.")),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -458,6 +600,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -473,6 +616,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -502,6 +646,7 @@ need to know if the list is empty or not.
                         text,
                         hint,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -535,6 +680,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -570,6 +716,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -592,6 +739,7 @@ the same values.\n"
                         text,
                         hint: Some("It can be safely removed.".into()),
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -629,6 +777,7 @@ See: https://tour.gleam.run/flow-control/multiple-subjects/"
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -649,6 +798,7 @@ can already tell which branch is going to match with this value.",
                     ),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -665,6 +815,7 @@ can already tell which branch is going to match with this value.",
                     text: "This type has no constructors so making it opaque is redundant.".into(),
                     hint: Some("Remove the `opaque` qualifier from the type definition.".into()),
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -681,6 +832,7 @@ can already tell which branch is going to match with this value.",
                     text: "".into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -714,6 +866,7 @@ hidden from the package's documentation.",
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -731,6 +884,7 @@ hidden from the package's documentation.",
                         .into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can remove this".into()),
@@ -787,6 +941,7 @@ See: https://tour.gleam.run/advanced-features/{name}/"
                         text: wrap(&text),
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -819,6 +974,7 @@ Your code will crash before reaching this point.",
                         text: wrap(text),
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        code,
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -841,6 +997,27 @@ See: https://tour.gleam.run/functions/pipelines/",
                     ),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    code,
+                    location: Some(Location {
+                        label: diagnostic::Label {
+                            text: Some("You can safely remove this".into()),
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![],
+                    }),
+                },
+
+                type_::Warning::UnusedWarningSuppression { location, name } => Diagnostic {
+                    title: "Unused warning suppression".into(),
+                    text: wrap(&format!(
+                        "No `{name}` warnings are emitted for this definition, so there is \
+nothing for this attribute to silence."
+                    )),
+                    hint: None,
+                    level: diagnostic::Level::Warning,
+                    code,
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can safely remove this".into()),
@@ -868,3 +1045,53 @@ See: https://tour.gleam.run/functions/pipelines/",
         String::from_utf8(nocolor.into_inner()).expect("Warning printing produced invalid utf8")
     }
 }
+
+#[test]
+fn kinds_are_unique() {
+    use itertools::Itertools;
+
+    assert!(kinds().iter().tuple_windows().all(|(a, b)| a < b));
+}
+
+#[test]
+fn warning_levels() {
+    let levels: WarningsConfig = toml::from_str(
+        r#"
+todo = "deny"
+unused_literal = "allow"
+"#,
+    )
+    .expect("warning levels");
+    let (emitter, io) = WarningEmitter::vector();
+    let emitter = emitter.with_levels(levels);
+    let emit = |warning| {
+        emitter.emit(Warning::Type {
+            path: Utf8PathBuf::new(),
+            src: "".into(),
+            warning,
+        })
+    };
+
+    emit(type_::Warning::UnusedLiteral {
+        location: SrcSpan::new(0, 1),
+    });
+    emit(type_::Warning::UnusedValue {
+        location: SrcSpan::new(0, 1),
+    });
+    emit(type_::Warning::Todo {
+        kind: TodoKind::Keyword,
+        location: SrcSpan::new(0, 1),
+        typ: type_::nil(),
+    });
+
+    assert_eq!(emitter.count(), 2);
+    assert_eq!(emitter.denied_count(), 1);
+    assert_eq!(
+        io.take().iter().map(Warning::name).collect::<Vec<_>>(),
+        vec!["unused_value", "todo"]
+    );
+
+    emitter.reset_count();
+    assert_eq!(emitter.count(), 0);
+    assert_eq!(emitter.denied_count(), 0);
+}
@@ -0,0 +1,132 @@
+-module('{{ application }}@@test').
+
+-export([run/0]).
+
+%% Runs the tests listed in a file, writing the outcome of each one to another
+%% file for `gleam test` to report on. The paths of the two files, the number
+%% of tests to run at once, and the timeout of each test in milliseconds are
+%% given as the plain arguments of the program. When the number of tests to
+%% run at once is zero there is one for each scheduler.
+run() ->
+    io:setopts(standard_io, [binary, {encoding, utf8}]),
+    io:setopts(standard_error, [{encoding, utf8}]),
+    [TestsPath, ResultsPath, Jobs, Timeout] = init:get_plain_arguments(),
+    try
+        {ok, _} = application:ensure_all_started('{{ application }}'),
+        Tests = read_tests(TestsPath),
+        Results = run_tests(
+            Tests, #{}, jobs(list_to_integer(Jobs)), list_to_integer(Timeout), []
+        ),
+        io:put_chars("\n"),
+        ok = file:write_file(ResultsPath, lists:map(fun format_result/1, Results)),
+        erlang:halt(0)
+    catch
+        Class:Reason:StackTrace ->
+            io:put_chars(erl_error:format_exception(Class, Reason, StackTrace)),
+            erlang:halt(127, [{flush, true}])
+    end.
+
+jobs(0) -> erlang:system_info(schedulers_online);
+jobs(Jobs) -> Jobs.
+
+read_tests(Path) ->
+    {ok, Contents} = file:read_file(Path),
+    Lines = binary:split(Contents, <<"\n">>, [global, trim_all]),
+    lists:map(
+        fun(Line) ->
+            [Module, Function] = binary:split(Line, <<"\t">>),
+            {binary_to_atom(Module, utf8), binary_to_atom(Function, utf8)}
+        end,
+        Lines
+    ).
+
+%% Running maps the monitor of each test process to the test, the process, the
+%% timer that times it out, and the time it started at. Tests are not linked to
+%% the runner, so a test that crashes or is killed by a crashing process it is
+%% linked to is reported as failing by its monitor.
+run_tests([], Running, _Jobs, _Timeout, Results) when map_size(Running) =:= 0 ->
+    lists:reverse(Results);
+run_tests([Test | Tests], Running, Jobs, Timeout, Results)
+        when map_size(Running) < Jobs ->
+    Started = erlang:monotonic_time(millisecond),
+    {Pid, Ref} = spawn_monitor(fun() -> run_test(Test) end),
+    Timer = erlang:send_after(Timeout, self(), {test_timeout, Ref}),
+    Running1 = maps:put(Ref, {Test, Pid, Timer, Started}, Running),
+    run_tests(Tests, Running1, Jobs, Timeout, Results);
+run_tests(Tests, Running, Jobs, Timeout, Results) ->
+    receive
+        {'DOWN', Ref, process, _, Outcome} when is_map_key(Ref, Running) ->
+            {Test, _, Timer, Started} = maps:get(Ref, Running),
+            ok = cancel_timeout(Timer, Ref),
+            Result = finished(Test, Outcome, Started),
+            run_tests(Tests, maps:remove(Ref, Running), Jobs, Timeout, [Result | Results]);
+        {test_timeout, Ref} when is_map_key(Ref, Running) ->
+            {Test, Pid, _, Started} = maps:get(Ref, Running),
+            true = erlang:demonitor(Ref, [flush]),
+            true = exit(Pid, kill),
+            Message = io_lib:format("Timed out after ~pms", [Timeout]),
+            Result = report({timed_out, Test, Started, Message}),
+            run_tests(Tests, maps:remove(Ref, Running), Jobs, Timeout, [Result | Results])
+    end.
+
+%% The timer may have fired after the test finished but before it could be
+%% cancelled, in which case its message is removed from the mailbox.
+cancel_timeout(Timer, Ref) ->
+    case erlang:cancel_timer(Timer) of
+        false ->
+            receive
+                {test_timeout, Ref} -> ok
+            after 0 -> ok
+            end;
+        _ ->
+            ok
+    end.
+
+run_test({Module, Function}) ->
+    try Module:Function() of
+        _ -> exit(passed)
+    catch
+        Class:Reason:StackTrace -> exit({failed, format_error(Class, Reason, StackTrace)})
+    end.
+
+finished(Test, passed, Started) ->
+    report({passed, Test, Started, ""});
+finished(Test, {failed, Message}, Started) ->
+    report({failed, Test, Started, Message});
+finished(Test, Reason, Started) ->
+    report({failed, Test, Started, io_lib:format("exit: ~tp", [Reason])}).
+
+report({Status, Test, Started, Message}) ->
+    io:put_chars(case Status of
+        passed -> ".";
+        _ -> "F"
+    end),
+    Duration = erlang:monotonic_time(millisecond) - Started,
+    {Status, Test, Duration, Message}.
+
+format_error(error, #{gleam_error := Kind, message := Message} = Error, _) ->
+    Details = case Error of
+        #{value := Value} -> io_lib:format("~nvalue: ~tp", [Value]);
+        _ -> ""
+    end,
+    io_lib:format("~ts: ~ts~ts", [Kind, Message, Details]);
+format_error(Class, Reason, StackTrace) ->
+    erl_error:format_exception(Class, Reason, StackTrace).
+
+format_result({Status, {Module, Function}, Duration, Message}) ->
+    [
+        atom_to_binary(Status, utf8), $\t,
+        atom_to_binary(Module, utf8), $\t,
+        atom_to_binary(Function, utf8), $\t,
+        integer_to_binary(Duration), $\t,
+        escape(unicode:characters_to_binary(Message)), $\n
+    ].
+
+escape(Message) ->
+    lists:foldl(
+        fun({Pattern, Replacement}, Acc) ->
+            binary:replace(Acc, Pattern, Replacement, [global])
+        end,
+        Message,
+        [{<<"\\">>, <<"\\\\">>}, {<<"\n">>, <<"\\n">>}, {<<"\t">>, <<"\\t">>}]
+    ).
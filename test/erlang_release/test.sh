@@ -0,0 +1,33 @@
+#!/bin/sh
+
+set -eu
+
+RELEASE=build/erlang-release
+
+should_contain() {
+    echo
+    echo Checking: "$1" contains "$2"
+    if ! grep -qF -- "$2" "$1"
+    then
+        echo ERROR: "$1" should contain "$2"
+        exit 1
+    fi
+}
+
+should_contain "$RELEASE/releases/1.0.0/erlang_release.rel" '{"erlang_release","1.0.0"}'
+should_contain "$RELEASE/releases/1.0.0/erlang_release.rel" '{erlang_release,"1.0.0"}'
+should_contain "$RELEASE/lib/erlang_release-1.0.0/ebin/erlang_release.app" '{vsn, "1.0.0"}'
+should_contain "$RELEASE/releases/1.0.0/sys.config" '{release, "erlang_release-1.0.0"}'
+should_contain "$RELEASE/releases/start_erl.data" ' 1.0.0'
+test -f "$RELEASE/releases/1.0.0/erlang_release.boot"
+test -f "$RELEASE/releases/1.0.0/vm.args"
+
+echo
+echo Running: "$RELEASE/bin/erlang_release" run
+OUTPUT=$("$RELEASE/bin/erlang_release" run)
+if [ "$OUTPUT" != "Hello from the release" ]
+then
+    echo ERROR: unexpected output from the start script: "$OUTPUT"
+    exit 1
+fi
+echo Test Passed
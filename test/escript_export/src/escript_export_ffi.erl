@@ -0,0 +1,8 @@
+-module(escript_export_ffi).
+
+-export([print_arguments/0]).
+
+% Prints the plain arguments of the VM, one per line.
+print_arguments() ->
+    Print = fun(Argument) -> io:format("~ts~n", [Argument]) end,
+    lists:foreach(Print, init:get_plain_arguments()).
@@ -0,0 +1,16 @@
+#!/bin/sh
+
+set -eu
+
+# The plain arguments of an escript are its path followed by the arguments it
+# was run with.
+EXPECTED=$(printf '%s\n' build/escript/escript_export wibble "wobble wubble")
+
+echo Running: build/escript/escript_export wibble "wobble wubble"
+OUTPUT=$(build/escript/escript_export wibble "wobble wubble")
+if [ "$OUTPUT" != "$EXPECTED" ]
+then
+    echo ERROR: unexpected output from the escript: "$OUTPUT"
+    exit 1
+fi
+echo Test Passed
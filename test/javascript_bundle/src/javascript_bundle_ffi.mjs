@@ -0,0 +1,5 @@
+import { exclaim } from "./javascript_bundle/text.mjs";
+
+export function greet(name) {
+  return exclaim(`Hello, ${name}`);
+}
@@ -0,0 +1,26 @@
+#!/bin/sh
+
+set -eu
+
+# The bundle is run from a directory of its own, as it must not import
+# anything from the build directory.
+OUT=$(mktemp -d)
+trap 'rm -rf "$OUT"' EXIT
+cp build/javascript-bundle/javascript_bundle.mjs build/javascript-bundle/javascript_bundle.d.mts "$OUT"
+
+echo Running the bundle with node
+OUTPUT=$(cd "$OUT" && node --input-type=module -e 'import { main } from "./javascript_bundle.mjs"; console.log(main());')
+if [ "$OUTPUT" != "Hello, Joe!" ]
+then
+    echo ERROR: unexpected output from the bundle: "$OUTPUT"
+    exit 1
+fi
+
+echo Checking the TypeScript declarations
+if ! grep -q "export function main(): string;" "$OUT/javascript_bundle.d.mts"
+then
+    echo ERROR: main is not declared in the TypeScript declarations:
+    cat "$OUT/javascript_bundle.d.mts"
+    exit 1
+fi
+echo Test Passed
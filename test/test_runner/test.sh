@@ -0,0 +1,58 @@
+#!/bin/sh
+
+set -eu
+
+GLEAM_COMMAND=${GLEAM_COMMAND:-"cargo run --quiet --"}
+
+g() {
+	echo "Running: $GLEAM_COMMAND $@"
+	$GLEAM_COMMAND "$@"
+}
+
+# Runs gleam, failing if its output does not contain the given text.
+g_outputs() {
+	expected=$1
+	shift
+	output=$(g "$@")
+	echo "$output"
+	if ! echo "$output" | grep -q -- "$expected"; then
+		echo "Expected output to contain: $expected"
+		exit 1
+	fi
+}
+
+echo Resetting the build directory to get to a known state
+rm -fr build
+
+for target in erlang javascript; do
+	echo The tests are found and run on $target
+	g_outputs "3 tests, 3 passed, 0 failed" test --target=$target
+
+	echo Tests can be selected by name and module on $target
+	g_outputs "1 test, 1 passed, 0 failed" test --target=$target --filter=negative
+	g_outputs "1 test, 1 passed, 0 failed" test --target=$target --module=nested/other_test
+
+	echo Running no tests should fail on $target
+	if g test --target=$target --filter=wibble; then
+		echo "Expected test to fail"
+		exit 1
+	fi
+done
+
+echo Running the main function of a test module that does not have one should fail
+if g test --main; then
+	echo "Expected test to fail"
+	exit 1
+fi
+
+echo The language tests are run by the main function of their test module
+cd ../language
+rm -fr build
+for target in erlang javascript; do
+	g_outputs "0 failures" test --target=$target
+	g_outputs "0 failures" test --target=$target -- --wibble
+done
+
+echo
+echo Success! 💖
+echo